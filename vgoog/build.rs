@@ -0,0 +1,609 @@
+//! Generates API wrappers from the Discovery documents in `discovery/`:
+//! a parameter struct and a method per Discovery method, plus the
+//! `vgoog exec` dispatch table and the actions' argument descriptions. The output lands in
+//! `$OUT_DIR/discovery.rs` and is included by `src/api/generated.rs`.
+//!
+//! Actions are named `<resource>_<method>` in snake case (`drives.hide`
+//! becomes `drives_hide`), arguments are the Discovery parameter names in
+//! snake case, and a request body is passed as `body`.
+//!
+//! Media methods get a second wrapper next to the JSON one: `<action>_media`
+//! streams `supportsMediaDownload` content to a file, and `<action>_upload`
+//! sends a file with the resumable protocol for `mediaUpload` methods. Their
+//! actions take `download_to`, and `upload_from` with `upload_mime_type`.
+//! A method whose only response is media (`files.export`) has no JSON
+//! wrapper, and its action requires `download_to`.
+//!
+//! The documents are vendored unmodified. `VGOOG_FETCH_DISCOVERY=1` fetches
+//! the current ones from Google's Discovery service into `discovery/` with
+//! curl; a service whose document is missing is left to its hand-written
+//! actions, with a build warning.
+
+use serde_json::{Map, Value};
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// A vendored Discovery document and where its methods land
+struct Api {
+    /// Discovery API name and version, as in `drive` `v3`
+    name: &'static str,
+    version: &'static str,
+    /// `vgoog exec` service and generated module name
+    service: &'static str,
+    /// `Endpoints` field holding the base URL
+    endpoint: &'static str,
+    /// Leading part of `servicePath` + method path that the base URL covers
+    base_path: &'static str,
+    /// Path parameters the base URL already fills in, such as Gmail's
+    /// `userId` (`me`)
+    fixed: &'static [&'static str],
+    /// `Endpoints` field and leading path of `mediaUpload` paths; without
+    /// one, upload methods only get their JSON wrapper
+    upload: Option<(&'static str, &'static str)>,
+}
+
+impl Api {
+    const fn new(name: &'static str, version: &'static str, service: &'static str, endpoint: &'static str, base_path: &'static str) -> Self {
+        Self { name, version, service, endpoint, base_path, fixed: &[], upload: None }
+    }
+
+    fn file(&self) -> String {
+        format!("{}.{}.json", self.name, self.version)
+    }
+
+    fn path(&self) -> PathBuf {
+        Path::new("discovery").join(self.file())
+    }
+
+    fn discovery_url(&self) -> String {
+        format!("https://www.googleapis.com/discovery/v1/apis/{}/{}/rest", self.name, self.version)
+    }
+}
+
+const APIS: &[Api] = &[
+    Api { fixed: &["userId"], ..Api::new("gmail", "v1", "gmail", "gmail", "gmail/v1/users/{userId}") },
+    Api::new("calendar", "v3", "calendar", "calendar", "calendar/v3"),
+    Api { upload: Some(("drive_upload", "/upload/drive/v3")), ..Api::new("drive", "v3", "drive", "drive", "drive/v3") },
+    Api::new("sheets", "v4", "sheets", "sheets", "v4/spreadsheets"),
+    Api::new("docs", "v1", "docs", "docs", "v1/documents"),
+    Api::new("slides", "v1", "slides", "slides", "v1/presentations"),
+    Api::new("forms", "v1", "forms", "forms", "v1/forms"),
+    Api::new("tasks", "v1", "tasks", "tasks", "tasks/v1"),
+    Api::new("people", "v1", "contacts", "people", "v1"),
+    Api::new("script", "v1", "apps_script", "script", "v1"),
+];
+
+/// Document-wide parameters worth exposing on every method
+const GLOBAL_PARAMETERS: &[&str] = &["fields"];
+
+/// Arguments added to media actions, which no Discovery parameter may share
+const MEDIA_ARGS: &[&str] = &["download_to", "upload_from", "upload_mime_type"];
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn", "else", "enum", "extern", "false", "fn",
+    "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref", "return", "self",
+    "static", "struct", "super", "trait", "true", "type", "unsafe", "use", "where", "while",
+];
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=discovery");
+    println!("cargo:rerun-if-env-changed=VGOOG_FETCH_DISCOVERY");
+
+    if std::env::var_os("VGOOG_FETCH_DISCOVERY").is_some_and(|v| v != "0") {
+        for api in APIS {
+            fetch(api);
+        }
+    }
+
+    let mut out = String::from("// Generated by build.rs from discovery/*.json; do not edit.\n");
+    let mut reads = Vec::new();
+    let mut generated = Vec::new();
+    for api in APIS {
+        let path = api.path();
+        let text = match std::fs::read_to_string(&path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                println!(
+                    "cargo:warning={} is missing, so {} has only its hand-written actions; build with VGOOG_FETCH_DISCOVERY=1 to fetch it",
+                    path.display(),
+                    api.service
+                );
+                continue;
+            }
+            Err(e) => panic!("{}: {e}", path.display()),
+        };
+        let doc: Value = serde_json::from_str(&text).unwrap_or_else(|e| panic!("{}: {e}", path.display()));
+        out.push_str(&module(api, &doc, &mut reads));
+        generated.push(api);
+    }
+
+    out.push_str("\n/// Generated actions by `vgoog exec` service\npub const SERVICES: &[(&str, &[crate::actions::Action])] = &[\n");
+    for api in &generated {
+        writeln!(out, "    (\"{0}\", {0}::ACTIONS),", api.service).unwrap();
+    }
+    out.push_str("];\n\n");
+    out.push_str("/// Generated actions that only read (GET methods), by service\npub const READS: &[(&str, &str)] = &[\n");
+    for (service, action) in &reads {
+        writeln!(out, "    (\"{service}\", \"{action}\"),").unwrap();
+    }
+    out.push_str("];\n\n");
+    out.push_str(
+        "pub async fn execute(client: &GoogleClient, service: &str, action: &str, args: Value) -> Result<Value> {\n    match service {\n",
+    );
+    for api in &generated {
+        writeln!(out, "        \"{0}\" => {0}::execute(client, action, args).await,", api.service).unwrap();
+    }
+    out.push_str("        _ => Err(VgoogError::Other(format!(\"Unknown service: {service}\"))),\n    }\n}\n");
+
+    let target = Path::new(&std::env::var("OUT_DIR").unwrap()).join("discovery.rs");
+    std::fs::write(target, out).unwrap();
+}
+
+/// Save the current Discovery document of `api` to `discovery/` as served
+fn fetch(api: &Api) {
+    let path = api.path();
+    let status = Command::new("curl")
+        .args(["--silent", "--show-error", "--fail", "--location", "--max-time", "60", "--output"])
+        .arg(&path)
+        .arg(api.discovery_url())
+        .status()
+        .unwrap_or_else(|e| panic!("running curl for {}: {e}", api.discovery_url()));
+    if !status.success() {
+        panic!("fetching {} into {} failed: {status}", api.discovery_url(), path.display());
+    }
+}
+
+/// A Discovery method with the resource path it was found under
+struct Method<'a> {
+    action: String,
+    spec: &'a Value,
+    /// Whether the API has an upload endpoint for `mediaUpload` methods
+    uploads: bool,
+}
+
+impl Method<'_> {
+    /// Only GET downloads are wrapped; `files.watch` also sets the flag
+    fn downloads(&self) -> bool {
+        self.spec.get("supportsMediaDownload") == Some(&Value::Bool(true)) && self.spec["httpMethod"] == "GET"
+    }
+
+    /// Downloads with no JSON response, such as `files.export`
+    fn media_only(&self) -> bool {
+        self.downloads() && self.spec.get("response").is_none()
+    }
+
+    /// For list methods, the response field holding a page's items: `items`
+    /// where the response schema has it, otherwise its one array. `None`
+    /// when the document lacks the schema or it is ambiguous.
+    fn items<'a>(&self, doc: &'a Value) -> Option<&'a str> {
+        self.spec["parameters"].get("pageToken")?;
+        let schema = self.spec["response"]["$ref"].as_str()?;
+        let properties = doc["schemas"][schema]["properties"].as_object()?;
+        let arrays: Vec<&String> = properties.iter().filter(|(_, p)| p["type"] == "array").map(|(name, _)| name).collect();
+        match arrays.as_slice() {
+            _ if arrays.iter().any(|name| *name == "items") => Some("items"),
+            [only] => Some(only.as_str()),
+            _ => None,
+        }
+    }
+
+    /// The `mediaUpload` path, as in `/upload/drive/v3/files/{fileId}`
+    fn upload_path(&self) -> Option<&str> {
+        if !self.uploads {
+            return None;
+        }
+        self.spec.get("mediaUpload")?["protocols"]["simple"]["path"].as_str()
+    }
+}
+
+fn collect<'a>(resources: &'a Map<String, Value>, prefix: &str, uploads: bool, methods: &mut Vec<Method<'a>>) {
+    for (name, resource) in resources {
+        let prefix = format!("{prefix}{}_", snake(name));
+        for (method, spec) in resource["methods"].as_object().into_iter().flatten() {
+            let action = format!("{prefix}{}", snake(method));
+            assert!(spec.is_object(), "{action} is not an object");
+            methods.push(Method { action, spec, uploads });
+        }
+        if let Some(nested) = resource["resources"].as_object() {
+            collect(nested, &prefix, uploads, methods);
+        }
+    }
+}
+
+fn module(api: &Api, doc: &Value, reads: &mut Vec<(&'static str, String)>) -> String {
+    let mut methods = Vec::new();
+    collect(doc["resources"].as_object().expect("resources"), "", api.upload.is_some(), &mut methods);
+    methods.sort_by(|a, b| a.action.cmp(&b.action));
+    reads.extend(methods.iter().filter(|m| m.spec["httpMethod"] == "GET").map(|m| (api.service, m.action.clone())));
+    let globals: Vec<(&String, &Value)> = doc["parameters"]
+        .as_object()
+        .into_iter()
+        .flatten()
+        .filter(|(name, _)| GLOBAL_PARAMETERS.contains(&name.as_str()))
+        .collect();
+
+    let title = doc["title"].as_str().unwrap_or(api.service);
+    let version = doc["version"].as_str().unwrap_or_default();
+    let mut out = String::new();
+    writeln!(out, "\n/// {title} {version}, from discovery/{}", api.file()).unwrap();
+    out.push_str("#[allow(clippy::doc_lazy_continuation)]\n");
+    writeln!(out, "pub mod {} {{", api.service).unwrap();
+    out.push_str("    use crate::client::GoogleClient;\n    use crate::error::{Result, VgoogError};\n");
+    out.push_str("    use crate::actions::{Action, Arg, ArgType};\n");
+    out.push_str("    use serde::Deserialize;\n    use serde_json::Value;\n");
+    let media = methods.iter().any(|m| m.downloads() || m.upload_path().is_some());
+    if media {
+        out.push_str("    use crate::transfer::ProgressFn;\n    use std::path::Path;\n");
+    }
+    if methods.iter().any(|m| m.upload_path().is_some()) {
+        out.push_str("    use crate::actions::Literal;\n");
+    }
+    for method in &methods {
+        let clash = parameters(api, method, &globals).into_iter().find(|(name, _)| MEDIA_ARGS.contains(&snake(name).as_str()));
+        if let Some((name, _)) = clash {
+            panic!("{}: parameter {name} of {} clashes with a media argument", api.file(), method.action);
+        }
+    }
+
+    for method in &methods {
+        out.push_str(&params_struct(api, method, &globals));
+    }
+
+    let upload = api.upload.filter(|_| methods.iter().any(|m| m.upload_path().is_some()));
+    out.push_str("\n    pub struct Api<'a> {\n        client: &'a GoogleClient,\n        base: &'a str,\n");
+    if upload.is_some() {
+        out.push_str("        upload_base: &'a str,\n");
+    }
+    out.push_str("    }\n");
+    writeln!(out, "\n    impl<'a> Api<'a> {{").unwrap();
+    let upload_base = match upload {
+        Some((endpoint, _)) => format!(", upload_base: &client.endpoints().{endpoint}"),
+        None => String::new(),
+    };
+    writeln!(
+        out,
+        "        pub fn new(client: &'a GoogleClient) -> Self {{\n            Self {{ client, base: &client.endpoints().{}{upload_base} }}\n        }}",
+        api.endpoint
+    )
+    .unwrap();
+    for method in &methods {
+        out.push_str(&wrapper(api, doc, method, &globals));
+    }
+    out.push_str("    }\n");
+
+    out.push_str("\n    pub const ACTIONS: &[Action] = &[\n");
+    for method in &methods {
+        out.push_str(&action(api, method, &globals, method.items(doc)));
+    }
+    out.push_str("    ];\n");
+
+    out.push_str("\n    pub async fn execute(client: &GoogleClient, action: &str, args: Value) -> Result<Value> {\n");
+    out.push_str("        let api = Api::new(client);\n        match action {\n");
+    for method in &methods {
+        out.push_str(&dispatch(method));
+    }
+    writeln!(
+        out,
+        "            _ => Err(VgoogError::Other(format!(\"Unknown {} action: {{action}}\"))),\n        }}\n    }}",
+        api.service
+    )
+    .unwrap();
+    out.push_str("}\n");
+    out
+}
+
+/// The method's parameters: path parameters in `parameterOrder` first,
+/// then the rest by name, then the document-wide ones. Those the base URL
+/// fills in are left out.
+fn parameters<'a>(api: &Api, method: &'a Method, globals: &[(&'a String, &'a Value)]) -> Vec<(&'a String, &'a Value)> {
+    let own = method.spec["parameters"].as_object();
+    let order: Vec<&str> = method.spec["parameterOrder"]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(Value::as_str)
+        .collect();
+    let mut params: Vec<(&String, &Value)> =
+        own.into_iter().flatten().filter(|(name, _)| !api.fixed.contains(&name.as_str())).collect();
+    params.sort_by_key(|(name, _)| (order.iter().position(|o| o == name).unwrap_or(usize::MAX), (*name).clone()));
+    params.extend(globals.iter().copied().filter(|(name, _)| own.is_none_or(|o| !o.contains_key(*name))));
+    params
+}
+
+fn params_struct(api: &Api, method: &Method, globals: &[(&String, &Value)]) -> String {
+    let mut out = String::new();
+    out.push('\n');
+    doc_comment(&mut out, "    ", method.spec["description"].as_str().unwrap_or_default());
+    out.push_str("    #[derive(Debug, Clone, Default, Deserialize)]\n    #[serde(deny_unknown_fields)]\n");
+    writeln!(out, "    pub struct {}Params {{", pascal(&method.action)).unwrap();
+    for (name, param) in parameters(api, method, globals) {
+        let mut description = param["description"].as_str().unwrap_or_default().to_string();
+        if let Some(values) = param["enum"].as_array() {
+            let values: Vec<String> = values.iter().filter_map(Value::as_str).map(|v| format!("`{v}`")).collect();
+            write!(description, "\nOne of {}.", values.join(", ")).unwrap();
+        }
+        doc_comment(&mut out, "        ", &description);
+        let (ty, attr) = field_type(param);
+        if let Some(attr) = attr {
+            writeln!(out, "        {attr}").unwrap();
+        }
+        writeln!(out, "        pub {}: {ty},", field(name)).unwrap();
+    }
+    if method.spec.get("request").is_some() {
+        let schema = method.spec["request"]["$ref"].as_str().unwrap_or("request");
+        writeln!(out, "        /// The {schema} to send").unwrap();
+        out.push_str("        #[serde(default)]\n        pub body: Value,\n");
+    }
+    if method.media_only() {
+        out.push_str("        /// Local file to save the content to\n        pub download_to: String,\n");
+    } else if method.downloads() {
+        out.push_str("        /// Local file to save the content to, instead of returning the metadata\n");
+        out.push_str("        pub download_to: Option<String>,\n");
+    }
+    if method.upload_path().is_some() {
+        out.push_str("        /// Local file to send as the content\n        pub upload_from: Option<String>,\n");
+        out.push_str("        /// MIME type of `upload_from`, `application/octet-stream` if left out\n");
+        out.push_str("        pub upload_mime_type: Option<String>,\n");
+    }
+    out.push_str("    }\n");
+    out
+}
+
+/// The action's entry in `ACTIONS`, describing its arguments for `exec`
+/// and `vgoog describe`
+fn action(api: &Api, method: &Method, globals: &[(&String, &Value)], items: Option<&str>) -> String {
+    let mut out = String::new();
+    let description = method.spec["description"].as_str().unwrap_or_default();
+    writeln!(out, "        Action::new(\n            \"{}\",\n            {description:?},\n            &[", method.action).unwrap();
+    for (name, param) in parameters(api, method, globals) {
+        let kind = match (param["type"].as_str(), param["repeated"] == Value::Bool(true)) {
+            (Some("string") | None, true) => "Strings",
+            (_, true) => "Array",
+            (Some("boolean"), _) => "Boolean",
+            (Some("integer"), _) => "Integer",
+            (Some("number"), _) => "Number",
+            _ => "String",
+        };
+        let description = param["description"].as_str().unwrap_or_default();
+        write!(out, "                Arg::new(\"{}\", ArgType::{kind}, {description:?})", snake(name)).unwrap();
+        if param["required"] == Value::Bool(true) {
+            out.push_str(".required()");
+        }
+        if let Some(values) = param["enum"].as_array() {
+            let values: Vec<String> = values.iter().filter_map(Value::as_str).map(|v| format!("{v:?}")).collect();
+            write!(out, ".one_of(&[{}])", values.join(", ")).unwrap();
+        }
+        out.push_str(",\n");
+    }
+    if method.spec.get("request").is_some() {
+        let schema = method.spec["request"]["$ref"].as_str().unwrap_or("request");
+        writeln!(out, "                Arg::object(\"body\", \"The {schema} to send\"),").unwrap();
+    }
+    if method.media_only() {
+        out.push_str("                Arg::string(\"download_to\", \"Local file to save the content to\").required(),\n");
+    } else if method.downloads() {
+        out.push_str("                Arg::string(\"download_to\", \"Local file to save the content to, instead of returning the metadata\"),\n");
+    }
+    if method.upload_path().is_some() {
+        out.push_str("                Arg::string(\"upload_from\", \"Local file to send as the content\"),\n");
+        out.push_str("                Arg::string(\"upload_mime_type\", \"MIME type of `upload_from`\").default(Literal::Str(\"application/octet-stream\")),\n");
+    }
+    match items {
+        Some(field) => writeln!(out, "            ],\n        )\n        .items({field:?}),").unwrap(),
+        None => out.push_str("            ],\n        ),\n"),
+    }
+    out
+}
+
+fn field_type(param: &Value) -> (String, Option<&'static str>) {
+    let scalar = match (param["type"].as_str(), param["format"].as_str()) {
+        (Some("boolean"), _) => "bool",
+        (Some("integer"), Some("uint32")) => "u32",
+        (Some("integer"), _) => "i32",
+        (Some("number"), _) => "f64",
+        _ => "String",
+    };
+    if param["repeated"] == Value::Bool(true) {
+        (format!("Vec<{scalar}>"), Some("#[serde(default)]"))
+    } else if param["required"] == Value::Bool(true) {
+        (scalar.to_string(), None)
+    } else {
+        (format!("Option<{scalar}>"), None)
+    }
+}
+
+/// `path` as a `format!` template plus its arguments: `{name}` is
+/// percent-encoded, `{+name}` inserted as is
+fn url_template(path: &str) -> (String, String) {
+    let mut template = String::new();
+    let mut args = String::new();
+    let mut rest = path;
+    while let Some(start) = rest.find('{') {
+        let end = rest[start..].find('}').expect("closed path parameter") + start;
+        template.push_str(&rest[..start]);
+        template.push_str("{}");
+        let name = &rest[start + 1..end];
+        match name.strip_prefix('+') {
+            Some(name) => write!(args, ", params.{}", field(name)).unwrap(),
+            None => write!(args, ", urlencoding::encode(&params.{})", field(name)).unwrap(),
+        }
+        rest = &rest[end + 1..];
+    }
+    template.push_str(rest);
+    (template, args)
+}
+
+/// Statements filling `query` from the method's query parameters
+fn query_code(api: &Api, method: &Method, globals: &[(&String, &Value)]) -> String {
+    let mut out = String::from("            let mut query: Vec<String> = Vec::new();\n");
+    for (name, param) in parameters(api, method, globals) {
+        if param["location"] != "query" {
+            continue;
+        }
+        let field = field(name);
+        let push = format!("query.push(format!(\"{name}={{}}\", urlencoding::encode(&value.to_string())));");
+        if param["repeated"] == Value::Bool(true) {
+            writeln!(out, "            for value in &params.{field} {{\n                {push}\n            }}").unwrap();
+        } else if param["required"] == Value::Bool(true) {
+            writeln!(out, "            let value = &params.{field};\n            {push}").unwrap();
+        } else {
+            writeln!(out, "            if let Some(value) = &params.{field} {{\n                {push}\n            }}").unwrap();
+        }
+    }
+    out
+}
+
+fn wrapper(api: &Api, doc: &Value, method: &Method, globals: &[(&String, &Value)]) -> String {
+    let full_path = format!("{}{}", doc["servicePath"].as_str().unwrap_or_default(), method.spec["path"].as_str().unwrap());
+    let (template, args) = url_template(under(api, &full_path, api.base_path));
+    let description = method.spec["description"].as_str().unwrap_or_default();
+    let params = format!("{}Params", pascal(&method.action));
+    let http_method = method.spec["httpMethod"].as_str().unwrap();
+
+    let mut out = String::new();
+    if !method.media_only() {
+        out.push('\n');
+        doc_comment(&mut out, "        ", description);
+        writeln!(out, "        pub async fn {}(&self, params: &{params}) -> Result<Value> {{", method.action).unwrap();
+        writeln!(out, "            let url = format!(\"{{}}{template}\", self.base{args});").unwrap();
+        out.push_str(&query_code(api, method, globals));
+        out.push_str("            let url = super::with_query(url, &query);\n");
+        let has_body = method.spec.get("request").is_some();
+        let call = match (http_method, has_body) {
+            ("GET", _) => "get(&url)",
+            ("DELETE", _) => "delete(&url)",
+            ("POST", true) => "post(&url, &super::body(&params.body))",
+            ("POST", false) => "post_empty(&url)",
+            ("PUT", true) => "put(&url, &super::body(&params.body))",
+            ("PUT", false) => "put(&url, &super::body(&Value::Null))",
+            ("PATCH", true) => "patch(&url, &super::body(&params.body))",
+            ("PATCH", false) => "patch(&url, &super::body(&Value::Null))",
+            (other, _) => panic!("{}: unsupported HTTP method {other}", method.action),
+        };
+        writeln!(out, "            self.client.{call}.await\n        }}").unwrap();
+    }
+
+    if method.downloads() {
+        out.push('\n');
+        doc_comment(&mut out, "        ", description);
+        out.push_str("        ///\n        /// Streams the content to `path` and returns its size in bytes.\n");
+        writeln!(
+            out,
+            "        pub async fn {}_media(&self, params: &{params}, path: &Path, progress: Option<&ProgressFn<'_>>) -> Result<u64> {{",
+            method.action
+        )
+        .unwrap();
+        writeln!(out, "            let url = format!(\"{{}}{template}\", self.base{args});").unwrap();
+        out.push_str(&query_code(api, method, globals));
+        if !method.media_only() {
+            out.push_str("            query.push(\"alt=media\".to_string());\n");
+        }
+        out.push_str("            let url = super::with_query(url, &query);\n");
+        out.push_str("            self.client.download_to_file(&url, path, progress).await\n        }\n");
+    }
+
+    if let (Some(upload_path), Some((_, upload_base))) = (method.upload_path(), api.upload) {
+        let (template, args) = url_template(under(api, upload_path, upload_base));
+        out.push('\n');
+        doc_comment(&mut out, "        ", description);
+        out.push_str("        ///\n        /// Sends `path` as the content with the resumable protocol, `params.body` as the metadata.\n");
+        writeln!(
+            out,
+            "        pub async fn {}_upload(\n            &self,\n            params: &{params},\n            path: &Path,\n            mime_type: &str,\n            progress: Option<&ProgressFn<'_>>,\n        ) -> Result<Value> {{",
+            method.action
+        )
+        .unwrap();
+        writeln!(out, "            let url = format!(\"{{}}{template}\", self.upload_base{args});").unwrap();
+        out.push_str(&query_code(api, method, globals));
+        out.push_str("            query.push(\"uploadType=resumable\".to_string());\n");
+        out.push_str("            let url = super::with_query(url, &query);\n");
+        writeln!(
+            out,
+            "            self.client\n                .upload_resumable(reqwest::Method::{http_method}, &url, &super::body(&params.body), path, mime_type, progress)\n                .await\n        }}"
+        )
+        .unwrap();
+    }
+    out
+}
+
+/// What follows `base` in `path`: empty, or starting with `/` or `:`
+fn under<'a>(api: &Api, path: &'a str, base: &str) -> &'a str {
+    match path.strip_prefix(base) {
+        Some(rest) if rest.is_empty() || rest.starts_with(['/', ':']) => rest,
+        _ => panic!("{}: {path} is not under {base}", api.file()),
+    }
+}
+
+/// The action's arm in `execute`: media actions pick their wrapper by
+/// whether `download_to` or `upload_from` is given
+fn dispatch(method: &Method) -> String {
+    let action = &method.action;
+    let params = format!("{}Params", pascal(action));
+    if method.media_only() {
+        return format!(
+            "            \"{action}\" => {{\n                let params: {params} = super::params(action, args)?;\n                let size = api.{action}_media(&params, Path::new(&params.download_to), None).await?;\n                Ok(super::saved(&params.download_to, size))\n            }}\n"
+        );
+    }
+    if method.downloads() {
+        return format!(
+            "            \"{action}\" => {{\n                let params: {params} = super::params(action, args)?;\n                match &params.download_to {{\n                    Some(path) => Ok(super::saved(path, api.{action}_media(&params, Path::new(path), None).await?)),\n                    None => api.{action}(&params).await,\n                }}\n            }}\n"
+        );
+    }
+    if method.upload_path().is_some() {
+        return format!(
+            "            \"{action}\" => {{\n                let params: {params} = super::params(action, args)?;\n                match &params.upload_from {{\n                    Some(path) => {{\n                        let mime_type = params.upload_mime_type.as_deref().unwrap_or(super::OCTET_STREAM);\n                        api.{action}_upload(&params, Path::new(path), mime_type, None).await\n                    }}\n                    None => api.{action}(&params).await,\n                }}\n            }}\n"
+        );
+    }
+    format!("            \"{action}\" => api.{action}(&super::params(action, args)?).await,\n")
+}
+
+/// Discovery prose as a doc comment. Lines are unindented and code
+/// fences defused, so nothing in it becomes a doctest.
+fn doc_comment(out: &mut String, indent: &str, text: &str) {
+    for line in text.lines().map(|line| line.trim().replace("```", "`` `")) {
+        if line.is_empty() {
+            writeln!(out, "{indent}///").unwrap();
+        } else {
+            writeln!(out, "{indent}/// {line}").unwrap();
+        }
+    }
+}
+
+fn snake(name: &str) -> String {
+    let mut out = String::new();
+    for (i, c) in name.chars().enumerate() {
+        if c.is_ascii_uppercase() {
+            if i > 0 {
+                out.push('_');
+            }
+            out.push(c.to_ascii_lowercase());
+        } else if c.is_ascii_alphanumeric() {
+            out.push(c);
+        } else {
+            out.push('_');
+        }
+    }
+    out
+}
+
+fn pascal(snake: &str) -> String {
+    snake
+        .split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map(|c| c.to_ascii_uppercase().to_string() + chars.as_str()).unwrap_or_default()
+        })
+        .collect()
+}
+
+/// A parameter's field name, raw when it is a keyword
+fn field(name: &str) -> String {
+    let name = snake(name);
+    if KEYWORDS.contains(&name.as_str()) {
+        format!("r#{name}")
+    } else {
+        name
+    }
+}
@@ -0,0 +1,299 @@
+//! Declarative descriptions of `vgoog exec` actions: each action's
+//! arguments with their types, defaults and whether they are required.
+//! `exec` checks arguments against them and fills in defaults before
+//! dispatching, so the per-service dispatchers hold no defaults of their
+//! own, and `vgoog describe` turns them into JSON Schema.
+//!
+//! The hand-written actions are declared next to their dispatcher in
+//! `cli/<service>.rs`; build.rs declares the generated ones from the
+//! Discovery documents. A hand-written action that a generated one came to
+//! duplicate is retired to an `Alias`, so old calls keep working.
+
+use crate::error::{Result, VgoogError};
+use serde_json::{json, Map, Value};
+
+/// JSON Schema dialect of `Action::schema`
+pub const SCHEMA_DIALECT: &str = "https://json-schema.org/draft/2020-12/schema";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArgType {
+    String,
+    Integer,
+    Number,
+    Boolean,
+    /// An array of strings
+    Strings,
+    /// An array of anything, such as batch requests or rows of values
+    Array,
+    Object,
+}
+
+impl ArgType {
+    /// The JSON Schema `type`
+    pub fn name(self) -> &'static str {
+        match self {
+            ArgType::String => "string",
+            ArgType::Integer => "integer",
+            ArgType::Number => "number",
+            ArgType::Boolean => "boolean",
+            ArgType::Strings | ArgType::Array => "array",
+            ArgType::Object => "object",
+        }
+    }
+
+    /// What a value of this type is called in errors
+    fn expected(self) -> &'static str {
+        match self {
+            ArgType::String => "a string",
+            ArgType::Integer => "an integer",
+            ArgType::Number => "a number",
+            ArgType::Boolean => "a boolean",
+            ArgType::Strings => "an array of strings",
+            ArgType::Array => "an array",
+            ArgType::Object => "an object",
+        }
+    }
+
+    fn accepts(self, value: &Value) -> bool {
+        match self {
+            ArgType::String => value.is_string(),
+            ArgType::Integer => value.is_i64() || value.is_u64(),
+            ArgType::Number => value.is_number(),
+            ArgType::Boolean => value.is_boolean(),
+            ArgType::Strings => value.as_array().is_some_and(|items| items.iter().all(Value::is_string)),
+            ArgType::Array => value.is_array(),
+            ArgType::Object => value.is_object(),
+        }
+    }
+}
+
+/// A default argument value; `Value` cannot be built in a const
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Literal {
+    Str(&'static str),
+    Int(i64),
+    Num(f64),
+    Bool(bool),
+}
+
+impl Literal {
+    pub fn to_value(self) -> Value {
+        match self {
+            Literal::Str(s) => json!(s),
+            Literal::Int(i) => json!(i),
+            Literal::Num(n) => json!(n),
+            Literal::Bool(b) => json!(b),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Arg {
+    pub name: &'static str,
+    pub kind: ArgType,
+    pub required: bool,
+    /// Filled in by `exec` when the argument is left out
+    pub default: Option<Literal>,
+    /// The only values accepted, if limited
+    pub values: &'static [&'static str],
+    pub description: &'static str,
+}
+
+impl Arg {
+    pub const fn new(name: &'static str, kind: ArgType, description: &'static str) -> Self {
+        Self { name, kind, required: false, default: None, values: &[], description }
+    }
+
+    pub const fn string(name: &'static str, description: &'static str) -> Self {
+        Self::new(name, ArgType::String, description)
+    }
+
+    pub const fn integer(name: &'static str, description: &'static str) -> Self {
+        Self::new(name, ArgType::Integer, description)
+    }
+
+    pub const fn number(name: &'static str, description: &'static str) -> Self {
+        Self::new(name, ArgType::Number, description)
+    }
+
+    pub const fn boolean(name: &'static str, description: &'static str) -> Self {
+        Self::new(name, ArgType::Boolean, description)
+    }
+
+    pub const fn strings(name: &'static str, description: &'static str) -> Self {
+        Self::new(name, ArgType::Strings, description)
+    }
+
+    pub const fn array(name: &'static str, description: &'static str) -> Self {
+        Self::new(name, ArgType::Array, description)
+    }
+
+    pub const fn object(name: &'static str, description: &'static str) -> Self {
+        Self::new(name, ArgType::Object, description)
+    }
+
+    pub const fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    pub const fn default(mut self, value: Literal) -> Self {
+        self.default = Some(value);
+        self
+    }
+
+    pub const fn one_of(mut self, values: &'static [&'static str]) -> Self {
+        self.values = values;
+        self
+    }
+
+    /// The argument's JSON Schema
+    pub fn schema(&self) -> Value {
+        let mut schema = json!({ "type": self.kind.name() });
+        if !self.description.is_empty() {
+            schema["description"] = json!(self.description);
+        }
+        if self.kind == ArgType::Strings {
+            schema["items"] = json!({ "type": "string" });
+        }
+        if !self.values.is_empty() {
+            schema["enum"] = json!(self.values);
+        }
+        if let Some(default) = self.default {
+            schema["default"] = default.to_value();
+        }
+        schema
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Action {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub args: &'static [Arg],
+    /// For list actions, the response field holding a page's items
+    pub items: Option<&'static str>,
+}
+
+impl Action {
+    pub const fn new(name: &'static str, description: &'static str, args: &'static [Arg]) -> Self {
+        Self { name, description, args, items: None }
+    }
+
+    /// A list action whose pages carry their items under `field`
+    pub const fn items(mut self, field: &'static str) -> Self {
+        self.items = Some(field);
+        self
+    }
+
+    pub fn arg(&self, name: &str) -> Option<&'static Arg> {
+        self.args.iter().find(|arg| arg.name == name)
+    }
+
+    /// JSON Schema of the arguments object, with `title` set to `title`
+    pub fn schema(&self, title: &str) -> Value {
+        let properties: Map<String, Value> = self.args.iter().map(|arg| (arg.name.to_string(), arg.schema())).collect();
+        let required: Vec<&str> = self.args.iter().filter(|arg| arg.required).map(|arg| arg.name).collect();
+        json!({
+            "$schema": SCHEMA_DIALECT,
+            "title": title,
+            "description": self.description,
+            "type": "object",
+            "properties": properties,
+            "required": required,
+            "additionalProperties": false,
+        })
+    }
+
+    /// `args` checked against the declared arguments, with defaults filled
+    /// in for those left out. A null argument counts as left out.
+    pub fn prepare(&self, args: Value) -> Result<Value> {
+        let invalid = |reason: String| VgoogError::Other(format!("Invalid arguments for {}: {reason}", self.name));
+        let mut given = match args {
+            Value::Null => Map::new(),
+            Value::Object(map) => map,
+            other => return Err(invalid(format!("expected an object, found {}", kind_of(&other)))),
+        };
+        given.retain(|_, value| !value.is_null());
+
+        for (name, value) in &given {
+            let Some(arg) = self.arg(name) else {
+                let expected: Vec<String> = self.args.iter().map(|arg| format!("`{}`", arg.name)).collect();
+                return Err(invalid(if expected.is_empty() {
+                    format!("unknown field `{name}`, it takes no arguments")
+                } else {
+                    format!("unknown field `{name}`, expected one of {}", expected.join(", "))
+                }));
+            };
+            if !arg.kind.accepts(value) {
+                return Err(invalid(format!(
+                    "invalid type for `{name}`: {}, expected {}",
+                    kind_of(value),
+                    arg.kind.expected()
+                )));
+            }
+            if let (false, Some(text)) = (arg.values.is_empty(), value.as_str()) {
+                if !arg.values.contains(&text) {
+                    return Err(invalid(format!(
+                        "invalid value `{text}` for `{name}`, expected one of {}",
+                        arg.values.join(", ")
+                    )));
+                }
+            }
+        }
+
+        for arg in self.args {
+            if given.contains_key(arg.name) {
+                continue;
+            }
+            match arg.default {
+                Some(default) => {
+                    given.insert(arg.name.to_string(), default.to_value());
+                }
+                None if arg.required => return Err(invalid(format!("missing field `{}`", arg.name))),
+                None => {}
+            }
+        }
+        Ok(Value::Object(given))
+    }
+}
+
+/// A retired action name, run as the generated action `target` with its
+/// arguments renamed. Aliases are not listed or described on their own.
+#[derive(Debug, Clone, Copy)]
+pub struct Alias {
+    pub name: &'static str,
+    pub target: &'static str,
+    /// Old argument name → the target's
+    pub renames: &'static [(&'static str, &'static str)],
+}
+
+impl Alias {
+    pub const fn new(name: &'static str, target: &'static str, renames: &'static [(&'static str, &'static str)]) -> Self {
+        Self { name, target, renames }
+    }
+
+    /// `args` under the target's argument names
+    pub fn rename(&self, mut args: Value) -> Value {
+        if let Value::Object(map) = &mut args {
+            for (old, new) in self.renames {
+                if let Some(value) = map.remove(*old) {
+                    map.insert(new.to_string(), value);
+                }
+            }
+        }
+        args
+    }
+}
+
+fn kind_of(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "a boolean",
+        Value::Number(n) if n.is_f64() => "a number",
+        Value::Number(_) => "an integer",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
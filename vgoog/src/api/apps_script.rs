@@ -2,21 +2,23 @@ use crate::client::GoogleClient;
 use crate::error::Result;
 use serde_json::{json, Value};
 
-const BASE: &str = "https://script.googleapis.com/v1";
-
 pub struct AppsScriptApi<'a> {
     client: &'a GoogleClient,
+    base: &'a str,
 }
 
 impl<'a> AppsScriptApi<'a> {
     pub fn new(client: &'a GoogleClient) -> Self {
-        Self { client }
+        Self {
+            client,
+            base: &client.endpoints().script,
+        }
     }
 
     // ── Projects ──
 
     pub async fn create_project(&self, title: &str, parent_id: Option<&str>) -> Result<Value> {
-        let url = format!("{BASE}/projects");
+        let url = format!("{}/projects", self.base);
         let mut body = json!({ "title": title });
         if let Some(pid) = parent_id {
             body["parentId"] = json!(pid);
@@ -25,12 +27,12 @@ impl<'a> AppsScriptApi<'a> {
     }
 
     pub async fn get_project(&self, script_id: &str) -> Result<Value> {
-        let url = format!("{BASE}/projects/{script_id}");
+        let url = format!("{}/projects/{script_id}", self.base);
         self.client.get(&url).await
     }
 
     pub async fn get_content(&self, script_id: &str, version: Option<i64>) -> Result<Value> {
-        let mut url = format!("{BASE}/projects/{script_id}/content");
+        let mut url = format!("{}/projects/{script_id}/content", self.base);
         if let Some(v) = version {
             url.push_str(&format!("?versionNumber={v}"));
         }
@@ -38,7 +40,7 @@ impl<'a> AppsScriptApi<'a> {
     }
 
     pub async fn update_content(&self, script_id: &str, files: &[Value]) -> Result<Value> {
-        let url = format!("{BASE}/projects/{script_id}/content");
+        let url = format!("{}/projects/{script_id}/content", self.base);
         self.client
             .put(&url, &json!({ "scriptId": script_id, "files": files }))
             .await
@@ -49,7 +51,7 @@ impl<'a> AppsScriptApi<'a> {
         script_id: &str,
         filter: Option<&Value>,
     ) -> Result<Value> {
-        let mut url = format!("{BASE}/projects/{script_id}/metrics");
+        let mut url = format!("{}/projects/{script_id}/metrics", self.base);
         if let Some(f) = filter {
             if let Some(deployment_id) = f.get("deploymentId").and_then(|v| v.as_str()) {
                 url.push_str(&format!("?metricsFilter.deploymentId={deployment_id}"));
@@ -66,7 +68,7 @@ impl<'a> AppsScriptApi<'a> {
         page_size: u32,
         page_token: Option<&str>,
     ) -> Result<Value> {
-        let mut url = format!("{BASE}/projects/{script_id}/versions?pageSize={page_size}");
+        let mut url = format!("{}/projects/{script_id}/versions?pageSize={page_size}", self.base);
         if let Some(pt) = page_token {
             url.push_str(&format!("&pageToken={pt}"));
         }
@@ -78,14 +80,14 @@ impl<'a> AppsScriptApi<'a> {
         script_id: &str,
         description: &str,
     ) -> Result<Value> {
-        let url = format!("{BASE}/projects/{script_id}/versions");
+        let url = format!("{}/projects/{script_id}/versions", self.base);
         self.client
             .post(&url, &json!({ "description": description }))
             .await
     }
 
     pub async fn get_version(&self, script_id: &str, version_number: i64) -> Result<Value> {
-        let url = format!("{BASE}/projects/{script_id}/versions/{version_number}");
+        let url = format!("{}/projects/{script_id}/versions/{version_number}", self.base);
         self.client.get(&url).await
     }
 
@@ -97,7 +99,7 @@ impl<'a> AppsScriptApi<'a> {
         page_size: u32,
         page_token: Option<&str>,
     ) -> Result<Value> {
-        let mut url = format!("{BASE}/projects/{script_id}/deployments?pageSize={page_size}");
+        let mut url = format!("{}/projects/{script_id}/deployments?pageSize={page_size}", self.base);
         if let Some(pt) = page_token {
             url.push_str(&format!("&pageToken={pt}"));
         }
@@ -110,7 +112,7 @@ impl<'a> AppsScriptApi<'a> {
         version_number: i64,
         description: &str,
     ) -> Result<Value> {
-        let url = format!("{BASE}/projects/{script_id}/deployments");
+        let url = format!("{}/projects/{script_id}/deployments", self.base);
         self.client
             .post(
                 &url,
@@ -128,7 +130,7 @@ impl<'a> AppsScriptApi<'a> {
         script_id: &str,
         deployment_id: &str,
     ) -> Result<Value> {
-        let url = format!("{BASE}/projects/{script_id}/deployments/{deployment_id}");
+        let url = format!("{}/projects/{script_id}/deployments/{deployment_id}", self.base);
         self.client.get(&url).await
     }
 
@@ -139,7 +141,7 @@ impl<'a> AppsScriptApi<'a> {
         version_number: i64,
         description: &str,
     ) -> Result<Value> {
-        let url = format!("{BASE}/projects/{script_id}/deployments/{deployment_id}");
+        let url = format!("{}/projects/{script_id}/deployments/{deployment_id}", self.base);
         self.client
             .put(
                 &url,
@@ -159,7 +161,7 @@ impl<'a> AppsScriptApi<'a> {
         script_id: &str,
         deployment_id: &str,
     ) -> Result<Value> {
-        let url = format!("{BASE}/projects/{script_id}/deployments/{deployment_id}");
+        let url = format!("{}/projects/{script_id}/deployments/{deployment_id}", self.base);
         self.client.delete(&url).await
     }
 
@@ -172,7 +174,7 @@ impl<'a> AppsScriptApi<'a> {
         parameters: Option<&[Value]>,
         dev_mode: bool,
     ) -> Result<Value> {
-        let url = format!("{BASE}/scripts/{script_id}:run");
+        let url = format!("{}/scripts/{script_id}:run", self.base);
         let mut body = json!({
             "function": function_name,
             "devMode": dev_mode,
@@ -190,7 +192,7 @@ impl<'a> AppsScriptApi<'a> {
         page_size: u32,
         page_token: Option<&str>,
     ) -> Result<Value> {
-        let mut url = format!("{BASE}/processes?pageSize={page_size}");
+        let mut url = format!("{}/processes?pageSize={page_size}", self.base);
         if let Some(pt) = page_token {
             url.push_str(&format!("&pageToken={pt}"));
         }
@@ -204,7 +206,8 @@ impl<'a> AppsScriptApi<'a> {
         page_token: Option<&str>,
     ) -> Result<Value> {
         let mut url = format!(
-            "{BASE}/processes:listScriptProcesses?pageSize={page_size}&scriptId={script_id}"
+            "{}/processes:listScriptProcesses?pageSize={page_size}&scriptId={script_id}",
+            self.base
         );
         if let Some(pt) = page_token {
             url.push_str(&format!("&pageToken={pt}"));
@@ -1,22 +1,28 @@
+use crate::api::models::{self, Calendar, Event, Page};
+use crate::batch::BatchRequest;
 use crate::client::GoogleClient;
 use crate::error::Result;
 use serde_json::{json, Value};
 
-const BASE: &str = "https://www.googleapis.com/calendar/v3";
-
 pub struct CalendarApi<'a> {
     client: &'a GoogleClient,
+    base: &'a str,
+    batch: &'a str,
 }
 
 impl<'a> CalendarApi<'a> {
     pub fn new(client: &'a GoogleClient) -> Self {
-        Self { client }
+        Self {
+            client,
+            base: &client.endpoints().calendar,
+            batch: &client.endpoints().calendar_batch,
+        }
     }
 
     // ── Calendar List ──
 
     pub async fn list_calendars(&self, page_token: Option<&str>) -> Result<Value> {
-        let mut url = format!("{BASE}/users/me/calendarList");
+        let mut url = format!("{}/users/me/calendarList", self.base);
         if let Some(pt) = page_token {
             url.push_str(&format!("?pageToken={pt}"));
         }
@@ -25,31 +31,31 @@ impl<'a> CalendarApi<'a> {
 
     pub async fn get_calendar(&self, id: &str) -> Result<Value> {
         let id = urlencoding::encode(id);
-        let url = format!("{BASE}/users/me/calendarList/{id}");
+        let url = format!("{}/users/me/calendarList/{id}", self.base);
         self.client.get(&url).await
     }
 
     pub async fn insert_calendar_to_list(&self, id: &str) -> Result<Value> {
-        let url = format!("{BASE}/users/me/calendarList");
+        let url = format!("{}/users/me/calendarList", self.base);
         self.client.post(&url, &json!({ "id": id })).await
     }
 
     pub async fn update_calendar_in_list(&self, id: &str, updates: &Value) -> Result<Value> {
         let id = urlencoding::encode(id);
-        let url = format!("{BASE}/users/me/calendarList/{id}");
+        let url = format!("{}/users/me/calendarList/{id}", self.base);
         self.client.patch(&url, updates).await
     }
 
     pub async fn remove_calendar_from_list(&self, id: &str) -> Result<Value> {
         let id = urlencoding::encode(id);
-        let url = format!("{BASE}/users/me/calendarList/{id}");
+        let url = format!("{}/users/me/calendarList/{id}", self.base);
         self.client.delete(&url).await
     }
 
     // ── Calendars ──
 
     pub async fn create_calendar(&self, summary: &str) -> Result<Value> {
-        let url = format!("{BASE}/calendars");
+        let url = format!("{}/calendars", self.base);
         self.client
             .post(&url, &json!({ "summary": summary }))
             .await
@@ -57,30 +63,31 @@ impl<'a> CalendarApi<'a> {
 
     pub async fn get_calendar_metadata(&self, id: &str) -> Result<Value> {
         let id = urlencoding::encode(id);
-        let url = format!("{BASE}/calendars/{id}");
+        let url = format!("{}/calendars/{id}", self.base);
         self.client.get(&url).await
     }
 
     pub async fn update_calendar_metadata(&self, id: &str, updates: &Value) -> Result<Value> {
         let id = urlencoding::encode(id);
-        let url = format!("{BASE}/calendars/{id}");
+        let url = format!("{}/calendars/{id}", self.base);
         self.client.patch(&url, updates).await
     }
 
     pub async fn delete_calendar(&self, id: &str) -> Result<Value> {
         let id = urlencoding::encode(id);
-        let url = format!("{BASE}/calendars/{id}");
+        let url = format!("{}/calendars/{id}", self.base);
         self.client.delete(&url).await
     }
 
     pub async fn clear_calendar(&self, id: &str) -> Result<Value> {
         let id = urlencoding::encode(id);
-        let url = format!("{BASE}/calendars/{id}/clear");
-        self.client.post_empty(&url).await
+        let url = format!("{}/calendars/{id}/clear", self.base);
+        self.client.post_empty_idempotent(&url).await
     }
 
     // ── Events ──
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn list_events(
         &self,
         calendar_id: &str,
@@ -94,7 +101,8 @@ impl<'a> CalendarApi<'a> {
     ) -> Result<Value> {
         let cal = urlencoding::encode(calendar_id);
         let mut url = format!(
-            "{BASE}/calendars/{cal}/events?maxResults={max_results}&singleEvents={single_events}"
+            "{}/calendars/{cal}/events?maxResults={max_results}&singleEvents={single_events}",
+            self.base
         );
         if let Some(t) = time_min {
             url.push_str(&format!("&timeMin={}", urlencoding::encode(t)));
@@ -116,31 +124,34 @@ impl<'a> CalendarApi<'a> {
 
     pub async fn get_event(&self, calendar_id: &str, event_id: &str) -> Result<Value> {
         let cal = urlencoding::encode(calendar_id);
-        let url = format!("{BASE}/calendars/{cal}/events/{event_id}");
+        let url = format!("{}/calendars/{cal}/events/{event_id}", self.base);
         self.client.get(&url).await
     }
 
     pub async fn create_event(&self, calendar_id: &str, event: &Value) -> Result<Value> {
         let cal = urlencoding::encode(calendar_id);
-        let url = format!("{BASE}/calendars/{cal}/events");
+        let url = format!("{}/calendars/{cal}/events", self.base);
         self.client.post(&url, event).await
     }
 
-    pub async fn update_event(
-        &self,
-        calendar_id: &str,
-        event_id: &str,
-        event: &Value,
-    ) -> Result<Value> {
+    pub async fn delete_event(&self, calendar_id: &str, event_id: &str) -> Result<Value> {
         let cal = urlencoding::encode(calendar_id);
-        let url = format!("{BASE}/calendars/{cal}/events/{event_id}");
-        self.client.patch(&url, event).await
+        let url = format!("{}/calendars/{cal}/events/{event_id}", self.base);
+        self.client.delete(&url).await
     }
 
-    pub async fn delete_event(&self, calendar_id: &str, event_id: &str) -> Result<Value> {
+    /// Delete many events in batch calls; one result per id, in order
+    pub async fn batch_delete_events(
+        &self,
+        calendar_id: &str,
+        event_ids: &[&str],
+    ) -> Result<Vec<Result<Value>>> {
         let cal = urlencoding::encode(calendar_id);
-        let url = format!("{BASE}/calendars/{cal}/events/{event_id}");
-        self.client.delete(&url).await
+        let requests: Vec<BatchRequest> = event_ids
+            .iter()
+            .map(|id| BatchRequest::delete(format!("{}/calendars/{cal}/events/{id}", self.base)))
+            .collect();
+        self.client.batch(self.batch, &requests).await
     }
 
     pub async fn move_event(
@@ -151,14 +162,15 @@ impl<'a> CalendarApi<'a> {
     ) -> Result<Value> {
         let cal = urlencoding::encode(calendar_id);
         let dest = urlencoding::encode(destination);
-        let url = format!("{BASE}/calendars/{cal}/events/{event_id}/move?destination={dest}");
-        self.client.post_empty(&url).await
+        let url = format!("{}/calendars/{cal}/events/{event_id}/move?destination={dest}", self.base);
+        self.client.post_empty_idempotent(&url).await
     }
 
     pub async fn quick_add_event(&self, calendar_id: &str, text: &str) -> Result<Value> {
         let cal = urlencoding::encode(calendar_id);
         let url = format!(
-            "{BASE}/calendars/{cal}/events/quickAdd?text={}",
+            "{}/calendars/{cal}/events/quickAdd?text={}",
+            self.base,
             urlencoding::encode(text)
         );
         self.client.post_empty(&url).await
@@ -173,7 +185,8 @@ impl<'a> CalendarApi<'a> {
     ) -> Result<Value> {
         let cal = urlencoding::encode(calendar_id);
         let mut url = format!(
-            "{BASE}/calendars/{cal}/events/{event_id}/instances?maxResults={max_results}"
+            "{}/calendars/{cal}/events/{event_id}/instances?maxResults={max_results}",
+            self.base
         );
         if let Some(pt) = page_token {
             url.push_str(&format!("&pageToken={pt}"));
@@ -183,15 +196,18 @@ impl<'a> CalendarApi<'a> {
 
     // ── ACL ──
 
-    pub async fn list_acl(&self, calendar_id: &str) -> Result<Value> {
+    pub async fn list_acl(&self, calendar_id: &str, page_token: Option<&str>) -> Result<Value> {
         let cal = urlencoding::encode(calendar_id);
-        let url = format!("{BASE}/calendars/{cal}/acl");
+        let mut url = format!("{}/calendars/{cal}/acl", self.base);
+        if let Some(pt) = page_token {
+            url.push_str(&format!("?pageToken={pt}"));
+        }
         self.client.get(&url).await
     }
 
     pub async fn insert_acl_rule(&self, calendar_id: &str, rule: &Value) -> Result<Value> {
         let cal = urlencoding::encode(calendar_id);
-        let url = format!("{BASE}/calendars/{cal}/acl");
+        let url = format!("{}/calendars/{cal}/acl", self.base);
         self.client.post(&url, rule).await
     }
 
@@ -202,39 +218,78 @@ impl<'a> CalendarApi<'a> {
         rule: &Value,
     ) -> Result<Value> {
         let cal = urlencoding::encode(calendar_id);
-        let url = format!("{BASE}/calendars/{cal}/acl/{rule_id}");
+        let url = format!("{}/calendars/{cal}/acl/{rule_id}", self.base);
         self.client.put(&url, rule).await
     }
 
     pub async fn delete_acl_rule(&self, calendar_id: &str, rule_id: &str) -> Result<Value> {
         let cal = urlencoding::encode(calendar_id);
-        let url = format!("{BASE}/calendars/{cal}/acl/{rule_id}");
+        let url = format!("{}/calendars/{cal}/acl/{rule_id}", self.base);
         self.client.delete(&url).await
     }
 
     // ── Settings ──
 
-    pub async fn list_settings(&self) -> Result<Value> {
-        let url = format!("{BASE}/users/me/settings");
+    pub async fn list_settings(&self, page_token: Option<&str>) -> Result<Value> {
+        let mut url = format!("{}/users/me/settings", self.base);
+        if let Some(pt) = page_token {
+            url.push_str(&format!("?pageToken={pt}"));
+        }
         self.client.get(&url).await
     }
 
     pub async fn get_setting(&self, setting: &str) -> Result<Value> {
-        let url = format!("{BASE}/users/me/settings/{setting}");
+        let url = format!("{}/users/me/settings/{setting}", self.base);
         self.client.get(&url).await
     }
 
     // ── Colors ──
 
     pub async fn get_colors(&self) -> Result<Value> {
-        let url = format!("{BASE}/colors");
+        let url = format!("{}/colors", self.base);
         self.client.get(&url).await
     }
 
     // ── Free/Busy ──
 
     pub async fn query_free_busy(&self, body: &Value) -> Result<Value> {
-        let url = format!("{BASE}/freeBusy");
-        self.client.post(&url, body).await
+        let url = format!("{}/freeBusy", self.base);
+        self.client.post_idempotent(&url, body).await
+    }
+
+    // ── Typed ──
+
+    pub async fn calendars(&self, page_token: Option<&str>) -> Result<Page<Calendar>> {
+        Page::parse(self.list_calendars(page_token).await?, "items")
+    }
+
+    pub async fn calendar(&self, id: &str) -> Result<Calendar> {
+        models::parse(self.get_calendar(id).await?)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn events(
+        &self,
+        calendar_id: &str,
+        time_min: Option<&str>,
+        time_max: Option<&str>,
+        query: Option<&str>,
+        max_results: u32,
+        page_token: Option<&str>,
+        single_events: bool,
+        order_by: Option<&str>,
+    ) -> Result<Page<Event>> {
+        let page = self
+            .list_events(calendar_id, time_min, time_max, query, max_results, page_token, single_events, order_by)
+            .await?;
+        Page::parse(page, "items")
+    }
+
+    pub async fn event(&self, calendar_id: &str, event_id: &str) -> Result<Event> {
+        models::parse(self.get_event(calendar_id, event_id).await?)
+    }
+
+    pub async fn insert_event(&self, calendar_id: &str, event: &Event) -> Result<Event> {
+        models::parse(self.create_event(calendar_id, &models::request_body(event)?).await?)
     }
 }
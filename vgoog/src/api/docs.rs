@@ -2,26 +2,28 @@ use crate::client::GoogleClient;
 use crate::error::Result;
 use serde_json::{json, Value};
 
-const BASE: &str = "https://docs.googleapis.com/v1/documents";
-
 pub struct DocsApi<'a> {
     client: &'a GoogleClient,
+    base: &'a str,
 }
 
 impl<'a> DocsApi<'a> {
     pub fn new(client: &'a GoogleClient) -> Self {
-        Self { client }
+        Self {
+            client,
+            base: &client.endpoints().docs,
+        }
     }
 
     pub async fn create_document(&self, title: &str) -> Result<Value> {
-        let url = BASE.to_string();
+        let url = self.base.to_string();
         self.client
             .post(&url, &json!({ "title": title }))
             .await
     }
 
     pub async fn get_document(&self, document_id: &str) -> Result<Value> {
-        let url = format!("{BASE}/{document_id}");
+        let url = format!("{}/{document_id}", self.base);
         self.client.get(&url).await
     }
 
@@ -30,7 +32,7 @@ impl<'a> DocsApi<'a> {
         document_id: &str,
         requests: &[Value],
     ) -> Result<Value> {
-        let url = format!("{BASE}/{document_id}:batchUpdate");
+        let url = format!("{}/{document_id}:batchUpdate", self.base);
         self.client
             .post(&url, &json!({ "requests": requests }))
             .await
@@ -120,6 +122,7 @@ impl<'a> DocsApi<'a> {
         .await
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn update_text_style(
         &self,
         document_id: &str,
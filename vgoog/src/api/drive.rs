@@ -1,17 +1,25 @@
+use crate::api::generated::drive;
+use crate::api::models::{self, File, Page, Permission, FILE_FIELDS};
+use crate::batch::BatchRequest;
 use crate::client::GoogleClient;
-use crate::error::Result;
+use crate::error::{Result, VgoogError};
+use crate::transfer::{self, ProgressFn};
 use serde_json::{json, Value};
-
-const BASE: &str = "https://www.googleapis.com/drive/v3";
-const UPLOAD_BASE: &str = "https://www.googleapis.com/upload/drive/v3";
+use std::path::Path;
 
 pub struct DriveApi<'a> {
     client: &'a GoogleClient,
+    base: &'a str,
+    batch: &'a str,
 }
 
 impl<'a> DriveApi<'a> {
     pub fn new(client: &'a GoogleClient) -> Self {
-        Self { client }
+        Self {
+            client,
+            base: &client.endpoints().drive,
+            batch: &client.endpoints().drive_batch,
+        }
     }
 
     // ── Files ──
@@ -26,7 +34,8 @@ impl<'a> DriveApi<'a> {
         spaces: Option<&str>,
     ) -> Result<Value> {
         let mut url = format!(
-            "{BASE}/files?pageSize={page_size}&fields={}",
+            "{}/files?pageSize={page_size}&fields={}",
+            self.base,
             urlencoding::encode(fields.unwrap_or("nextPageToken,files(id,name,mimeType,size,modifiedTime,parents,webViewLink,iconLink)"))
         );
         if let Some(q) = query {
@@ -48,79 +57,91 @@ impl<'a> DriveApi<'a> {
         let f = urlencoding::encode(
             fields.unwrap_or("id,name,mimeType,size,modifiedTime,parents,webViewLink,description,starred,trashed"),
         );
-        let url = format!("{BASE}/files/{file_id}?fields={f}");
+        let url = format!("{}/files/{file_id}?fields={f}", self.base);
         self.client.get(&url).await
     }
 
     pub async fn create_file(&self, metadata: &Value) -> Result<Value> {
-        let url = format!("{BASE}/files");
+        let url = format!("{}/files", self.base);
         self.client.post(&url, metadata).await
     }
 
+    /// Upload a new file from disk in resumable chunks
     pub async fn upload_file(
         &self,
         metadata: &Value,
-        content: Vec<u8>,
+        path: &Path,
         mime_type: &str,
+        progress: Option<&ProgressFn<'_>>,
     ) -> Result<Value> {
-        let url = format!("{UPLOAD_BASE}/files?uploadType=multipart");
-        self.client
-            .upload_multipart(&url, metadata, content, mime_type)
-            .await
-    }
-
-    pub async fn update_file_metadata(
-        &self,
-        file_id: &str,
-        metadata: &Value,
-    ) -> Result<Value> {
-        let url = format!("{BASE}/files/{file_id}");
-        self.client.patch(&url, metadata).await
+        let params = drive::FilesCreateParams { body: metadata.clone(), ..Default::default() };
+        drive::Api::new(self.client).files_create_upload(&params, path, mime_type, progress).await
     }
 
+    /// Replace a file's content from disk in resumable chunks
     pub async fn update_file_content(
         &self,
         file_id: &str,
         metadata: &Value,
-        content: Vec<u8>,
+        path: &Path,
         mime_type: &str,
+        progress: Option<&ProgressFn<'_>>,
     ) -> Result<Value> {
-        let url = format!("{UPLOAD_BASE}/files/{file_id}?uploadType=multipart");
-        self.client
-            .upload_multipart(&url, metadata, content, mime_type)
-            .await
+        let params = drive::FilesUpdateParams { file_id: file_id.to_string(), body: metadata.clone(), ..Default::default() };
+        drive::Api::new(self.client).files_update_upload(&params, path, mime_type, progress).await
     }
 
     pub async fn delete_file(&self, file_id: &str) -> Result<Value> {
-        let url = format!("{BASE}/files/{file_id}");
+        let url = format!("{}/files/{file_id}", self.base);
         self.client.delete(&url).await
     }
 
     pub async fn copy_file(&self, file_id: &str, metadata: &Value) -> Result<Value> {
-        let url = format!("{BASE}/files/{file_id}/copy");
+        let url = format!("{}/files/{file_id}/copy", self.base);
         self.client.post(&url, metadata).await
     }
 
-    pub async fn download_file(&self, file_id: &str) -> Result<Vec<u8>> {
-        let url = format!("{BASE}/files/{file_id}?alt=media");
-        self.client.download(&url).await
-    }
-
-    pub async fn export_file(&self, file_id: &str, mime_type: &str) -> Result<Vec<u8>> {
-        let url = format!(
-            "{BASE}/files/{file_id}/export?mimeType={}",
-            urlencoding::encode(mime_type)
-        );
-        self.client.download(&url).await
+    /// Stream a file's content to `path`, resuming a previous partial
+    /// download, and check it against Drive's `md5Checksum`
+    pub async fn download_file_to(
+        &self,
+        file_id: &str,
+        path: &Path,
+        progress: Option<&ProgressFn<'_>>,
+    ) -> Result<Value> {
+        // Never from the cache: a stale md5Checksum would fail a good download
+        let url = format!("{}/files/{file_id}?fields={}", self.base, urlencoding::encode("id,name,size,md5Checksum"));
+        let meta = self.client.get_uncached(&url).await?;
+        let params = drive::FilesGetParams { file_id: file_id.to_string(), ..Default::default() };
+        let size = drive::Api::new(self.client).files_get_media(&params, path, progress).await?;
+
+        let expected = meta.get("md5Checksum").and_then(|v| v.as_str());
+        if let Some(expected) = expected {
+            let actual = transfer::file_md5(path).await?;
+            if actual != expected {
+                tokio::fs::remove_file(path).await?;
+                return Err(VgoogError::Other(format!(
+                    "Checksum mismatch downloading {file_id}: expected {expected}, got {actual}"
+                )));
+            }
+        }
+        Ok(json!({
+            "id": file_id,
+            "name": meta["name"],
+            "path": path.display().to_string(),
+            "size": size,
+            "md5Checksum": expected,
+            "verified": expected.is_some(),
+        }))
     }
 
     pub async fn empty_trash(&self) -> Result<Value> {
-        let url = format!("{BASE}/files/trash");
+        let url = format!("{}/files/trash", self.base);
         self.client.delete(&url).await
     }
 
     pub async fn generate_file_ids(&self, count: u32) -> Result<Value> {
-        let url = format!("{BASE}/files/generateIds?count={count}");
+        let url = format!("{}/files/generateIds?count={count}", self.base);
         self.client.get(&url).await
     }
 
@@ -133,7 +154,8 @@ impl<'a> DriveApi<'a> {
         remove_parents: &str,
     ) -> Result<Value> {
         let url = format!(
-            "{BASE}/files/{file_id}?addParents={}&removeParents={}",
+            "{}/files/{file_id}?addParents={}&removeParents={}",
+            self.base,
             urlencoding::encode(add_parents),
             urlencoding::encode(remove_parents)
         );
@@ -155,15 +177,19 @@ impl<'a> DriveApi<'a> {
 
     // ── Permissions ──
 
-    pub async fn list_permissions(&self, file_id: &str) -> Result<Value> {
-        let url = format!(
-            "{BASE}/files/{file_id}/permissions?fields=permissions(id,type,role,emailAddress,displayName)"
+    pub async fn list_permissions(&self, file_id: &str, page_token: Option<&str>) -> Result<Value> {
+        let mut url = format!(
+            "{}/files/{file_id}/permissions?fields=nextPageToken,permissions(id,type,role,emailAddress,displayName)",
+            self.base
         );
+        if let Some(pt) = page_token {
+            url.push_str(&format!("&pageToken={pt}"));
+        }
         self.client.get(&url).await
     }
 
     pub async fn get_permission(&self, file_id: &str, permission_id: &str) -> Result<Value> {
-        let url = format!("{BASE}/files/{file_id}/permissions/{permission_id}?fields=*");
+        let url = format!("{}/files/{file_id}/permissions/{permission_id}?fields=*", self.base);
         self.client.get(&url).await
     }
 
@@ -174,7 +200,7 @@ impl<'a> DriveApi<'a> {
         perm_type: &str,
         email: Option<&str>,
     ) -> Result<Value> {
-        let url = format!("{BASE}/files/{file_id}/permissions");
+        let url = format!("{}/files/{file_id}/permissions", self.base);
         let mut body = json!({ "role": role, "type": perm_type });
         if let Some(e) = email {
             body["emailAddress"] = json!(e);
@@ -188,19 +214,39 @@ impl<'a> DriveApi<'a> {
         permission_id: &str,
         role: &str,
     ) -> Result<Value> {
-        let url = format!("{BASE}/files/{file_id}/permissions/{permission_id}");
+        let url = format!("{}/files/{file_id}/permissions/{permission_id}", self.base);
         self.client.patch(&url, &json!({ "role": role })).await
     }
 
+    /// Set the same role on many permissions in batch calls; one result per
+    /// permission, in order
+    pub async fn batch_update_permissions(
+        &self,
+        file_id: &str,
+        permission_ids: &[&str],
+        role: &str,
+    ) -> Result<Vec<Result<Value>>> {
+        let requests: Vec<BatchRequest> = permission_ids
+            .iter()
+            .map(|id| {
+                BatchRequest::patch(
+                    format!("{}/files/{file_id}/permissions/{id}", self.base),
+                    json!({ "role": role }),
+                )
+            })
+            .collect();
+        self.client.batch(self.batch, &requests).await
+    }
+
     pub async fn delete_permission(&self, file_id: &str, permission_id: &str) -> Result<Value> {
-        let url = format!("{BASE}/files/{file_id}/permissions/{permission_id}");
+        let url = format!("{}/files/{file_id}/permissions/{permission_id}", self.base);
         self.client.delete(&url).await
     }
 
     // ── Comments ──
 
     pub async fn list_comments(&self, file_id: &str, page_token: Option<&str>) -> Result<Value> {
-        let mut url = format!("{BASE}/files/{file_id}/comments?fields=comments(id,content,author,createdTime,resolved)");
+        let mut url = format!("{}/files/{file_id}/comments?fields=nextPageToken,comments(id,content,author,createdTime,resolved)", self.base);
         if let Some(pt) = page_token {
             url.push_str(&format!("&pageToken={pt}"));
         }
@@ -208,7 +254,7 @@ impl<'a> DriveApi<'a> {
     }
 
     pub async fn create_comment(&self, file_id: &str, content: &str) -> Result<Value> {
-        let url = format!("{BASE}/files/{file_id}/comments?fields=*");
+        let url = format!("{}/files/{file_id}/comments?fields=*", self.base);
         self.client
             .post(&url, &json!({ "content": content }))
             .await
@@ -220,14 +266,14 @@ impl<'a> DriveApi<'a> {
         comment_id: &str,
         content: &str,
     ) -> Result<Value> {
-        let url = format!("{BASE}/files/{file_id}/comments/{comment_id}?fields=*");
+        let url = format!("{}/files/{file_id}/comments/{comment_id}?fields=*", self.base);
         self.client
             .patch(&url, &json!({ "content": content }))
             .await
     }
 
     pub async fn delete_comment(&self, file_id: &str, comment_id: &str) -> Result<Value> {
-        let url = format!("{BASE}/files/{file_id}/comments/{comment_id}");
+        let url = format!("{}/files/{file_id}/comments/{comment_id}", self.base);
         self.client.delete(&url).await
     }
 
@@ -237,10 +283,15 @@ impl<'a> DriveApi<'a> {
         &self,
         file_id: &str,
         comment_id: &str,
+        page_token: Option<&str>,
     ) -> Result<Value> {
-        let url = format!(
-            "{BASE}/files/{file_id}/comments/{comment_id}/replies?fields=replies(id,content,author,createdTime)"
+        let mut url = format!(
+            "{}/files/{file_id}/comments/{comment_id}/replies?fields=nextPageToken,replies(id,content,author,createdTime)",
+            self.base
         );
+        if let Some(pt) = page_token {
+            url.push_str(&format!("&pageToken={pt}"));
+        }
         self.client.get(&url).await
     }
 
@@ -250,7 +301,7 @@ impl<'a> DriveApi<'a> {
         comment_id: &str,
         content: &str,
     ) -> Result<Value> {
-        let url = format!("{BASE}/files/{file_id}/comments/{comment_id}/replies?fields=*");
+        let url = format!("{}/files/{file_id}/comments/{comment_id}/replies?fields=*", self.base);
         self.client
             .post(&url, &json!({ "content": content }))
             .await
@@ -258,31 +309,35 @@ impl<'a> DriveApi<'a> {
 
     // ── Revisions ──
 
-    pub async fn list_revisions(&self, file_id: &str) -> Result<Value> {
-        let url = format!("{BASE}/files/{file_id}/revisions?fields=revisions(id,modifiedTime,size,keepForever)");
+    pub async fn list_revisions(&self, file_id: &str, page_token: Option<&str>) -> Result<Value> {
+        let mut url = format!("{}/files/{file_id}/revisions?fields=nextPageToken,revisions(id,modifiedTime,size,keepForever)", self.base);
+        if let Some(pt) = page_token {
+            url.push_str(&format!("&pageToken={pt}"));
+        }
         self.client.get(&url).await
     }
 
     pub async fn get_revision(&self, file_id: &str, revision_id: &str) -> Result<Value> {
-        let url = format!("{BASE}/files/{file_id}/revisions/{revision_id}?fields=*");
+        let url = format!("{}/files/{file_id}/revisions/{revision_id}?fields=*", self.base);
         self.client.get(&url).await
     }
 
     pub async fn delete_revision(&self, file_id: &str, revision_id: &str) -> Result<Value> {
-        let url = format!("{BASE}/files/{file_id}/revisions/{revision_id}");
+        let url = format!("{}/files/{file_id}/revisions/{revision_id}", self.base);
         self.client.delete(&url).await
     }
 
     // ── Changes ──
 
     pub async fn get_start_page_token(&self) -> Result<Value> {
-        let url = format!("{BASE}/changes/startPageToken");
+        let url = format!("{}/changes/startPageToken", self.base);
         self.client.get(&url).await
     }
 
     pub async fn list_changes(&self, page_token: &str, page_size: u32) -> Result<Value> {
         let url = format!(
-            "{BASE}/changes?pageToken={page_token}&pageSize={page_size}&fields=nextPageToken,newStartPageToken,changes(fileId,removed,file(id,name,mimeType))"
+            "{}/changes?pageToken={page_token}&pageSize={page_size}&fields=nextPageToken,newStartPageToken,changes(fileId,removed,file(id,name,mimeType))",
+            self.base
         );
         self.client.get(&url).await
     }
@@ -290,14 +345,14 @@ impl<'a> DriveApi<'a> {
     // ── About ──
 
     pub async fn get_about(&self) -> Result<Value> {
-        let url = format!("{BASE}/about?fields=user,storageQuota");
+        let url = format!("{}/about?fields=user,storageQuota", self.base);
         self.client.get(&url).await
     }
 
     // ── Shared Drives ──
 
     pub async fn list_shared_drives(&self, page_token: Option<&str>) -> Result<Value> {
-        let mut url = format!("{BASE}/drives?pageSize=100");
+        let mut url = format!("{}/drives?pageSize=100", self.base);
         if let Some(pt) = page_token {
             url.push_str(&format!("&pageToken={pt}"));
         }
@@ -306,12 +361,33 @@ impl<'a> DriveApi<'a> {
 
     pub async fn create_shared_drive(&self, name: &str) -> Result<Value> {
         let request_id = uuid::Uuid::new_v4().to_string();
-        let url = format!("{BASE}/drives?requestId={request_id}");
-        self.client.post(&url, &json!({ "name": name })).await
+        let url = format!("{}/drives?requestId={request_id}", self.base);
+        self.client.post_idempotent(&url, &json!({ "name": name })).await
     }
 
     pub async fn delete_shared_drive(&self, drive_id: &str) -> Result<Value> {
-        let url = format!("{BASE}/drives/{drive_id}");
+        let url = format!("{}/drives/{drive_id}", self.base);
         self.client.delete(&url).await
     }
+
+    // ── Typed ──
+
+    pub async fn files(
+        &self,
+        query: Option<&str>,
+        page_size: u32,
+        page_token: Option<&str>,
+        order_by: Option<&str>,
+    ) -> Result<Page<File>> {
+        let fields = format!("nextPageToken,files({FILE_FIELDS})");
+        Page::parse(self.list_files(query, page_size, page_token, order_by, Some(&fields), None).await?, "files")
+    }
+
+    pub async fn file(&self, file_id: &str) -> Result<File> {
+        models::parse(self.get_file(file_id, Some(FILE_FIELDS)).await?)
+    }
+
+    pub async fn permissions(&self, file_id: &str, page_token: Option<&str>) -> Result<Page<Permission>> {
+        Page::parse(self.list_permissions(file_id, page_token).await?, "permissions")
+    }
 }
@@ -2,21 +2,23 @@ use crate::client::GoogleClient;
 use crate::error::Result;
 use serde_json::{json, Value};
 
-const BASE: &str = "https://forms.googleapis.com/v1/forms";
-
 pub struct FormsApi<'a> {
     client: &'a GoogleClient,
+    base: &'a str,
 }
 
 impl<'a> FormsApi<'a> {
     pub fn new(client: &'a GoogleClient) -> Self {
-        Self { client }
+        Self {
+            client,
+            base: &client.endpoints().forms,
+        }
     }
 
     // ── Forms ──
 
     pub async fn create_form(&self, title: &str, document_title: &str) -> Result<Value> {
-        let url = BASE.to_string();
+        let url = self.base.to_string();
         self.client
             .post(
                 &url,
@@ -31,12 +33,12 @@ impl<'a> FormsApi<'a> {
     }
 
     pub async fn get_form(&self, form_id: &str) -> Result<Value> {
-        let url = format!("{BASE}/{form_id}");
+        let url = format!("{}/{form_id}", self.base);
         self.client.get(&url).await
     }
 
     pub async fn batch_update(&self, form_id: &str, requests: &[Value]) -> Result<Value> {
-        let url = format!("{BASE}/{form_id}:batchUpdate");
+        let url = format!("{}/{form_id}:batchUpdate", self.base);
         self.client
             .post(
                 &url,
@@ -56,7 +58,7 @@ impl<'a> FormsApi<'a> {
         page_token: Option<&str>,
         page_size: u32,
     ) -> Result<Value> {
-        let mut url = format!("{BASE}/{form_id}/responses?pageSize={page_size}");
+        let mut url = format!("{}/{form_id}/responses?pageSize={page_size}", self.base);
         if let Some(pt) = page_token {
             url.push_str(&format!("&pageToken={pt}"));
         }
@@ -64,7 +66,7 @@ impl<'a> FormsApi<'a> {
     }
 
     pub async fn get_response(&self, form_id: &str, response_id: &str) -> Result<Value> {
-        let url = format!("{BASE}/{form_id}/responses/{response_id}");
+        let url = format!("{}/{form_id}/responses/{response_id}", self.base);
         self.client.get(&url).await
     }
 
@@ -76,7 +78,7 @@ impl<'a> FormsApi<'a> {
         event_type: &str,
         topic_name: &str,
     ) -> Result<Value> {
-        let url = format!("{BASE}/{form_id}/watches");
+        let url = format!("{}/{form_id}/watches", self.base);
         self.client
             .post(
                 &url,
@@ -93,18 +95,18 @@ impl<'a> FormsApi<'a> {
     }
 
     pub async fn list_watches(&self, form_id: &str) -> Result<Value> {
-        let url = format!("{BASE}/{form_id}/watches");
+        let url = format!("{}/{form_id}/watches", self.base);
         self.client.get(&url).await
     }
 
     pub async fn delete_watch(&self, form_id: &str, watch_id: &str) -> Result<Value> {
-        let url = format!("{BASE}/{form_id}/watches/{watch_id}");
+        let url = format!("{}/{form_id}/watches/{watch_id}", self.base);
         self.client.delete(&url).await
     }
 
     pub async fn renew_watch(&self, form_id: &str, watch_id: &str) -> Result<Value> {
-        let url = format!("{BASE}/{form_id}/watches/{watch_id}:renew");
-        self.client.post_empty(&url).await
+        let url = format!("{}/{form_id}/watches/{watch_id}:renew", self.base);
+        self.client.post_empty_idempotent(&url).await
     }
 
     // ── Convenience: Add items ──
@@ -172,6 +174,7 @@ impl<'a> FormsApi<'a> {
         .await
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn add_scale_question(
         &self,
         form_id: &str,
@@ -0,0 +1,49 @@
+//! API methods generated by build.rs from the Discovery documents in
+//! `discovery/`, reachable from `vgoog exec` next to the hand-written
+//! actions. Each method takes a `<Action>Params` struct that `exec`
+//! arguments deserialize into, so unknown or missing arguments are errors.
+//! Media methods also have `<method>_media` and `<method>_upload` wrappers,
+//! which stream content to and from files.
+
+use crate::client::GoogleClient;
+use crate::error::{Result, VgoogError};
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+
+include!(concat!(env!("OUT_DIR"), "/discovery.rs"));
+
+/// Whether `service` has a generated action called `action`
+pub fn has_action(service: &str, action: &str) -> bool {
+    SERVICES.iter().any(|(s, actions)| *s == service && actions.iter().any(|a| a.name == action))
+}
+
+/// `exec` arguments as an action's parameter struct
+fn params<T: DeserializeOwned>(action: &str, args: Value) -> Result<T> {
+    let args = if args.is_null() { json!({}) } else { args };
+    serde_json::from_value(args).map_err(|e| VgoogError::Other(format!("Invalid arguments for {action}: {e}")))
+}
+
+fn with_query(mut url: String, query: &[String]) -> String {
+    if !query.is_empty() {
+        url.push('?');
+        url.push_str(&query.join("&"));
+    }
+    url
+}
+
+/// MIME type of an upload whose type was not given
+const OCTET_STREAM: &str = "application/octet-stream";
+
+/// What a media action reports after saving content to `path`
+fn saved(path: &str, size: u64) -> Value {
+    json!({ "path": path, "size": size })
+}
+
+/// A request body, `{}` when none was given
+fn body(body: &Value) -> Value {
+    if body.is_null() {
+        json!({})
+    } else {
+        body.clone()
+    }
+}
@@ -1,16 +1,22 @@
+use crate::api::models::{self, Label, Message, Page, Thread};
+use crate::batch::BatchRequest;
 use crate::client::GoogleClient;
 use crate::error::Result;
 use serde_json::{json, Value};
 
-const BASE: &str = "https://gmail.googleapis.com/gmail/v1/users/me";
-
 pub struct GmailApi<'a> {
     client: &'a GoogleClient,
+    base: &'a str,
+    batch: &'a str,
 }
 
 impl<'a> GmailApi<'a> {
     pub fn new(client: &'a GoogleClient) -> Self {
-        Self { client }
+        Self {
+            client,
+            base: &client.endpoints().gmail,
+            batch: &client.endpoints().gmail_batch,
+        }
     }
 
     // ── Messages ──
@@ -22,7 +28,7 @@ impl<'a> GmailApi<'a> {
         max_results: u32,
         page_token: Option<&str>,
     ) -> Result<Value> {
-        let mut url = format!("{BASE}/messages?maxResults={max_results}");
+        let mut url = format!("{}/messages?maxResults={max_results}", self.base);
         if let Some(q) = query {
             url.push_str(&format!("&q={}", urlencoding::encode(q)));
         }
@@ -38,27 +44,36 @@ impl<'a> GmailApi<'a> {
     }
 
     pub async fn get_message(&self, id: &str, format: &str) -> Result<Value> {
-        let url = format!("{BASE}/messages/{id}?format={format}");
+        let url = format!("{}/messages/{id}?format={format}", self.base);
         self.client.get(&url).await
     }
 
+    /// Fetch many messages in batch calls; one result per id, in order
+    pub async fn batch_get_messages(&self, ids: &[&str], format: &str) -> Result<Vec<Result<Value>>> {
+        let requests: Vec<BatchRequest> = ids
+            .iter()
+            .map(|id| BatchRequest::get(format!("{}/messages/{id}?format={format}", self.base)))
+            .collect();
+        self.client.batch(self.batch, &requests).await
+    }
+
     pub async fn send_message(&self, raw: &str) -> Result<Value> {
-        let url = format!("{BASE}/messages/send");
+        let url = format!("{}/messages/send", self.base);
         self.client.post(&url, &json!({ "raw": raw })).await
     }
 
     pub async fn trash_message(&self, id: &str) -> Result<Value> {
-        let url = format!("{BASE}/messages/{id}/trash");
-        self.client.post_empty(&url).await
+        let url = format!("{}/messages/{id}/trash", self.base);
+        self.client.post_empty_idempotent(&url).await
     }
 
     pub async fn untrash_message(&self, id: &str) -> Result<Value> {
-        let url = format!("{BASE}/messages/{id}/untrash");
-        self.client.post_empty(&url).await
+        let url = format!("{}/messages/{id}/untrash", self.base);
+        self.client.post_empty_idempotent(&url).await
     }
 
     pub async fn delete_message(&self, id: &str) -> Result<Value> {
-        let url = format!("{BASE}/messages/{id}");
+        let url = format!("{}/messages/{id}", self.base);
         self.client.delete(&url).await
     }
 
@@ -68,9 +83,9 @@ impl<'a> GmailApi<'a> {
         add_labels: &[&str],
         remove_labels: &[&str],
     ) -> Result<Value> {
-        let url = format!("{BASE}/messages/{id}/modify");
+        let url = format!("{}/messages/{id}/modify", self.base);
         self.client
-            .post(
+            .post_idempotent(
                 &url,
                 &json!({
                     "addLabelIds": add_labels,
@@ -86,9 +101,9 @@ impl<'a> GmailApi<'a> {
         add_labels: &[&str],
         remove_labels: &[&str],
     ) -> Result<Value> {
-        let url = format!("{BASE}/messages/batchModify");
+        let url = format!("{}/messages/batchModify", self.base);
         self.client
-            .post(
+            .post_idempotent(
                 &url,
                 &json!({
                     "ids": ids,
@@ -100,12 +115,12 @@ impl<'a> GmailApi<'a> {
     }
 
     pub async fn batch_delete_messages(&self, ids: &[&str]) -> Result<Value> {
-        let url = format!("{BASE}/messages/batchDelete");
-        self.client.post(&url, &json!({ "ids": ids })).await
+        let url = format!("{}/messages/batchDelete", self.base);
+        self.client.post_idempotent(&url, &json!({ "ids": ids })).await
     }
 
     pub async fn get_attachment(&self, message_id: &str, attachment_id: &str) -> Result<Value> {
-        let url = format!("{BASE}/messages/{message_id}/attachments/{attachment_id}");
+        let url = format!("{}/messages/{message_id}/attachments/{attachment_id}", self.base);
         self.client.get(&url).await
     }
 
@@ -117,7 +132,7 @@ impl<'a> GmailApi<'a> {
         max_results: u32,
         page_token: Option<&str>,
     ) -> Result<Value> {
-        let mut url = format!("{BASE}/threads?maxResults={max_results}");
+        let mut url = format!("{}/threads?maxResults={max_results}", self.base);
         if let Some(q) = query {
             url.push_str(&format!("&q={}", urlencoding::encode(q)));
         }
@@ -128,22 +143,22 @@ impl<'a> GmailApi<'a> {
     }
 
     pub async fn get_thread(&self, id: &str, format: &str) -> Result<Value> {
-        let url = format!("{BASE}/threads/{id}?format={format}");
+        let url = format!("{}/threads/{id}?format={format}", self.base);
         self.client.get(&url).await
     }
 
     pub async fn trash_thread(&self, id: &str) -> Result<Value> {
-        let url = format!("{BASE}/threads/{id}/trash");
-        self.client.post_empty(&url).await
+        let url = format!("{}/threads/{id}/trash", self.base);
+        self.client.post_empty_idempotent(&url).await
     }
 
     pub async fn untrash_thread(&self, id: &str) -> Result<Value> {
-        let url = format!("{BASE}/threads/{id}/untrash");
-        self.client.post_empty(&url).await
+        let url = format!("{}/threads/{id}/untrash", self.base);
+        self.client.post_empty_idempotent(&url).await
     }
 
     pub async fn delete_thread(&self, id: &str) -> Result<Value> {
-        let url = format!("{BASE}/threads/{id}");
+        let url = format!("{}/threads/{id}", self.base);
         self.client.delete(&url).await
     }
 
@@ -153,9 +168,9 @@ impl<'a> GmailApi<'a> {
         add_labels: &[&str],
         remove_labels: &[&str],
     ) -> Result<Value> {
-        let url = format!("{BASE}/threads/{id}/modify");
+        let url = format!("{}/threads/{id}/modify", self.base);
         self.client
-            .post(
+            .post_idempotent(
                 &url,
                 &json!({
                     "addLabelIds": add_labels,
@@ -168,17 +183,17 @@ impl<'a> GmailApi<'a> {
     // ── Labels ──
 
     pub async fn list_labels(&self) -> Result<Value> {
-        let url = format!("{BASE}/labels");
+        let url = format!("{}/labels", self.base);
         self.client.get(&url).await
     }
 
     pub async fn get_label(&self, id: &str) -> Result<Value> {
-        let url = format!("{BASE}/labels/{id}");
+        let url = format!("{}/labels/{id}", self.base);
         self.client.get(&url).await
     }
 
     pub async fn create_label(&self, name: &str, label_list_visibility: &str, message_list_visibility: &str) -> Result<Value> {
-        let url = format!("{BASE}/labels");
+        let url = format!("{}/labels", self.base);
         self.client
             .post(
                 &url,
@@ -192,14 +207,14 @@ impl<'a> GmailApi<'a> {
     }
 
     pub async fn update_label(&self, id: &str, name: &str) -> Result<Value> {
-        let url = format!("{BASE}/labels/{id}");
+        let url = format!("{}/labels/{id}", self.base);
         self.client
             .patch(&url, &json!({ "id": id, "name": name }))
             .await
     }
 
     pub async fn delete_label(&self, id: &str) -> Result<Value> {
-        let url = format!("{BASE}/labels/{id}");
+        let url = format!("{}/labels/{id}", self.base);
         self.client.delete(&url).await
     }
 
@@ -210,7 +225,7 @@ impl<'a> GmailApi<'a> {
         max_results: u32,
         page_token: Option<&str>,
     ) -> Result<Value> {
-        let mut url = format!("{BASE}/drafts?maxResults={max_results}");
+        let mut url = format!("{}/drafts?maxResults={max_results}", self.base);
         if let Some(pt) = page_token {
             url.push_str(&format!("&pageToken={pt}"));
         }
@@ -218,83 +233,83 @@ impl<'a> GmailApi<'a> {
     }
 
     pub async fn get_draft(&self, id: &str, format: &str) -> Result<Value> {
-        let url = format!("{BASE}/drafts/{id}?format={format}");
+        let url = format!("{}/drafts/{id}?format={format}", self.base);
         self.client.get(&url).await
     }
 
     pub async fn create_draft(&self, raw: &str) -> Result<Value> {
-        let url = format!("{BASE}/drafts");
+        let url = format!("{}/drafts", self.base);
         self.client
             .post(&url, &json!({ "message": { "raw": raw } }))
             .await
     }
 
     pub async fn update_draft(&self, id: &str, raw: &str) -> Result<Value> {
-        let url = format!("{BASE}/drafts/{id}");
+        let url = format!("{}/drafts/{id}", self.base);
         self.client
             .put(&url, &json!({ "message": { "raw": raw } }))
             .await
     }
 
     pub async fn send_draft(&self, id: &str) -> Result<Value> {
-        let url = format!("{BASE}/drafts/send");
+        let url = format!("{}/drafts/send", self.base);
         self.client.post(&url, &json!({ "id": id })).await
     }
 
     pub async fn delete_draft(&self, id: &str) -> Result<Value> {
-        let url = format!("{BASE}/drafts/{id}");
+        let url = format!("{}/drafts/{id}", self.base);
         self.client.delete(&url).await
     }
 
     // ── Settings ──
 
     pub async fn get_vacation_settings(&self) -> Result<Value> {
-        let url = format!("{BASE}/settings/vacation");
+        let url = format!("{}/settings/vacation", self.base);
         self.client.get(&url).await
     }
 
     pub async fn update_vacation_settings(&self, settings: &Value) -> Result<Value> {
-        let url = format!("{BASE}/settings/vacation");
+        let url = format!("{}/settings/vacation", self.base);
         self.client.put(&url, settings).await
     }
 
     pub async fn get_auto_forwarding(&self) -> Result<Value> {
-        let url = format!("{BASE}/settings/autoForwarding");
+        let url = format!("{}/settings/autoForwarding", self.base);
         self.client.get(&url).await
     }
 
     pub async fn update_auto_forwarding(&self, settings: &Value) -> Result<Value> {
-        let url = format!("{BASE}/settings/autoForwarding");
+        let url = format!("{}/settings/autoForwarding", self.base);
         self.client.put(&url, settings).await
     }
 
     pub async fn get_imap_settings(&self) -> Result<Value> {
-        let url = format!("{BASE}/settings/imap");
+        let url = format!("{}/settings/imap", self.base);
         self.client.get(&url).await
     }
 
     pub async fn update_imap_settings(&self, settings: &Value) -> Result<Value> {
-        let url = format!("{BASE}/settings/imap");
+        let url = format!("{}/settings/imap", self.base);
         self.client.put(&url, settings).await
     }
 
     pub async fn get_pop_settings(&self) -> Result<Value> {
-        let url = format!("{BASE}/settings/pop");
+        let url = format!("{}/settings/pop", self.base);
         self.client.get(&url).await
     }
 
     pub async fn update_pop_settings(&self, settings: &Value) -> Result<Value> {
-        let url = format!("{BASE}/settings/pop");
+        let url = format!("{}/settings/pop", self.base);
         self.client.put(&url, settings).await
     }
 
     pub async fn get_language_settings(&self) -> Result<Value> {
-        let url = format!("{BASE}/settings/language");
+        let url = format!("{}/settings/language", self.base);
         self.client.get(&url).await
     }
 
     pub async fn update_language_settings(&self, display_language: &str) -> Result<Value> {
-        let url = format!("{BASE}/settings/language");
+        let url = format!("{}/settings/language", self.base);
         self.client
             .put(&url, &json!({ "displayLanguage": display_language }))
             .await
@@ -303,99 +318,99 @@ impl<'a> GmailApi<'a> {
     // ── Filters ──
 
     pub async fn list_filters(&self) -> Result<Value> {
-        let url = format!("{BASE}/settings/filters");
+        let url = format!("{}/settings/filters", self.base);
         self.client.get(&url).await
     }
 
     pub async fn get_filter(&self, id: &str) -> Result<Value> {
-        let url = format!("{BASE}/settings/filters/{id}");
+        let url = format!("{}/settings/filters/{id}", self.base);
         self.client.get(&url).await
     }
 
     pub async fn create_filter(&self, filter: &Value) -> Result<Value> {
-        let url = format!("{BASE}/settings/filters");
+        let url = format!("{}/settings/filters", self.base);
         self.client.post(&url, filter).await
     }
 
     pub async fn delete_filter(&self, id: &str) -> Result<Value> {
-        let url = format!("{BASE}/settings/filters/{id}");
+        let url = format!("{}/settings/filters/{id}", self.base);
         self.client.delete(&url).await
     }
 
     // ── Forwarding Addresses ──
 
     pub async fn list_forwarding_addresses(&self) -> Result<Value> {
-        let url = format!("{BASE}/settings/forwardingAddresses");
+        let url = format!("{}/settings/forwardingAddresses", self.base);
         self.client.get(&url).await
     }
 
     pub async fn create_forwarding_address(&self, email: &str) -> Result<Value> {
-        let url = format!("{BASE}/settings/forwardingAddresses");
+        let url = format!("{}/settings/forwardingAddresses", self.base);
         self.client
             .post(&url, &json!({ "forwardingEmail": email }))
             .await
     }
 
     pub async fn delete_forwarding_address(&self, email: &str) -> Result<Value> {
-        let url = format!("{BASE}/settings/forwardingAddresses/{email}");
+        let url = format!("{}/settings/forwardingAddresses/{email}", self.base);
         self.client.delete(&url).await
     }
 
     // ── Send As ──
 
     pub async fn list_send_as(&self) -> Result<Value> {
-        let url = format!("{BASE}/settings/sendAs");
+        let url = format!("{}/settings/sendAs", self.base);
         self.client.get(&url).await
     }
 
     pub async fn get_send_as(&self, email: &str) -> Result<Value> {
-        let url = format!("{BASE}/settings/sendAs/{email}");
+        let url = format!("{}/settings/sendAs/{email}", self.base);
         self.client.get(&url).await
     }
 
     pub async fn create_send_as(&self, send_as: &Value) -> Result<Value> {
-        let url = format!("{BASE}/settings/sendAs");
+        let url = format!("{}/settings/sendAs", self.base);
         self.client.post(&url, send_as).await
     }
 
     pub async fn update_send_as(&self, email: &str, send_as: &Value) -> Result<Value> {
-        let url = format!("{BASE}/settings/sendAs/{email}");
+        let url = format!("{}/settings/sendAs/{email}", self.base);
         self.client.patch(&url, send_as).await
     }
 
     pub async fn delete_send_as(&self, email: &str) -> Result<Value> {
-        let url = format!("{BASE}/settings/sendAs/{email}");
+        let url = format!("{}/settings/sendAs/{email}", self.base);
         self.client.delete(&url).await
     }
 
     pub async fn verify_send_as(&self, email: &str) -> Result<Value> {
-        let url = format!("{BASE}/settings/sendAs/{email}/verify");
+        let url = format!("{}/settings/sendAs/{email}/verify", self.base);
         self.client.post_empty(&url).await
     }
 
     // ── Delegates ──
 
     pub async fn list_delegates(&self) -> Result<Value> {
-        let url = format!("{BASE}/settings/delegates");
+        let url = format!("{}/settings/delegates", self.base);
         self.client.get(&url).await
     }
 
     pub async fn add_delegate(&self, email: &str) -> Result<Value> {
-        let url = format!("{BASE}/settings/delegates");
+        let url = format!("{}/settings/delegates", self.base);
         self.client
             .post(&url, &json!({ "delegateEmail": email }))
             .await
     }
 
     pub async fn remove_delegate(&self, email: &str) -> Result<Value> {
-        let url = format!("{BASE}/settings/delegates/{email}");
+        let url = format!("{}/settings/delegates/{email}", self.base);
         self.client.delete(&url).await
     }
 
     // ── Profile ──
 
     pub async fn get_profile(&self) -> Result<Value> {
-        let url = format!("{BASE}/profile");
+        let url = format!("{}/profile", self.base);
         self.client.get(&url).await
     }
 
@@ -408,13 +423,40 @@ impl<'a> GmailApi<'a> {
         page_token: Option<&str>,
     ) -> Result<Value> {
         let mut url = format!(
-            "{BASE}/history?startHistoryId={start_history_id}&maxResults={max_results}"
+            "{}/history?startHistoryId={start_history_id}&maxResults={max_results}",
+            self.base
         );
         if let Some(pt) = page_token {
             url.push_str(&format!("&pageToken={pt}"));
         }
         self.client.get(&url).await
     }
+
+    // ── Typed ──
+
+    pub async fn messages(&self, query: Option<&str>, max_results: u32, page_token: Option<&str>) -> Result<Page<Message>> {
+        Page::parse(self.list_messages(query, None, max_results, page_token).await?, "messages")
+    }
+
+    pub async fn message(&self, id: &str, format: &str) -> Result<Message> {
+        models::parse(self.get_message(id, format).await?)
+    }
+
+    pub async fn threads(&self, query: Option<&str>, max_results: u32, page_token: Option<&str>) -> Result<Page<Thread>> {
+        Page::parse(self.list_threads(query, max_results, page_token).await?, "threads")
+    }
+
+    pub async fn thread(&self, id: &str, format: &str) -> Result<Thread> {
+        models::parse(self.get_thread(id, format).await?)
+    }
+
+    pub async fn labels(&self) -> Result<Vec<Label>> {
+        Ok(Page::parse(self.list_labels().await?, "labels")?.items)
+    }
+
+    pub async fn label(&self, id: &str) -> Result<Label> {
+        models::parse(self.get_label(id).await?)
+    }
 }
 
 /// Build a base64url-encoded RFC 2822 message
@@ -8,3 +8,5 @@ pub mod forms;
 pub mod tasks;
 pub mod people;
 pub mod apps_script;
+pub mod models;
+pub mod generated;
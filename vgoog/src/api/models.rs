@@ -0,0 +1,394 @@
+//! Typed views of the main Gmail, Calendar, Drive, Tasks and People
+//! resources. Every field is optional on the wire, so missing ones
+//! deserialize to their defaults and unknown ones are ignored; the raw
+//! `Value` methods stay for anything these don't cover.
+
+use crate::error::Result;
+use crate::pager;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Read a response into a model
+pub fn parse<T: DeserializeOwned>(value: Value) -> Result<T> {
+    Ok(serde_json::from_value(value)?)
+}
+
+/// A model as a request body, leaving out the fields that were never set
+/// (empty ids, `None`s and empty lists) so Google applies its defaults
+pub fn request_body<T: Serialize>(model: &T) -> Result<Value> {
+    let mut body = serde_json::to_value(model)?;
+    prune(&mut body);
+    Ok(body)
+}
+
+fn prune(value: &mut Value) {
+    match value {
+        Value::Object(fields) => {
+            fields.retain(|_, v| {
+                prune(v);
+                !matches!(v, Value::Null) && v.as_str() != Some("") && v.as_array().is_none_or(|a| !a.is_empty())
+            });
+        }
+        Value::Array(items) => items.iter_mut().for_each(prune),
+        _ => {}
+    }
+}
+
+/// One page of a list call
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next_page_token: Option<String>,
+}
+
+impl<T: DeserializeOwned> Page<T> {
+    /// Read a list response whose items live under `field` (`messages`,
+    /// `files`, `items`, ...)
+    pub fn parse(page: Value, field: &str) -> Result<Self> {
+        let next_page_token = pager::next_page_token(&page).map(String::from);
+        let items = match pager::page_items(&page, Some(field)) {
+            Some((_, items)) => items.iter().cloned().map(parse).collect::<Result<_>>()?,
+            None => Vec::new(),
+        };
+        Ok(Self { items, next_page_token })
+    }
+}
+
+// ── Gmail ──
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Message {
+    pub id: String,
+    pub thread_id: String,
+    pub label_ids: Vec<String>,
+    pub snippet: Option<String>,
+    pub history_id: Option<String>,
+    /// Milliseconds since the epoch, as a string
+    pub internal_date: Option<String>,
+    pub size_estimate: Option<u64>,
+    pub payload: Option<MessagePart>,
+    /// base64url RFC 2822 message, with `format=raw`
+    pub raw: Option<String>,
+}
+
+impl Message {
+    /// A top-level header by name, ignoring case
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.payload.as_ref()?.header(name)
+    }
+
+    pub fn has_label(&self, label: &str) -> bool {
+        self.label_ids.iter().any(|l| l == label)
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct MessagePart {
+    pub part_id: Option<String>,
+    pub mime_type: Option<String>,
+    pub filename: Option<String>,
+    pub headers: Vec<Header>,
+    pub body: Option<MessagePartBody>,
+    pub parts: Vec<MessagePart>,
+}
+
+impl MessagePart {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|h| h.name.eq_ignore_ascii_case(name)).map(|h| h.value.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Header {
+    pub name: String,
+    pub value: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct MessagePartBody {
+    pub attachment_id: Option<String>,
+    pub size: Option<u64>,
+    /// base64url content, absent for attachments fetched separately
+    pub data: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Thread {
+    pub id: String,
+    pub snippet: Option<String>,
+    pub history_id: Option<String>,
+    pub messages: Vec<Message>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Label {
+    pub id: String,
+    pub name: String,
+    /// `system` or `user`
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+    pub message_list_visibility: Option<String>,
+    pub label_list_visibility: Option<String>,
+    pub messages_total: Option<u64>,
+    pub messages_unread: Option<u64>,
+    pub threads_total: Option<u64>,
+    pub threads_unread: Option<u64>,
+    pub color: Option<LabelColor>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct LabelColor {
+    pub text_color: String,
+    pub background_color: String,
+}
+
+// ── Calendar ──
+
+/// A calendar as it appears in the user's calendar list
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Calendar {
+    pub id: String,
+    pub summary: Option<String>,
+    pub description: Option<String>,
+    pub time_zone: Option<String>,
+    /// `owner`, `writer`, `reader` or `freeBusyReader`
+    pub access_role: Option<String>,
+    pub primary: bool,
+    pub selected: bool,
+    pub background_color: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Event {
+    pub id: String,
+    /// `confirmed`, `tentative` or `cancelled`
+    pub status: Option<String>,
+    pub summary: Option<String>,
+    pub description: Option<String>,
+    pub location: Option<String>,
+    pub start: Option<EventTime>,
+    pub end: Option<EventTime>,
+    pub attendees: Vec<Attendee>,
+    pub organizer: Option<EventPerson>,
+    pub recurrence: Vec<String>,
+    pub recurring_event_id: Option<String>,
+    pub html_link: Option<String>,
+    pub created: Option<String>,
+    pub updated: Option<String>,
+}
+
+/// When an event starts or ends: `date` for all-day events, `date_time`
+/// (RFC 3339) otherwise
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct EventTime {
+    pub date: Option<String>,
+    pub date_time: Option<String>,
+    pub time_zone: Option<String>,
+}
+
+impl EventTime {
+    pub fn as_str(&self) -> Option<&str> {
+        self.date_time.as_deref().or(self.date.as_deref())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Attendee {
+    pub email: String,
+    pub display_name: Option<String>,
+    /// `needsAction`, `declined`, `tentative` or `accepted`
+    pub response_status: Option<String>,
+    pub optional: bool,
+    pub organizer: bool,
+    #[serde(rename = "self")]
+    pub is_self: bool,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct EventPerson {
+    pub email: Option<String>,
+    pub display_name: Option<String>,
+    #[serde(rename = "self")]
+    pub is_self: bool,
+}
+
+// ── Drive ──
+
+/// Fields asked for by the typed Drive calls
+pub const FILE_FIELDS: &str =
+    "id,name,mimeType,size,parents,createdTime,modifiedTime,webViewLink,description,starred,trashed,md5Checksum,owners";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct File {
+    pub id: String,
+    pub name: String,
+    pub mime_type: String,
+    /// Bytes, as a string; absent for folders and Google Docs
+    pub size: Option<String>,
+    pub parents: Vec<String>,
+    pub created_time: Option<String>,
+    pub modified_time: Option<String>,
+    pub web_view_link: Option<String>,
+    pub description: Option<String>,
+    pub starred: bool,
+    pub trashed: bool,
+    pub md5_checksum: Option<String>,
+    pub owners: Vec<DriveUser>,
+}
+
+impl File {
+    pub fn is_folder(&self) -> bool {
+        self.mime_type == "application/vnd.google-apps.folder"
+    }
+
+    pub fn size_bytes(&self) -> Option<u64> {
+        self.size.as_deref()?.parse().ok()
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DriveUser {
+    pub display_name: Option<String>,
+    pub email_address: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Permission {
+    pub id: String,
+    /// `user`, `group`, `domain` or `anyone`
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// `owner`, `organizer`, `fileOrganizer`, `writer`, `commenter` or `reader`
+    pub role: String,
+    pub email_address: Option<String>,
+    pub display_name: Option<String>,
+    pub domain: Option<String>,
+}
+
+// ── Tasks ──
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct TaskList {
+    pub id: String,
+    pub title: String,
+    pub updated: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Task {
+    pub id: String,
+    pub title: String,
+    pub notes: Option<String>,
+    /// `needsAction` or `completed`
+    pub status: Option<String>,
+    /// RFC 3339; only the date part is kept by Google
+    pub due: Option<String>,
+    pub completed: Option<String>,
+    pub parent: Option<String>,
+    pub position: Option<String>,
+    pub updated: Option<String>,
+    pub deleted: bool,
+    pub hidden: bool,
+}
+
+impl Task {
+    pub fn is_completed(&self) -> bool {
+        self.status.as_deref() == Some("completed")
+    }
+}
+
+// ── People ──
+
+/// Person fields the typed People calls ask for
+pub const PERSON_FIELDS: &str = "names,emailAddresses,phoneNumbers,organizations,memberships";
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Person {
+    /// `people/<id>`
+    pub resource_name: String,
+    pub etag: Option<String>,
+    pub names: Vec<Name>,
+    pub email_addresses: Vec<ContactValue>,
+    pub phone_numbers: Vec<ContactValue>,
+    pub organizations: Vec<Organization>,
+    pub memberships: Vec<Membership>,
+}
+
+impl Person {
+    pub fn display_name(&self) -> Option<&str> {
+        self.names.first()?.display_name.as_deref()
+    }
+
+    pub fn email(&self) -> Option<&str> {
+        Some(self.email_addresses.first()?.value.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Name {
+    pub display_name: Option<String>,
+    pub given_name: Option<String>,
+    pub family_name: Option<String>,
+}
+
+/// An email address or phone number with its label (`home`, `work`, ...)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ContactValue {
+    pub value: String,
+    #[serde(rename = "type")]
+    pub kind: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Organization {
+    pub name: Option<String>,
+    pub title: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Membership {
+    pub contact_group_membership: Option<GroupMembership>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct GroupMembership {
+    pub contact_group_resource_name: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ContactGroup {
+    /// `contactGroups/<id>`
+    pub resource_name: String,
+    pub etag: Option<String>,
+    pub name: String,
+    pub formatted_name: Option<String>,
+    /// `USER_CONTACT_GROUP` or `SYSTEM_CONTACT_GROUP`
+    pub group_type: Option<String>,
+    pub member_count: Option<u32>,
+    pub member_resource_names: Vec<String>,
+}
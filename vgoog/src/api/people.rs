@@ -1,23 +1,28 @@
+use crate::api::models::{self, ContactGroup, Page, Person, PERSON_FIELDS};
 use crate::client::GoogleClient;
 use crate::error::Result;
 use serde_json::{json, Value};
 
-const BASE: &str = "https://people.googleapis.com/v1";
-
 pub struct PeopleApi<'a> {
     client: &'a GoogleClient,
+    base: &'a str,
 }
 
 impl<'a> PeopleApi<'a> {
     pub fn new(client: &'a GoogleClient) -> Self {
-        Self { client }
+        Self {
+            client,
+            base: &client.endpoints().people,
+        }
     }
 
     // ── People ──
 
     pub async fn get_person(&self, resource_name: &str, person_fields: &str) -> Result<Value> {
         let url = format!(
-            "{BASE}/{resource_name}?personFields={}", urlencoding::encode(person_fields)
+            "{}/{resource_name}?personFields={}",
+            self.base,
+            urlencoding::encode(person_fields)
         );
         self.client.get(&url).await
     }
@@ -35,7 +40,7 @@ impl<'a> PeopleApi<'a> {
         resource_names: &[&str],
         person_fields: &str,
     ) -> Result<Value> {
-        let mut url = format!("{BASE}/people:batchGet?personFields={}", urlencoding::encode(person_fields));
+        let mut url = format!("{}/people:batchGet?personFields={}", self.base, urlencoding::encode(person_fields));
         for rn in resource_names {
             url.push_str(&format!("&resourceNames={}", urlencoding::encode(rn)));
         }
@@ -52,7 +57,8 @@ impl<'a> PeopleApi<'a> {
         sort_order: Option<&str>,
     ) -> Result<Value> {
         let mut url = format!(
-            "{BASE}/people/me/connections?pageSize={page_size}&personFields={}",
+            "{}/people/me/connections?pageSize={page_size}&personFields={}",
+            self.base,
             urlencoding::encode(person_fields)
         );
         if let Some(pt) = page_token {
@@ -66,14 +72,15 @@ impl<'a> PeopleApi<'a> {
 
     pub async fn search_contacts(&self, query: &str, page_size: u32) -> Result<Value> {
         let url = format!(
-            "{BASE}/people:searchContacts?query={}&pageSize={page_size}&readMask=names,emailAddresses,phoneNumbers,organizations",
+            "{}/people:searchContacts?query={}&pageSize={page_size}&readMask=names,emailAddresses,phoneNumbers,organizations",
+            self.base,
             urlencoding::encode(query)
         );
         self.client.get(&url).await
     }
 
     pub async fn create_contact(&self, person: &Value) -> Result<Value> {
-        let url = format!("{BASE}/people:createContact");
+        let url = format!("{}/people:createContact", self.base);
         self.client.post(&url, person).await
     }
 
@@ -84,19 +91,20 @@ impl<'a> PeopleApi<'a> {
         update_mask: &str,
     ) -> Result<Value> {
         let url = format!(
-            "{BASE}/{resource_name}:updateContact?updatePersonFields={}",
+            "{}/{resource_name}:updateContact?updatePersonFields={}",
+            self.base,
             urlencoding::encode(update_mask)
         );
         self.client.patch(&url, person).await
     }
 
     pub async fn delete_contact(&self, resource_name: &str) -> Result<Value> {
-        let url = format!("{BASE}/{resource_name}:deleteContact");
+        let url = format!("{}/{resource_name}:deleteContact", self.base);
         self.client.delete(&url).await
     }
 
     pub async fn batch_create_contacts(&self, contacts: &[Value]) -> Result<Value> {
-        let url = format!("{BASE}/people:batchCreateContacts");
+        let url = format!("{}/people:batchCreateContacts", self.base);
         let body: Vec<Value> = contacts
             .iter()
             .map(|c| json!({ "contactPerson": c }))
@@ -107,7 +115,7 @@ impl<'a> PeopleApi<'a> {
     }
 
     pub async fn batch_delete_contacts(&self, resource_names: &[&str]) -> Result<Value> {
-        let url = format!("{BASE}/people:batchDeleteContacts");
+        let url = format!("{}/people:batchDeleteContacts", self.base);
         self.client
             .post(&url, &json!({ "resourceNames": resource_names }))
             .await
@@ -118,7 +126,7 @@ impl<'a> PeopleApi<'a> {
         contacts: &Value,
         update_mask: &str,
     ) -> Result<Value> {
-        let url = format!("{BASE}/people:batchUpdateContacts");
+        let url = format!("{}/people:batchUpdateContacts", self.base);
         self.client
             .post(
                 &url,
@@ -134,7 +142,7 @@ impl<'a> PeopleApi<'a> {
     // ── Contact Groups ──
 
     pub async fn list_contact_groups(&self, page_size: u32, page_token: Option<&str>) -> Result<Value> {
-        let mut url = format!("{BASE}/contactGroups?pageSize={page_size}&groupFields=name,memberCount");
+        let mut url = format!("{}/contactGroups?pageSize={page_size}&groupFields=name,memberCount", self.base);
         if let Some(pt) = page_token {
             url.push_str(&format!("&pageToken={pt}"));
         }
@@ -142,12 +150,12 @@ impl<'a> PeopleApi<'a> {
     }
 
     pub async fn get_contact_group(&self, resource_name: &str) -> Result<Value> {
-        let url = format!("{BASE}/{resource_name}?groupFields=name,memberCount&maxMembers=1000");
+        let url = format!("{}/{resource_name}?groupFields=name,memberCount&maxMembers=1000", self.base);
         self.client.get(&url).await
     }
 
     pub async fn create_contact_group(&self, name: &str) -> Result<Value> {
-        let url = format!("{BASE}/contactGroups");
+        let url = format!("{}/contactGroups", self.base);
         self.client
             .post(
                 &url,
@@ -159,7 +167,7 @@ impl<'a> PeopleApi<'a> {
     }
 
     pub async fn update_contact_group(&self, resource_name: &str, name: &str) -> Result<Value> {
-        let url = format!("{BASE}/{resource_name}");
+        let url = format!("{}/{resource_name}", self.base);
         self.client
             .put(
                 &url,
@@ -177,7 +185,8 @@ impl<'a> PeopleApi<'a> {
         delete_contacts: bool,
     ) -> Result<Value> {
         let url = format!(
-            "{BASE}/{resource_name}?deleteContacts={delete_contacts}"
+            "{}/{resource_name}?deleteContacts={delete_contacts}",
+            self.base
         );
         self.client.delete(&url).await
     }
@@ -188,7 +197,7 @@ impl<'a> PeopleApi<'a> {
         add: &[&str],
         remove: &[&str],
     ) -> Result<Value> {
-        let url = format!("{BASE}/{resource_name}/members:modify");
+        let url = format!("{}/{resource_name}/members:modify", self.base);
         self.client
             .post(
                 &url,
@@ -208,7 +217,8 @@ impl<'a> PeopleApi<'a> {
         page_token: Option<&str>,
     ) -> Result<Value> {
         let mut url = format!(
-            "{BASE}/otherContacts?pageSize={page_size}&readMask=names,emailAddresses,phoneNumbers"
+            "{}/otherContacts?pageSize={page_size}&readMask=names,emailAddresses,phoneNumbers",
+            self.base
         );
         if let Some(pt) = page_token {
             url.push_str(&format!("&pageToken={pt}"));
@@ -217,7 +227,7 @@ impl<'a> PeopleApi<'a> {
     }
 
     pub async fn copy_other_contact_to_contacts(&self, resource_name: &str) -> Result<Value> {
-        let url = format!("{BASE}/{resource_name}:copyOtherContactToMyContactsGroup");
+        let url = format!("{}/{resource_name}:copyOtherContactToMyContactsGroup", self.base);
         self.client
             .post(
                 &url,
@@ -235,7 +245,8 @@ impl<'a> PeopleApi<'a> {
         page_token: Option<&str>,
     ) -> Result<Value> {
         let mut url = format!(
-            "{BASE}/people:searchDirectoryPeople?query={}&pageSize={page_size}&readMask=names,emailAddresses,phoneNumbers,organizations&sources=DIRECTORY_SOURCE_TYPE_DOMAIN_PROFILE",
+            "{}/people:searchDirectoryPeople?query={}&pageSize={page_size}&readMask=names,emailAddresses,phoneNumbers,organizations&sources=DIRECTORY_SOURCE_TYPE_DOMAIN_PROFILE",
+            self.base,
             urlencoding::encode(query)
         );
         if let Some(pt) = page_token {
@@ -243,4 +254,26 @@ impl<'a> PeopleApi<'a> {
         }
         self.client.get(&url).await
     }
+
+    // ── Typed ──
+
+    pub async fn person(&self, resource_name: &str) -> Result<Person> {
+        models::parse(self.get_person(resource_name, PERSON_FIELDS).await?)
+    }
+
+    pub async fn contacts(&self, page_size: u32, page_token: Option<&str>, sort_order: Option<&str>) -> Result<Page<Person>> {
+        Page::parse(self.list_contacts(page_size, page_token, PERSON_FIELDS, sort_order).await?, "connections")
+    }
+
+    pub async fn insert_contact(&self, person: &Person) -> Result<Person> {
+        models::parse(self.create_contact(&models::request_body(person)?).await?)
+    }
+
+    pub async fn contact_groups(&self, page_size: u32, page_token: Option<&str>) -> Result<Page<ContactGroup>> {
+        Page::parse(self.list_contact_groups(page_size, page_token).await?, "contactGroups")
+    }
+
+    pub async fn contact_group(&self, resource_name: &str) -> Result<ContactGroup> {
+        models::parse(self.get_contact_group(resource_name).await?)
+    }
 }
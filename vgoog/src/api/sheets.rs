@@ -2,21 +2,23 @@ use crate::client::GoogleClient;
 use crate::error::Result;
 use serde_json::{json, Value};
 
-const BASE: &str = "https://sheets.googleapis.com/v4/spreadsheets";
-
 pub struct SheetsApi<'a> {
     client: &'a GoogleClient,
+    base: &'a str,
 }
 
 impl<'a> SheetsApi<'a> {
     pub fn new(client: &'a GoogleClient) -> Self {
-        Self { client }
+        Self {
+            client,
+            base: &client.endpoints().sheets,
+        }
     }
 
     // ── Spreadsheets ──
 
     pub async fn create_spreadsheet(&self, title: &str) -> Result<Value> {
-        let url = BASE.to_string();
+        let url = self.base.to_string();
         self.client
             .post(
                 &url,
@@ -28,7 +30,7 @@ impl<'a> SheetsApi<'a> {
     }
 
     pub async fn get_spreadsheet(&self, id: &str) -> Result<Value> {
-        let url = format!("{BASE}/{id}");
+        let url = format!("{}/{id}", self.base);
         self.client.get(&url).await
     }
 
@@ -37,7 +39,7 @@ impl<'a> SheetsApi<'a> {
         id: &str,
         ranges: &[&str],
     ) -> Result<Value> {
-        let mut url = format!("{BASE}/{id}?includeGridData=true");
+        let mut url = format!("{}/{id}?includeGridData=true", self.base);
         for r in ranges {
             url.push_str(&format!("&ranges={}", urlencoding::encode(r)));
         }
@@ -53,7 +55,7 @@ impl<'a> SheetsApi<'a> {
         value_render: Option<&str>,
     ) -> Result<Value> {
         let r = urlencoding::encode(range);
-        let mut url = format!("{BASE}/{spreadsheet_id}/values/{r}");
+        let mut url = format!("{}/{spreadsheet_id}/values/{r}", self.base);
         if let Some(vr) = value_render {
             url.push_str(&format!("?valueRenderOption={vr}"));
         }
@@ -65,7 +67,7 @@ impl<'a> SheetsApi<'a> {
         spreadsheet_id: &str,
         ranges: &[&str],
     ) -> Result<Value> {
-        let mut url = format!("{BASE}/{spreadsheet_id}/values:batchGet?");
+        let mut url = format!("{}/{spreadsheet_id}/values:batchGet?", self.base);
         for (i, r) in ranges.iter().enumerate() {
             if i > 0 {
                 url.push('&');
@@ -84,7 +86,8 @@ impl<'a> SheetsApi<'a> {
     ) -> Result<Value> {
         let r = urlencoding::encode(range);
         let url = format!(
-            "{BASE}/{spreadsheet_id}/values/{r}?valueInputOption={input_option}"
+            "{}/{spreadsheet_id}/values/{r}?valueInputOption={input_option}",
+            self.base
         );
         self.client
             .put(
@@ -106,7 +109,8 @@ impl<'a> SheetsApi<'a> {
     ) -> Result<Value> {
         let r = urlencoding::encode(range);
         let url = format!(
-            "{BASE}/{spreadsheet_id}/values/{r}:append?valueInputOption={input_option}&insertDataOption=INSERT_ROWS"
+            "{}/{spreadsheet_id}/values/{r}:append?valueInputOption={input_option}&insertDataOption=INSERT_ROWS",
+            self.base
         );
         self.client
             .post(
@@ -121,7 +125,7 @@ impl<'a> SheetsApi<'a> {
 
     pub async fn clear_values(&self, spreadsheet_id: &str, range: &str) -> Result<Value> {
         let r = urlencoding::encode(range);
-        let url = format!("{BASE}/{spreadsheet_id}/values/{r}:clear");
+        let url = format!("{}/{spreadsheet_id}/values/{r}:clear", self.base);
         self.client.post(&url, &json!({})).await
     }
 
@@ -131,7 +135,7 @@ impl<'a> SheetsApi<'a> {
         data: &[Value],
         input_option: &str,
     ) -> Result<Value> {
-        let url = format!("{BASE}/{spreadsheet_id}/values:batchUpdate");
+        let url = format!("{}/{spreadsheet_id}/values:batchUpdate", self.base);
         self.client
             .post(
                 &url,
@@ -148,7 +152,7 @@ impl<'a> SheetsApi<'a> {
         spreadsheet_id: &str,
         ranges: &[&str],
     ) -> Result<Value> {
-        let url = format!("{BASE}/{spreadsheet_id}/values:batchClear");
+        let url = format!("{}/{spreadsheet_id}/values:batchClear", self.base);
         self.client
             .post(&url, &json!({ "ranges": ranges }))
             .await
@@ -161,7 +165,7 @@ impl<'a> SheetsApi<'a> {
         spreadsheet_id: &str,
         requests: &[Value],
     ) -> Result<Value> {
-        let url = format!("{BASE}/{spreadsheet_id}:batchUpdate");
+        let url = format!("{}/{spreadsheet_id}:batchUpdate", self.base);
         self.client
             .post(&url, &json!({ "requests": requests }))
             .await
@@ -237,6 +241,7 @@ impl<'a> SheetsApi<'a> {
         .await
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn sort_range(
         &self,
         spreadsheet_id: &str,
@@ -270,6 +275,7 @@ impl<'a> SheetsApi<'a> {
         .await
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn create_named_range(
         &self,
         spreadsheet_id: &str,
@@ -2,31 +2,33 @@ use crate::client::GoogleClient;
 use crate::error::Result;
 use serde_json::{json, Value};
 
-const BASE: &str = "https://slides.googleapis.com/v1/presentations";
-
 pub struct SlidesApi<'a> {
     client: &'a GoogleClient,
+    base: &'a str,
 }
 
 impl<'a> SlidesApi<'a> {
     pub fn new(client: &'a GoogleClient) -> Self {
-        Self { client }
+        Self {
+            client,
+            base: &client.endpoints().slides,
+        }
     }
 
     pub async fn create_presentation(&self, title: &str) -> Result<Value> {
-        let url = BASE.to_string();
+        let url = self.base.to_string();
         self.client
             .post(&url, &json!({ "title": title }))
             .await
     }
 
     pub async fn get_presentation(&self, id: &str) -> Result<Value> {
-        let url = format!("{BASE}/{id}");
+        let url = format!("{}/{id}", self.base);
         self.client.get(&url).await
     }
 
     pub async fn get_page(&self, presentation_id: &str, page_id: &str) -> Result<Value> {
-        let url = format!("{BASE}/{presentation_id}/pages/{page_id}");
+        let url = format!("{}/{presentation_id}/pages/{page_id}", self.base);
         self.client.get(&url).await
     }
 
@@ -36,7 +38,8 @@ impl<'a> SlidesApi<'a> {
         page_id: &str,
     ) -> Result<Value> {
         let url = format!(
-            "{BASE}/{presentation_id}/pages/{page_id}/thumbnail?thumbnailProperties.mimeType=PNG"
+            "{}/{presentation_id}/pages/{page_id}/thumbnail?thumbnailProperties.mimeType=PNG",
+            self.base
         );
         self.client.get(&url).await
     }
@@ -46,7 +49,7 @@ impl<'a> SlidesApi<'a> {
         presentation_id: &str,
         requests: &[Value],
     ) -> Result<Value> {
-        let url = format!("{BASE}/{presentation_id}:batchUpdate");
+        let url = format!("{}/{presentation_id}:batchUpdate", self.base);
         self.client
             .post(&url, &json!({ "requests": requests }))
             .await
@@ -182,6 +185,7 @@ impl<'a> SlidesApi<'a> {
         .await
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn create_shape(
         &self,
         presentation_id: &str,
@@ -219,6 +223,7 @@ impl<'a> SlidesApi<'a> {
         .await
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn create_image(
         &self,
         presentation_id: &str,
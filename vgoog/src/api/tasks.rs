@@ -1,16 +1,19 @@
+use crate::api::models::{self, Page, Task, TaskList};
 use crate::client::GoogleClient;
 use crate::error::Result;
 use serde_json::{json, Value};
 
-const BASE: &str = "https://tasks.googleapis.com/tasks/v1";
-
 pub struct TasksApi<'a> {
     client: &'a GoogleClient,
+    base: &'a str,
 }
 
 impl<'a> TasksApi<'a> {
     pub fn new(client: &'a GoogleClient) -> Self {
-        Self { client }
+        Self {
+            client,
+            base: &client.endpoints().tasks,
+        }
     }
 
     // ── Task Lists ──
@@ -20,7 +23,7 @@ impl<'a> TasksApi<'a> {
         max_results: u32,
         page_token: Option<&str>,
     ) -> Result<Value> {
-        let mut url = format!("{BASE}/users/@me/lists?maxResults={max_results}");
+        let mut url = format!("{}/users/@me/lists?maxResults={max_results}", self.base);
         if let Some(pt) = page_token {
             url.push_str(&format!("&pageToken={pt}"));
         }
@@ -28,27 +31,28 @@ impl<'a> TasksApi<'a> {
     }
 
     pub async fn get_task_list(&self, id: &str) -> Result<Value> {
-        let url = format!("{BASE}/users/@me/lists/{id}");
+        let url = format!("{}/users/@me/lists/{id}", self.base);
         self.client.get(&url).await
     }
 
     pub async fn create_task_list(&self, title: &str) -> Result<Value> {
-        let url = format!("{BASE}/users/@me/lists");
+        let url = format!("{}/users/@me/lists", self.base);
         self.client.post(&url, &json!({ "title": title })).await
     }
 
     pub async fn update_task_list(&self, id: &str, title: &str) -> Result<Value> {
-        let url = format!("{BASE}/users/@me/lists/{id}");
+        let url = format!("{}/users/@me/lists/{id}", self.base);
         self.client.put(&url, &json!({ "title": title })).await
     }
 
     pub async fn delete_task_list(&self, id: &str) -> Result<Value> {
-        let url = format!("{BASE}/users/@me/lists/{id}");
+        let url = format!("{}/users/@me/lists/{id}", self.base);
         self.client.delete(&url).await
     }
 
     // ── Tasks ──
 
+    #[allow(clippy::too_many_arguments)]
     pub async fn list_tasks(
         &self,
         task_list_id: &str,
@@ -61,7 +65,8 @@ impl<'a> TasksApi<'a> {
         due_max: Option<&str>,
     ) -> Result<Value> {
         let mut url = format!(
-            "{BASE}/lists/{task_list_id}/tasks?maxResults={max_results}&showCompleted={show_completed}&showDeleted={show_deleted}&showHidden={show_hidden}"
+            "{}/lists/{task_list_id}/tasks?maxResults={max_results}&showCompleted={show_completed}&showDeleted={show_deleted}&showHidden={show_hidden}",
+            self.base
         );
         if let Some(pt) = page_token {
             url.push_str(&format!("&pageToken={pt}"));
@@ -76,7 +81,7 @@ impl<'a> TasksApi<'a> {
     }
 
     pub async fn get_task(&self, task_list_id: &str, task_id: &str) -> Result<Value> {
-        let url = format!("{BASE}/lists/{task_list_id}/tasks/{task_id}");
+        let url = format!("{}/lists/{task_list_id}/tasks/{task_id}", self.base);
         self.client.get(&url).await
     }
 
@@ -89,7 +94,7 @@ impl<'a> TasksApi<'a> {
         parent: Option<&str>,
         previous: Option<&str>,
     ) -> Result<Value> {
-        let mut url = format!("{BASE}/lists/{task_list_id}/tasks?");
+        let mut url = format!("{}/lists/{task_list_id}/tasks?", self.base);
         if let Some(p) = parent {
             url.push_str(&format!("parent={p}&"));
         }
@@ -112,7 +117,7 @@ impl<'a> TasksApi<'a> {
         task_id: &str,
         updates: &Value,
     ) -> Result<Value> {
-        let url = format!("{BASE}/lists/{task_list_id}/tasks/{task_id}");
+        let url = format!("{}/lists/{task_list_id}/tasks/{task_id}", self.base);
         self.client.patch(&url, updates).await
     }
 
@@ -131,7 +136,7 @@ impl<'a> TasksApi<'a> {
     }
 
     pub async fn delete_task(&self, task_list_id: &str, task_id: &str) -> Result<Value> {
-        let url = format!("{BASE}/lists/{task_list_id}/tasks/{task_id}");
+        let url = format!("{}/lists/{task_list_id}/tasks/{task_id}", self.base);
         self.client.delete(&url).await
     }
 
@@ -142,18 +147,55 @@ impl<'a> TasksApi<'a> {
         parent: Option<&str>,
         previous: Option<&str>,
     ) -> Result<Value> {
-        let mut url = format!("{BASE}/lists/{task_list_id}/tasks/{task_id}/move?");
+        let mut url = format!("{}/lists/{task_list_id}/tasks/{task_id}/move?", self.base);
         if let Some(p) = parent {
             url.push_str(&format!("parent={p}&"));
         }
         if let Some(pr) = previous {
             url.push_str(&format!("previous={pr}&"));
         }
-        self.client.post_empty(&url).await
+        self.client.post_empty_idempotent(&url).await
     }
 
     pub async fn clear_completed(&self, task_list_id: &str) -> Result<Value> {
-        let url = format!("{BASE}/lists/{task_list_id}/clear");
-        self.client.post_empty(&url).await
+        let url = format!("{}/lists/{task_list_id}/clear", self.base);
+        self.client.post_empty_idempotent(&url).await
+    }
+
+    // ── Typed ──
+
+    pub async fn task_lists(&self, max_results: u32, page_token: Option<&str>) -> Result<Page<TaskList>> {
+        Page::parse(self.list_task_lists(max_results, page_token).await?, "items")
+    }
+
+    pub async fn task_list(&self, id: &str) -> Result<TaskList> {
+        models::parse(self.get_task_list(id).await?)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub async fn tasks(
+        &self,
+        task_list_id: &str,
+        max_results: u32,
+        page_token: Option<&str>,
+        show_completed: bool,
+        show_deleted: bool,
+        show_hidden: bool,
+        due_min: Option<&str>,
+        due_max: Option<&str>,
+    ) -> Result<Page<Task>> {
+        let page = self
+            .list_tasks(task_list_id, max_results, page_token, show_completed, show_deleted, show_hidden, due_min, due_max)
+            .await?;
+        Page::parse(page, "items")
+    }
+
+    pub async fn task(&self, task_list_id: &str, task_id: &str) -> Result<Task> {
+        models::parse(self.get_task(task_list_id, task_id).await?)
+    }
+
+    pub async fn insert_task(&self, task_list_id: &str, task: &Task) -> Result<Task> {
+        let url = format!("{}/lists/{task_list_id}/tasks", self.base);
+        models::parse(self.client.post(&url, &models::request_body(task)?).await?)
     }
 }
@@ -0,0 +1,80 @@
+use super::{post_form, Grant, TokenResponse};
+use crate::endpoints::Endpoints;
+use crate::error::{Result, VgoogError};
+use reqwest::Client;
+use serde::Deserialize;
+use std::time::Duration;
+use tokio::time::Instant;
+
+const DEVICE_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:device_code";
+
+/// Seconds added to the polling interval on each `slow_down` response (RFC 8628 §3.5)
+const SLOW_DOWN_STEP_SECS: u64 = 5;
+
+/// Response from the device authorization endpoint
+#[derive(Debug, Clone, Deserialize)]
+pub struct DeviceCode {
+    pub device_code: String,
+    pub user_code: String,
+    /// Google sends `verification_url`; RFC 8628 names it `verification_uri`
+    #[serde(alias = "verification_uri")]
+    pub verification_url: String,
+    pub expires_in: u64,
+    #[serde(default = "default_interval")]
+    pub interval: u64,
+}
+
+fn default_interval() -> u64 {
+    5
+}
+
+/// Ask the device authorization endpoint for a user code
+pub async fn request_code(
+    http: &Client,
+    endpoints: &Endpoints,
+    client_id: &str,
+    scopes: &[&str],
+) -> Result<DeviceCode> {
+    let scope = scopes.join(" ");
+    Ok(post_form(http, &endpoints.device_uri, &[("client_id", client_id), ("scope", &scope)]).await??)
+}
+
+/// Poll the token endpoint until the user approves, denies, or the code expires
+pub async fn poll_for_token(
+    http: &Client,
+    endpoints: &Endpoints,
+    client_id: &str,
+    client_secret: &str,
+    code: &DeviceCode,
+) -> Result<Grant> {
+    let deadline = Instant::now() + Duration::from_secs(code.expires_in);
+    let mut interval = code.interval.max(1);
+
+    loop {
+        tokio::time::sleep(Duration::from_secs(interval)).await;
+        if Instant::now() >= deadline {
+            return Err(VgoogError::Auth("Device code expired before approval".into()));
+        }
+
+        let resp = post_form::<TokenResponse>(
+            http,
+            &endpoints.token_uri,
+            &[
+                ("client_id", client_id),
+                ("client_secret", client_secret),
+                ("device_code", code.device_code.as_str()),
+                ("grant_type", DEVICE_GRANT_TYPE),
+            ],
+        )
+        .await?;
+
+        match resp {
+            Ok(token) => return token.into_grant(client_id, client_secret),
+            Err(err) => match err.error.as_str() {
+                "authorization_pending" => {}
+                "slow_down" => interval += SLOW_DOWN_STEP_SECS,
+                _ => return Err(err.into()),
+            },
+        }
+    }
+}
@@ -0,0 +1,129 @@
+use super::{token_request, Grant};
+use crate::config::AuthConfig;
+use crate::endpoints::Endpoints;
+use crate::error::{Result, VgoogError};
+use chrono::{Duration, Utc};
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// File name gcloud writes for `gcloud auth application-default login`
+pub const ADC_FILE_NAME: &str = "application_default_credentials.json";
+
+/// Credentials read from a file downloaded from the Cloud console or
+/// written by gcloud
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CredentialsFile {
+    /// An OAuth client (`client_secret_*.json`); tokens still need a sign-in
+    Client { client_id: String, client_secret: String },
+    /// An `authorized_user` ADC file, complete with a refresh token
+    AuthorizedUser {
+        client_id: String,
+        client_secret: String,
+        refresh_token: String,
+    },
+}
+
+/// The `installed` or `web` section of a `client_secret_*.json`
+#[derive(Deserialize)]
+struct ClientSection {
+    client_id: String,
+    client_secret: String,
+}
+
+#[derive(Deserialize)]
+struct AuthorizedUserFile {
+    client_id: String,
+    client_secret: String,
+    refresh_token: String,
+}
+
+impl CredentialsFile {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            VgoogError::Config(format!("Cannot read credentials file {}: {e}", path.display()))
+        })?;
+        Self::parse(&content).map_err(|e| match e {
+            VgoogError::Config(msg) => VgoogError::Config(format!("{}: {msg}", path.display())),
+            other => other,
+        })
+    }
+
+    pub fn parse(content: &str) -> Result<Self> {
+        let value: Value = serde_json::from_str(content)?;
+        if let Some(section) = value.get("installed").or_else(|| value.get("web")) {
+            let client: ClientSection = serde_json::from_value(section.clone())?;
+            return Ok(Self::Client { client_id: client.client_id, client_secret: client.client_secret });
+        }
+        match value.get("type").and_then(Value::as_str) {
+            Some("authorized_user") => {
+                let user: AuthorizedUserFile = serde_json::from_value(value)?;
+                Ok(Self::AuthorizedUser {
+                    client_id: user.client_id,
+                    client_secret: user.client_secret,
+                    refresh_token: user.refresh_token,
+                })
+            }
+            Some("service_account") => Err(VgoogError::Config(
+                "this is a service-account key; add it with `vgoog auth service-account --key-file`".into(),
+            )),
+            Some(other) => Err(VgoogError::Config(format!("unsupported credentials type '{other}'"))),
+            None => Err(VgoogError::Config(
+                "not an OAuth client (installed/web) or authorized_user credentials file".into(),
+            )),
+        }
+    }
+}
+
+/// Where Application Default Credentials are looked for:
+/// `GOOGLE_APPLICATION_CREDENTIALS`, then gcloud's own config directory
+/// (`CLOUDSDK_CONFIG`, else `~/.config/gcloud`, `%APPDATA%\gcloud` on Windows)
+pub fn default_adc_path() -> Result<PathBuf> {
+    if let Ok(path) = std::env::var("GOOGLE_APPLICATION_CREDENTIALS") {
+        return Ok(PathBuf::from(path));
+    }
+    let gcloud = match std::env::var("CLOUDSDK_CONFIG") {
+        Ok(dir) => PathBuf::from(dir),
+        Err(_) if cfg!(windows) => dirs::config_dir()
+            .ok_or_else(|| VgoogError::Config("Cannot find %APPDATA%".into()))?
+            .join("gcloud"),
+        Err(_) => dirs::home_dir()
+            .ok_or_else(|| VgoogError::Config("Cannot find home directory".into()))?
+            .join(".config")
+            .join("gcloud"),
+    };
+    Ok(gcloud.join(ADC_FILE_NAME))
+}
+
+/// Exchange an imported refresh token for an access token. This proves
+/// the credentials work before anything is saved.
+pub async fn redeem(
+    http: &Client,
+    endpoints: &Endpoints,
+    client_id: &str,
+    client_secret: &str,
+    refresh_token: &str,
+) -> Result<Grant> {
+    let token = token_request(
+        http,
+        &endpoints.token_uri,
+        &[
+            ("client_id", client_id),
+            ("client_secret", client_secret),
+            ("refresh_token", refresh_token),
+            ("grant_type", "refresh_token"),
+        ],
+    )
+    .await?;
+
+    let scopes = token.granted_scopes();
+    let auth = AuthConfig {
+        client_id: client_id.to_string(),
+        client_secret: client_secret.to_string(),
+        access_token: token.access_token,
+        refresh_token: token.refresh_token.unwrap_or_else(|| refresh_token.to_string()),
+        token_expiry: Utc::now() + Duration::seconds(token.expires_in),
+    };
+    Ok(Grant { auth, scopes })
+}
@@ -0,0 +1,252 @@
+use super::{token_request, Grant};
+use crate::endpoints::Endpoints;
+use crate::error::{Result, VgoogError};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use reqwest::Client;
+use std::collections::HashMap;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+/// How long to wait for the browser to come back before giving up
+const REDIRECT_TIMEOUT_SECS: u64 = 300;
+
+const SUCCESS_PAGE: &str = "<html><body style=\"font-family:sans-serif\">\
+<h2>vgoog: authorization complete</h2><p>You can close this tab and return to the terminal.</p>\
+</body></html>";
+
+const FAILURE_PAGE: &str = "<html><body style=\"font-family:sans-serif\">\
+<h2>vgoog: authorization failed</h2><p>Return to the terminal for details.</p>\
+</body></html>";
+
+/// PKCE verifier/challenge pair (S256)
+pub struct Pkce {
+    pub verifier: String,
+    pub challenge: String,
+}
+
+impl Pkce {
+    pub fn generate() -> Result<Self> {
+        let verifier = URL_SAFE_NO_PAD.encode(random_bytes::<32>()?);
+        let digest = ring::digest::digest(&ring::digest::SHA256, verifier.as_bytes());
+        let challenge = URL_SAFE_NO_PAD.encode(digest.as_ref());
+        Ok(Self { verifier, challenge })
+    }
+}
+
+fn random_bytes<const N: usize>() -> Result<[u8; N]> {
+    use ring::rand::SecureRandom;
+    let mut buf = [0u8; N];
+    ring::rand::SystemRandom::new()
+        .fill(&mut buf)
+        .map_err(|_| VgoogError::Auth("System RNG unavailable".into()))?;
+    Ok(buf)
+}
+
+/// Build the consent-screen URL. An incremental request sets
+/// `include_granted_scopes`, so the new token keeps every scope granted
+/// before and the consent screen only asks about `scopes`.
+pub fn authorization_url(
+    endpoints: &Endpoints,
+    client_id: &str,
+    redirect_uri: &str,
+    scopes: &[&str],
+    challenge: &str,
+    state: &str,
+    incremental: bool,
+) -> String {
+    let mut url = format!(
+        "{}?response_type=code&client_id={}&redirect_uri={}&scope={}&code_challenge={}&code_challenge_method=S256&state={}&access_type=offline&prompt=consent",
+        endpoints.auth_uri,
+        urlencoding::encode(client_id),
+        urlencoding::encode(redirect_uri),
+        urlencoding::encode(&scopes.join(" ")),
+        challenge,
+        state,
+    );
+    if incremental {
+        url.push_str("&include_granted_scopes=true");
+    }
+    url
+}
+
+/// A consent in progress: the redirect listener is bound and the URL is
+/// ready to be opened
+pub struct PendingConsent {
+    listener: TcpListener,
+    redirect_uri: String,
+    url: String,
+    state: String,
+    verifier: String,
+}
+
+impl PendingConsent {
+    pub async fn start(
+        endpoints: &Endpoints,
+        client_id: &str,
+        scopes: &[&str],
+        incremental: bool,
+    ) -> Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let redirect_uri = format!("http://127.0.0.1:{}", listener.local_addr()?.port());
+        let pkce = Pkce::generate()?;
+        let state = URL_SAFE_NO_PAD.encode(random_bytes::<16>()?);
+        let url = authorization_url(
+            endpoints, client_id, &redirect_uri, scopes, &pkce.challenge, &state, incremental,
+        );
+        Ok(Self { listener, redirect_uri, url, state, verifier: pkce.verifier })
+    }
+
+    /// The consent-screen URL for the browser
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub fn redirect_uri(&self) -> &str {
+        &self.redirect_uri
+    }
+
+    /// Wait for the browser to come back, then exchange the code
+    pub async fn finish(
+        self,
+        http: &Client,
+        endpoints: &Endpoints,
+        client_id: &str,
+        client_secret: &str,
+    ) -> Result<Grant> {
+        let wait = wait_for_code(&self.listener, &self.state);
+        let code = tokio::time::timeout(std::time::Duration::from_secs(REDIRECT_TIMEOUT_SECS), wait)
+            .await
+            .map_err(|_| VgoogError::Auth("Timed out waiting for browser authorization".into()))??;
+
+        exchange_code(http, endpoints, client_id, client_secret, &code, &self.redirect_uri, &self.verifier).await
+    }
+}
+
+/// Run the authorization-code flow with PKCE: a temporary listener on
+/// 127.0.0.1 catches the consent redirect, then the code is exchanged
+/// at the token endpoint
+pub async fn run(
+    http: &Client,
+    endpoints: &Endpoints,
+    client_id: &str,
+    client_secret: &str,
+    scopes: &[&str],
+    incremental: bool,
+) -> Result<Grant> {
+    let consent = PendingConsent::start(endpoints, client_id, scopes, incremental).await?;
+
+    eprintln!("\n  Open this URL in your browser to authorize vgoog:\n");
+    eprintln!("  {}\n", consent.url());
+    if open_browser(consent.url()) {
+        eprintln!("  (A browser window should have opened automatically.)");
+    }
+    eprintln!("  Waiting for authorization on {} ...\n", consent.redirect_uri());
+
+    consent.finish(http, endpoints, client_id, client_secret).await
+}
+
+/// Exchange an authorization code (plus PKCE verifier) for tokens
+pub async fn exchange_code(
+    http: &Client,
+    endpoints: &Endpoints,
+    client_id: &str,
+    client_secret: &str,
+    code: &str,
+    redirect_uri: &str,
+    verifier: &str,
+) -> Result<Grant> {
+    let token = token_request(
+        http,
+        &endpoints.token_uri,
+        &[
+            ("client_id", client_id),
+            ("client_secret", client_secret),
+            ("code", code),
+            ("code_verifier", verifier),
+            ("redirect_uri", redirect_uri),
+            ("grant_type", "authorization_code"),
+        ],
+    )
+    .await?;
+
+    token.into_grant(client_id, client_secret)
+}
+
+/// Accept connections until one carries the redirect; stray requests
+/// (favicon etc.) are answered and ignored.
+async fn wait_for_code(listener: &TcpListener, expected_state: &str) -> Result<String> {
+    loop {
+        let (mut stream, _) = listener.accept().await?;
+        let mut buf = vec![0u8; 8192];
+        let n = stream.read(&mut buf).await?;
+        let request = String::from_utf8_lossy(&buf[..n]);
+        let target = request
+            .lines()
+            .next()
+            .and_then(|line| line.split_whitespace().nth(1))
+            .unwrap_or("/");
+        let params = parse_query(target.split_once('?').map(|(_, q)| q).unwrap_or(""));
+
+        let outcome = if let Some(err) = params.get("error") {
+            Some(Err(VgoogError::Auth(format!("Authorization denied: {err}"))))
+        } else if let Some(code) = params.get("code") {
+            if params.get("state").map(String::as_str) != Some(expected_state) {
+                Some(Err(VgoogError::Auth("OAuth state mismatch".into())))
+            } else {
+                Some(Ok(code.clone()))
+            }
+        } else {
+            None
+        };
+
+        let (status, page) = match &outcome {
+            Some(Ok(_)) => ("200 OK", SUCCESS_PAGE),
+            Some(Err(_)) => ("400 Bad Request", FAILURE_PAGE),
+            None => ("404 Not Found", ""),
+        };
+        let response = format!(
+            "HTTP/1.1 {status}\r\nContent-Type: text/html; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{page}",
+            page.len()
+        );
+        let _ = stream.write_all(response.as_bytes()).await;
+        let _ = stream.shutdown().await;
+
+        if let Some(result) = outcome {
+            return result;
+        }
+    }
+}
+
+fn parse_query(query: &str) -> HashMap<String, String> {
+    query
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .map(|(k, v)| {
+            let v = v.replace('+', " ");
+            let v = urlencoding::decode(&v).map(|c| c.into_owned()).unwrap_or(v);
+            (k.to_string(), v)
+        })
+        .collect()
+}
+
+/// Best-effort attempt to open the system browser
+fn open_browser(url: &str) -> bool {
+    let mut cmd = if cfg!(target_os = "macos") {
+        let mut c = std::process::Command::new("open");
+        c.arg(url);
+        c
+    } else if cfg!(target_os = "windows") {
+        let mut c = std::process::Command::new("rundll32");
+        c.args(["url.dll,FileProtocolHandler", url]);
+        c
+    } else {
+        let mut c = std::process::Command::new("xdg-open");
+        c.arg(url);
+        c
+    };
+    cmd.stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .is_ok()
+}
@@ -0,0 +1,226 @@
+pub mod device;
+pub mod import;
+pub mod loopback;
+pub mod scopes;
+pub mod service_account;
+
+use crate::config::{AuthConfig, ConfigLock, SingleAccountConfig};
+use crate::endpoints::Endpoints;
+use crate::error::{Result, VgoogError};
+use chrono::{Duration, Utc};
+use reqwest::Client;
+use serde::Deserialize;
+
+/// Scopes requested when authorizing a new account
+pub const DEFAULT_SCOPES: &[&str] = &[
+    "https://www.googleapis.com/auth/gmail.modify",
+    "https://www.googleapis.com/auth/gmail.compose",
+    "https://www.googleapis.com/auth/gmail.settings.basic",
+    "https://www.googleapis.com/auth/gmail.settings.sharing",
+    "https://www.googleapis.com/auth/calendar",
+    "https://www.googleapis.com/auth/drive",
+    "https://www.googleapis.com/auth/spreadsheets",
+    "https://www.googleapis.com/auth/documents",
+    "https://www.googleapis.com/auth/presentations",
+    "https://www.googleapis.com/auth/forms.body",
+    "https://www.googleapis.com/auth/forms.responses.readonly",
+    "https://www.googleapis.com/auth/tasks",
+    "https://www.googleapis.com/auth/contacts",
+    "https://www.googleapis.com/auth/script.projects",
+    "https://www.googleapis.com/auth/script.processes",
+];
+
+#[derive(Deserialize)]
+pub struct TokenResponse {
+    pub access_token: String,
+    pub expires_in: i64,
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+    #[serde(default)]
+    pub token_type: String,
+    #[serde(default)]
+    pub scope: Option<String>,
+}
+
+impl TokenResponse {
+    /// Build account credentials from a fresh grant; a refresh token is required
+    pub fn into_grant(self, client_id: &str, client_secret: &str) -> Result<Grant> {
+        let scopes = self.granted_scopes();
+        let refresh_token = self.refresh_token.ok_or_else(|| {
+            VgoogError::Auth("Token endpoint returned no refresh token".into())
+        })?;
+        let auth = AuthConfig {
+            client_id: client_id.to_string(),
+            client_secret: client_secret.to_string(),
+            access_token: self.access_token,
+            refresh_token,
+            token_expiry: Utc::now() + Duration::seconds(self.expires_in),
+        };
+        Ok(Grant { auth, scopes })
+    }
+
+    /// The space-separated `scope` field as a list; empty when it was left out
+    pub fn granted_scopes(&self) -> Vec<String> {
+        self.scope.as_deref().unwrap_or_default().split_whitespace().map(String::from).collect()
+    }
+}
+
+/// Credentials from a completed consent, with the scopes Google granted
+#[derive(Debug, Clone)]
+pub struct Grant {
+    pub auth: AuthConfig,
+    pub scopes: Vec<String>,
+}
+
+#[derive(Deserialize)]
+pub struct TokenErrorResponse {
+    pub error: String,
+    pub error_description: Option<String>,
+}
+
+impl From<TokenErrorResponse> for VgoogError {
+    fn from(err: TokenErrorResponse) -> Self {
+        VgoogError::Auth(format!(
+            "{}: {}",
+            err.error,
+            err.error_description.unwrap_or_default()
+        ))
+    }
+}
+
+/// POST a form to an OAuth endpoint. OAuth-level errors (`invalid_grant`,
+/// `authorization_pending`, ...) come back as the inner `Err` so callers
+/// can react to the error code.
+pub async fn post_form<T: serde::de::DeserializeOwned>(
+    http: &Client,
+    uri: &str,
+    form: &[(&str, &str)],
+) -> Result<std::result::Result<T, TokenErrorResponse>> {
+    let resp = http.post(uri).form(form).send().await?;
+
+    if !resp.status().is_success() {
+        let status = resp.status().as_u16();
+        let body = resp.text().await.unwrap_or_default();
+        if let Ok(err) = serde_json::from_str::<TokenErrorResponse>(&body) {
+            return Ok(Err(err));
+        }
+        return Err(VgoogError::Api {
+            status,
+            message: body,
+        });
+    }
+
+    Ok(Ok(resp.json().await?))
+}
+
+/// POST a form to the token endpoint and decode the token response
+pub async fn token_request(http: &Client, token_uri: &str, form: &[(&str, &str)]) -> Result<TokenResponse> {
+    Ok(post_form(http, token_uri, form).await??)
+}
+
+/// What Google's tokeninfo endpoint knows about an access token
+#[derive(Debug, Clone, Deserialize, serde::Serialize)]
+pub struct TokenInfo {
+    /// Space-separated granted scopes
+    #[serde(default)]
+    pub scope: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    /// OAuth client the token was issued to
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub aud: Option<String>,
+    /// Seconds left, sent as a string
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_in: Option<String>,
+}
+
+impl TokenInfo {
+    pub fn scopes(&self) -> Vec<String> {
+        self.scope.split_whitespace().map(String::from).collect()
+    }
+}
+
+/// Look up an access token's scopes, expiry and owner
+pub async fn token_info(http: &Client, endpoints: &Endpoints, access_token: &str) -> Result<TokenInfo> {
+    let resp = http
+        .get(&endpoints.tokeninfo_uri)
+        .query(&[("access_token", access_token)])
+        .send()
+        .await?;
+    if !resp.status().is_success() {
+        let status = resp.status().as_u16();
+        let body = resp.text().await.unwrap_or_default();
+        return Err(match serde_json::from_str::<TokenErrorResponse>(&body) {
+            Ok(err) => err.into(),
+            Err(_) => VgoogError::Api { status, message: body },
+        });
+    }
+    Ok(resp.json().await?)
+}
+
+/// Revoke a refresh token (and the access tokens issued from it) or a
+/// single access token. Returns false if Google no longer knew the token.
+pub async fn revoke(http: &Client, endpoints: &Endpoints, token: &str) -> Result<bool> {
+    match post_form::<serde_json::Value>(http, &endpoints.revoke_uri, &[("token", token)]).await? {
+        Ok(_) => Ok(true),
+        Err(err) if err.error == "invalid_token" => Ok(false),
+        Err(err) => Err(err.into()),
+    }
+}
+
+/// Refresh access token if expired (2-minute buffer). The config lock is
+/// held throughout, so when several processes find the same token expired
+/// only the first one refreshes it and the rest pick up its result.
+pub async fn refresh_token_if_needed(http: &Client, config: &mut SingleAccountConfig) -> Result<bool> {
+    if Utc::now() < config.auth.token_expiry - Duration::minutes(2) {
+        return Ok(false);
+    }
+
+    // A config from the environment has no file to lock, reload or save into
+    let lock = match config.config_ref.ephemeral {
+        true => None,
+        false => Some(ConfigLock::acquire_async().await?),
+    };
+    if let Some(lock) = &lock {
+        if config.reload_token(lock)? {
+            return Ok(false);
+        }
+    }
+
+    if let Some(sa) = &config.service_account {
+        let (access_token, expiry) = service_account::fetch_token(http, sa, &config.endpoints).await?;
+        config.auth.access_token = access_token;
+        config.auth.token_expiry = expiry;
+        if let Some(lock) = &lock {
+            config.save(lock)?;
+        }
+        return Ok(true);
+    }
+
+    let token = token_request(
+        http,
+        &config.endpoints.token_uri,
+        &[
+            ("client_id", config.auth.client_id.as_str()),
+            ("client_secret", config.auth.client_secret.as_str()),
+            ("refresh_token", config.auth.refresh_token.as_str()),
+            ("grant_type", "refresh_token"),
+        ],
+    )
+    .await?;
+
+    let scopes = token.granted_scopes();
+    if !scopes.is_empty() {
+        config.scopes = scopes;
+    }
+    config.auth.access_token = token.access_token;
+    config.auth.token_expiry = Utc::now() + Duration::seconds(token.expires_in);
+    if let Some(rt) = token.refresh_token {
+        config.auth.refresh_token = rt;
+    }
+    if let Some(lock) = &lock {
+        config.save(lock)?;
+    }
+
+    Ok(true)
+}
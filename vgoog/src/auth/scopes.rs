@@ -0,0 +1,122 @@
+//! Which services a set of granted scopes can use.
+
+use crate::api::generated;
+use crate::error::{Result, VgoogError};
+use crate::ui::app::Service;
+use std::collections::BTreeMap;
+
+/// Prefix of every scope URL except full Gmail
+const SCOPE_PREFIX: &str = "https://www.googleapis.com/auth/";
+
+/// Full Gmail access, a superset of every `gmail.*` scope
+const FULL_GMAIL: &str = "https://mail.google.com/";
+
+/// Whether `granted` includes `needed` or a broader scope implying it:
+/// a scope implies its `.readonly` form (`drive` implies `drive.readonly`)
+/// and full Gmail implies every Gmail scope. Nothing else is assumed;
+/// `contacts` does not imply `contacts.other.readonly`.
+pub fn covers(granted: &[String], needed: &str) -> bool {
+    granted.iter().any(|g| {
+        g == needed
+            || needed.strip_prefix(g.as_str()) == Some(".readonly")
+            || (g == FULL_GMAIL && needed.starts_with("https://www.googleapis.com/auth/gmail."))
+    })
+}
+
+/// Scopes to ask for at login: those of the named services (read-only
+/// ones with `read_only`, every service when none is named) plus `extra`,
+/// where a bare name like `gmail.readonly` stands for its full URL
+pub fn for_login(services: &[String], read_only: bool, extra: &[String]) -> Result<Vec<String>> {
+    let selected = if services.is_empty() && (read_only || extra.is_empty()) {
+        Service::ALL.to_vec()
+    } else {
+        services
+            .iter()
+            .map(|key| {
+                Service::from_key(key)
+                    .ok_or_else(|| VgoogError::Config(format!("Unknown service: {key}")))
+            })
+            .collect::<Result<Vec<_>>>()?
+    };
+
+    let wanted = selected
+        .iter()
+        .flat_map(|s| if read_only { s.read_scopes() } else { s.scopes() })
+        .map(|s| s.to_string())
+        .chain(extra.iter().map(|s| {
+            if s.contains("://") {
+                s.clone()
+            } else {
+                format!("{SCOPE_PREFIX}{s}")
+            }
+        }));
+    let mut scopes: Vec<String> = Vec::new();
+    for scope in wanted {
+        if !scopes.contains(&scope) {
+            scopes.push(scope);
+        }
+    }
+    Ok(scopes)
+}
+
+/// Scopes each service still lacks, keyed by its `vgoog exec` name;
+/// services with everything they need are left out
+pub fn missing(granted: &[String]) -> BTreeMap<&'static str, Vec<&'static str>> {
+    Service::ALL
+        .iter()
+        .filter_map(|service| {
+            let lacking: Vec<&str> =
+                service.scopes().iter().copied().filter(|s| !covers(granted, s)).collect();
+            (!lacking.is_empty()).then(|| (service.key(), lacking))
+        })
+        .collect()
+}
+
+/// One line per service that is missing scopes, for people to read
+pub fn warnings(granted: &[String]) -> Vec<String> {
+    missing(granted)
+        .into_iter()
+        .map(|(service, scopes)| format!("{service} will fail with 403: missing {}", scopes.join(", ")))
+        .collect()
+}
+
+/// Scopes single actions need on top of their service's
+const ACTION_SCOPES: &[(&str, &str, &[&str])] = &[
+    ("contacts", "search_directory", &["https://www.googleapis.com/auth/directory.readonly"]),
+    ("contacts", "list_other_contacts", &["https://www.googleapis.com/auth/contacts.other.readonly"]),
+    (
+        "contacts",
+        "copy_other_contact_to_contacts",
+        &["https://www.googleapis.com/auth/contacts.other.readonly"],
+    ),
+];
+
+/// Whether `action` of `service` only reads, so the service's read-only
+/// scopes do for it. Generated actions are looked up under their own
+/// service, since another API may have a write of the same name.
+pub fn is_read(service: &str, action: &str) -> bool {
+    ["get_", "list_", "search_", "batch_get_", "download_", "export_", "query_"]
+        .iter()
+        .any(|prefix| action.starts_with(prefix))
+        || generated::READS.contains(&(service, action))
+}
+
+/// Scopes `action` of `service` needs that `granted` lacks; no action
+/// means browsing the service. Reads ask for the read-only scopes unless
+/// the full ones are already there, so permissions only grow as far as
+/// they are used.
+pub fn lacking(granted: &[String], service: Service, action: Option<&str>) -> Vec<&'static str> {
+    let extra = ACTION_SCOPES
+        .iter()
+        .filter(|(key, name, _)| *key == service.key() && Some(*name) == action)
+        .flat_map(|(_, _, scopes)| scopes.iter().copied());
+    let uncovered = |scopes: &[&'static str]| -> Vec<&'static str> {
+        scopes.iter().copied().chain(extra.clone()).filter(|s| !covers(granted, s)).collect()
+    };
+
+    let full = uncovered(service.scopes());
+    if full.is_empty() || action.is_some_and(|a| !is_read(service.key(), a)) {
+        return full;
+    }
+    uncovered(service.read_scopes())
+}
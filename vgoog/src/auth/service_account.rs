@@ -0,0 +1,140 @@
+use super::{token_request, DEFAULT_SCOPES};
+use crate::config::ServiceAccountAuth;
+use crate::endpoints::{Endpoints, DEFAULT_TOKEN_URI};
+use crate::error::{Result, VgoogError};
+use base64::engine::general_purpose::{STANDARD, URL_SAFE_NO_PAD};
+use base64::Engine;
+use chrono::{DateTime, Duration, Utc};
+use reqwest::Client;
+use serde::Deserialize;
+use serde_json::json;
+use std::path::Path;
+
+const JWT_GRANT_TYPE: &str = "urn:ietf:params:oauth:grant-type:jwt-bearer";
+
+/// Assertions are valid for the maximum Google accepts
+const ASSERTION_LIFETIME_SECS: i64 = 3600;
+
+/// The fields vgoog needs from a downloaded service-account JSON key
+#[derive(Debug, Clone, Deserialize)]
+pub struct ServiceAccountKeyFile {
+    #[serde(rename = "type")]
+    pub key_type: String,
+    pub client_email: String,
+    pub private_key: String,
+    #[serde(default)]
+    pub private_key_id: Option<String>,
+    #[serde(default)]
+    pub token_uri: Option<String>,
+}
+
+impl ServiceAccountKeyFile {
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = std::fs::read_to_string(path).map_err(|e| {
+            VgoogError::Config(format!("Cannot read service account key {}: {e}", path.display()))
+        })?;
+        let key: Self = serde_json::from_str(&content)?;
+        if key.key_type != "service_account" {
+            return Err(VgoogError::Config(format!(
+                "{} is a '{}' key, expected 'service_account'",
+                path.display(),
+                key.key_type
+            )));
+        }
+        Ok(key)
+    }
+
+    /// A configured token endpoint wins over the key's own `token_uri`
+    pub fn token_uri(&self, endpoints: &Endpoints) -> String {
+        if endpoints.token_uri_overridden() {
+            return endpoints.token_uri.clone();
+        }
+        self.token_uri
+            .clone()
+            .unwrap_or_else(|| DEFAULT_TOKEN_URI.to_string())
+    }
+}
+
+/// Build and sign an RS256 JWT assertion for the token endpoint
+pub fn sign_assertion(
+    key: &ServiceAccountKeyFile,
+    scopes: &[String],
+    subject: Option<&str>,
+    audience: &str,
+    now: DateTime<Utc>,
+) -> Result<String> {
+    let mut header = json!({ "alg": "RS256", "typ": "JWT" });
+    if let Some(kid) = &key.private_key_id {
+        header["kid"] = json!(kid);
+    }
+    let iat = now.timestamp();
+    let mut claims = json!({
+        "iss": key.client_email,
+        "scope": scopes.join(" "),
+        "aud": audience,
+        "iat": iat,
+        "exp": iat + ASSERTION_LIFETIME_SECS,
+    });
+    if let Some(sub) = subject {
+        claims["sub"] = json!(sub);
+    }
+
+    let signing_input = format!(
+        "{}.{}",
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(&header)?),
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(&claims)?)
+    );
+
+    let der = pem_to_der(&key.private_key)?;
+    let key_pair = ring::signature::RsaKeyPair::from_pkcs8(&der)
+        .map_err(|e| VgoogError::Auth(format!("Invalid service account private key: {e}")))?;
+    let mut signature = vec![0u8; key_pair.public().modulus_len()];
+    key_pair
+        .sign(
+            &ring::signature::RSA_PKCS1_SHA256,
+            &ring::rand::SystemRandom::new(),
+            signing_input.as_bytes(),
+            &mut signature,
+        )
+        .map_err(|_| VgoogError::Auth("Failed to sign JWT assertion".into()))?;
+
+    Ok(format!("{signing_input}.{}", URL_SAFE_NO_PAD.encode(signature)))
+}
+
+pub(crate) fn pem_to_der(pem: &str) -> Result<Vec<u8>> {
+    let body: String = pem
+        .lines()
+        .map(str::trim)
+        .filter(|l| !l.is_empty() && !l.starts_with("-----"))
+        .collect();
+    STANDARD
+        .decode(body)
+        .map_err(|e| VgoogError::Auth(format!("Invalid PEM private key: {e}")))
+}
+
+/// Exchange a signed assertion for an access token. Returns the token and
+/// its expiry; service accounts never receive refresh tokens.
+pub async fn fetch_token(
+    http: &Client,
+    sa: &ServiceAccountAuth,
+    endpoints: &Endpoints,
+) -> Result<(String, DateTime<Utc>)> {
+    let key = ServiceAccountKeyFile::load(&sa.key_file)?;
+    let scopes: Vec<String> = if sa.scopes.is_empty() {
+        DEFAULT_SCOPES.iter().map(|s| s.to_string()).collect()
+    } else {
+        sa.scopes.clone()
+    };
+    let token_uri = key.token_uri(endpoints);
+    let now = Utc::now();
+    let assertion = sign_assertion(&key, &scopes, sa.subject.as_deref(), &token_uri, now)?;
+
+    let token = token_request(
+        http,
+        &token_uri,
+        &[("grant_type", JWT_GRANT_TYPE), ("assertion", assertion.as_str())],
+    )
+    .await?;
+
+    Ok((token.access_token, now + Duration::seconds(token.expires_in)))
+}
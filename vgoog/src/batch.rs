@@ -0,0 +1,171 @@
+//! Google batch HTTP: many API calls packed into one `multipart/mixed` request
+//! to a service's batch endpoint, with one result per call.
+
+use crate::error::{Result, VgoogError};
+use reqwest::Method;
+use serde_json::Value;
+
+/// Most sub-requests Google accepts in one batch call
+pub const MAX_BATCH_SIZE: usize = 100;
+
+/// One call inside a batch; `url` is the same absolute URL the call would be
+/// sent to on its own
+#[derive(Debug, Clone)]
+pub struct BatchRequest {
+    pub method: Method,
+    pub url: String,
+    pub body: Option<Value>,
+}
+
+impl BatchRequest {
+    pub fn new(method: Method, url: impl Into<String>, body: Option<Value>) -> Self {
+        Self {
+            method,
+            url: url.into(),
+            body,
+        }
+    }
+
+    pub fn get(url: impl Into<String>) -> Self {
+        Self::new(Method::GET, url, None)
+    }
+
+    pub fn post(url: impl Into<String>, body: Value) -> Self {
+        Self::new(Method::POST, url, Some(body))
+    }
+
+    pub fn put(url: impl Into<String>, body: Value) -> Self {
+        Self::new(Method::PUT, url, Some(body))
+    }
+
+    pub fn patch(url: impl Into<String>, body: Value) -> Self {
+        Self::new(Method::PATCH, url, Some(body))
+    }
+
+    pub fn delete(url: impl Into<String>) -> Self {
+        Self::new(Method::DELETE, url, None)
+    }
+}
+
+/// Results as CLI JSON: `{"ok":true,"data":...}` or the error object, in
+/// request order
+pub fn results_to_json(results: Vec<Result<Value>>) -> Value {
+    Value::Array(
+        results
+            .into_iter()
+            .map(|r| match r {
+                Ok(data) => serde_json::json!({ "ok": true, "data": data }),
+                Err(e) => e.to_json(),
+            })
+            .collect(),
+    )
+}
+
+/// The `multipart/mixed` body for `requests`; parts are numbered from 1 via
+/// `Content-ID: <item-N>`
+pub(crate) fn encode(boundary: &str, requests: &[BatchRequest]) -> Result<Vec<u8>> {
+    let mut out = String::new();
+    for (i, request) in requests.iter().enumerate() {
+        let url = reqwest::Url::parse(&request.url)
+            .map_err(|e| VgoogError::Other(format!("Invalid batch URL {}: {e}", request.url)))?;
+        let target = match url.query() {
+            Some(query) => format!("{}?{query}", url.path()),
+            None => url.path().to_string(),
+        };
+
+        out.push_str(&format!("--{boundary}\r\n"));
+        out.push_str("Content-Type: application/http\r\n");
+        out.push_str(&format!("Content-ID: <item-{}>\r\n\r\n", i + 1));
+        out.push_str(&format!("{} {target} HTTP/1.1\r\n", request.method));
+        match &request.body {
+            Some(body) => {
+                let json = serde_json::to_string(body)?;
+                out.push_str("Content-Type: application/json; charset=UTF-8\r\n");
+                out.push_str(&format!("Content-Length: {}\r\n\r\n{json}\r\n", json.len()));
+            }
+            None => out.push_str("\r\n\r\n"),
+        }
+    }
+    out.push_str(&format!("--{boundary}--\r\n"));
+    Ok(out.into_bytes())
+}
+
+/// Split a batch response into `count` results, matched to requests by
+/// `Content-ID: <response-item-N>` (falling back to part order)
+pub(crate) fn decode(content_type: &str, body: &[u8], count: usize) -> Result<Vec<Result<Value>>> {
+    let boundary = boundary(content_type).ok_or_else(|| {
+        VgoogError::Other(format!("Batch response is not multipart: {content_type}"))
+    })?;
+    let body = String::from_utf8_lossy(body);
+    let delimiter = format!("--{boundary}");
+
+    let mut results: Vec<Option<Result<Value>>> = (0..count).map(|_| None).collect();
+    for (position, part) in body.split(delimiter.as_str()).skip(1).enumerate() {
+        if part.starts_with("--") {
+            break;
+        }
+        let (part_headers, message) = split_head(part.trim_start_matches(['\r', '\n']));
+        let index = header(part_headers, "content-id")
+            .and_then(|id| id.trim_matches(['<', '>']).strip_prefix("response-item-")?.parse::<usize>().ok())
+            .and_then(|n| n.checked_sub(1))
+            .unwrap_or(position);
+        if let Some(slot) = results.get_mut(index) {
+            *slot = Some(parse_response(message));
+        }
+    }
+
+    Ok(results
+        .into_iter()
+        .enumerate()
+        .map(|(i, r)| {
+            r.unwrap_or_else(|| Err(VgoogError::Other(format!("No response for batch item {}", i + 1))))
+        })
+        .collect())
+}
+
+/// One embedded `HTTP/1.1 <status>` response
+fn parse_response(message: &str) -> Result<Value> {
+    let (head, body) = split_head(message);
+    let mut lines = head.lines();
+    let status: u16 = lines
+        .next()
+        .and_then(|line| line.split_whitespace().nth(1))
+        .and_then(|code| code.parse().ok())
+        .ok_or_else(|| VgoogError::Other("Malformed response in batch".into()))?;
+    let body = body.trim_end_matches(['\r', '\n']);
+
+    if (200..300).contains(&status) {
+        if body.trim().is_empty() {
+            return Ok(Value::Null);
+        }
+        return Ok(serde_json::from_str(body)?);
+    }
+    let retry_after = header(head, "retry-after").and_then(|v| v.parse().ok());
+    Err(VgoogError::from_api_response(
+        status,
+        retry_after,
+        header(head, "www-authenticate"),
+        body,
+    ))
+}
+
+fn boundary(content_type: &str) -> Option<&str> {
+    content_type
+        .split(';')
+        .find_map(|p| p.trim().strip_prefix("boundary="))
+        .map(|b| b.trim_matches('"'))
+}
+
+/// Split headers from what follows the first blank line
+fn split_head(text: &str) -> (&str, &str) {
+    text.split_once("\r\n\r\n")
+        .or_else(|| text.split_once("\n\n"))
+        .unwrap_or((text, ""))
+}
+
+fn header<'a>(head: &'a str, name: &str) -> Option<&'a str> {
+    head.lines()
+        .filter_map(|line| line.split_once(':'))
+        .find(|(k, _)| k.trim().eq_ignore_ascii_case(name))
+        .map(|(_, v)| v.trim())
+}
@@ -0,0 +1,253 @@
+//! On-disk cache of GET responses for resources that rarely change, such
+//! as labels, calendar lists, task lists and Drive folder listings, keyed by account and URL.
+//! A response younger than its resource's TTL is served without a request.
+//! Once it is older, it is revalidated with `If-None-Match`, and a 304 serves it again.
+//! Any other request to a resource drops what is cached for it.
+
+use crate::config::{self, Config};
+use crate::endpoints::Endpoints;
+use crate::error::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+/// Cache directory in the config dir, one subdirectory per account
+pub const DIR: &str = "cache";
+
+/// Responses larger than this are not kept
+const MAX_ENTRY_BYTES: usize = 1024 * 1024;
+
+/// Resources that are cached and the seconds a response is used before it
+/// is revalidated, by `<api>.<resource>` (see `resource`)
+pub const DEFAULT_TTLS: &[(&str, u64)] = &[
+    ("gmail.labels", 300),
+    ("calendar.calendarList", 300),
+    ("tasks.lists", 300),
+    ("drive.folders", 60),
+];
+
+/// Writes to the first resource change the second as well
+const LINKED: &[(&str, &str)] = &[
+    ("calendar.calendars", "calendar.calendarList"),
+    ("drive.files", "drive.folders"),
+];
+
+/// Response cache settings (`[cache]` in config.toml)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CacheConfig {
+    /// `false` fetches everything from Google, like `--no-cache`
+    pub enabled: bool,
+    /// TTLs in seconds, by resource, on top of `DEFAULT_TTLS`. Listing a
+    /// resource caches it; 0 revalidates on every use.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub ttl_secs: BTreeMap<String, u64>,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self { enabled: true, ttl_secs: BTreeMap::new() }
+    }
+}
+
+impl CacheConfig {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// The defaults with `ttl_secs` applied on top
+    pub fn ttls(&self) -> BTreeMap<String, u64> {
+        let mut ttls: BTreeMap<String, u64> =
+            DEFAULT_TTLS.iter().map(|(resource, ttl)| (resource.to_string(), *ttl)).collect();
+        ttls.extend(self.ttl_secs.iter().map(|(resource, ttl)| (resource.clone(), *ttl)));
+        ttls
+    }
+}
+
+/// The resource a request URL belongs to, as `<api>.<resource>`: the API
+/// whose base URL it starts with and the last collection in its path, so
+/// `.../gmail/v1/users/me/labels/Label_1` is `gmail.labels`. Drive folder
+/// listings are `drive.folders`, apart from the rest of `drive.files`.
+pub fn resource(endpoints: &Endpoints, url: &str) -> Option<String> {
+    let resource = collections(endpoints, url).and_then(|(api, collections)| Some(format!("{api}.{}", collections.last()?)))?;
+    if resource == "drive.files" && is_folder_listing(endpoints, url) {
+        return Some("drive.folders".to_string());
+    }
+    Some(resource)
+}
+
+/// Whether `url` lists the children of a folder (`files?q='<id>' in parents`),
+/// which is all of Drive worth caching: searches, single files and their
+/// `md5Checksum` have to be current
+fn is_folder_listing(endpoints: &Endpoints, url: &str) -> bool {
+    let Some(rest) = url.strip_prefix(endpoints.drive.as_str()) else {
+        return false;
+    };
+    let (path, query) = rest.split_once('?').unwrap_or((rest, ""));
+    path.trim_end_matches('/') == "/files"
+        && query.split('&').filter_map(|pair| pair.strip_prefix("q=")).any(|q| {
+            urlencoding::decode(&q.replace('+', " ")).is_ok_and(|q| q.contains(" in parents"))
+        })
+}
+
+/// Every collection along a URL's path (`files` and `permissions` for
+/// `files/<id>/permissions/<id>`), so a write drops all of them; a custom
+/// method such as `files/<id>/copy` passes for one, which only costs a
+/// refetch
+fn touched(endpoints: &Endpoints, url: &str) -> Vec<String> {
+    let Some((api, collections)) = collections(endpoints, url) else {
+        return Vec::new();
+    };
+    let mut resources: Vec<String> = collections.iter().map(|c| format!("{api}.{c}")).collect();
+    for (written, linked) in LINKED {
+        if resources.iter().any(|r| r == written) {
+            resources.push(linked.to_string());
+        }
+    }
+    resources
+}
+
+/// The API of a URL and the collection names in its path: the segments
+/// at even positions below the API's base URL
+fn collections<'u>(endpoints: &Endpoints, url: &'u str) -> Option<(&'static str, Vec<&'u str>)> {
+    let bases = [
+        (&endpoints.gmail, "gmail"),
+        (&endpoints.calendar, "calendar"),
+        (&endpoints.drive, "drive"),
+        (&endpoints.drive_upload, "drive"),
+        (&endpoints.sheets, "sheets"),
+        (&endpoints.docs, "docs"),
+        (&endpoints.slides, "slides"),
+        (&endpoints.forms, "forms"),
+        (&endpoints.tasks, "tasks"),
+        (&endpoints.people, "people"),
+        (&endpoints.script, "script"),
+    ];
+    let (rest, api) = bases
+        .into_iter()
+        .filter_map(|(base, api)| {
+            let rest = url.strip_prefix(base.as_str())?;
+            (rest.is_empty() || rest.starts_with(['/', '?'])).then_some((base.len(), rest, api))
+        })
+        .max_by_key(|(len, _, _)| *len)
+        .map(|(_, rest, api)| (rest, api))?;
+    let path = rest.split('?').next().unwrap_or_default();
+    let collections = path.split('/').filter(|s| !s.is_empty()).step_by(2).collect();
+    Some((api, collections))
+}
+
+/// A cached response
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Entry {
+    pub url: String,
+    /// From the `ETag` header, or the body's `etag` field
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub etag: Option<String>,
+    /// When the response was fetched or last revalidated
+    pub stored_at: DateTime<Utc>,
+    pub body: Value,
+}
+
+pub struct ResponseCache {
+    dir: PathBuf,
+    ttls: BTreeMap<String, u64>,
+}
+
+impl ResponseCache {
+    /// The cache for `config` in the config dir. `None` when it is turned
+    /// off, or for environment-only configs, which leave nothing on disk.
+    pub fn open(config: &Config) -> Result<Option<Self>> {
+        if !config.cache.enabled || config.ephemeral {
+            return Ok(None);
+        }
+        Ok(Some(Self::at(Config::config_dir()?.join(DIR), config.cache.ttls())))
+    }
+
+    pub fn at(dir: PathBuf, ttls: BTreeMap<String, u64>) -> Self {
+        Self { dir, ttls }
+    }
+
+    /// How long responses of `resource` are used; `None` if it is not cached
+    pub fn ttl(&self, resource: &str) -> Option<u64> {
+        self.ttls.get(resource).copied()
+    }
+
+    /// Whether `entry` may be served without asking Google
+    pub fn is_fresh(&self, resource: &str, entry: &Entry) -> bool {
+        let age = Utc::now().signed_duration_since(entry.stored_at).num_seconds();
+        self.ttl(resource).is_some_and(|ttl| age >= 0 && (age as u64) < ttl)
+    }
+
+    /// The cached response to `url`, if any; unreadable entries count as none
+    pub fn lookup(&self, account: &str, resource: &str, url: &str) -> Option<Entry> {
+        let text = std::fs::read_to_string(self.entry_path(account, resource, url)).ok()?;
+        serde_json::from_str::<Entry>(&text).ok().filter(|entry| entry.url == url)
+    }
+
+    pub fn store(&self, account: &str, resource: &str, entry: &Entry) -> Result<()> {
+        let text = serde_json::to_string(entry)?;
+        if text.len() > MAX_ENTRY_BYTES {
+            return Ok(());
+        }
+        // Bodies are mail, files and events, so every level stays private
+        let account_dir = self.account_dir(account);
+        for dir in [&self.dir, &account_dir, &account_dir.join(resource)] {
+            config::create_private_dir(dir)?;
+        }
+        config::write_private(&self.entry_path(account, resource, &entry.url), text.as_bytes())
+    }
+
+    /// Drop what is cached for the resources a request to `url` may have changed
+    pub fn invalidate(&self, endpoints: &Endpoints, account: &str, url: &str) {
+        for resource in touched(endpoints, url) {
+            let _ = std::fs::remove_dir_all(self.account_dir(account).join(resource));
+        }
+    }
+
+    fn account_dir(&self, account: &str) -> PathBuf {
+        self.dir.join(digest(account))
+    }
+
+    fn entry_path(&self, account: &str, resource: &str, url: &str) -> PathBuf {
+        self.account_dir(account).join(resource).join(format!("{}.json", digest(url)))
+    }
+}
+
+/// Remove cached responses of one account, or of all; the number removed
+pub fn clear(account: Option<&str>) -> Result<usize> {
+    let dir = Config::config_dir()?.join(DIR);
+    let target = match account {
+        Some(account) => dir.join(digest(account)),
+        None => dir,
+    };
+    let removed = count_entries(&target);
+    match std::fs::remove_dir_all(&target) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.into()),
+        _ => Ok(removed),
+    }
+}
+
+fn count_entries(dir: &Path) -> usize {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| {
+            let path = entry.path();
+            if path.is_dir() {
+                count_entries(&path)
+            } else {
+                usize::from(path.extension().is_some_and(|ext| ext == "json"))
+            }
+        })
+        .sum()
+}
+
+/// File name for an account or URL: hex of the first half of its SHA-256
+fn digest(text: &str) -> String {
+    let digest = ring::digest::digest(&ring::digest::SHA256, text.as_bytes());
+    digest.as_ref()[..16].iter().map(|b| format!("{b:02x}")).collect()
+}
@@ -0,0 +1,319 @@
+//! Record/replay of API traffic.
+//!
+//! In record mode every request `GoogleClient` sends, and the response it got,
+//! is appended to a JSON cassette file with credentials redacted. In replay
+//! mode responses are served from the cassette and the network (including
+//! token refresh) is never touched, so a recorded `vgoog exec` run or TUI
+//! session can be reproduced exactly.
+
+use crate::error::{Result, VgoogError};
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// Stand-in for credentials in recorded requests, and the bearer token sent
+/// while replaying
+pub const REDACTED: &str = "[REDACTED]";
+
+/// Query parameters that carry credentials
+const SECRET_PARAMS: &[&str] = &["access_token", "key"];
+
+/// Response headers that describe the original transfer rather than the
+/// response, and would be wrong for the re-encoded body
+const SKIPPED_RESPONSE_HEADERS: &[&str] = &[
+    "content-length",
+    "transfer-encoding",
+    "connection",
+    "date",
+    "set-cookie",
+    "alt-svc",
+    "server",
+];
+
+/// Which cassette to use and how
+#[derive(Debug, Clone, PartialEq)]
+pub enum CassetteMode {
+    /// Send requests and append every exchange to the file
+    Record(PathBuf),
+    /// Answer requests from the file without touching the network
+    Replay(PathBuf),
+}
+
+impl CassetteMode {
+    /// `VGOOG_RECORD=<file>` or `VGOOG_REPLAY=<file>`
+    pub fn from_env() -> Result<Option<Self>> {
+        let record = std::env::var_os("VGOOG_RECORD").filter(|v| !v.is_empty());
+        let replay = std::env::var_os("VGOOG_REPLAY").filter(|v| !v.is_empty());
+        match (record, replay) {
+            (Some(_), Some(_)) => Err(VgoogError::Config(
+                "VGOOG_RECORD and VGOOG_REPLAY cannot both be set".into(),
+            )),
+            (Some(path), None) => Ok(Some(Self::Record(path.into()))),
+            (None, Some(path)) => Ok(Some(Self::Replay(path.into()))),
+            (None, None) => Ok(None),
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct CassetteFile {
+    interactions: Vec<Interaction>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Interaction {
+    request: RecordedRequest,
+    response: RecordedResponse,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecordedRequest {
+    method: String,
+    url: String,
+    #[serde(default)]
+    headers: BTreeMap<String, String>,
+    /// JSON or text body; `null` for empty and streamed (upload) bodies
+    #[serde(default)]
+    body: Value,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RecordedResponse {
+    status: u16,
+    #[serde(default)]
+    headers: BTreeMap<String, String>,
+    #[serde(flatten)]
+    body: RecordedBody,
+}
+
+/// Stored as `"json"`, `"text"` or `"base64"` next to the status
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum RecordedBody {
+    Json(Value),
+    Text(String),
+    Base64(String),
+}
+
+impl RecordedBody {
+    fn encode(content_type: &str, bytes: &[u8]) -> Self {
+        if content_type.contains("json") {
+            if let Ok(value) = serde_json::from_slice(bytes) {
+                return Self::Json(value);
+            }
+        }
+        match std::str::from_utf8(bytes) {
+            Ok(text) => Self::Text(text.to_string()),
+            Err(_) => Self::Base64(base64::engine::general_purpose::STANDARD.encode(bytes)),
+        }
+    }
+
+    fn decode(&self) -> Result<Vec<u8>> {
+        Ok(match self {
+            Self::Json(value) => serde_json::to_vec(value)?,
+            Self::Text(text) => text.clone().into_bytes(),
+            Self::Base64(data) => base64::engine::general_purpose::STANDARD
+                .decode(data)
+                .map_err(|e| VgoogError::Other(format!("Corrupt cassette body: {e}")))?,
+        })
+    }
+}
+
+struct State {
+    file: CassetteFile,
+    /// Replay: which interactions have been served
+    played: Vec<bool>,
+}
+
+/// An open cassette shared by every request a client sends
+pub struct Cassette {
+    mode: CassetteMode,
+    state: Mutex<State>,
+}
+
+impl Cassette {
+    /// Open a cassette; recording appends to an existing file
+    pub fn open(mode: CassetteMode) -> Result<Self> {
+        let file = match &mode {
+            CassetteMode::Record(path) if !path.exists() => CassetteFile::default(),
+            CassetteMode::Record(path) | CassetteMode::Replay(path) => load(path)?,
+        };
+        let played = vec![false; file.interactions.len()];
+        Ok(Self {
+            mode,
+            state: Mutex::new(State { file, played }),
+        })
+    }
+
+    pub fn is_replay(&self) -> bool {
+        matches!(self.mode, CassetteMode::Replay(_))
+    }
+
+    fn path(&self) -> &Path {
+        match &self.mode {
+            CassetteMode::Record(path) | CassetteMode::Replay(path) => path,
+        }
+    }
+
+    /// Send `request` through the cassette. The inner result is the network
+    /// outcome when recording; failed sends are not recorded.
+    pub async fn send(
+        &self,
+        http: &reqwest::Client,
+        request: reqwest::Request,
+    ) -> Result<reqwest::Result<reqwest::Response>> {
+        if self.is_replay() {
+            return self.replay(&request).map(Ok);
+        }
+
+        let recorded_request = record_request(&request);
+        let resp = match http.execute(request).await {
+            Ok(resp) => resp,
+            Err(e) => return Ok(Err(e)),
+        };
+        let status = resp.status().as_u16();
+        let headers: BTreeMap<String, String> = resp
+            .headers()
+            .iter()
+            .filter(|(name, _)| !SKIPPED_RESPONSE_HEADERS.contains(&name.as_str()))
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+            .collect();
+        let bytes = match resp.bytes().await {
+            Ok(bytes) => bytes,
+            Err(e) => return Ok(Err(e)),
+        };
+        let content_type = headers.get("content-type").map_or("", String::as_str);
+        let response = RecordedResponse {
+            status,
+            body: RecordedBody::encode(content_type, &bytes),
+            headers,
+        };
+
+        let rebuilt = build_response(&response, bytes.to_vec())?;
+        let mut state = self.state.lock().expect("cassette state poisoned");
+        state.file.interactions.push(Interaction {
+            request: recorded_request,
+            response,
+        });
+        save(self.path(), &state.file)?;
+        Ok(Ok(rebuilt))
+    }
+
+    /// The first unplayed interaction with the same method, path and query;
+    /// the host is ignored so a cassette survives endpoint changes
+    fn replay(&self, request: &reqwest::Request) -> Result<reqwest::Response> {
+        let method = request.method().as_str();
+        let target = match_target(request.url().as_str());
+        let mut state = self.state.lock().expect("cassette state poisoned");
+        let State { file, played } = &mut *state;
+        let index = file
+            .interactions
+            .iter()
+            .enumerate()
+            .position(|(i, interaction)| {
+                !played[i]
+                    && interaction.request.method == method
+                    && match_target(&interaction.request.url) == target
+            })
+            .ok_or_else(|| {
+                VgoogError::Other(format!(
+                    "No recorded response for {method} {target} in cassette {}",
+                    self.path().display()
+                ))
+            })?;
+        played[index] = true;
+        let response = &file.interactions[index].response;
+        build_response(response, response.body.decode()?)
+    }
+}
+
+fn load(path: &Path) -> Result<CassetteFile> {
+    let content = std::fs::read_to_string(path).map_err(|e| {
+        VgoogError::Config(format!("Cannot read cassette {}: {e}", path.display()))
+    })?;
+    serde_json::from_str(&content).map_err(|e| {
+        VgoogError::Config(format!("Invalid cassette {}: {e}", path.display()))
+    })
+}
+
+fn save(path: &Path, file: &CassetteFile) -> Result<()> {
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(file)?)?;
+    Ok(())
+}
+
+fn record_request(request: &reqwest::Request) -> RecordedRequest {
+    let mut url = request.url().clone();
+    redact_query(&mut url);
+
+    let headers = request
+        .headers()
+        .iter()
+        .filter_map(|(name, value)| {
+            let value = match name.as_str() {
+                "authorization" => format!("Bearer {REDACTED}"),
+                "x-goog-api-key" | "cookie" => REDACTED.to_string(),
+                _ => value.to_str().ok()?.to_string(),
+            };
+            Some((name.to_string(), value))
+        })
+        .collect();
+
+    let body = request
+        .body()
+        .and_then(|b| b.as_bytes())
+        .filter(|b| !b.is_empty())
+        .map_or(Value::Null, |bytes| {
+            serde_json::from_slice(bytes)
+                .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(bytes).into_owned()))
+        });
+
+    RecordedRequest {
+        method: request.method().to_string(),
+        url: url.to_string(),
+        headers,
+        body,
+    }
+}
+
+fn build_response(recorded: &RecordedResponse, body: Vec<u8>) -> Result<reqwest::Response> {
+    let mut builder = http::Response::builder().status(recorded.status);
+    for (name, value) in &recorded.headers {
+        builder = builder.header(name, value);
+    }
+    let response = builder
+        .body(body)
+        .map_err(|e| VgoogError::Other(format!("Invalid recorded response: {e}")))?;
+    Ok(reqwest::Response::from(response))
+}
+
+fn redact_query(url: &mut reqwest::Url) {
+    if url.query_pairs().any(|(k, _)| SECRET_PARAMS.contains(&k.as_ref())) {
+        let pairs: Vec<(String, String)> = url
+            .query_pairs()
+            .map(|(k, v)| {
+                let v = if SECRET_PARAMS.contains(&k.as_ref()) { REDACTED.into() } else { v.into_owned() };
+                (k.into_owned(), v)
+            })
+            .collect();
+        url.query_pairs_mut().clear().extend_pairs(pairs);
+    }
+}
+
+/// `https://host/a/b?x=1` → `/a/b?x=1`, with credential parameters redacted
+/// so live requests line up with recorded ones
+fn match_target(url: &str) -> String {
+    let Ok(mut url) = reqwest::Url::parse(url) else {
+        return url.to_string();
+    };
+    redact_query(&mut url);
+    match url.query() {
+        Some(query) => format!("{}?{query}", url.path()),
+        None => url.path().to_string(),
+    }
+}
@@ -1,3 +1,4 @@
+use crate::actions::{Action, Arg, Literal};
 use crate::api::apps_script::AppsScriptApi;
 use crate::client::GoogleClient;
 use crate::error::{Result, VgoogError};
@@ -11,26 +12,90 @@ fn so<'a>(args: &'a Value, key: &str) -> Option<&'a str> {
     args.get(key).and_then(|v| v.as_str())
 }
 
-fn u(args: &Value, key: &str, default: u32) -> u32 {
-    args.get(key).and_then(|v| v.as_u64()).map(|v| v as u32).unwrap_or(default)
+fn u(args: &Value, key: &str) -> u32 {
+    args.get(key).and_then(|v| v.as_u64()).map(|v| v as u32).unwrap_or_default()
 }
 
-fn i(args: &Value, key: &str, default: i64) -> i64 {
-    args.get(key).and_then(|v| v.as_i64()).unwrap_or(default)
+fn i(args: &Value, key: &str) -> i64 {
+    args.get(key).and_then(|v| v.as_i64()).unwrap_or_default()
 }
 
 fn io(args: &Value, key: &str) -> Option<i64> {
     args.get(key).and_then(|v| v.as_i64())
 }
 
-fn b(args: &Value, key: &str, default: bool) -> bool {
-    args.get(key).and_then(|v| v.as_bool()).unwrap_or(default)
+fn b(args: &Value, key: &str) -> bool {
+    args.get(key).and_then(|v| v.as_bool()).unwrap_or_default()
 }
 
 fn val_array(args: &Value, key: &str) -> Vec<Value> {
     args.get(key).and_then(|v| v.as_array()).cloned().unwrap_or_default()
 }
 
+const SCRIPT_ID: Arg = Arg::string("script_id", "Script project ID").required();
+const DEPLOYMENT_ID: Arg = Arg::string("deployment_id", "Deployment ID").required();
+const VERSION_NUMBER: Arg = Arg::integer("version_number", "Version number").default(Literal::Int(1));
+const DESCRIPTION: Arg = Arg::string("description", "Description");
+const PAGE_SIZE: Arg = Arg::integer("page_size", "Results per page").default(Literal::Int(20));
+const PAGE_TOKEN: Arg = Arg::string("page_token", "nextPageToken of the previous page");
+
+pub const ACTIONS: &[Action] = &[
+    Action::new(
+        "create_project",
+        "Create a script project",
+        &[
+            Arg::string("title", "Project title").required(),
+            Arg::string("parent_id", "Drive file the script is bound to, such as a spreadsheet"),
+        ],
+    ),
+    Action::new("get_project", "Get a project's metadata", &[SCRIPT_ID]),
+    Action::new(
+        "get_content",
+        "Get a project's source files",
+        &[SCRIPT_ID, Arg::integer("version", "Version to get; the head if left out")],
+    ),
+    Action::new(
+        "update_content",
+        "Replace a project's source files",
+        &[SCRIPT_ID, Arg::array("files", "File objects, each with `name`, `type` and `source`").required()],
+    ),
+    Action::new(
+        "get_metrics",
+        "Get usage metrics of a project",
+        &[SCRIPT_ID, Arg::object("filter", "Metrics filter, such as `{\"deploymentId\": ...}`")],
+    ),
+    Action::new("list_versions", "List a project's versions", &[SCRIPT_ID, PAGE_SIZE, PAGE_TOKEN]).items("versions"),
+    Action::new("create_version", "Snapshot the project's current code as a version", &[SCRIPT_ID, DESCRIPTION]),
+    Action::new("get_version", "Get a version", &[SCRIPT_ID, VERSION_NUMBER]),
+    Action::new(
+        "list_deployments",
+        "List a project's deployments",
+        &[SCRIPT_ID, PAGE_SIZE, PAGE_TOKEN],
+    )
+    .items("deployments"),
+    Action::new("create_deployment", "Deploy a version", &[SCRIPT_ID, VERSION_NUMBER, DESCRIPTION]),
+    Action::new("get_deployment", "Get a deployment", &[SCRIPT_ID, DEPLOYMENT_ID]),
+    Action::new("update_deployment", "Point a deployment at another version", &[SCRIPT_ID, DEPLOYMENT_ID, VERSION_NUMBER, DESCRIPTION]),
+    Action::new("delete_deployment", "Delete a deployment", &[SCRIPT_ID, DEPLOYMENT_ID]),
+    Action::new(
+        "run",
+        "Run a function of a project deployed as an API executable",
+        &[
+            SCRIPT_ID,
+            Arg::string("function_name", "Function to call").required(),
+            Arg::array("parameters", "Arguments to pass to the function"),
+            Arg::boolean("dev_mode", "Run the latest saved code instead of the deployed version").default(Literal::Bool(false)),
+        ],
+    ),
+    Action::new("list_processes", "List the user's script executions", &[PAGE_SIZE, PAGE_TOKEN]).items("processes"),
+    Action::new(
+        "list_script_processes",
+        "List the executions of a project",
+        &[SCRIPT_ID, PAGE_SIZE, PAGE_TOKEN],
+    )
+    .items("processes"),
+];
+
 pub async fn execute(client: &GoogleClient, action: &str, args: Value) -> Result<Value> {
     let api = AppsScriptApi::new(client);
     match action {
@@ -45,27 +110,27 @@ pub async fn execute(client: &GoogleClient, action: &str, args: Value) -> Result
             let filter = args.get("filter");
             api.get_metrics(s(&args, "script_id"), filter).await
         }
-        "list_versions" => api.list_versions(s(&args, "script_id"), u(&args, "page_size", 20), so(&args, "page_token")).await,
+        "list_versions" => api.list_versions(s(&args, "script_id"), u(&args, "page_size"), so(&args, "page_token")).await,
         "create_version" => api.create_version(s(&args, "script_id"), s(&args, "description")).await,
-        "get_version" => api.get_version(s(&args, "script_id"), i(&args, "version_number", 1)).await,
-        "list_deployments" => api.list_deployments(s(&args, "script_id"), u(&args, "page_size", 20), so(&args, "page_token")).await,
+        "get_version" => api.get_version(s(&args, "script_id"), i(&args, "version_number")).await,
+        "list_deployments" => api.list_deployments(s(&args, "script_id"), u(&args, "page_size"), so(&args, "page_token")).await,
         "create_deployment" => api.create_deployment(
-            s(&args, "script_id"), i(&args, "version_number", 1), s(&args, "description"),
+            s(&args, "script_id"), i(&args, "version_number"), s(&args, "description"),
         ).await,
         "get_deployment" => api.get_deployment(s(&args, "script_id"), s(&args, "deployment_id")).await,
         "update_deployment" => api.update_deployment(
             s(&args, "script_id"), s(&args, "deployment_id"),
-            i(&args, "version_number", 1), s(&args, "description"),
+            i(&args, "version_number"), s(&args, "description"),
         ).await,
         "delete_deployment" => api.delete_deployment(s(&args, "script_id"), s(&args, "deployment_id")).await,
         "run" => {
             let params = args.get("parameters").and_then(|v| v.as_array());
             let params_ref = params.map(|p| p.as_slice());
-            api.run(s(&args, "script_id"), s(&args, "function_name"), params_ref, b(&args, "dev_mode", false)).await
+            api.run(s(&args, "script_id"), s(&args, "function_name"), params_ref, b(&args, "dev_mode")).await
         }
-        "list_processes" => api.list_processes(u(&args, "page_size", 20), so(&args, "page_token")).await,
+        "list_processes" => api.list_processes(u(&args, "page_size"), so(&args, "page_token")).await,
         "list_script_processes" => api.list_script_processes(
-            s(&args, "script_id"), u(&args, "page_size", 20), so(&args, "page_token"),
+            s(&args, "script_id"), u(&args, "page_size"), so(&args, "page_token"),
         ).await,
         _ => Err(VgoogError::Other(format!("Unknown apps_script action: {action}"))),
     }
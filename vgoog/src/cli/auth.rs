@@ -0,0 +1,94 @@
+use crate::auth::{self, scopes};
+use crate::cache;
+use crate::client::GoogleClient;
+use crate::error::Result;
+use chrono::Utc;
+use serde_json::{json, Value};
+
+/// Granted scopes, expiry and email of `account`, or of every account,
+/// with the services each one lacks scopes for. An account whose token
+/// can't be checked reports its error instead of failing the rest.
+pub async fn info(client: &GoogleClient, account: Option<&str>) -> Result<Value> {
+    let names = match account {
+        Some(name) => vec![name.to_string()],
+        None => client.account_names().await,
+    };
+    let active = client.active_account_name().await;
+
+    let mut accounts = Vec::new();
+    for name in names {
+        let bound = client.for_account(&name).await?;
+        let mut entry = json!({
+            "account": name,
+            "label": bound.account_label().await,
+            "active": name == active,
+        });
+        let token = match bound.access_token().await {
+            Ok(token) => token,
+            Err(e) => {
+                entry["error"] = json!(e.to_string());
+                entry["error_kind"] = json!(e.kind());
+                accounts.push(entry);
+                continue;
+            }
+        };
+        match auth::token_info(client.http(), client.endpoints(), &token).await {
+            Ok(info) => {
+                let granted = info.scopes();
+                entry["email"] = json!(info.email);
+                entry["client_id"] = json!(info.aud);
+                let expires_in = info.expires_in.as_deref().and_then(|s| s.parse::<i64>().ok());
+                entry["expires_in"] = json!(expires_in);
+                entry["expires_at"] =
+                    json!(expires_in.map(|secs| Utc::now() + chrono::Duration::seconds(secs)));
+                entry["missing_scopes"] = json!(scopes::missing(&granted));
+                entry["warnings"] = json!(scopes::warnings(&granted));
+                entry["scopes"] = json!(granted);
+            }
+            Err(e) => {
+                entry["error"] = json!(e.to_string());
+                entry["error_kind"] = json!(e.kind());
+            }
+        }
+        accounts.push(entry);
+    }
+    Ok(json!(accounts))
+}
+
+/// Revoke `account`'s refresh token (its access token for service
+/// accounts) and clear its stored tokens, or remove it entirely
+pub async fn revoke(client: &GoogleClient, account: &str, remove: bool) -> Result<Value> {
+    let config = client.get_full_config().await;
+    let stored = config
+        .accounts
+        .get(account)
+        .ok_or_else(|| crate::error::VgoogError::Config(format!("Account '{account}' not found")))?;
+    let token = [&stored.auth.refresh_token, &stored.auth.access_token]
+        .into_iter()
+        .find(|t| !t.is_empty())
+        .cloned();
+    let revoked = match &token {
+        Some(token) => auth::revoke(client.http(), client.endpoints(), token).await?,
+        None => false,
+    };
+
+    client
+        .update_config(|config| {
+            if remove {
+                config.remove_account(account);
+            } else if let Some(stored) = config.accounts.get_mut(account) {
+                stored.auth.access_token.clear();
+                stored.auth.refresh_token.clear();
+                stored.auth.token_expiry = Utc::now();
+            }
+        })
+        .await?;
+    // What was cached with the account's access goes with it
+    cache::clear(Some(account))?;
+
+    Ok(json!({
+        "account": account,
+        "revoked": revoked,
+        "removed": remove,
+    }))
+}
@@ -0,0 +1,47 @@
+use crate::batch::{self, BatchRequest};
+use crate::client::GoogleClient;
+use crate::error::{Result, VgoogError};
+use crate::ui::app::Service;
+use reqwest::Method;
+use serde_json::Value;
+
+/// Send raw requests through a service's batch endpoint. `requests` is an
+/// array of `{"method": "GET", "path": "/messages/abc", "body": {...}}` with
+/// paths relative to the service's base URL.
+pub async fn execute(client: &GoogleClient, service: &str, requests: Value) -> Result<Value> {
+    let endpoints = client.endpoints();
+    let (base, batch_url) = match service {
+        "gmail" => (&endpoints.gmail, &endpoints.gmail_batch),
+        "calendar" => (&endpoints.calendar, &endpoints.calendar_batch),
+        "drive" => (&endpoints.drive, &endpoints.drive_batch),
+        _ => {
+            return Err(VgoogError::Other(format!(
+                "Batch is not supported for service: {service} (expected gmail, calendar or drive)"
+            )))
+        }
+    };
+
+    let items = requests
+        .as_array()
+        .ok_or_else(|| VgoogError::Other("Batch requests must be a JSON array".into()))?;
+    let requests = items
+        .iter()
+        .map(|item| {
+            let method = item.get("method").and_then(|v| v.as_str()).unwrap_or("GET");
+            let method = Method::from_bytes(method.to_uppercase().as_bytes())
+                .map_err(|_| VgoogError::Other(format!("Invalid method: {method}")))?;
+            let path = item.get("path").and_then(|v| v.as_str()).ok_or_else(|| {
+                VgoogError::Other("Every batch request needs a \"path\"".into())
+            })?;
+            let url = format!("{base}/{}", path.trim_start_matches('/'));
+            Ok(BatchRequest::new(method, url, item.get("body").cloned()))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    // Raw requests may write, so the service's full scopes are needed
+    if let Some(known) = Service::from_key(service) {
+        client.ensure_scopes(known, Some("batch")).await?;
+    }
+
+    Ok(batch::results_to_json(client.batch(batch_url, &requests).await?))
+}
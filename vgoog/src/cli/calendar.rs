@@ -1,4 +1,6 @@
+use crate::actions::{Action, Alias, Arg, Literal};
 use crate::api::calendar::CalendarApi;
+use crate::batch;
 use crate::client::GoogleClient;
 use crate::error::{Result, VgoogError};
 use serde_json::Value;
@@ -11,14 +13,99 @@ fn so<'a>(args: &'a Value, key: &str) -> Option<&'a str> {
     args.get(key).and_then(|v| v.as_str())
 }
 
-fn u(args: &Value, key: &str, default: u32) -> u32 {
-    args.get(key).and_then(|v| v.as_u64()).map(|v| v as u32).unwrap_or(default)
+fn u(args: &Value, key: &str) -> u32 {
+    args.get(key).and_then(|v| v.as_u64()).map(|v| v as u32).unwrap_or_default()
 }
 
-fn b(args: &Value, key: &str, default: bool) -> bool {
-    args.get(key).and_then(|v| v.as_bool()).unwrap_or(default)
+fn b(args: &Value, key: &str) -> bool {
+    args.get(key).and_then(|v| v.as_bool()).unwrap_or_default()
 }
 
+fn str_array(args: &Value, key: &str) -> Vec<String> {
+    args.get(key)
+        .and_then(|v| v.as_array())
+        .map(|a| a.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+        .unwrap_or_default()
+}
+
+const ID: Arg = Arg::string("id", "Calendar ID, or `primary`").required();
+const CALENDAR_ID: Arg = Arg::string("calendar_id", "Calendar ID, or `primary`").required();
+const CALENDAR_ID_OR_PRIMARY: Arg = Arg::string("calendar_id", "Calendar ID").default(Literal::Str("primary"));
+const EVENT_ID: Arg = Arg::string("event_id", "Event ID").required();
+const EVENT: Arg = Arg::object("event", "The Event resource").required();
+const RULE_ID: Arg = Arg::string("rule_id", "ACL rule ID").required();
+const RULE: Arg = Arg::object("rule", "The AclRule resource, with `role` and `scope`").required();
+const UPDATES: Arg = Arg::object("updates", "Fields to change").required();
+const MAX_RESULTS: Arg = Arg::integer("max_results", "Results per page").default(Literal::Int(20));
+const PAGE_TOKEN: Arg = Arg::string("page_token", "nextPageToken of the previous page");
+
+pub const ACTIONS: &[Action] = &[
+    Action::new("list_calendars", "List the calendars in the user's calendar list", &[PAGE_TOKEN]).items("items"),
+    Action::new("get_calendar", "Get a calendar list entry", &[ID]),
+    Action::new("insert_calendar_to_list", "Add an existing calendar to the calendar list", &[ID]),
+    Action::new("update_calendar_in_list", "Update a calendar list entry, such as its color", &[ID, UPDATES]),
+    Action::new("remove_calendar_from_list", "Remove a calendar from the calendar list", &[ID]),
+    Action::new("create_calendar", "Create a secondary calendar", &[Arg::string("summary", "Calendar title").required()]),
+    Action::new("get_calendar_metadata", "Get a calendar's metadata", &[ID]),
+    Action::new("update_calendar_metadata", "Update a calendar's metadata", &[ID, UPDATES]),
+    Action::new("delete_calendar", "Delete a secondary calendar", &[ID]),
+    Action::new("clear_calendar", "Delete every event of a primary calendar", &[ID]),
+    Action::new(
+        "list_events",
+        "List events, optionally within a time range or matching text",
+        &[
+            CALENDAR_ID_OR_PRIMARY,
+            Arg::string("time_min", "Lower bound (exclusive) for an event's end, RFC 3339"),
+            Arg::string("time_max", "Upper bound (exclusive) for an event's start, RFC 3339"),
+            Arg::string("query", "Free text to match"),
+            MAX_RESULTS,
+            PAGE_TOKEN,
+            Arg::boolean("single_events", "Expand recurring events into their instances").default(Literal::Bool(false)),
+            Arg::string("order_by", "Sort order; `startTime` needs single_events").one_of(&["startTime", "updated"]),
+        ],
+    )
+    .items("items"),
+    Action::new("get_event", "Get an event", &[CALENDAR_ID, EVENT_ID]),
+    Action::new("create_event", "Create an event", &[CALENDAR_ID_OR_PRIMARY, EVENT]),
+    Action::new("delete_event", "Delete an event", &[CALENDAR_ID, EVENT_ID]),
+    Action::new(
+        "batch_delete_events",
+        "Delete several events in one batch request",
+        &[CALENDAR_ID_OR_PRIMARY, Arg::strings("event_ids", "Event IDs").required()],
+    ),
+    Action::new(
+        "move_event",
+        "Move an event to another calendar",
+        &[CALENDAR_ID, EVENT_ID, Arg::string("destination", "Calendar ID to move it to").required()],
+    ),
+    Action::new(
+        "quick_add_event",
+        "Create an event from a sentence such as \"Lunch with Ann tomorrow 1pm\"",
+        &[CALENDAR_ID_OR_PRIMARY, Arg::string("text", "The event, in plain words").required()],
+    ),
+    Action::new(
+        "list_event_instances",
+        "List the instances of a recurring event",
+        &[CALENDAR_ID, EVENT_ID, MAX_RESULTS, PAGE_TOKEN],
+    )
+    .items("items"),
+    Action::new("list_acl", "List a calendar's sharing rules", &[CALENDAR_ID, PAGE_TOKEN]).items("items"),
+    Action::new("insert_acl_rule", "Share a calendar", &[CALENDAR_ID, RULE]),
+    Action::new("update_acl_rule", "Replace a sharing rule", &[CALENDAR_ID, RULE_ID, RULE]),
+    Action::new("delete_acl_rule", "Remove a sharing rule", &[CALENDAR_ID, RULE_ID]),
+    Action::new("list_settings", "List the user's calendar settings", &[PAGE_TOKEN]).items("items"),
+    Action::new("get_setting", "Get one calendar setting", &[Arg::string("setting", "Setting ID, such as `timezone`").required()]),
+    Action::new("get_colors", "Get the calendar and event color palettes", &[]),
+    Action::new(
+        "query_free_busy",
+        "Get busy times of calendars",
+        &[Arg::object("body", "The request, with `timeMin`, `timeMax` and `items`").required()],
+    ),
+];
+
+/// Actions retired in favour of the generated ones making the same call
+pub const ALIASES: &[Alias] = &[Alias::new("update_event", "events_patch", &[("event", "body")])];
+
 pub async fn execute(client: &GoogleClient, action: &str, args: Value) -> Result<Value> {
     let api = CalendarApi::new(client);
     match action {
@@ -33,26 +120,31 @@ pub async fn execute(client: &GoogleClient, action: &str, args: Value) -> Result
         "delete_calendar" => api.delete_calendar(s(&args, "id")).await,
         "clear_calendar" => api.clear_calendar(s(&args, "id")).await,
         "list_events" => api.list_events(
-            so(&args, "calendar_id").unwrap_or("primary"),
+            s(&args, "calendar_id"),
             so(&args, "time_min"), so(&args, "time_max"), so(&args, "query"),
-            u(&args, "max_results", 20), so(&args, "page_token"),
-            b(&args, "single_events", false), so(&args, "order_by"),
+            u(&args, "max_results"), so(&args, "page_token"),
+            b(&args, "single_events"), so(&args, "order_by"),
         ).await,
         "get_event" => api.get_event(s(&args, "calendar_id"), s(&args, "event_id")).await,
-        "create_event" => api.create_event(so(&args, "calendar_id").unwrap_or("primary"), &args["event"]).await,
-        "update_event" => api.update_event(s(&args, "calendar_id"), s(&args, "event_id"), &args["event"]).await,
+        "create_event" => api.create_event(s(&args, "calendar_id"), &args["event"]).await,
         "delete_event" => api.delete_event(s(&args, "calendar_id"), s(&args, "event_id")).await,
+        "batch_delete_events" => {
+            let ids = str_array(&args, "event_ids");
+            let id_refs: Vec<&str> = ids.iter().map(|s| s.as_str()).collect();
+            let results = api.batch_delete_events(s(&args, "calendar_id"), &id_refs).await?;
+            Ok(batch::results_to_json(results))
+        }
         "move_event" => api.move_event(s(&args, "calendar_id"), s(&args, "event_id"), s(&args, "destination")).await,
-        "quick_add_event" => api.quick_add_event(so(&args, "calendar_id").unwrap_or("primary"), s(&args, "text")).await,
+        "quick_add_event" => api.quick_add_event(s(&args, "calendar_id"), s(&args, "text")).await,
         "list_event_instances" => api.list_event_instances(
             s(&args, "calendar_id"), s(&args, "event_id"),
-            u(&args, "max_results", 20), so(&args, "page_token"),
+            u(&args, "max_results"), so(&args, "page_token"),
         ).await,
-        "list_acl" => api.list_acl(s(&args, "calendar_id")).await,
+        "list_acl" => api.list_acl(s(&args, "calendar_id"), so(&args, "page_token")).await,
         "insert_acl_rule" => api.insert_acl_rule(s(&args, "calendar_id"), &args["rule"]).await,
         "update_acl_rule" => api.update_acl_rule(s(&args, "calendar_id"), s(&args, "rule_id"), &args["rule"]).await,
         "delete_acl_rule" => api.delete_acl_rule(s(&args, "calendar_id"), s(&args, "rule_id")).await,
-        "list_settings" => api.list_settings().await,
+        "list_settings" => api.list_settings(so(&args, "page_token")).await,
         "get_setting" => api.get_setting(s(&args, "setting")).await,
         "get_colors" => api.get_colors().await,
         "query_free_busy" => api.query_free_busy(&args["body"]).await,
@@ -1,3 +1,4 @@
+use crate::actions::{Action, Arg, Literal};
 use crate::api::docs::DocsApi;
 use crate::client::GoogleClient;
 use crate::error::{Result, VgoogError};
@@ -7,16 +8,16 @@ fn s<'a>(args: &'a Value, key: &str) -> &'a str {
     args.get(key).and_then(|v| v.as_str()).unwrap_or("")
 }
 
-fn so<'a>(args: &'a Value, key: &str) -> Option<&'a str> {
-    args.get(key).and_then(|v| v.as_str())
+fn i(args: &Value, key: &str) -> i64 {
+    args.get(key).and_then(|v| v.as_i64()).unwrap_or_default()
 }
 
-fn i(args: &Value, key: &str, default: i64) -> i64 {
-    args.get(key).and_then(|v| v.as_i64()).unwrap_or(default)
+fn f(args: &Value, key: &str) -> f64 {
+    args.get(key).and_then(|v| v.as_f64()).unwrap_or_default()
 }
 
-fn f(args: &Value, key: &str, default: f64) -> f64 {
-    args.get(key).and_then(|v| v.as_f64()).unwrap_or(default)
+fn b(args: &Value, key: &str) -> bool {
+    args.get(key).and_then(|v| v.as_bool()).unwrap_or_default()
 }
 
 fn bo(args: &Value, key: &str) -> Option<bool> {
@@ -35,6 +36,98 @@ fn val_array(args: &Value, key: &str) -> Vec<Value> {
     args.get(key).and_then(|v| v.as_array()).cloned().unwrap_or_default()
 }
 
+const DOCUMENT_ID: Arg = Arg::string("document_id", "Document ID").required();
+const INDEX: Arg = Arg::integer("index", "Position in the body; 1 is the start").default(Literal::Int(1));
+const START_INDEX: Arg = Arg::integer("start_index", "Start of the range").default(Literal::Int(1));
+const END_INDEX: Arg = Arg::integer("end_index", "End of the range, exclusive").default(Literal::Int(2));
+const SECTION_INDEX: Arg = Arg::integer("section_idx", "Index of the section break to attach to; the document style if left out");
+
+pub const ACTIONS: &[Action] = &[
+    Action::new("create_document", "Create a blank document", &[Arg::string("title", "Document title").required()]),
+    Action::new("get_document", "Get a document with its content", &[DOCUMENT_ID]),
+    Action::new(
+        "batch_update",
+        "Apply raw documents.batchUpdate requests",
+        &[DOCUMENT_ID, Arg::array("requests", "Request objects").required()],
+    ),
+    Action::new("insert_text", "Insert text", &[DOCUMENT_ID, Arg::string("text", "Text to insert").required(), INDEX]),
+    Action::new("delete_content", "Delete a range of content", &[DOCUMENT_ID, START_INDEX, END_INDEX]),
+    Action::new(
+        "insert_table",
+        "Insert an empty table",
+        &[
+            DOCUMENT_ID,
+            Arg::integer("rows", "Number of rows").default(Literal::Int(2)),
+            Arg::integer("cols", "Number of columns").default(Literal::Int(2)),
+            INDEX,
+        ],
+    ),
+    Action::new(
+        "insert_inline_image",
+        "Insert an image from a public URL",
+        &[
+            DOCUMENT_ID,
+            Arg::string("uri", "Image URL").required(),
+            INDEX,
+            Arg::number("width_pt", "Width in points").default(Literal::Num(200.0)),
+            Arg::number("height_pt", "Height in points").default(Literal::Num(200.0)),
+        ],
+    ),
+    Action::new(
+        "update_text_style",
+        "Set bold, italic, underline or font size on a range; others are left as they are",
+        &[
+            DOCUMENT_ID,
+            START_INDEX,
+            END_INDEX,
+            Arg::boolean("bold", "Bold"),
+            Arg::boolean("italic", "Italic"),
+            Arg::boolean("underline", "Underline"),
+            Arg::number("font_size", "Font size in points"),
+        ],
+    ),
+    Action::new(
+        "update_paragraph_style",
+        "Apply a named style to the paragraphs of a range",
+        &[
+            DOCUMENT_ID,
+            START_INDEX,
+            END_INDEX,
+            Arg::string("named_style", "Paragraph style")
+                .one_of(&[
+                    "NORMAL_TEXT",
+                    "TITLE",
+                    "SUBTITLE",
+                    "HEADING_1",
+                    "HEADING_2",
+                    "HEADING_3",
+                    "HEADING_4",
+                    "HEADING_5",
+                    "HEADING_6",
+                ])
+                .default(Literal::Str("NORMAL_TEXT")),
+        ],
+    ),
+    Action::new(
+        "replace_all_text",
+        "Replace every occurrence of some text",
+        &[
+            DOCUMENT_ID,
+            Arg::string("find", "Text to find").required(),
+            Arg::string("replace", "Replacement text").required(),
+            Arg::boolean("match_case", "Match case").default(Literal::Bool(true)),
+        ],
+    ),
+    Action::new(
+        "create_named_range",
+        "Name a range of content",
+        &[DOCUMENT_ID, Arg::string("name", "Range name").required(), START_INDEX, END_INDEX],
+    ),
+    Action::new("insert_page_break", "Insert a page break", &[DOCUMENT_ID, INDEX]),
+    Action::new("create_header", "Add a header", &[DOCUMENT_ID, SECTION_INDEX]),
+    Action::new("create_footer", "Add a footer", &[DOCUMENT_ID, SECTION_INDEX]),
+];
+
 pub async fn execute(client: &GoogleClient, action: &str, args: Value) -> Result<Value> {
     let api = DocsApi::new(client);
     match action {
@@ -44,30 +137,30 @@ pub async fn execute(client: &GoogleClient, action: &str, args: Value) -> Result
             let requests = val_array(&args, "requests");
             api.batch_update(s(&args, "document_id"), &requests).await
         }
-        "insert_text" => api.insert_text(s(&args, "document_id"), s(&args, "text"), i(&args, "index", 1)).await,
-        "delete_content" => api.delete_content(s(&args, "document_id"), i(&args, "start_index", 1), i(&args, "end_index", 2)).await,
-        "insert_table" => api.insert_table(s(&args, "document_id"), i(&args, "rows", 2), i(&args, "cols", 2), i(&args, "index", 1)).await,
+        "insert_text" => api.insert_text(s(&args, "document_id"), s(&args, "text"), i(&args, "index")).await,
+        "delete_content" => api.delete_content(s(&args, "document_id"), i(&args, "start_index"), i(&args, "end_index")).await,
+        "insert_table" => api.insert_table(s(&args, "document_id"), i(&args, "rows"), i(&args, "cols"), i(&args, "index")).await,
         "insert_inline_image" => api.insert_inline_image(
-            s(&args, "document_id"), s(&args, "uri"), i(&args, "index", 1),
-            f(&args, "width_pt", 200.0), f(&args, "height_pt", 200.0),
+            s(&args, "document_id"), s(&args, "uri"), i(&args, "index"),
+            f(&args, "width_pt"), f(&args, "height_pt"),
         ).await,
         "update_text_style" => api.update_text_style(
-            s(&args, "document_id"), i(&args, "start_index", 1), i(&args, "end_index", 2),
+            s(&args, "document_id"), i(&args, "start_index"), i(&args, "end_index"),
             bo(&args, "bold"), bo(&args, "italic"), bo(&args, "underline"), fo(&args, "font_size"),
         ).await,
         "update_paragraph_style" => api.update_paragraph_style(
-            s(&args, "document_id"), i(&args, "start_index", 1), i(&args, "end_index", 2),
-            so(&args, "named_style").unwrap_or("NORMAL_TEXT"),
+            s(&args, "document_id"), i(&args, "start_index"), i(&args, "end_index"),
+            s(&args, "named_style"),
         ).await,
         "replace_all_text" => api.replace_all_text(
             s(&args, "document_id"), s(&args, "find"), s(&args, "replace"),
-            args.get("match_case").and_then(|v| v.as_bool()).unwrap_or(true),
+            b(&args, "match_case"),
         ).await,
         "create_named_range" => api.create_named_range(
             s(&args, "document_id"), s(&args, "name"),
-            i(&args, "start_index", 1), i(&args, "end_index", 2),
+            i(&args, "start_index"), i(&args, "end_index"),
         ).await,
-        "insert_page_break" => api.insert_page_break(s(&args, "document_id"), i(&args, "index", 1)).await,
+        "insert_page_break" => api.insert_page_break(s(&args, "document_id"), i(&args, "index")).await,
         "create_header" => api.create_header(s(&args, "document_id"), io(&args, "section_idx")).await,
         "create_footer" => api.create_footer(s(&args, "document_id"), io(&args, "section_idx")).await,
         _ => Err(VgoogError::Other(format!("Unknown docs action: {action}"))),
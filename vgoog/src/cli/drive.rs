@@ -1,7 +1,12 @@
+use crate::actions::{Action, Alias, Arg, Literal};
 use crate::api::drive::DriveApi;
+use crate::batch;
 use crate::client::GoogleClient;
 use crate::error::{Result, VgoogError};
-use serde_json::Value;
+use crate::transfer::Progress;
+use serde_json::{json, Value};
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
 
 fn s<'a>(args: &'a Value, key: &str) -> &'a str {
     args.get(key).and_then(|v| v.as_str()).unwrap_or("")
@@ -11,44 +16,222 @@ fn so<'a>(args: &'a Value, key: &str) -> Option<&'a str> {
     args.get(key).and_then(|v| v.as_str())
 }
 
-fn u(args: &Value, key: &str, default: u32) -> u32 {
-    args.get(key).and_then(|v| v.as_u64()).map(|v| v as u32).unwrap_or(default)
+fn u(args: &Value, key: &str) -> u32 {
+    args.get(key).and_then(|v| v.as_u64()).map(|v| v as u32).unwrap_or_default()
 }
 
+fn str_array(args: &Value, key: &str) -> Vec<String> {
+    args.get(key)
+        .and_then(|v| v.as_array())
+        .map(|a| a.iter().filter_map(|v| v.as_str().map(String::from)).collect())
+        .unwrap_or_default()
+}
+
+fn mime_type(args: &Value, path: &Path) -> String {
+    so(args, "mime_type")
+        .map(String::from)
+        .unwrap_or_else(|| mime_guess::from_path(path).first_or_octet_stream().to_string())
+}
+
+/// Transfer progress on stderr, only when someone is watching it
+fn progress_line(verb: &'static str) -> impl Fn(Progress) + Send + Sync {
+    move |progress| {
+        if std::io::stderr().is_terminal() {
+            eprint!("\r\x1b[2K  {verb} {progress}");
+        }
+    }
+}
+
+/// Wipe the progress line so only the JSON result remains
+fn clear_progress_line() {
+    if std::io::stderr().is_terminal() {
+        eprint!("\r\x1b[2K");
+    }
+}
+
+const FILE_ID: Arg = Arg::string("file_id", "File ID").required();
+const METADATA: Arg = Arg::object("metadata", "File resource fields, such as `name`, `mimeType` and `parents`");
+const PATH: Arg = Arg::string("path", "Local file path").required();
+const MIME_TYPE: Arg = Arg::string("mime_type", "Content type; guessed from the file extension if left out");
+const PERMISSION_ID: Arg = Arg::string("permission_id", "Permission ID").required();
+const ROLE: Arg = Arg::string("role", "Access granted")
+    .one_of(&["owner", "organizer", "fileOrganizer", "writer", "commenter", "reader"])
+    .required();
+const COMMENT_ID: Arg = Arg::string("comment_id", "Comment ID").required();
+const CONTENT: Arg = Arg::string("content", "Plain text of the comment").required();
+const REVISION_ID: Arg = Arg::string("revision_id", "Revision ID").required();
+const PAGE_TOKEN: Arg = Arg::string("page_token", "nextPageToken of the previous page");
+
+pub const ACTIONS: &[Action] = &[
+    Action::new(
+        "list_files",
+        "List or search files",
+        &[
+            Arg::string("query", "Drive search query, such as `name contains 'report'`"),
+            Arg::integer("page_size", "Files per page").default(Literal::Int(20)),
+            PAGE_TOKEN,
+            Arg::string("order_by", "Comma-separated sort keys, such as `modifiedTime desc`"),
+            Arg::string("fields", "Partial response selector"),
+            Arg::string("spaces", "Comma-separated spaces to search: `drive`, `appDataFolder`"),
+        ],
+    )
+    .items("files"),
+    Action::new(
+        "upload_file",
+        "Upload a local file; large ones go up in resumable chunks",
+        &[PATH, METADATA, MIME_TYPE],
+    ),
+    Action::new("update_file_content", "Replace a file's content with a local file", &[FILE_ID, PATH, METADATA, MIME_TYPE]),
+    Action::new(
+        "download_file",
+        "Download a file's content to disk",
+        &[FILE_ID, Arg::string("path", "Where to save it; the file's name in the current directory if left out")],
+    ),
+    Action::new("delete_file", "Delete a file for good, skipping the trash", &[FILE_ID]),
+    Action::new("copy_file", "Copy a file", &[FILE_ID, METADATA]),
+    Action::new("empty_trash", "Delete every trashed file for good", &[]),
+    Action::new(
+        "generate_file_ids",
+        "Generate IDs to create files with",
+        &[Arg::integer("count", "How many").default(Literal::Int(10))],
+    ),
+    Action::new(
+        "move_file",
+        "Move a file between folders",
+        &[
+            FILE_ID,
+            Arg::string("add_parents", "Comma-separated folder IDs to add"),
+            Arg::string("remove_parents", "Comma-separated folder IDs to remove"),
+        ],
+    ),
+    Action::new(
+        "create_folder",
+        "Create a folder",
+        &[Arg::string("name", "Folder name").required(), Arg::string("parent", "Parent folder ID")],
+    ),
+    Action::new("list_permissions", "List who a file is shared with", &[FILE_ID, PAGE_TOKEN]).items("permissions"),
+    Action::new("get_permission", "Get a permission", &[FILE_ID, PERMISSION_ID]),
+    Action::new(
+        "create_permission",
+        "Share a file",
+        &[
+            FILE_ID,
+            ROLE,
+            Arg::string("type", "Who it is shared with").one_of(&["user", "group", "domain", "anyone"]).required(),
+            Arg::string("email", "Address of the user or group"),
+        ],
+    ),
+    Action::new("update_permission", "Change a permission's role", &[FILE_ID, PERMISSION_ID, ROLE]),
+    Action::new(
+        "batch_update_permissions",
+        "Change the role of several permissions in one batch request",
+        &[FILE_ID, Arg::strings("permission_ids", "Permission IDs").required(), ROLE],
+    ),
+    Action::new("delete_permission", "Stop sharing a file with someone", &[FILE_ID, PERMISSION_ID]),
+    Action::new("list_comments", "List a file's comments", &[FILE_ID, PAGE_TOKEN]).items("comments"),
+    Action::new("create_comment", "Comment on a file", &[FILE_ID, CONTENT]),
+    Action::new("update_comment", "Edit a comment", &[FILE_ID, COMMENT_ID, CONTENT]),
+    Action::new("delete_comment", "Delete a comment", &[FILE_ID, COMMENT_ID]),
+    Action::new("list_replies", "List the replies to a comment", &[FILE_ID, COMMENT_ID, PAGE_TOKEN]).items("replies"),
+    Action::new("create_reply", "Reply to a comment", &[FILE_ID, COMMENT_ID, CONTENT]),
+    Action::new("list_revisions", "List a file's revisions", &[FILE_ID, PAGE_TOKEN]).items("revisions"),
+    Action::new("get_revision", "Get a revision's metadata", &[FILE_ID, REVISION_ID]),
+    Action::new("delete_revision", "Delete a revision of a binary file", &[FILE_ID, REVISION_ID]),
+    Action::new("get_start_page_token", "Get the page token to list future changes from", &[]),
+    Action::new(
+        "list_changes",
+        "List changes since a page token",
+        &[
+            Arg::string("page_token", "From get_start_page_token, or the previous page's nextPageToken").required(),
+            Arg::integer("page_size", "Changes per page").default(Literal::Int(100)),
+        ],
+    )
+    .items("changes"),
+    Action::new("get_about", "Get the user, storage quota and supported formats", &[]),
+    Action::new("list_shared_drives", "List shared drives", &[PAGE_TOKEN]).items("drives"),
+    Action::new("create_shared_drive", "Create a shared drive", &[Arg::string("name", "Drive name").required()]),
+    Action::new("delete_shared_drive", "Delete an empty shared drive", &[Arg::string("drive_id", "Shared drive ID").required()]),
+];
+
+/// Actions retired in favour of the generated ones making the same call
+pub const ALIASES: &[Alias] = &[
+    Alias::new("get_file", "files_get", &[]),
+    Alias::new("create_file", "files_create", &[("metadata", "body")]),
+    Alias::new("export_file", "files_export", &[("path", "download_to")]),
+    Alias::new("update_file_metadata", "files_update", &[("metadata", "body")]),
+];
+
 pub async fn execute(client: &GoogleClient, action: &str, args: Value) -> Result<Value> {
     let api = DriveApi::new(client);
     match action {
         "list_files" => api.list_files(
-            so(&args, "query"), u(&args, "page_size", 20), so(&args, "page_token"),
+            so(&args, "query"), u(&args, "page_size"), so(&args, "page_token"),
             so(&args, "order_by"), so(&args, "fields"), so(&args, "spaces"),
         ).await,
-        "get_file" => api.get_file(s(&args, "file_id"), so(&args, "fields")).await,
-        "create_file" => api.create_file(&args["metadata"]).await,
-        "update_file_metadata" => api.update_file_metadata(s(&args, "file_id"), &args["metadata"]).await,
+        "upload_file" => {
+            let path = Path::new(s(&args, "path"));
+            let mut metadata = args.get("metadata").cloned().unwrap_or_else(|| json!({}));
+            if metadata.get("name").is_none() {
+                metadata["name"] = json!(path.file_name().and_then(|n| n.to_str()).unwrap_or("upload"));
+            }
+            let mime = mime_type(&args, path);
+            let result = api.upload_file(&metadata, path, &mime, Some(&progress_line("Uploading"))).await;
+            clear_progress_line();
+            result
+        }
+        "update_file_content" => {
+            let path = Path::new(s(&args, "path"));
+            let metadata = args.get("metadata").cloned().unwrap_or_else(|| json!({}));
+            let mime = mime_type(&args, path);
+            let result = api
+                .update_file_content(s(&args, "file_id"), &metadata, path, &mime, Some(&progress_line("Uploading")))
+                .await;
+            clear_progress_line();
+            result
+        }
+        "download_file" => {
+            let file_id = s(&args, "file_id");
+            let path = match so(&args, "path") {
+                Some(p) => PathBuf::from(p),
+                None => {
+                    let meta = api.get_file(file_id, Some("name")).await?;
+                    PathBuf::from(meta["name"].as_str().unwrap_or(file_id))
+                }
+            };
+            let result = api.download_file_to(file_id, &path, Some(&progress_line("Downloading"))).await;
+            clear_progress_line();
+            result
+        }
         "delete_file" => api.delete_file(s(&args, "file_id")).await,
         "copy_file" => api.copy_file(s(&args, "file_id"), &args["metadata"]).await,
         "empty_trash" => api.empty_trash().await,
-        "generate_file_ids" => api.generate_file_ids(u(&args, "count", 10)).await,
+        "generate_file_ids" => api.generate_file_ids(u(&args, "count")).await,
         "move_file" => api.move_file(s(&args, "file_id"), s(&args, "add_parents"), s(&args, "remove_parents")).await,
         "create_folder" => api.create_folder(s(&args, "name"), so(&args, "parent")).await,
-        "list_permissions" => api.list_permissions(s(&args, "file_id")).await,
+        "list_permissions" => api.list_permissions(s(&args, "file_id"), so(&args, "page_token")).await,
         "get_permission" => api.get_permission(s(&args, "file_id"), s(&args, "permission_id")).await,
         "create_permission" => api.create_permission(
             s(&args, "file_id"), s(&args, "role"), s(&args, "type"), so(&args, "email"),
         ).await,
         "update_permission" => api.update_permission(s(&args, "file_id"), s(&args, "permission_id"), s(&args, "role")).await,
+        "batch_update_permissions" => {
+            let ids = str_array(&args, "permission_ids");
+            let id_refs: Vec<&str> = ids.iter().map(|s| s.as_str()).collect();
+            let results = api.batch_update_permissions(s(&args, "file_id"), &id_refs, s(&args, "role")).await?;
+            Ok(batch::results_to_json(results))
+        }
         "delete_permission" => api.delete_permission(s(&args, "file_id"), s(&args, "permission_id")).await,
         "list_comments" => api.list_comments(s(&args, "file_id"), so(&args, "page_token")).await,
         "create_comment" => api.create_comment(s(&args, "file_id"), s(&args, "content")).await,
         "update_comment" => api.update_comment(s(&args, "file_id"), s(&args, "comment_id"), s(&args, "content")).await,
         "delete_comment" => api.delete_comment(s(&args, "file_id"), s(&args, "comment_id")).await,
-        "list_replies" => api.list_replies(s(&args, "file_id"), s(&args, "comment_id")).await,
+        "list_replies" => api.list_replies(s(&args, "file_id"), s(&args, "comment_id"), so(&args, "page_token")).await,
         "create_reply" => api.create_reply(s(&args, "file_id"), s(&args, "comment_id"), s(&args, "content")).await,
-        "list_revisions" => api.list_revisions(s(&args, "file_id")).await,
+        "list_revisions" => api.list_revisions(s(&args, "file_id"), so(&args, "page_token")).await,
         "get_revision" => api.get_revision(s(&args, "file_id"), s(&args, "revision_id")).await,
         "delete_revision" => api.delete_revision(s(&args, "file_id"), s(&args, "revision_id")).await,
         "get_start_page_token" => api.get_start_page_token().await,
-        "list_changes" => api.list_changes(s(&args, "page_token"), u(&args, "page_size", 100)).await,
+        "list_changes" => api.list_changes(s(&args, "page_token"), u(&args, "page_size")).await,
         "get_about" => api.get_about().await,
         "list_shared_drives" => api.list_shared_drives(so(&args, "page_token")).await,
         "create_shared_drive" => api.create_shared_drive(s(&args, "name")).await,
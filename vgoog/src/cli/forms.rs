@@ -1,3 +1,4 @@
+use crate::actions::{Action, Arg, Literal};
 use crate::api::forms::FormsApi;
 use crate::client::GoogleClient;
 use crate::error::{Result, VgoogError};
@@ -11,16 +12,16 @@ fn so<'a>(args: &'a Value, key: &str) -> Option<&'a str> {
     args.get(key).and_then(|v| v.as_str())
 }
 
-fn u(args: &Value, key: &str, default: u32) -> u32 {
-    args.get(key).and_then(|v| v.as_u64()).map(|v| v as u32).unwrap_or(default)
+fn u(args: &Value, key: &str) -> u32 {
+    args.get(key).and_then(|v| v.as_u64()).map(|v| v as u32).unwrap_or_default()
 }
 
-fn i(args: &Value, key: &str, default: i64) -> i64 {
-    args.get(key).and_then(|v| v.as_i64()).unwrap_or(default)
+fn i(args: &Value, key: &str) -> i64 {
+    args.get(key).and_then(|v| v.as_i64()).unwrap_or_default()
 }
 
-fn b(args: &Value, key: &str, default: bool) -> bool {
-    args.get(key).and_then(|v| v.as_bool()).unwrap_or(default)
+fn b(args: &Value, key: &str) -> bool {
+    args.get(key).and_then(|v| v.as_bool()).unwrap_or_default()
 }
 
 fn str_array(args: &Value, key: &str) -> Vec<String> {
@@ -34,6 +35,165 @@ fn val_array(args: &Value, key: &str) -> Vec<Value> {
     args.get(key).and_then(|v| v.as_array()).cloned().unwrap_or_default()
 }
 
+const FORM_ID: Arg = Arg::string("form_id", "Form ID").required();
+const WATCH_ID: Arg = Arg::string("watch_id", "Watch ID").required();
+const TITLE: Arg = Arg::string("title", "Question title").required();
+const REQUIRED: Arg = Arg::boolean("required", "Whether an answer is required").default(Literal::Bool(false));
+const INDEX: Arg = Arg::integer("index", "Position among the form's items, 0-based").default(Literal::Int(0));
+
+pub const ACTIONS: &[Action] = &[
+    Action::new(
+        "create_form",
+        "Create a form; questions are added afterwards",
+        &[
+            Arg::string("title", "Title shown to respondents").required(),
+            Arg::string("document_title", "Title of the form in Drive"),
+        ],
+    ),
+    Action::new("get_form", "Get a form with its items", &[FORM_ID]),
+    Action::new(
+        "batch_update",
+        "Apply raw forms.batchUpdate requests",
+        &[FORM_ID, Arg::array("requests", "Request objects").required()],
+    ),
+    Action::new(
+        "list_responses",
+        "List a form's responses",
+        &[
+            FORM_ID,
+            Arg::string("page_token", "nextPageToken of the previous page"),
+            Arg::integer("page_size", "Responses per page").default(Literal::Int(50)),
+        ],
+    )
+    .items("responses"),
+    Action::new("get_response", "Get a response", &[FORM_ID, Arg::string("response_id", "Response ID").required()]),
+    Action::new(
+        "create_watch",
+        "Publish form changes or new responses to a Cloud Pub/Sub topic",
+        &[
+            FORM_ID,
+            Arg::string("event_type", "What to watch").one_of(&["SCHEMA", "RESPONSES"]).required(),
+            Arg::string("topic_name", "Pub/Sub topic, `projects/<project>/topics/<topic>`").required(),
+        ],
+    ),
+    Action::new("list_watches", "List a form's watches", &[FORM_ID]),
+    Action::new("delete_watch", "Delete a watch", &[FORM_ID, WATCH_ID]),
+    Action::new("renew_watch", "Renew a watch for another seven days", &[FORM_ID, WATCH_ID]),
+    Action::new(
+        "add_text_question",
+        "Add a short answer or paragraph question",
+        &[
+            FORM_ID,
+            TITLE,
+            REQUIRED,
+            INDEX,
+            Arg::boolean("paragraph", "Accept a paragraph instead of a short answer").default(Literal::Bool(false)),
+        ],
+    ),
+    Action::new(
+        "add_choice_question",
+        "Add a multiple choice, checkbox or dropdown question",
+        &[
+            FORM_ID,
+            TITLE,
+            REQUIRED,
+            INDEX,
+            Arg::string("choice_type", "Kind of choice").one_of(&["RADIO", "CHECKBOX", "DROP_DOWN"]).required(),
+            Arg::strings("options", "The choices").required(),
+        ],
+    ),
+    Action::new(
+        "add_scale_question",
+        "Add a linear scale question",
+        &[
+            FORM_ID,
+            TITLE,
+            REQUIRED,
+            INDEX,
+            Arg::integer("low", "Lowest value, 0 or 1").default(Literal::Int(1)),
+            Arg::integer("high", "Highest value, 2 to 10").default(Literal::Int(5)),
+            Arg::string("low_label", "Label of the lowest value"),
+            Arg::string("high_label", "Label of the highest value"),
+        ],
+    ),
+    Action::new(
+        "add_date_question",
+        "Add a date question",
+        &[
+            FORM_ID,
+            TITLE,
+            REQUIRED,
+            INDEX,
+            Arg::boolean("include_time", "Ask for a time too").default(Literal::Bool(false)),
+            Arg::boolean("include_year", "Ask for the year").default(Literal::Bool(true)),
+        ],
+    ),
+    Action::new(
+        "add_time_question",
+        "Add a time question",
+        &[
+            FORM_ID,
+            TITLE,
+            REQUIRED,
+            INDEX,
+            Arg::boolean("include_duration", "Ask for a duration instead of a time of day").default(Literal::Bool(false)),
+        ],
+    ),
+    Action::new(
+        "add_section_header",
+        "Add a title and description between questions",
+        &[FORM_ID, Arg::string("title", "Header title").required(), Arg::string("description", "Text under the title"), INDEX],
+    ),
+    Action::new("delete_item", "Delete an item", &[FORM_ID, INDEX]),
+    Action::new(
+        "move_item",
+        "Move an item",
+        &[
+            FORM_ID,
+            Arg::integer("original_index", "Current position, 0-based").default(Literal::Int(0)),
+            Arg::integer("new_index", "New position, 0-based").default(Literal::Int(1)),
+        ],
+    ),
+    Action::new(
+        "update_form_info",
+        "Change a form's title or description",
+        &[FORM_ID, Arg::string("title", "New title"), Arg::string("description", "New description")],
+    ),
+    Action::new(
+        "update_settings",
+        "Update form settings, such as quiz mode",
+        &[
+            FORM_ID,
+            Arg::object("settings", "FormSettings fields to set").required(),
+            Arg::string("update_mask", "Field mask of the settings to change, such as `quizSettings.isQuiz`").required(),
+        ],
+    ),
+    Action::new(
+        "add_file_upload_question",
+        "Add a file upload question",
+        &[
+            FORM_ID,
+            TITLE,
+            REQUIRED,
+            INDEX,
+            Arg::integer("max_files", "Most files a respondent may upload").default(Literal::Int(1)),
+            Arg::string("max_file_size", "Largest file accepted, such as `10MB`").default(Literal::Str("10MB")),
+        ],
+    ),
+    Action::new(
+        "add_grid_question",
+        "Add a multiple choice grid question",
+        &[
+            FORM_ID,
+            TITLE,
+            REQUIRED,
+            INDEX,
+            Arg::strings("rows", "Row labels, one question each").required(),
+            Arg::strings("columns", "Column labels, the choices").required(),
+        ],
+    ),
+];
+
 pub async fn execute(client: &GoogleClient, action: &str, args: Value) -> Result<Value> {
     let api = FormsApi::new(client);
     match action {
@@ -43,47 +203,47 @@ pub async fn execute(client: &GoogleClient, action: &str, args: Value) -> Result
             let requests = val_array(&args, "requests");
             api.batch_update(s(&args, "form_id"), &requests).await
         }
-        "list_responses" => api.list_responses(s(&args, "form_id"), so(&args, "page_token"), u(&args, "page_size", 50)).await,
+        "list_responses" => api.list_responses(s(&args, "form_id"), so(&args, "page_token"), u(&args, "page_size")).await,
         "get_response" => api.get_response(s(&args, "form_id"), s(&args, "response_id")).await,
         "create_watch" => api.create_watch(s(&args, "form_id"), s(&args, "event_type"), s(&args, "topic_name")).await,
         "list_watches" => api.list_watches(s(&args, "form_id")).await,
         "delete_watch" => api.delete_watch(s(&args, "form_id"), s(&args, "watch_id")).await,
         "renew_watch" => api.renew_watch(s(&args, "form_id"), s(&args, "watch_id")).await,
         "add_text_question" => api.add_text_question(
-            s(&args, "form_id"), s(&args, "title"), b(&args, "required", false),
-            i(&args, "index", 0), b(&args, "paragraph", false),
+            s(&args, "form_id"), s(&args, "title"), b(&args, "required"),
+            i(&args, "index"), b(&args, "paragraph"),
         ).await,
         "add_choice_question" => {
             let options = str_array(&args, "options");
             let option_refs: Vec<&str> = options.iter().map(|s| s.as_str()).collect();
             api.add_choice_question(
-                s(&args, "form_id"), s(&args, "title"), b(&args, "required", false),
-                i(&args, "index", 0), s(&args, "choice_type"), &option_refs,
+                s(&args, "form_id"), s(&args, "title"), b(&args, "required"),
+                i(&args, "index"), s(&args, "choice_type"), &option_refs,
             ).await
         }
         "add_scale_question" => api.add_scale_question(
-            s(&args, "form_id"), s(&args, "title"), b(&args, "required", false),
-            i(&args, "index", 0), i(&args, "low", 1), i(&args, "high", 5),
+            s(&args, "form_id"), s(&args, "title"), b(&args, "required"),
+            i(&args, "index"), i(&args, "low"), i(&args, "high"),
             s(&args, "low_label"), s(&args, "high_label"),
         ).await,
         "add_date_question" => api.add_date_question(
-            s(&args, "form_id"), s(&args, "title"), b(&args, "required", false),
-            i(&args, "index", 0), b(&args, "include_time", false), b(&args, "include_year", true),
+            s(&args, "form_id"), s(&args, "title"), b(&args, "required"),
+            i(&args, "index"), b(&args, "include_time"), b(&args, "include_year"),
         ).await,
         "add_time_question" => api.add_time_question(
-            s(&args, "form_id"), s(&args, "title"), b(&args, "required", false),
-            i(&args, "index", 0), b(&args, "include_duration", false),
+            s(&args, "form_id"), s(&args, "title"), b(&args, "required"),
+            i(&args, "index"), b(&args, "include_duration"),
         ).await,
         "add_section_header" => api.add_section_header(
-            s(&args, "form_id"), s(&args, "title"), s(&args, "description"), i(&args, "index", 0),
+            s(&args, "form_id"), s(&args, "title"), s(&args, "description"), i(&args, "index"),
         ).await,
-        "delete_item" => api.delete_item(s(&args, "form_id"), i(&args, "index", 0)).await,
-        "move_item" => api.move_item(s(&args, "form_id"), i(&args, "original_index", 0), i(&args, "new_index", 1)).await,
+        "delete_item" => api.delete_item(s(&args, "form_id"), i(&args, "index")).await,
+        "move_item" => api.move_item(s(&args, "form_id"), i(&args, "original_index"), i(&args, "new_index")).await,
         "update_form_info" => api.update_form_info(s(&args, "form_id"), so(&args, "title"), so(&args, "description")).await,
         "update_settings" => api.update_settings(s(&args, "form_id"), &args["settings"], s(&args, "update_mask")).await,
         "add_file_upload_question" => api.add_file_upload_question(
-            s(&args, "form_id"), s(&args, "title"), b(&args, "required", false),
-            i(&args, "index", 0), i(&args, "max_files", 1), so(&args, "max_file_size").unwrap_or("10MB"),
+            s(&args, "form_id"), s(&args, "title"), b(&args, "required"),
+            i(&args, "index"), i(&args, "max_files"), s(&args, "max_file_size"),
         ).await,
         "add_grid_question" => {
             let rows = str_array(&args, "rows");
@@ -91,8 +251,8 @@ pub async fn execute(client: &GoogleClient, action: &str, args: Value) -> Result
             let row_refs: Vec<&str> = rows.iter().map(|s| s.as_str()).collect();
             let col_refs: Vec<&str> = columns.iter().map(|s| s.as_str()).collect();
             api.add_grid_question(
-                s(&args, "form_id"), s(&args, "title"), b(&args, "required", false),
-                i(&args, "index", 0), &row_refs, &col_refs,
+                s(&args, "form_id"), s(&args, "title"), b(&args, "required"),
+                i(&args, "index"), &row_refs, &col_refs,
             ).await
         }
         _ => Err(VgoogError::Other(format!("Unknown forms action: {action}"))),
@@ -1,4 +1,6 @@
+use crate::actions::{Action, Arg, Literal};
 use crate::api::gmail::GmailApi;
+use crate::batch;
 use crate::client::GoogleClient;
 use crate::error::{Result, VgoogError};
 use serde_json::Value;
@@ -11,8 +13,8 @@ fn str_opt<'a>(args: &'a Value, key: &str) -> Option<&'a str> {
     args.get(key).and_then(|v| v.as_str())
 }
 
-fn u32_field(args: &Value, key: &str, default: u32) -> u32 {
-    args.get(key).and_then(|v| v.as_u64()).map(|v| v as u32).unwrap_or(default)
+fn u32_field(args: &Value, key: &str) -> u32 {
+    args.get(key).and_then(|v| v.as_u64()).map(|v| v as u32).unwrap_or_default()
 }
 
 fn str_array(args: &Value, key: &str) -> Vec<String> {
@@ -22,13 +24,147 @@ fn str_array(args: &Value, key: &str) -> Vec<String> {
         .unwrap_or_default()
 }
 
+const MESSAGE_ID: Arg = Arg::string("id", "Message ID").required();
+const THREAD_ID: Arg = Arg::string("id", "Thread ID").required();
+const LABEL_ID: Arg = Arg::string("id", "Label ID").required();
+const DRAFT_ID: Arg = Arg::string("id", "Draft ID").required();
+const FILTER_ID: Arg = Arg::string("id", "Filter ID").required();
+const MESSAGE_IDS: Arg = Arg::strings("ids", "Message IDs").required();
+const QUERY: Arg = Arg::string("query", "Gmail search query, as in the search box");
+const MAX_RESULTS: Arg = Arg::integer("max_results", "Results per page").default(Literal::Int(20));
+const PAGE_TOKEN: Arg = Arg::string("page_token", "nextPageToken of the previous page");
+const FORMAT: Arg = Arg::string("format", "How much of the message to return")
+    .one_of(&["full", "metadata", "minimal", "raw"])
+    .default(Literal::Str("full"));
+const RAW: Arg = Arg::string("raw", "The RFC 2822 message, base64url encoded").required();
+const ADD_LABELS: Arg = Arg::strings("add_labels", "Label IDs to add");
+const REMOVE_LABELS: Arg = Arg::strings("remove_labels", "Label IDs to remove");
+const SETTINGS: Arg = Arg::object("settings", "The settings resource to store").required();
+const EMAIL: Arg = Arg::string("email", "Email address").required();
+const SEND_AS: Arg = Arg::object("send_as", "The SendAs resource").required();
+
+pub const ACTIONS: &[Action] = &[
+    Action::new("list_messages", "List messages, newest first", &[QUERY, MAX_RESULTS, PAGE_TOKEN]).items("messages"),
+    Action::new("get_message", "Get a message", &[MESSAGE_ID, FORMAT]),
+    Action::new("batch_get_messages", "Get several messages in one batch request", &[MESSAGE_IDS, FORMAT]),
+    Action::new("send_message", "Send a message", &[RAW]),
+    Action::new("trash_message", "Move a message to the trash", &[MESSAGE_ID]),
+    Action::new("untrash_message", "Take a message out of the trash", &[MESSAGE_ID]),
+    Action::new("delete_message", "Delete a message for good, skipping the trash", &[MESSAGE_ID]),
+    Action::new("modify_message", "Add and remove labels on a message", &[MESSAGE_ID, ADD_LABELS, REMOVE_LABELS]),
+    Action::new(
+        "batch_modify_messages",
+        "Add and remove labels on several messages",
+        &[MESSAGE_IDS, ADD_LABELS, REMOVE_LABELS],
+    ),
+    Action::new("batch_delete_messages", "Delete several messages for good", &[MESSAGE_IDS]),
+    Action::new(
+        "get_attachment",
+        "Get a message attachment, base64url encoded",
+        &[
+            Arg::string("message_id", "Message ID").required(),
+            Arg::string("attachment_id", "Attachment ID from the message payload").required(),
+        ],
+    ),
+    Action::new("list_threads", "List threads, newest first", &[QUERY, MAX_RESULTS, PAGE_TOKEN]).items("threads"),
+    Action::new(
+        "get_thread",
+        "Get a thread with its messages",
+        &[
+            THREAD_ID,
+            Arg::string("format", "How much of each message to return")
+                .one_of(&["full", "metadata", "minimal"])
+                .default(Literal::Str("full")),
+        ],
+    ),
+    Action::new("trash_thread", "Move a thread to the trash", &[THREAD_ID]),
+    Action::new("untrash_thread", "Take a thread out of the trash", &[THREAD_ID]),
+    Action::new("delete_thread", "Delete a thread for good, skipping the trash", &[THREAD_ID]),
+    Action::new("modify_thread", "Add and remove labels on every message of a thread", &[THREAD_ID, ADD_LABELS, REMOVE_LABELS]),
+    Action::new("list_labels", "List labels, system and user", &[]),
+    Action::new("get_label", "Get a label with its message counts", &[LABEL_ID]),
+    Action::new(
+        "create_label",
+        "Create a label",
+        &[
+            Arg::string("name", "Label name; `/` nests it under another").required(),
+            Arg::string("label_list_visibility", "Visibility in the label list")
+                .one_of(&["labelShow", "labelShowIfUnread", "labelHide"])
+                .default(Literal::Str("labelShow")),
+            Arg::string("message_list_visibility", "Visibility in the message list")
+                .one_of(&["show", "hide"])
+                .default(Literal::Str("show")),
+        ],
+    ),
+    Action::new("update_label", "Rename a label", &[LABEL_ID, Arg::string("name", "New name").required()]),
+    Action::new("delete_label", "Delete a label and remove it from its messages", &[LABEL_ID]),
+    Action::new("list_drafts", "List drafts", &[MAX_RESULTS, PAGE_TOKEN]).items("drafts"),
+    Action::new("get_draft", "Get a draft", &[DRAFT_ID, FORMAT]),
+    Action::new("create_draft", "Create a draft", &[RAW]),
+    Action::new("update_draft", "Replace a draft's message", &[DRAFT_ID, RAW]),
+    Action::new("send_draft", "Send a draft", &[DRAFT_ID]),
+    Action::new("delete_draft", "Delete a draft", &[DRAFT_ID]),
+    Action::new("get_vacation_settings", "Get the vacation responder", &[]),
+    Action::new("update_vacation_settings", "Update the vacation responder", &[SETTINGS]),
+    Action::new("get_auto_forwarding", "Get the auto-forwarding setting", &[]),
+    Action::new("update_auto_forwarding", "Update the auto-forwarding setting", &[SETTINGS]),
+    Action::new("get_imap_settings", "Get IMAP settings", &[]),
+    Action::new("update_imap_settings", "Update IMAP settings", &[SETTINGS]),
+    Action::new("get_pop_settings", "Get POP settings", &[]),
+    Action::new("update_pop_settings", "Update POP settings", &[SETTINGS]),
+    Action::new("get_language_settings", "Get the display language", &[]),
+    Action::new(
+        "update_language_settings",
+        "Set the display language",
+        &[Arg::string("display_language", "RFC 3066 language tag, such as `en-GB`").required()],
+    ),
+    Action::new("list_filters", "List message filters", &[]),
+    Action::new("get_filter", "Get a message filter", &[FILTER_ID]),
+    Action::new(
+        "create_filter",
+        "Create a message filter",
+        &[Arg::object("filter", "The Filter resource, with `criteria` and `action`").required()],
+    ),
+    Action::new("delete_filter", "Delete a message filter", &[FILTER_ID]),
+    Action::new("list_forwarding_addresses", "List forwarding addresses", &[]),
+    Action::new("create_forwarding_address", "Add a forwarding address, which Google asks to verify", &[EMAIL]),
+    Action::new("delete_forwarding_address", "Remove a forwarding address", &[EMAIL]),
+    Action::new("list_send_as", "List send-as aliases", &[]),
+    Action::new("get_send_as", "Get a send-as alias", &[EMAIL]),
+    Action::new("create_send_as", "Create a send-as alias", &[SEND_AS]),
+    Action::new("update_send_as", "Update a send-as alias", &[EMAIL, SEND_AS]),
+    Action::new("delete_send_as", "Delete a send-as alias", &[EMAIL]),
+    Action::new("verify_send_as", "Send the verification email for a send-as alias", &[EMAIL]),
+    Action::new("list_delegates", "List delegates", &[]),
+    Action::new("add_delegate", "Add a delegate", &[EMAIL]),
+    Action::new("remove_delegate", "Remove a delegate", &[EMAIL]),
+    Action::new("get_profile", "Get the mailbox's address, message counts and history ID", &[]),
+    Action::new(
+        "list_history",
+        "List mailbox changes since a history ID",
+        &[
+            Arg::string("start_history_id", "historyId to start from, as returned by get_profile or a message")
+                .required(),
+            Arg::integer("max_results", "Results per page").default(Literal::Int(100)),
+            PAGE_TOKEN,
+        ],
+    )
+    .items("history"),
+];
+
 pub async fn execute(client: &GoogleClient, action: &str, args: Value) -> Result<Value> {
     let api = GmailApi::new(client);
     match action {
         "list_messages" => {
-            api.list_messages(str_opt(&args, "query"), None, u32_field(&args, "max_results", 20), str_opt(&args, "page_token")).await
+            api.list_messages(str_opt(&args, "query"), None, u32_field(&args, "max_results"), str_opt(&args, "page_token")).await
+        }
+        "get_message" => api.get_message(str_field(&args, "id"), str_field(&args, "format")).await,
+        "batch_get_messages" => {
+            let ids = str_array(&args, "ids");
+            let id_refs: Vec<&str> = ids.iter().map(|s| s.as_str()).collect();
+            let results = api.batch_get_messages(&id_refs, str_field(&args, "format")).await?;
+            Ok(batch::results_to_json(results))
         }
-        "get_message" => api.get_message(str_field(&args, "id"), str_opt(&args, "format").unwrap_or("full")).await,
         "send_message" => api.send_message(str_field(&args, "raw")).await,
         "trash_message" => api.trash_message(str_field(&args, "id")).await,
         "untrash_message" => api.untrash_message(str_field(&args, "id")).await,
@@ -55,8 +191,8 @@ pub async fn execute(client: &GoogleClient, action: &str, args: Value) -> Result
             api.batch_delete_messages(&id_refs).await
         }
         "get_attachment" => api.get_attachment(str_field(&args, "message_id"), str_field(&args, "attachment_id")).await,
-        "list_threads" => api.list_threads(str_opt(&args, "query"), u32_field(&args, "max_results", 20), str_opt(&args, "page_token")).await,
-        "get_thread" => api.get_thread(str_field(&args, "id"), str_opt(&args, "format").unwrap_or("full")).await,
+        "list_threads" => api.list_threads(str_opt(&args, "query"), u32_field(&args, "max_results"), str_opt(&args, "page_token")).await,
+        "get_thread" => api.get_thread(str_field(&args, "id"), str_field(&args, "format")).await,
         "trash_thread" => api.trash_thread(str_field(&args, "id")).await,
         "untrash_thread" => api.untrash_thread(str_field(&args, "id")).await,
         "delete_thread" => api.delete_thread(str_field(&args, "id")).await,
@@ -71,13 +207,13 @@ pub async fn execute(client: &GoogleClient, action: &str, args: Value) -> Result
         "get_label" => api.get_label(str_field(&args, "id")).await,
         "create_label" => api.create_label(
             str_field(&args, "name"),
-            str_opt(&args, "label_list_visibility").unwrap_or("labelShow"),
-            str_opt(&args, "message_list_visibility").unwrap_or("show"),
+            str_field(&args, "label_list_visibility"),
+            str_field(&args, "message_list_visibility"),
         ).await,
         "update_label" => api.update_label(str_field(&args, "id"), str_field(&args, "name")).await,
         "delete_label" => api.delete_label(str_field(&args, "id")).await,
-        "list_drafts" => api.list_drafts(u32_field(&args, "max_results", 20), str_opt(&args, "page_token")).await,
-        "get_draft" => api.get_draft(str_field(&args, "id"), str_opt(&args, "format").unwrap_or("full")).await,
+        "list_drafts" => api.list_drafts(u32_field(&args, "max_results"), str_opt(&args, "page_token")).await,
+        "get_draft" => api.get_draft(str_field(&args, "id"), str_field(&args, "format")).await,
         "create_draft" => api.create_draft(str_field(&args, "raw")).await,
         "update_draft" => api.update_draft(str_field(&args, "id"), str_field(&args, "raw")).await,
         "send_draft" => api.send_draft(str_field(&args, "id")).await,
@@ -111,7 +247,7 @@ pub async fn execute(client: &GoogleClient, action: &str, args: Value) -> Result
         "get_profile" => api.get_profile().await,
         "list_history" => api.list_history(
             str_field(&args, "start_history_id"),
-            u32_field(&args, "max_results", 100),
+            u32_field(&args, "max_results"),
             str_opt(&args, "page_token"),
         ).await,
         _ => Err(VgoogError::Other(format!("Unknown gmail action: {action}"))),
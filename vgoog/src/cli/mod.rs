@@ -1,4 +1,6 @@
 pub mod apps_script;
+pub mod auth;
+pub mod batch;
 pub mod calendar;
 pub mod docs;
 pub mod drive;
@@ -6,17 +8,40 @@ pub mod exec;
 pub mod forms;
 pub mod gmail;
 pub mod people;
+pub mod quota;
 pub mod sheets;
 pub mod slides;
 pub mod tasks;
 
-use clap::{Parser, Subcommand};
+use crate::cassette::CassetteMode;
+use clap::{Args, Parser, Subcommand};
+use std::path::PathBuf;
 
 #[derive(Parser)]
 #[command(name = "vgoog", about = "Google Workspace CLI & TUI")]
 pub struct Cli {
     #[command(subcommand)]
     pub command: Option<CliCommand>,
+    /// Record every API request and response to a cassette file, tokens redacted (or VGOOG_RECORD)
+    #[arg(long, global = true, value_name = "FILE", conflicts_with = "replay")]
+    pub record: Option<PathBuf>,
+    /// Serve API responses from a recorded cassette instead of the network (or VGOOG_REPLAY)
+    #[arg(long, global = true, value_name = "FILE")]
+    pub replay: Option<PathBuf>,
+    /// Fetch everything from Google instead of the local response cache
+    #[arg(long, global = true)]
+    pub no_cache: bool,
+}
+
+impl Cli {
+    /// The cassette from `--record`/`--replay`, falling back to the environment
+    pub fn cassette(&self) -> crate::error::Result<Option<CassetteMode>> {
+        match (&self.record, &self.replay) {
+            (Some(path), _) => Ok(Some(CassetteMode::Record(path.clone()))),
+            (None, Some(path)) => Ok(Some(CassetteMode::Replay(path.clone()))),
+            (None, None) => CassetteMode::from_env(),
+        }
+    }
 }
 
 #[derive(Subcommand)]
@@ -32,9 +57,175 @@ pub enum CliCommand {
         /// Account name to use (overrides active_account)
         #[arg(long)]
         account: Option<String>,
+        /// Follow nextPageToken and return the items of every page
+        #[arg(long)]
+        all: bool,
+        /// Stop after this many items, following pages as needed (implies --all)
+        #[arg(long)]
+        limit: Option<usize>,
+    },
+    /// Send several raw requests in one batch call and return one result each
+    Batch {
+        /// Service: gmail, calendar, drive
+        service: String,
+        /// JSON array of {"method", "path", "body"}; paths are relative to the service base URL
+        requests: String,
+        /// Account name to use (overrides active_account)
+        #[arg(long)]
+        account: Option<String>,
     },
     /// List all available services and actions
     List,
+    /// Describe how to call actions: their arguments as JSON Schema
+    Describe {
+        /// Service; every service if left out
+        service: Option<String>,
+        /// Action; every action of the service if left out
+        action: Option<String>,
+    },
     /// Check auth status
     Status,
+    /// Show the per-API rate limits and what recent commands spent of them
+    Quota {
+        /// How many recent commands to show
+        #[arg(long, default_value_t = 10)]
+        last: usize,
+    },
+    /// Manage the local cache of API responses
+    Cache {
+        #[command(subcommand)]
+        command: CacheCommand,
+    },
+    /// Manage account credentials
+    Auth {
+        #[command(subcommand)]
+        command: AuthCommand,
+    },
+    /// Run the in-memory fake Google server (for tests and offline development)
+    #[cfg(feature = "test-support")]
+    #[command(hide = true)]
+    FakeServer {
+        /// Port to listen on (0 picks a free one)
+        #[arg(long, default_value_t = 8931)]
+        port: u16,
+    },
+}
+
+/// Which scopes a sign-in asks for
+#[derive(Args)]
+pub struct ScopeArgs {
+    /// Only ask for this service's scopes (repeatable, e.g. gmail; defaults to every service)
+    #[arg(long = "service")]
+    pub services: Vec<String>,
+    /// Ask for read-only access to the services
+    #[arg(long)]
+    pub read_only: bool,
+    /// Extra scope to ask for (repeatable; a bare name like gmail.readonly is expanded)
+    #[arg(long = "scope")]
+    pub scopes: Vec<String>,
+}
+
+impl ScopeArgs {
+    pub fn resolve(&self) -> crate::error::Result<Vec<String>> {
+        crate::auth::scopes::for_login(&self.services, self.read_only, &self.scopes)
+    }
+}
+
+#[derive(Subcommand)]
+pub enum CacheCommand {
+    /// Remove cached responses, so the next requests go to Google
+    Clear {
+        /// Only this account's responses
+        #[arg(long)]
+        account: Option<String>,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum AuthCommand {
+    /// Authorize an account (browser sign-in, or --device for headless machines)
+    Login {
+        /// Account name to create or re-authorize (defaults to the active account)
+        #[arg(long)]
+        account: Option<String>,
+        /// Display label for a new account
+        #[arg(long)]
+        label: Option<String>,
+        /// Use the device-code flow instead of a local browser
+        #[arg(long)]
+        device: bool,
+        /// OAuth client ID (falls back to VGOOG_CLIENT_ID, then the account's stored client)
+        #[arg(long)]
+        client_id: Option<String>,
+        /// OAuth client secret (falls back to VGOOG_CLIENT_SECRET, then the account's stored client)
+        #[arg(long)]
+        client_secret: Option<String>,
+        #[command(flatten)]
+        scopes: ScopeArgs,
+    },
+    /// Add or re-authorize an account from a downloaded client_secret_*.json (then
+    /// sign in) or a gcloud application_default_credentials.json (used as is)
+    Import {
+        /// Credentials file (defaults to GOOGLE_APPLICATION_CREDENTIALS, then gcloud's ADC file)
+        path: Option<PathBuf>,
+        /// Account name to create or update (defaults to the active account)
+        #[arg(long)]
+        account: Option<String>,
+        /// Display label for a new account
+        #[arg(long)]
+        label: Option<String>,
+        /// For client secrets: use the device-code flow instead of a local browser
+        #[arg(long)]
+        device: bool,
+        #[command(flatten)]
+        scopes: ScopeArgs,
+    },
+    /// Add an account backed by a service-account key (domain-wide delegation)
+    ServiceAccount {
+        /// Account name to create
+        #[arg(long)]
+        account: String,
+        /// User to impersonate; omit to act as the service account itself
+        #[arg(long)]
+        subject: Option<String>,
+        /// Path to the downloaded JSON key (optional if --key-name is already registered)
+        #[arg(long)]
+        key_file: Option<std::path::PathBuf>,
+        /// Name to register the key under (defaults to the key's client email prefix)
+        #[arg(long)]
+        key_name: Option<String>,
+        /// Scopes for a new key (repeatable; defaults to all vgoog scopes).
+        /// They belong to the key, so every account using it shares them.
+        #[arg(long = "scope", requires = "key_file")]
+        scopes: Vec<String>,
+        /// Overwrite a key already registered under the same name
+        #[arg(long, requires = "key_file")]
+        replace: bool,
+        /// Display label
+        #[arg(long)]
+        label: Option<String>,
+    },
+    /// Show granted scopes, token expiry and email per account, and the services missing scopes
+    Info {
+        /// Only this account (defaults to all)
+        #[arg(long)]
+        account: Option<String>,
+    },
+    /// Revoke an account's refresh token at Google and clear its stored tokens
+    Revoke {
+        /// Account to revoke (defaults to the active account)
+        #[arg(long)]
+        account: Option<String>,
+        /// Also remove the account from the config
+        #[arg(long)]
+        remove: bool,
+    },
+    /// Encrypt stored secrets with a passphrase (VGOOG_PASSPHRASE or a prompt) or a key file
+    Lock {
+        /// Key file to seal with instead of a passphrase; 32 random bytes are written if it does not exist
+        #[arg(long)]
+        key_file: Option<std::path::PathBuf>,
+    },
+    /// Decrypt stored secrets back into config.toml
+    Unlock,
 }
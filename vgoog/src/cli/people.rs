@@ -1,3 +1,4 @@
+use crate::actions::{Action, Arg, Literal};
 use crate::api::people::PeopleApi;
 use crate::client::GoogleClient;
 use crate::error::{Result, VgoogError};
@@ -11,12 +12,12 @@ fn so<'a>(args: &'a Value, key: &str) -> Option<&'a str> {
     args.get(key).and_then(|v| v.as_str())
 }
 
-fn u(args: &Value, key: &str, default: u32) -> u32 {
-    args.get(key).and_then(|v| v.as_u64()).map(|v| v as u32).unwrap_or(default)
+fn u(args: &Value, key: &str) -> u32 {
+    args.get(key).and_then(|v| v.as_u64()).map(|v| v as u32).unwrap_or_default()
 }
 
-fn b(args: &Value, key: &str, default: bool) -> bool {
-    args.get(key).and_then(|v| v.as_bool()).unwrap_or(default)
+fn b(args: &Value, key: &str) -> bool {
+    args.get(key).and_then(|v| v.as_bool()).unwrap_or_default()
 }
 
 fn str_array(args: &Value, key: &str) -> Vec<String> {
@@ -30,12 +31,114 @@ fn val_array(args: &Value, key: &str) -> Vec<Value> {
     args.get(key).and_then(|v| v.as_array()).cloned().unwrap_or_default()
 }
 
+const RESOURCE_NAME: Arg = Arg::string("resource_name", "Contact resource name, `people/<id>`").required();
+const GROUP_RESOURCE_NAME: Arg = Arg::string("resource_name", "Group resource name, `contactGroups/<id>`").required();
+const RESOURCE_NAMES: Arg = Arg::strings("resource_names", "Contact resource names").required();
+const PAGE_SIZE: Arg = Arg::integer("page_size", "Results per page").default(Literal::Int(20));
+const PAGE_TOKEN: Arg = Arg::string("page_token", "nextPageToken of the previous page");
+const PERSON: Arg = Arg::object("person", "The Person resource").required();
+const UPDATE_MASK: Arg = Arg::string("update_mask", "Comma-separated person fields to change, such as `names,emailAddresses`")
+    .required();
+
+pub const ACTIONS: &[Action] = &[
+    Action::new(
+        "get_person",
+        "Get a contact or profile",
+        &[
+            Arg::string("resource_name", "Resource name, `people/<id>` or `people/me`").required(),
+            Arg::string("person_fields", "Comma-separated fields to return")
+                .default(Literal::Str("names,emailAddresses,phoneNumbers")),
+        ],
+    ),
+    Action::new("get_me", "Get the signed-in user's profile", &[]),
+    Action::new(
+        "get_batch_people",
+        "Get several contacts or profiles",
+        &[RESOURCE_NAMES, Arg::string("person_fields", "Comma-separated fields to return").default(Literal::Str("names,emailAddresses"))],
+    ),
+    Action::new(
+        "list_contacts",
+        "List the user's contacts",
+        &[
+            PAGE_SIZE,
+            PAGE_TOKEN,
+            Arg::string("person_fields", "Comma-separated fields to return")
+                .default(Literal::Str("names,emailAddresses,phoneNumbers")),
+            Arg::string("sort_order", "Sort order").one_of(&[
+                "LAST_MODIFIED_ASCENDING",
+                "LAST_MODIFIED_DESCENDING",
+                "FIRST_NAME_ASCENDING",
+                "LAST_NAME_ASCENDING",
+            ]),
+        ],
+    )
+    .items("connections"),
+    Action::new(
+        "search_contacts",
+        "Search contacts by name, email or phone prefix",
+        &[Arg::string("query", "Prefix to match").required(), Arg::integer("page_size", "Most results").default(Literal::Int(10))],
+    ),
+    Action::new("create_contact", "Create a contact", &[PERSON]),
+    Action::new("update_contact", "Update fields of a contact", &[RESOURCE_NAME, PERSON, UPDATE_MASK]),
+    Action::new("delete_contact", "Delete a contact", &[RESOURCE_NAME]),
+    Action::new(
+        "batch_create_contacts",
+        "Create several contacts",
+        &[Arg::array("contacts", "ContactToCreate objects, each with `contactPerson`").required()],
+    ),
+    Action::new("batch_delete_contacts", "Delete several contacts", &[RESOURCE_NAMES]),
+    Action::new(
+        "batch_update_contacts",
+        "Update several contacts",
+        &[Arg::object("contacts", "Person resources by resource name").required(), UPDATE_MASK],
+    ),
+    Action::new("list_contact_groups", "List contact groups", &[PAGE_SIZE, PAGE_TOKEN]).items("contactGroups"),
+    Action::new("get_contact_group", "Get a contact group", &[GROUP_RESOURCE_NAME]),
+    Action::new("create_contact_group", "Create a contact group", &[Arg::string("name", "Group name").required()]),
+    Action::new("update_contact_group", "Rename a contact group", &[GROUP_RESOURCE_NAME, Arg::string("name", "New name").required()]),
+    Action::new(
+        "delete_contact_group",
+        "Delete a contact group",
+        &[GROUP_RESOURCE_NAME, Arg::boolean("delete_contacts", "Delete its contacts too").default(Literal::Bool(false))],
+    ),
+    Action::new(
+        "modify_contact_group_members",
+        "Add and remove contacts in a group",
+        &[
+            GROUP_RESOURCE_NAME,
+            Arg::strings("add", "Contact resource names to add"),
+            Arg::strings("remove", "Contact resource names to remove"),
+        ],
+    ),
+    Action::new(
+        "list_other_contacts",
+        "List \"other contacts\", people the user interacted with",
+        &[PAGE_SIZE, PAGE_TOKEN],
+    )
+    .items("otherContacts"),
+    Action::new(
+        "copy_other_contact_to_contacts",
+        "Copy an \"other contact\" into the user's contacts",
+        &[Arg::string("resource_name", "Resource name, `otherContacts/<id>`").required()],
+    ),
+    Action::new(
+        "search_directory",
+        "Search the domain directory",
+        &[
+            Arg::string("query", "Prefix to match").required(),
+            Arg::integer("page_size", "Results per page").default(Literal::Int(10)),
+            PAGE_TOKEN,
+        ],
+    )
+    .items("people"),
+];
+
 pub async fn execute(client: &GoogleClient, action: &str, args: Value) -> Result<Value> {
     let api = PeopleApi::new(client);
     match action {
         "get_person" => api.get_person(
             s(&args, "resource_name"),
-            so(&args, "person_fields").unwrap_or("names,emailAddresses,phoneNumbers"),
+            s(&args, "person_fields"),
         ).await,
         "get_me" => api.get_me().await,
         "get_batch_people" => {
@@ -43,15 +146,15 @@ pub async fn execute(client: &GoogleClient, action: &str, args: Value) -> Result
             let name_refs: Vec<&str> = names.iter().map(|s| s.as_str()).collect();
             api.get_batch_people(
                 &name_refs,
-                so(&args, "person_fields").unwrap_or("names,emailAddresses"),
+                s(&args, "person_fields"),
             ).await
         }
         "list_contacts" => api.list_contacts(
-            u(&args, "page_size", 20), so(&args, "page_token"),
-            so(&args, "person_fields").unwrap_or("names,emailAddresses,phoneNumbers"),
+            u(&args, "page_size"), so(&args, "page_token"),
+            s(&args, "person_fields"),
             so(&args, "sort_order"),
         ).await,
-        "search_contacts" => api.search_contacts(s(&args, "query"), u(&args, "page_size", 10)).await,
+        "search_contacts" => api.search_contacts(s(&args, "query"), u(&args, "page_size")).await,
         "create_contact" => api.create_contact(&args["person"]).await,
         "update_contact" => api.update_contact(s(&args, "resource_name"), &args["person"], s(&args, "update_mask")).await,
         "delete_contact" => api.delete_contact(s(&args, "resource_name")).await,
@@ -65,11 +168,11 @@ pub async fn execute(client: &GoogleClient, action: &str, args: Value) -> Result
             api.batch_delete_contacts(&name_refs).await
         }
         "batch_update_contacts" => api.batch_update_contacts(&args["contacts"], s(&args, "update_mask")).await,
-        "list_contact_groups" => api.list_contact_groups(u(&args, "page_size", 20), so(&args, "page_token")).await,
+        "list_contact_groups" => api.list_contact_groups(u(&args, "page_size"), so(&args, "page_token")).await,
         "get_contact_group" => api.get_contact_group(s(&args, "resource_name")).await,
         "create_contact_group" => api.create_contact_group(s(&args, "name")).await,
         "update_contact_group" => api.update_contact_group(s(&args, "resource_name"), s(&args, "name")).await,
-        "delete_contact_group" => api.delete_contact_group(s(&args, "resource_name"), b(&args, "delete_contacts", false)).await,
+        "delete_contact_group" => api.delete_contact_group(s(&args, "resource_name"), b(&args, "delete_contacts")).await,
         "modify_contact_group_members" => {
             let add = str_array(&args, "add");
             let remove = str_array(&args, "remove");
@@ -77,9 +180,9 @@ pub async fn execute(client: &GoogleClient, action: &str, args: Value) -> Result
             let remove_refs: Vec<&str> = remove.iter().map(|s| s.as_str()).collect();
             api.modify_contact_group_members(s(&args, "resource_name"), &add_refs, &remove_refs).await
         }
-        "list_other_contacts" => api.list_other_contacts(u(&args, "page_size", 20), so(&args, "page_token")).await,
+        "list_other_contacts" => api.list_other_contacts(u(&args, "page_size"), so(&args, "page_token")).await,
         "copy_other_contact_to_contacts" => api.copy_other_contact_to_contacts(s(&args, "resource_name")).await,
-        "search_directory" => api.search_directory(s(&args, "query"), u(&args, "page_size", 10), so(&args, "page_token")).await,
+        "search_directory" => api.search_directory(s(&args, "query"), u(&args, "page_size"), so(&args, "page_token")).await,
         _ => Err(VgoogError::Other(format!("Unknown contacts action: {action}"))),
     }
 }
@@ -0,0 +1,49 @@
+use crate::config::Config;
+use crate::error::Result;
+use crate::quota::{self, ApiUsage};
+use serde_json::{json, Value};
+use std::collections::BTreeMap;
+
+/// The configured budgets, then the newest `last` commands from the usage
+/// log with what each spent per API and the share of the budget that was,
+/// plus their totals
+pub fn report(config: &Config, last: usize) -> Result<Value> {
+    let limits = quota::limits(&config.rate_limits);
+    let entries = quota::recent(last)?;
+
+    let mut totals: BTreeMap<String, ApiUsage> = BTreeMap::new();
+    let commands: Vec<Value> = entries
+        .iter()
+        .map(|entry| {
+            let apis: serde_json::Map<String, Value> = entry
+                .apis
+                .iter()
+                .map(|(api, usage)| {
+                    let total = totals.entry(api.clone()).or_default();
+                    total.requests += usage.requests;
+                    total.units += usage.units;
+                    total.waited_ms += usage.waited_ms;
+
+                    let mut value = json!(usage);
+                    value["budget_percent"] = json!(limits
+                        .get(api)
+                        .and_then(|limit| quota::budget_percent(usage, limit, entry.elapsed_ms)));
+                    (api.clone(), value)
+                })
+                .collect();
+            json!({
+                "at": entry.at,
+                "command": entry.command,
+                "account": entry.account,
+                "elapsed_ms": entry.elapsed_ms,
+                "apis": apis,
+            })
+        })
+        .collect();
+
+    Ok(json!({
+        "limits": limits,
+        "commands": commands,
+        "totals": totals,
+    }))
+}
@@ -1,3 +1,4 @@
+use crate::actions::{Action, Arg, Literal};
 use crate::api::sheets::SheetsApi;
 use crate::client::GoogleClient;
 use crate::error::{Result, VgoogError};
@@ -11,12 +12,12 @@ fn so<'a>(args: &'a Value, key: &str) -> Option<&'a str> {
     args.get(key).and_then(|v| v.as_str())
 }
 
-fn i(args: &Value, key: &str, default: i64) -> i64 {
-    args.get(key).and_then(|v| v.as_i64()).unwrap_or(default)
+fn i(args: &Value, key: &str) -> i64 {
+    args.get(key).and_then(|v| v.as_i64()).unwrap_or_default()
 }
 
-fn b(args: &Value, key: &str, default: bool) -> bool {
-    args.get(key).and_then(|v| v.as_bool()).unwrap_or(default)
+fn b(args: &Value, key: &str) -> bool {
+    args.get(key).and_then(|v| v.as_bool()).unwrap_or_default()
 }
 
 fn str_array(args: &Value, key: &str) -> Vec<String> {
@@ -33,6 +34,77 @@ fn val_array(args: &Value, key: &str) -> Vec<Value> {
         .unwrap_or_default()
 }
 
+const SPREADSHEET_ID: Arg = Arg::string("spreadsheet_id", "Spreadsheet ID").required();
+const RANGE: Arg = Arg::string("range", "A1 range, such as `Sheet1!A1:C10`").required();
+const RANGES: Arg = Arg::strings("ranges", "A1 ranges").required();
+const VALUES: Arg = Arg::array("values", "Rows of cell values").required();
+const INPUT_OPTION: Arg = Arg::string("input_option", "How input is interpreted")
+    .one_of(&["RAW", "USER_ENTERED"])
+    .default(Literal::Str("USER_ENTERED"));
+const SHEET_ID: Arg = Arg::integer("sheet_id", "Numeric sheet ID (not its title)").default(Literal::Int(0));
+const START_ROW: Arg = Arg::integer("start_row", "First row, 0-based").default(Literal::Int(0));
+const END_ROW: Arg = Arg::integer("end_row", "Row after the last, 0-based").default(Literal::Int(100));
+const START_COL: Arg = Arg::integer("start_col", "First column, 0-based").default(Literal::Int(0));
+const END_COL: Arg = Arg::integer("end_col", "Column after the last, 0-based").default(Literal::Int(26));
+
+pub const ACTIONS: &[Action] = &[
+    Action::new("create_spreadsheet", "Create a spreadsheet", &[Arg::string("title", "Spreadsheet title").required()]),
+    Action::new("get_spreadsheet", "Get a spreadsheet's properties and sheets", &[Arg::string("id", "Spreadsheet ID").required()]),
+    Action::new(
+        "get_spreadsheet_with_ranges",
+        "Get a spreadsheet with the cell data of some ranges",
+        &[Arg::string("id", "Spreadsheet ID").required(), RANGES],
+    ),
+    Action::new(
+        "get_values",
+        "Get the values of a range",
+        &[
+            SPREADSHEET_ID,
+            RANGE,
+            Arg::string("value_render", "How values are rendered")
+                .one_of(&["FORMATTED_VALUE", "UNFORMATTED_VALUE", "FORMULA"]),
+        ],
+    ),
+    Action::new("batch_get_values", "Get the values of several ranges", &[SPREADSHEET_ID, RANGES]),
+    Action::new("update_values", "Write values to a range", &[SPREADSHEET_ID, RANGE, VALUES, INPUT_OPTION]),
+    Action::new("append_values", "Append rows after the table in a range", &[SPREADSHEET_ID, RANGE, VALUES, INPUT_OPTION]),
+    Action::new("clear_values", "Clear the values of a range, keeping formatting", &[SPREADSHEET_ID, RANGE]),
+    Action::new(
+        "batch_update_values",
+        "Write values to several ranges",
+        &[SPREADSHEET_ID, Arg::array("data", "ValueRange objects, each with `range` and `values`").required(), INPUT_OPTION],
+    ),
+    Action::new("batch_clear_values", "Clear the values of several ranges", &[SPREADSHEET_ID, RANGES]),
+    Action::new(
+        "batch_update",
+        "Apply raw spreadsheets.batchUpdate requests",
+        &[SPREADSHEET_ID, Arg::array("requests", "Request objects").required()],
+    ),
+    Action::new("add_sheet", "Add a sheet", &[SPREADSHEET_ID, Arg::string("title", "Sheet title").required()]),
+    Action::new("delete_sheet", "Delete a sheet", &[SPREADSHEET_ID, SHEET_ID]),
+    Action::new("rename_sheet", "Rename a sheet", &[SPREADSHEET_ID, SHEET_ID, Arg::string("new_title", "New title").required()]),
+    Action::new("auto_resize_columns", "Fit columns to their contents", &[SPREADSHEET_ID, SHEET_ID, START_COL, END_COL]),
+    Action::new(
+        "sort_range",
+        "Sort the rows of a range by one column",
+        &[
+            SPREADSHEET_ID,
+            SHEET_ID,
+            START_ROW,
+            END_ROW,
+            START_COL,
+            END_COL,
+            Arg::integer("sort_col", "Column to sort by, 0-based").default(Literal::Int(0)),
+            Arg::boolean("ascending", "Sort ascending").default(Literal::Bool(true)),
+        ],
+    ),
+    Action::new(
+        "create_named_range",
+        "Name a range",
+        &[SPREADSHEET_ID, Arg::string("name", "Range name").required(), SHEET_ID, START_ROW, END_ROW, START_COL, END_COL],
+    ),
+];
+
 pub async fn execute(client: &GoogleClient, action: &str, args: Value) -> Result<Value> {
     let api = SheetsApi::new(client);
     match action {
@@ -51,18 +123,18 @@ pub async fn execute(client: &GoogleClient, action: &str, args: Value) -> Result
         }
         "update_values" => api.update_values(
             s(&args, "spreadsheet_id"), s(&args, "range"), &args["values"],
-            so(&args, "input_option").unwrap_or("USER_ENTERED"),
+            s(&args, "input_option"),
         ).await,
         "append_values" => api.append_values(
             s(&args, "spreadsheet_id"), s(&args, "range"), &args["values"],
-            so(&args, "input_option").unwrap_or("USER_ENTERED"),
+            s(&args, "input_option"),
         ).await,
         "clear_values" => api.clear_values(s(&args, "spreadsheet_id"), s(&args, "range")).await,
         "batch_update_values" => {
             let data = val_array(&args, "data");
             api.batch_update_values(
                 s(&args, "spreadsheet_id"), &data,
-                so(&args, "input_option").unwrap_or("USER_ENTERED"),
+                s(&args, "input_option"),
             ).await
         }
         "batch_clear_values" => {
@@ -75,22 +147,22 @@ pub async fn execute(client: &GoogleClient, action: &str, args: Value) -> Result
             api.batch_update(s(&args, "spreadsheet_id"), &requests).await
         }
         "add_sheet" => api.add_sheet(s(&args, "spreadsheet_id"), s(&args, "title")).await,
-        "delete_sheet" => api.delete_sheet(s(&args, "spreadsheet_id"), i(&args, "sheet_id", 0)).await,
-        "rename_sheet" => api.rename_sheet(s(&args, "spreadsheet_id"), i(&args, "sheet_id", 0), s(&args, "new_title")).await,
+        "delete_sheet" => api.delete_sheet(s(&args, "spreadsheet_id"), i(&args, "sheet_id")).await,
+        "rename_sheet" => api.rename_sheet(s(&args, "spreadsheet_id"), i(&args, "sheet_id"), s(&args, "new_title")).await,
         "auto_resize_columns" => api.auto_resize_columns(
-            s(&args, "spreadsheet_id"), i(&args, "sheet_id", 0),
-            i(&args, "start_col", 0), i(&args, "end_col", 26),
+            s(&args, "spreadsheet_id"), i(&args, "sheet_id"),
+            i(&args, "start_col"), i(&args, "end_col"),
         ).await,
         "sort_range" => api.sort_range(
-            s(&args, "spreadsheet_id"), i(&args, "sheet_id", 0),
-            i(&args, "start_row", 0), i(&args, "end_row", 100),
-            i(&args, "start_col", 0), i(&args, "end_col", 26),
-            i(&args, "sort_col", 0), b(&args, "ascending", true),
+            s(&args, "spreadsheet_id"), i(&args, "sheet_id"),
+            i(&args, "start_row"), i(&args, "end_row"),
+            i(&args, "start_col"), i(&args, "end_col"),
+            i(&args, "sort_col"), b(&args, "ascending"),
         ).await,
         "create_named_range" => api.create_named_range(
-            s(&args, "spreadsheet_id"), s(&args, "name"), i(&args, "sheet_id", 0),
-            i(&args, "start_row", 0), i(&args, "end_row", 100),
-            i(&args, "start_col", 0), i(&args, "end_col", 26),
+            s(&args, "spreadsheet_id"), s(&args, "name"), i(&args, "sheet_id"),
+            i(&args, "start_row"), i(&args, "end_row"),
+            i(&args, "start_col"), i(&args, "end_col"),
         ).await,
         _ => Err(VgoogError::Other(format!("Unknown sheets action: {action}"))),
     }
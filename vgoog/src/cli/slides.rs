@@ -1,3 +1,4 @@
+use crate::actions::{Action, Arg, Literal};
 use crate::api::slides::SlidesApi;
 use crate::client::GoogleClient;
 use crate::error::{Result, VgoogError};
@@ -7,30 +8,146 @@ fn s<'a>(args: &'a Value, key: &str) -> &'a str {
     args.get(key).and_then(|v| v.as_str()).unwrap_or("")
 }
 
-fn so<'a>(args: &'a Value, key: &str) -> Option<&'a str> {
-    args.get(key).and_then(|v| v.as_str())
-}
-
-fn i(args: &Value, key: &str, default: i64) -> i64 {
-    args.get(key).and_then(|v| v.as_i64()).unwrap_or(default)
+fn i(args: &Value, key: &str) -> i64 {
+    args.get(key).and_then(|v| v.as_i64()).unwrap_or_default()
 }
 
 fn io(args: &Value, key: &str) -> Option<i64> {
     args.get(key).and_then(|v| v.as_i64())
 }
 
-fn f(args: &Value, key: &str, default: f64) -> f64 {
-    args.get(key).and_then(|v| v.as_f64()).unwrap_or(default)
+fn f(args: &Value, key: &str) -> f64 {
+    args.get(key).and_then(|v| v.as_f64()).unwrap_or_default()
 }
 
-fn b(args: &Value, key: &str, default: bool) -> bool {
-    args.get(key).and_then(|v| v.as_bool()).unwrap_or(default)
+fn b(args: &Value, key: &str) -> bool {
+    args.get(key).and_then(|v| v.as_bool()).unwrap_or_default()
 }
 
 fn val_array(args: &Value, key: &str) -> Vec<Value> {
     args.get(key).and_then(|v| v.as_array()).cloned().unwrap_or_default()
 }
 
+const PRESENTATION_ID: Arg = Arg::string("presentation_id", "Presentation ID").required();
+const PAGE_ID: Arg = Arg::string("page_id", "Page (slide) object ID").required();
+const SLIDE_ID: Arg = Arg::string("slide_id", "Slide object ID").required();
+const OBJECT_ID: Arg = Arg::string("object_id", "Object ID of the shape or table").required();
+const START_INDEX: Arg = Arg::integer("start_index", "Start of the text range").default(Literal::Int(0));
+const END_INDEX: Arg = Arg::integer("end_index", "End of the text range, exclusive").default(Literal::Int(1));
+const MATCH_CASE: Arg = Arg::boolean("match_case", "Match case").default(Literal::Bool(true));
+const FIELDS: Arg = Arg::string("fields", "Field mask of the properties to change").default(Literal::Str("*"));
+const PROPERTIES: Arg = Arg::object("properties", "Properties to set").required();
+const X_PT: Arg = Arg::number("x_pt", "Left edge in points").default(Literal::Num(100.0));
+const Y_PT: Arg = Arg::number("y_pt", "Top edge in points").default(Literal::Num(100.0));
+const WIDTH_PT: Arg = Arg::number("width_pt", "Width in points").default(Literal::Num(200.0));
+const HEIGHT_PT: Arg = Arg::number("height_pt", "Height in points").default(Literal::Num(200.0));
+
+pub const ACTIONS: &[Action] = &[
+    Action::new("create_presentation", "Create a blank presentation", &[Arg::string("title", "Presentation title").required()]),
+    Action::new("get_presentation", "Get a presentation with its slides", &[Arg::string("id", "Presentation ID").required()]),
+    Action::new("get_page", "Get a page", &[PRESENTATION_ID, PAGE_ID]),
+    Action::new("get_page_thumbnail", "Get a thumbnail image URL of a page", &[PRESENTATION_ID, PAGE_ID]),
+    Action::new(
+        "batch_update",
+        "Apply raw presentations.batchUpdate requests",
+        &[PRESENTATION_ID, Arg::array("requests", "Request objects").required()],
+    ),
+    Action::new(
+        "create_slide",
+        "Add a slide",
+        &[
+            PRESENTATION_ID,
+            Arg::string("layout", "Predefined layout, such as `TITLE_AND_BODY`; blank if left out"),
+            Arg::integer("insertion_index", "Position; the end if left out"),
+        ],
+    ),
+    Action::new("delete_slide", "Delete a slide", &[PRESENTATION_ID, SLIDE_ID]),
+    Action::new("duplicate_slide", "Duplicate a slide", &[PRESENTATION_ID, SLIDE_ID]),
+    Action::new(
+        "move_slide",
+        "Move a slide",
+        &[PRESENTATION_ID, SLIDE_ID, Arg::integer("insertion_index", "New position").default(Literal::Int(0))],
+    ),
+    Action::new(
+        "insert_text",
+        "Insert text into a shape or table cell",
+        &[
+            PRESENTATION_ID,
+            OBJECT_ID,
+            Arg::string("text", "Text to insert").required(),
+            Arg::integer("insertion_index", "Position in the existing text").default(Literal::Int(0)),
+        ],
+    ),
+    Action::new("delete_text", "Delete a range of text from a shape", &[PRESENTATION_ID, OBJECT_ID, START_INDEX, END_INDEX]),
+    Action::new(
+        "replace_all_text",
+        "Replace every occurrence of some text",
+        &[
+            PRESENTATION_ID,
+            Arg::string("find", "Text to find").required(),
+            Arg::string("replace", "Replacement text").required(),
+            MATCH_CASE,
+        ],
+    ),
+    Action::new(
+        "create_shape",
+        "Add a shape to a page",
+        &[
+            PRESENTATION_ID,
+            PAGE_ID,
+            Arg::string("shape_type", "Shape type, such as `TEXT_BOX` or `RECTANGLE`").required(),
+            X_PT,
+            Y_PT,
+            WIDTH_PT,
+            HEIGHT_PT,
+        ],
+    ),
+    Action::new(
+        "create_image",
+        "Add an image from a public URL to a page",
+        &[PRESENTATION_ID, PAGE_ID, Arg::string("url", "Image URL").required(), X_PT, Y_PT, WIDTH_PT, HEIGHT_PT],
+    ),
+    Action::new(
+        "create_table",
+        "Add a table to a page",
+        &[
+            PRESENTATION_ID,
+            PAGE_ID,
+            Arg::integer("rows", "Number of rows").default(Literal::Int(2)),
+            Arg::integer("cols", "Number of columns").default(Literal::Int(2)),
+        ],
+    ),
+    Action::new(
+        "update_text_style",
+        "Style a range of text in a shape",
+        &[
+            PRESENTATION_ID,
+            OBJECT_ID,
+            START_INDEX,
+            END_INDEX,
+            Arg::object("style", "TextStyle to apply").required(),
+            FIELDS,
+        ],
+    ),
+    Action::new("update_shape_properties", "Update a shape's properties", &[PRESENTATION_ID, OBJECT_ID, PROPERTIES, FIELDS]),
+    Action::new(
+        "replace_all_shapes_with_image",
+        "Replace every shape containing some text with an image",
+        &[
+            PRESENTATION_ID,
+            Arg::string("find_text", "Text the shapes contain").required(),
+            Arg::string("image_url", "Image URL").required(),
+            MATCH_CASE,
+        ],
+    ),
+    Action::new("update_page_properties", "Update a page's properties, such as its background", &[PRESENTATION_ID, PAGE_ID, PROPERTIES, FIELDS]),
+    Action::new(
+        "create_speaker_notes",
+        "Set a slide's speaker notes",
+        &[PRESENTATION_ID, SLIDE_ID, Arg::string("notes_text", "Notes text").required()],
+    ),
+];
+
 pub async fn execute(client: &GoogleClient, action: &str, args: Value) -> Result<Value> {
     let api = SlidesApi::new(client);
     match action {
@@ -45,45 +162,45 @@ pub async fn execute(client: &GoogleClient, action: &str, args: Value) -> Result
         "create_slide" => api.create_slide(s(&args, "presentation_id"), s(&args, "layout"), io(&args, "insertion_index")).await,
         "delete_slide" => api.delete_slide(s(&args, "presentation_id"), s(&args, "slide_id")).await,
         "duplicate_slide" => api.duplicate_slide(s(&args, "presentation_id"), s(&args, "slide_id")).await,
-        "move_slide" => api.move_slide(s(&args, "presentation_id"), s(&args, "slide_id"), i(&args, "insertion_index", 0)).await,
+        "move_slide" => api.move_slide(s(&args, "presentation_id"), s(&args, "slide_id"), i(&args, "insertion_index")).await,
         "insert_text" => api.insert_text(
-            s(&args, "presentation_id"), s(&args, "object_id"), s(&args, "text"), i(&args, "insertion_index", 0),
+            s(&args, "presentation_id"), s(&args, "object_id"), s(&args, "text"), i(&args, "insertion_index"),
         ).await,
         "delete_text" => api.delete_text(
-            s(&args, "presentation_id"), s(&args, "object_id"), i(&args, "start_index", 0), i(&args, "end_index", 1),
+            s(&args, "presentation_id"), s(&args, "object_id"), i(&args, "start_index"), i(&args, "end_index"),
         ).await,
         "replace_all_text" => api.replace_all_text(
-            s(&args, "presentation_id"), s(&args, "find"), s(&args, "replace"), b(&args, "match_case", true),
+            s(&args, "presentation_id"), s(&args, "find"), s(&args, "replace"), b(&args, "match_case"),
         ).await,
         "create_shape" => api.create_shape(
             s(&args, "presentation_id"), s(&args, "page_id"), s(&args, "shape_type"),
-            f(&args, "x_pt", 100.0), f(&args, "y_pt", 100.0),
-            f(&args, "width_pt", 200.0), f(&args, "height_pt", 200.0),
+            f(&args, "x_pt"), f(&args, "y_pt"),
+            f(&args, "width_pt"), f(&args, "height_pt"),
         ).await,
         "create_image" => api.create_image(
             s(&args, "presentation_id"), s(&args, "page_id"), s(&args, "url"),
-            f(&args, "x_pt", 100.0), f(&args, "y_pt", 100.0),
-            f(&args, "width_pt", 200.0), f(&args, "height_pt", 200.0),
+            f(&args, "x_pt"), f(&args, "y_pt"),
+            f(&args, "width_pt"), f(&args, "height_pt"),
         ).await,
         "create_table" => api.create_table(
             s(&args, "presentation_id"), s(&args, "page_id"),
-            i(&args, "rows", 2), i(&args, "cols", 2),
+            i(&args, "rows"), i(&args, "cols"),
         ).await,
         "update_text_style" => api.update_text_style(
             s(&args, "presentation_id"), s(&args, "object_id"),
-            i(&args, "start_index", 0), i(&args, "end_index", 1),
-            &args["style"], so(&args, "fields").unwrap_or("*"),
+            i(&args, "start_index"), i(&args, "end_index"),
+            &args["style"], s(&args, "fields"),
         ).await,
         "update_shape_properties" => api.update_shape_properties(
             s(&args, "presentation_id"), s(&args, "object_id"),
-            &args["properties"], so(&args, "fields").unwrap_or("*"),
+            &args["properties"], s(&args, "fields"),
         ).await,
         "replace_all_shapes_with_image" => api.replace_all_shapes_with_image(
-            s(&args, "presentation_id"), s(&args, "find_text"), s(&args, "image_url"), b(&args, "match_case", true),
+            s(&args, "presentation_id"), s(&args, "find_text"), s(&args, "image_url"), b(&args, "match_case"),
         ).await,
         "update_page_properties" => api.update_page_properties(
             s(&args, "presentation_id"), s(&args, "page_id"),
-            &args["properties"], so(&args, "fields").unwrap_or("*"),
+            &args["properties"], s(&args, "fields"),
         ).await,
         "create_speaker_notes" => api.create_speaker_notes(
             s(&args, "presentation_id"), s(&args, "slide_id"), s(&args, "notes_text"),
@@ -1,3 +1,4 @@
+use crate::actions::{Action, Alias, Arg, Literal};
 use crate::api::tasks::TasksApi;
 use crate::client::GoogleClient;
 use crate::error::{Result, VgoogError};
@@ -11,33 +12,85 @@ fn so<'a>(args: &'a Value, key: &str) -> Option<&'a str> {
     args.get(key).and_then(|v| v.as_str())
 }
 
-fn u(args: &Value, key: &str, default: u32) -> u32 {
-    args.get(key).and_then(|v| v.as_u64()).map(|v| v as u32).unwrap_or(default)
+fn u(args: &Value, key: &str) -> u32 {
+    args.get(key).and_then(|v| v.as_u64()).map(|v| v as u32).unwrap_or_default()
 }
 
-fn b(args: &Value, key: &str, default: bool) -> bool {
-    args.get(key).and_then(|v| v.as_bool()).unwrap_or(default)
+fn b(args: &Value, key: &str) -> bool {
+    args.get(key).and_then(|v| v.as_bool()).unwrap_or_default()
 }
 
+const LIST_ID: Arg = Arg::string("id", "Task list ID").required();
+const TASK_LIST_ID: Arg = Arg::string("task_list_id", "Task list ID").required();
+const TASK_ID: Arg = Arg::string("task_id", "Task ID").required();
+const MAX_RESULTS: Arg = Arg::integer("max_results", "Results per page").default(Literal::Int(20));
+const PAGE_TOKEN: Arg = Arg::string("page_token", "nextPageToken of the previous page");
+const PARENT: Arg = Arg::string("parent", "Parent task ID, making it a subtask");
+const PREVIOUS: Arg = Arg::string("previous", "Sibling task ID to place it after; first if left out");
+
+pub const ACTIONS: &[Action] = &[
+    Action::new("list_task_lists", "List the user's task lists", &[MAX_RESULTS, PAGE_TOKEN]).items("items"),
+    Action::new("get_task_list", "Get a task list", &[LIST_ID]),
+    Action::new("create_task_list", "Create a task list", &[Arg::string("title", "Title").required()]),
+    Action::new("update_task_list", "Rename a task list", &[LIST_ID, Arg::string("title", "New title").required()]),
+    Action::new("delete_task_list", "Delete a task list with its tasks", &[LIST_ID]),
+    Action::new(
+        "list_tasks",
+        "List the tasks of a list",
+        &[
+            TASK_LIST_ID,
+            MAX_RESULTS,
+            PAGE_TOKEN,
+            Arg::boolean("show_completed", "Include completed tasks").default(Literal::Bool(true)),
+            Arg::boolean("show_deleted", "Include deleted tasks").default(Literal::Bool(false)),
+            Arg::boolean("show_hidden", "Include hidden tasks").default(Literal::Bool(false)),
+            Arg::string("due_min", "Lower bound for the due date, RFC 3339"),
+            Arg::string("due_max", "Upper bound for the due date, RFC 3339"),
+        ],
+    )
+    .items("items"),
+    Action::new("get_task", "Get a task", &[TASK_LIST_ID, TASK_ID]),
+    Action::new(
+        "create_task",
+        "Create a task",
+        &[
+            TASK_LIST_ID,
+            Arg::string("title", "Title").required(),
+            Arg::string("notes", "Notes"),
+            Arg::string("due", "Due date, RFC 3339; only the date is kept"),
+            PARENT,
+            PREVIOUS,
+        ],
+    ),
+    Action::new("complete_task", "Mark a task completed", &[TASK_LIST_ID, TASK_ID]),
+    Action::new("uncomplete_task", "Mark a task not completed", &[TASK_LIST_ID, TASK_ID]),
+    Action::new("delete_task", "Delete a task", &[TASK_LIST_ID, TASK_ID]),
+    Action::new("move_task", "Move a task under another parent or after a sibling", &[TASK_LIST_ID, TASK_ID, PARENT, PREVIOUS]),
+    Action::new("clear_completed", "Hide every completed task of a list", &[TASK_LIST_ID]),
+];
+
+/// Actions retired in favour of the generated ones making the same call
+pub const ALIASES: &[Alias] =
+    &[Alias::new("update_task", "tasks_patch", &[("task_list_id", "tasklist"), ("task_id", "task"), ("updates", "body")])];
+
 pub async fn execute(client: &GoogleClient, action: &str, args: Value) -> Result<Value> {
     let api = TasksApi::new(client);
     match action {
-        "list_task_lists" => api.list_task_lists(u(&args, "max_results", 20), so(&args, "page_token")).await,
+        "list_task_lists" => api.list_task_lists(u(&args, "max_results"), so(&args, "page_token")).await,
         "get_task_list" => api.get_task_list(s(&args, "id")).await,
         "create_task_list" => api.create_task_list(s(&args, "title")).await,
         "update_task_list" => api.update_task_list(s(&args, "id"), s(&args, "title")).await,
         "delete_task_list" => api.delete_task_list(s(&args, "id")).await,
         "list_tasks" => api.list_tasks(
-            s(&args, "task_list_id"), u(&args, "max_results", 20), so(&args, "page_token"),
-            b(&args, "show_completed", true), b(&args, "show_deleted", false),
-            b(&args, "show_hidden", false), so(&args, "due_min"), so(&args, "due_max"),
+            s(&args, "task_list_id"), u(&args, "max_results"), so(&args, "page_token"),
+            b(&args, "show_completed"), b(&args, "show_deleted"),
+            b(&args, "show_hidden"), so(&args, "due_min"), so(&args, "due_max"),
         ).await,
         "get_task" => api.get_task(s(&args, "task_list_id"), s(&args, "task_id")).await,
         "create_task" => api.create_task(
             s(&args, "task_list_id"), s(&args, "title"),
             so(&args, "notes"), so(&args, "due"), so(&args, "parent"), so(&args, "previous"),
         ).await,
-        "update_task" => api.update_task(s(&args, "task_list_id"), s(&args, "task_id"), &args["updates"]).await,
         "complete_task" => api.complete_task(s(&args, "task_list_id"), s(&args, "task_id")).await,
         "uncomplete_task" => api.uncomplete_task(s(&args, "task_list_id"), s(&args, "task_id")).await,
         "delete_task" => api.delete_task(s(&args, "task_list_id"), s(&args, "task_id")).await,
@@ -1,9 +1,20 @@
-use crate::auth::refresh_token_if_needed;
-use crate::config::{Config, SingleAccountConfig};
-use crate::error::{Result, VgoogError};
-use reqwest::{Client, Method, Response};
+use crate::auth::{loopback, refresh_token_if_needed, scopes, Grant};
+use crate::batch::{self, BatchRequest};
+use crate::cache::{self, ResponseCache};
+use crate::cassette::{self, Cassette, CassetteMode};
+use crate::config::{Config, ConfigLock, SingleAccountConfig};
+use crate::endpoints::Endpoints;
+use crate::error::{ApiErrorDetail, Result, VgoogError};
+use crate::quota::{self, RateLimiter, UsageEntry};
+use crate::retry::{self, RetryPolicy};
+use crate::transfer::{self, Progress, ProgressFn};
+use crate::ui::app::Service;
+use reqwest::{Client, Method, RequestBuilder, Response, StatusCode};
 use serde_json::Value;
+use std::path::Path;
 use std::sync::Arc;
+use std::time::Instant;
+use tokio::io::{AsyncReadExt, AsyncSeekExt, AsyncWriteExt};
 use tokio::sync::Mutex;
 
 pub struct GoogleClient {
@@ -11,35 +22,133 @@ pub struct GoogleClient {
     account_config: Arc<Mutex<SingleAccountConfig>>,
     /// The full multi-account config for account switching
     full_config: Arc<Mutex<Config>>,
+    /// Base URLs for every service, fixed for the client's lifetime
+    endpoints: Endpoints,
+    /// Record/replay of every API exchange, when enabled
+    cassette: Option<Arc<Cassette>>,
+    /// Bytes per resumable upload request
+    upload_chunk_size: usize,
+    /// Whether missing scopes may be asked for in the browser
+    prompt_consent: bool,
+    /// Paces requests per API and counts what they cost
+    limiter: Arc<RateLimiter>,
+    /// When the client was made, for the usage log
+    started: Instant,
+    /// GET responses kept on disk, unless turned off
+    cache: Option<Arc<ResponseCache>>,
 }
 
 impl GoogleClient {
+    /// Client for the active account; `VGOOG_RECORD`/`VGOOG_REPLAY` enable
+    /// a cassette
     pub fn new(config: Config) -> Result<Self> {
+        Self::with_cassette(config, CassetteMode::from_env()?)
+    }
+
+    /// Client with an explicit cassette choice (e.g. from `--record`/`--replay`)
+    pub fn with_cassette(config: Config, cassette: Option<CassetteMode>) -> Result<Self> {
         let account_config = config.for_active_account()?;
-        let http = Client::builder()
-            .user_agent("vgoog/0.1.0")
-            .build()
-            .expect("Failed to create HTTP client");
+        let endpoints = Endpoints::resolve(&config.endpoints)?;
+        let http = config.network.client()?;
+        let limiter = RateLimiter::new(quota::limits(&config.rate_limits));
+        // Pick up the pace where earlier commands left it; replays send nothing
+        if !config.ephemeral && !matches!(cassette, Some(CassetteMode::Replay(_))) {
+            limiter.resume(&quota::load_buckets(&quota::buckets_path()?));
+        }
+        // Cassettes hold exactly what was sent, so they bypass the cache
+        let cache = match cassette {
+            Some(_) => None,
+            None => ResponseCache::open(&config)?.map(Arc::new),
+        };
         Ok(Self {
             http,
             account_config: Arc::new(Mutex::new(account_config)),
             full_config: Arc::new(Mutex::new(config)),
+            endpoints,
+            cassette: cassette.map(Cassette::open).transpose()?.map(Arc::new),
+            upload_chunk_size: transfer::CHUNK_SIZE,
+            prompt_consent: false,
+            limiter: Arc::new(limiter),
+            started: Instant::now(),
+            cache,
         })
     }
 
+    /// Resumable upload chunk size, rounded up to Drive's 256 KiB granularity
+    pub fn set_upload_chunk_size(&mut self, bytes: usize) {
+        self.upload_chunk_size = bytes.max(1).div_ceil(transfer::CHUNK_ALIGN) * transfer::CHUNK_ALIGN;
+    }
+
+    /// Let `ensure_scopes` run an incremental consent for missing scopes;
+    /// only worth turning on when someone is at the terminal
+    pub fn set_prompt_consent(&mut self, prompt: bool) {
+        self.prompt_consent = prompt;
+    }
+
+    /// Fetch everything from Google, ignoring and not filling the response
+    /// cache (`--no-cache`)
+    pub fn disable_cache(&mut self) {
+        self.cache = None;
+    }
+
+    /// Base URLs the API modules build requests against
+    pub fn endpoints(&self) -> &Endpoints {
+        &self.endpoints
+    }
+
+    /// The HTTP client built from `[network]`, for requests made outside
+    /// `send`, such as to the OAuth endpoints
+    pub fn http(&self) -> &Client {
+        &self.http
+    }
+
     /// Switch to a different account by name
     pub async fn switch_account(&self, name: &str) -> Result<()> {
         let mut full = self.full_config.lock().await;
-        if !full.switch_account(name) {
+        if !full.update_async(|config| config.switch_account(name)).await? {
             return Err(VgoogError::Config(format!("Account '{name}' not found")));
         }
-        full.save()?;
         let new_account_config = full.for_active_account()?;
         let mut current = self.account_config.lock().await;
         *current = new_account_config;
         Ok(())
     }
 
+    /// An independent client bound to account `name`, with its own token
+    /// state. It shares this client's connection pool and cassette, and
+    /// using it never changes the active account, so several accounts can
+    /// be queried at once.
+    pub async fn for_account(&self, name: &str) -> Result<GoogleClient> {
+        let account_config = self.full_config.lock().await.for_account(name)?;
+        Ok(Self {
+            http: self.http.clone(),
+            account_config: Arc::new(Mutex::new(account_config)),
+            full_config: self.full_config.clone(),
+            endpoints: self.endpoints.clone(),
+            cassette: self.cassette.clone(),
+            upload_chunk_size: self.upload_chunk_size,
+            prompt_consent: self.prompt_consent,
+            limiter: self.limiter.clone(),
+            started: self.started,
+            cache: self.cache.clone(),
+        })
+    }
+
+    /// Name of the account this client sends requests as
+    pub async fn account_name(&self) -> String {
+        self.account_config.lock().await.account.clone()
+    }
+
+    /// Label of the account this client sends requests as
+    pub async fn account_label(&self) -> String {
+        let name = self.account_name().await;
+        let full = self.full_config.lock().await;
+        full.accounts
+            .get(&name)
+            .map(|a| a.label.clone())
+            .unwrap_or_else(|| "Unknown".to_string())
+    }
+
     /// Get the active account name
     pub async fn active_account_name(&self) -> String {
         self.full_config.lock().await.active_account.clone()
@@ -69,23 +178,268 @@ impl GoogleClient {
         self.full_config.lock().await.clone()
     }
 
-    /// Update the full config (after adding/removing accounts)
-    pub async fn update_full_config(&self, config: Config) -> Result<()> {
-        config.save()?;
-        let new_account_config = config.for_active_account()?;
+    /// Apply `change` to the latest saved config (see `Config::update`) and
+    /// pick up the result, including this client's own account
+    pub async fn update_config<T>(&self, change: impl FnOnce(&mut Config) -> T) -> Result<T> {
         let mut full = self.full_config.lock().await;
-        *full = config;
+        let result = full.update_async(change).await?;
         let mut current = self.account_config.lock().await;
-        *current = new_account_config;
-        Ok(())
+        if let Ok(latest) = full.for_account(&current.account) {
+            *current = latest;
+        }
+        Ok(result)
+    }
+
+    /// A valid access token for this client's account, refreshed if needed
+    pub async fn access_token(&self) -> Result<String> {
+        self.ensure_token().await
+    }
+
+    /// Scopes `action` of `service` needs that this account was not granted.
+    /// Nothing is reported for service accounts, accounts whose scopes were
+    /// never recorded, or while replaying a cassette.
+    pub async fn missing_scopes(&self, service: Service, action: Option<&str>) -> Vec<&'static str> {
+        if self.cassette.as_ref().is_some_and(|c| c.is_replay()) {
+            return Vec::new();
+        }
+        let config = self.account_config.lock().await;
+        if config.service_account.is_some() || config.scopes.is_empty() {
+            return Vec::new();
+        }
+        scopes::lacking(&config.scopes, service, action)
+    }
+
+    /// Make sure the account may run `action` of `service`. Missing scopes
+    /// are asked for through an incremental consent when prompting is on;
+    /// otherwise the error says how to grant them.
+    pub async fn ensure_scopes(&self, service: Service, action: Option<&str>) -> Result<()> {
+        let missing = self.missing_scopes(service, action).await;
+        if missing.is_empty() {
+            return Ok(());
+        }
+        let account = self.account_name().await;
+        if !self.prompt_consent {
+            return Err(consent_required(&account, service, &missing));
+        }
+        eprintln!("  {} needs more access for account '{account}': {}", service.name(), missing.join(", "));
+        self.request_scopes(&missing).await?;
+        let still_missing = self.missing_scopes(service, action).await;
+        if still_missing.is_empty() {
+            Ok(())
+        } else {
+            Err(consent_required(&account, service, &still_missing))
+        }
+    }
+
+    /// Ask Google for `scopes` on top of those already granted, through the
+    /// browser, and store the combined grant
+    pub async fn request_scopes(&self, scopes: &[&str]) -> Result<()> {
+        let (endpoints, client_id, client_secret) = {
+            let config = self.account_config.lock().await;
+            (config.endpoints.clone(), config.auth.client_id.clone(), config.auth.client_secret.clone())
+        };
+        let grant = loopback::run(&self.http, &endpoints, &client_id, &client_secret, scopes, true).await?;
+        self.save_grant(grant).await
+    }
+
+    /// Store the tokens and scopes of a consent for this client's account
+    pub async fn save_grant(&self, grant: Grant) -> Result<()> {
+        let name = self.account_name().await;
+        self.update_config(|c| {
+            if let Some(account) = c.accounts.get_mut(&name) {
+                account.auth = grant.auth;
+                account.scopes = grant.scopes;
+            }
+        })
+        .await
     }
 
     async fn ensure_token(&self) -> Result<String> {
         let mut config = self.account_config.lock().await;
-        refresh_token_if_needed(&mut config).await?;
+        refresh_token_if_needed(&self.http, &mut config).await?;
         Ok(config.auth.access_token.clone())
     }
 
+    async fn retry_policy(&self) -> RetryPolicy {
+        self.account_config.lock().await.retry.clone()
+    }
+
+    /// Send a request built fresh for each attempt, retrying transient
+    /// failures with jittered exponential backoff. `replay_safe` marks
+    /// requests that may be repeated even if the server might have seen them.
+    async fn send_with_retry<F>(&self, replay_safe: bool, build: F) -> Result<Response>
+    where
+        F: Fn(&Client, &str) -> Result<RequestBuilder>,
+    {
+        let policy = self.retry_policy().await;
+        let mut attempt = 1;
+        loop {
+            let outcome = match &self.cassette {
+                // Replayed responses don't depend on credentials
+                Some(cassette) if cassette.is_replay() => {
+                    let request = build(&self.http, cassette::REDACTED)?.build()?;
+                    cassette.send(&self.http, request).await?
+                }
+                Some(cassette) => {
+                    let token = self.ensure_token().await?;
+                    let request = build(&self.http, &token)?.build()?;
+                    self.pace(request.method(), request.url().as_str()).await;
+                    cassette.send(&self.http, request).await?
+                }
+                None => {
+                    let token = self.ensure_token().await?;
+                    let request = build(&self.http, &token)?.build()?;
+                    self.pace(request.method(), request.url().as_str()).await;
+                    self.http.execute(request).await
+                }
+            };
+            let delay = match outcome {
+                Ok(resp) if retry::is_retryable_status(resp.status(), replay_safe) => {
+                    match policy.next_delay(attempt, retry::retry_after(resp.headers())) {
+                        Some(d) => d,
+                        None => return Ok(resp),
+                    }
+                }
+                // Google reports per-user rate limits as 403 with a reason in the body
+                Ok(resp) if resp.status() == reqwest::StatusCode::FORBIDDEN => {
+                    let retry_after = retry::retry_after(resp.headers());
+                    let err = api_error(resp).await;
+                    match policy.next_delay(attempt, retry_after) {
+                        Some(d) if err.is_rate_limit() => d,
+                        _ => return Err(err),
+                    }
+                }
+                Ok(resp) => return Ok(resp),
+                Err(e) if retry::is_retryable_error(&e, replay_safe) => {
+                    match policy.next_delay(attempt, None) {
+                        Some(d) => d,
+                        None => return Err(e.into()),
+                    }
+                }
+                Err(e) => return Err(e.into()),
+            };
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    /// Wait for the API's budget to cover a request about to go out
+    async fn pace(&self, method: &Method, url: &str) {
+        if let Some(charge) = quota::charge(&self.endpoints, method.as_str(), url) {
+            self.limiter.acquire(charge).await;
+        }
+    }
+
+    /// Requests sent so far by API, with their cost and time spent waiting
+    pub fn usage(&self) -> std::collections::BTreeMap<String, quota::ApiUsage> {
+        self.limiter.usage()
+    }
+
+    /// Append what this client's requests cost to the usage log read by
+    /// `vgoog quota`, and save the bucket levels for the next command.
+    /// Replays and environment-only configs leave no trace.
+    pub async fn record_usage(&self, command: &str) -> Result<()> {
+        let apis = self.usage();
+        let replay = self.cassette.as_ref().is_some_and(|c| c.is_replay());
+        if apis.is_empty() || replay || self.full_config.lock().await.ephemeral {
+            return Ok(());
+        }
+        let entry = UsageEntry {
+            at: chrono::Utc::now(),
+            command: command.to_string(),
+            account: self.account_name().await,
+            elapsed_ms: self.started.elapsed().as_millis() as u64,
+            apis,
+        };
+        let lock = ConfigLock::acquire_async().await?;
+        quota::record(&entry, &lock)?;
+        self.limiter.save(&lock)
+    }
+
+    async fn send_request(
+        &self,
+        method: Method,
+        url: &str,
+        body: Option<&Value>,
+        replay_safe: bool,
+    ) -> Result<Value> {
+        if method == Method::GET {
+            if let Some((cache, resource)) = self.cache_for(url) {
+                return self.cached_get(cache, &resource, url).await;
+            }
+        }
+        self.invalidate(&method, url).await;
+        let resp = self
+            .send_with_retry(replay_safe, |http, token| {
+                let mut req = http.request(method.clone(), url).bearer_auth(token);
+                if let Some(b) = body {
+                    req = req.json(b);
+                }
+                Ok(req)
+            })
+            .await?;
+        self.handle_response(resp).await
+    }
+
+    /// The cache and resource for a GET of `url`, when its resource is cached
+    fn cache_for(&self, url: &str) -> Option<(&ResponseCache, String)> {
+        let cache = self.cache.as_deref()?;
+        let resource = cache::resource(&self.endpoints, url)?;
+        cache.ttl(&resource)?;
+        Some((cache, resource))
+    }
+
+    /// GET through the cache: a fresh entry is served as is, a stale one is
+    /// revalidated with its ETag. Failing to write the cache never fails
+    /// the request.
+    async fn cached_get(&self, cache: &ResponseCache, resource: &str, url: &str) -> Result<Value> {
+        let account = self.account_name().await;
+        let cached = cache.lookup(&account, resource, url);
+        if let Some(entry) = cached.as_ref().filter(|entry| cache.is_fresh(resource, entry)) {
+            return Ok(entry.body.clone());
+        }
+
+        let etag = cached.as_ref().and_then(|entry| entry.etag.clone());
+        let resp = self
+            .send_with_retry(true, |http, token| {
+                let mut req = http.get(url).bearer_auth(token);
+                if let Some(etag) = &etag {
+                    req = req.header(reqwest::header::IF_NONE_MATCH, etag);
+                }
+                Ok(req)
+            })
+            .await?;
+        if resp.status() == StatusCode::NOT_MODIFIED {
+            if let Some(mut entry) = cached {
+                entry.stored_at = chrono::Utc::now();
+                let _ = cache.store(&account, resource, &entry);
+                return Ok(entry.body);
+            }
+        }
+
+        let header_etag = resp
+            .headers()
+            .get(reqwest::header::ETAG)
+            .and_then(|v| v.to_str().ok())
+            .map(String::from);
+        let body = self.handle_response(resp).await?;
+        let entry = cache::Entry {
+            url: url.to_string(),
+            etag: header_etag.or_else(|| body["etag"].as_str().map(String::from)),
+            stored_at: chrono::Utc::now(),
+            body,
+        };
+        let _ = cache.store(&account, resource, &entry);
+        Ok(entry.body)
+    }
+
+    /// Drop cached responses a `method` request to `url` may change
+    async fn invalidate(&self, method: &Method, url: &str) {
+        if let Some(cache) = self.cache.as_deref().filter(|_| method != Method::GET) {
+            cache.invalidate(&self.endpoints, &self.account_name().await, url);
+        }
+    }
+
     async fn handle_response(&self, resp: Response) -> Result<Value> {
         let status = resp.status();
         if status.is_success() {
@@ -98,25 +452,8 @@ impl GoogleClient {
             }
             let val: Value = serde_json::from_str(&body)?;
             Ok(val)
-        } else if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
-            let retry_after = resp
-                .headers()
-                .get("retry-after")
-                .and_then(|v| v.to_str().ok())
-                .and_then(|v| v.parse::<u64>().ok())
-                .unwrap_or(60);
-            Err(VgoogError::RateLimited {
-                retry_after_secs: retry_after,
-            })
-        } else if status == reqwest::StatusCode::NOT_FOUND {
-            Err(VgoogError::NotFound("Resource not found".into()))
         } else {
-            let code = status.as_u16();
-            let body = resp.text().await.unwrap_or_default();
-            Err(VgoogError::Api {
-                status: code,
-                message: body,
-            })
+            Err(api_error(resp).await)
         }
     }
 
@@ -126,23 +463,31 @@ impl GoogleClient {
         url: &str,
         body: Option<&Value>,
     ) -> Result<Value> {
-        let token = self.ensure_token().await?;
-        let mut req = self.http.request(method, url).bearer_auth(&token);
-        if let Some(b) = body {
-            req = req.json(b);
-        }
-        let resp = req.send().await?;
-        self.handle_response(resp).await
+        let replay_safe = retry::is_idempotent(&method);
+        self.send_request(method, url, body, replay_safe).await
     }
 
     pub async fn get(&self, url: &str) -> Result<Value> {
         self.request(Method::GET, url, None).await
     }
 
+    /// GET straight from Google, skipping the response cache, for data that
+    /// has to be current, such as the checksum a download is verified against
+    pub async fn get_uncached(&self, url: &str) -> Result<Value> {
+        let resp = self.send_with_retry(true, |http, token| Ok(http.get(url).bearer_auth(token))).await?;
+        self.handle_response(resp).await
+    }
+
     pub async fn post(&self, url: &str, body: &Value) -> Result<Value> {
         self.request(Method::POST, url, Some(body)).await
     }
 
+    /// POST that is safe to replay on transient failures (read-only queries,
+    /// state-setting calls like trash/modify, requests carrying a request ID)
+    pub async fn post_idempotent(&self, url: &str, body: &Value) -> Result<Value> {
+        self.send_request(Method::POST, url, Some(body), true).await
+    }
+
     pub async fn put(&self, url: &str, body: &Value) -> Result<Value> {
         self.request(Method::PUT, url, Some(body)).await
     }
@@ -162,43 +507,365 @@ impl GoogleClient {
         file_bytes: Vec<u8>,
         mime_type: &str,
     ) -> Result<Value> {
-        let token = self.ensure_token().await?;
-        let metadata_part = reqwest::multipart::Part::text(serde_json::to_string(metadata)?)
-            .mime_str("application/json")?;
-        let file_part = reqwest::multipart::Part::bytes(file_bytes).mime_str(mime_type)?;
-        let form = reqwest::multipart::Form::new()
-            .part("metadata", metadata_part)
-            .part("file", file_part);
-
+        self.invalidate(&Method::POST, url).await;
+        let metadata_json = serde_json::to_string(metadata)?;
+        let file_bytes = bytes::Bytes::from(file_bytes);
         let resp = self
-            .http
-            .post(url)
-            .bearer_auth(&token)
-            .multipart(form)
-            .send()
+            .send_with_retry(false, |http, token| {
+                let metadata_part = reqwest::multipart::Part::text(metadata_json.clone())
+                    .mime_str("application/json")?;
+                let file_part = reqwest::multipart::Part::stream_with_length(
+                    file_bytes.clone(),
+                    file_bytes.len() as u64,
+                )
+                .mime_str(mime_type)?;
+                let form = reqwest::multipart::Form::new()
+                    .part("metadata", metadata_part)
+                    .part("file", file_part);
+                Ok(http.post(url).bearer_auth(token).multipart(form))
+            })
             .await?;
         self.handle_response(resp).await
     }
 
-    pub async fn download(&self, url: &str) -> Result<Vec<u8>> {
-        let token = self.ensure_token().await?;
-        let resp = self.http.get(url).bearer_auth(&token).send().await?;
-        let status = resp.status();
-        if status.is_success() {
-            Ok(resp.bytes().await?.to_vec())
-        } else {
-            let code = status.as_u16();
-            let body = resp.text().await.unwrap_or_default();
-            Err(VgoogError::Api {
-                status: code,
-                message: body,
+    /// Upload a file from disk with the resumable protocol: start a session
+    /// with `method` on `url` (which must carry `uploadType=resumable`), then
+    /// send the file in chunks. After a failed chunk the server is asked how
+    /// much it kept and the upload carries on from there.
+    pub async fn upload_resumable(
+        &self,
+        method: Method,
+        url: &str,
+        metadata: &Value,
+        path: &Path,
+        mime_type: &str,
+        progress: Option<&ProgressFn<'_>>,
+    ) -> Result<Value> {
+        let mut file = tokio::fs::File::open(path).await?;
+        let total = file.metadata().await?.len();
+        self.invalidate(&method, url).await;
+
+        let resp = self
+            .send_with_retry(false, |http, token| {
+                Ok(http
+                    .request(method.clone(), url)
+                    .bearer_auth(token)
+                    .header("X-Upload-Content-Type", mime_type)
+                    .header("X-Upload-Content-Length", total)
+                    .json(metadata))
+            })
+            .await?;
+        if !resp.status().is_success() {
+            return Err(api_error(resp).await);
+        }
+        let session = resp
+            .headers()
+            .get(reqwest::header::LOCATION)
+            .and_then(|v| v.to_str().ok())
+            .map(String::from)
+            .ok_or_else(|| VgoogError::Other("Upload session has no Location".into()))?;
+
+        let policy = self.retry_policy().await;
+        let mut offset = 0u64;
+        let mut failures = 0;
+        let mut chunk = vec![0u8; self.upload_chunk_size];
+        loop {
+            file.seek(std::io::SeekFrom::Start(offset)).await?;
+            let len = read_full(&mut file, &mut chunk).await?;
+            let body = bytes::Bytes::copy_from_slice(&chunk[..len]);
+            let range = if len == 0 {
+                format!("bytes */{total}")
+            } else {
+                format!("bytes {offset}-{}/{total}", offset + len as u64 - 1)
+            };
+
+            let sent = self
+                .send_with_retry(false, |http, token| {
+                    Ok(http
+                        .put(&session)
+                        .bearer_auth(token)
+                        .header(reqwest::header::CONTENT_RANGE, &range)
+                        .body(body.clone()))
+                })
+                .await;
+            match sent {
+                Ok(resp) if resp.status().is_success() => {
+                    if let Some(report) = progress {
+                        report(Progress { done: total, total: Some(total) });
+                    }
+                    return self.handle_response(resp).await;
+                }
+                Ok(resp) if resp.status().as_u16() == 308 => {
+                    offset = upload_offset(&resp);
+                    failures = 0;
+                    if let Some(report) = progress {
+                        report(Progress { done: offset, total: Some(total) });
+                    }
+                    continue;
+                }
+                Ok(resp) if resp.status().is_server_error() => {
+                    failures += 1;
+                    let err = api_error(resp).await;
+                    match policy.next_delay(failures, None) {
+                        Some(delay) => tokio::time::sleep(delay).await,
+                        None => return Err(err),
+                    }
+                }
+                Ok(resp) => return Err(api_error(resp).await),
+                Err(VgoogError::Http(e)) => {
+                    failures += 1;
+                    match policy.next_delay(failures, None) {
+                        Some(delay) => tokio::time::sleep(delay).await,
+                        None => return Err(e.into()),
+                    }
+                }
+                Err(e) => return Err(e),
+            }
+            offset = self.query_upload_offset(&session, total).await?;
+        }
+    }
+
+    /// Ask an upload session how many bytes it has kept
+    async fn query_upload_offset(&self, session: &str, total: u64) -> Result<u64> {
+        let range = format!("bytes */{total}");
+        let resp = self
+            .send_with_retry(true, |http, token| {
+                Ok(http
+                    .put(session)
+                    .bearer_auth(token)
+                    .header(reqwest::header::CONTENT_RANGE, &range)
+                    .header(reqwest::header::CONTENT_LENGTH, 0))
             })
+            .await?;
+        match resp.status().as_u16() {
+            308 => Ok(upload_offset(&resp)),
+            // Everything arrived; the next (empty) chunk fetches the result
+            200 | 201 => Ok(total),
+            _ => Err(api_error(resp).await),
         }
     }
 
+    /// Stream `url` into `path`, writing chunks as they arrive. Data lands in
+    /// `<path>.part` first; a part file left by an interrupted run, or a body
+    /// cut off mid-transfer, is resumed with a `Range` request. Returns the
+    /// size of the finished file.
+    pub async fn download_to_file(
+        &self,
+        url: &str,
+        path: &Path,
+        progress: Option<&ProgressFn<'_>>,
+    ) -> Result<u64> {
+        let part = transfer::part_path(path);
+        // Opened once Google answers with content, so a refused request
+        // leaves no empty part file behind
+        let mut file: Option<tokio::fs::File> = None;
+        let policy = self.retry_policy().await;
+        let mut failures = 0;
+        loop {
+            let offset = match &file {
+                Some(file) => file.metadata().await?.len(),
+                None => part_len(&part).await?,
+            };
+            let mut resp = self
+                .send_with_retry(true, |http, token| {
+                    let mut req = http.get(url).bearer_auth(token);
+                    if offset > 0 {
+                        req = req.header(reqwest::header::RANGE, format!("bytes={offset}-"));
+                    }
+                    Ok(req)
+                })
+                .await?;
+            let (start, total) = match resp.status() {
+                StatusCode::PARTIAL_CONTENT => match content_range(&resp) {
+                    Some((start, total)) if start == offset => (start, total),
+                    _ => return Err(VgoogError::Other(format!("Unexpected Content-Range resuming {url}"))),
+                },
+                status if status.is_success() => (0, resp.content_length()),
+                // The part file already holds everything
+                StatusCode::RANGE_NOT_SATISFIABLE
+                    if content_range(&resp).and_then(|(_, total)| total) == Some(offset) =>
+                {
+                    break;
+                }
+                StatusCode::RANGE_NOT_SATISFIABLE => {
+                    file = None;
+                    tokio::fs::remove_file(&part).await?;
+                    continue;
+                }
+                _ => return Err(api_error(resp).await),
+            };
+            if file.is_none() {
+                file = Some(tokio::fs::OpenOptions::new().create(true).append(true).open(&part).await?);
+            }
+            let out = file.as_mut().expect("part file was just opened");
+            if start == 0 {
+                out.set_len(0).await?;
+            }
+
+            let mut done = start;
+            if let Some(report) = progress {
+                report(Progress { done, total });
+            }
+            let streamed = loop {
+                match resp.chunk().await {
+                    Ok(Some(bytes)) => {
+                        out.write_all(&bytes).await?;
+                        done += bytes.len() as u64;
+                        if let Some(report) = progress {
+                            report(Progress { done, total });
+                        }
+                    }
+                    Ok(None) => break Ok(()),
+                    Err(e) => break Err(e),
+                }
+            };
+            match streamed {
+                Ok(()) => break,
+                Err(e) => {
+                    failures = if done > start { 1 } else { failures + 1 };
+                    match policy.next_delay(failures, None) {
+                        Some(delay) => tokio::time::sleep(delay).await,
+                        None => return Err(e.into()),
+                    }
+                }
+            }
+        }
+
+        if let Some(mut file) = file {
+            file.flush().await?;
+        }
+        let size = part_len(&part).await?;
+        tokio::fs::rename(&part, path).await?;
+        Ok(size)
+    }
+
     pub async fn post_empty(&self, url: &str) -> Result<Value> {
-        let token = self.ensure_token().await?;
-        let resp = self.http.post(url).bearer_auth(&token).send().await?;
+        self.send_empty_post(url, false).await
+    }
+
+    /// Body-less POST that is safe to replay (see `post_idempotent`)
+    pub async fn post_empty_idempotent(&self, url: &str) -> Result<Value> {
+        self.send_empty_post(url, true).await
+    }
+
+    async fn send_empty_post(&self, url: &str, replay_safe: bool) -> Result<Value> {
+        self.invalidate(&Method::POST, url).await;
+        let resp = self
+            .send_with_retry(replay_safe, |http, token| Ok(http.post(url).bearer_auth(token)))
+            .await?;
         self.handle_response(resp).await
     }
+
+    /// Send `requests` through a service batch endpoint (e.g.
+    /// `endpoints().gmail_batch`), `MAX_BATCH_SIZE` per HTTP call. One result
+    /// per request, in order; only a failure of a whole call is an outer error.
+    pub async fn batch(&self, batch_url: &str, requests: &[BatchRequest]) -> Result<Vec<Result<Value>>> {
+        let mut results = Vec::with_capacity(requests.len());
+        for request in requests {
+            self.invalidate(&request.method, &request.url).await;
+        }
+        for chunk in requests.chunks(batch::MAX_BATCH_SIZE) {
+            let boundary = format!("batch_{}", uuid::Uuid::new_v4().simple());
+            let body = bytes::Bytes::from(batch::encode(&boundary, chunk)?);
+            let replay_safe = chunk.iter().all(|r| retry::is_idempotent(&r.method));
+            // Google charges each call in a batch as if sent on its own
+            if !self.cassette.as_ref().is_some_and(|c| c.is_replay()) {
+                for request in chunk {
+                    self.pace(&request.method, &request.url).await;
+                }
+            }
+            let resp = self
+                .send_with_retry(replay_safe, |http, token| {
+                    Ok(http
+                        .post(batch_url)
+                        .bearer_auth(token)
+                        .header(
+                            reqwest::header::CONTENT_TYPE,
+                            format!("multipart/mixed; boundary={boundary}"),
+                        )
+                        .body(body.clone()))
+                })
+                .await?;
+            if !resp.status().is_success() {
+                return Err(api_error(resp).await);
+            }
+            let content_type = resp
+                .headers()
+                .get(reqwest::header::CONTENT_TYPE)
+                .and_then(|v| v.to_str().ok())
+                .unwrap_or_default()
+                .to_string();
+            let bytes = resp.bytes().await?;
+            results.extend(batch::decode(&content_type, &bytes, chunk.len())?);
+        }
+        Ok(results)
+    }
+}
+
+/// Bytes a resumable session holds, from `Range: bytes=0-<last>` on a 308
+fn upload_offset(resp: &Response) -> u64 {
+    resp.headers()
+        .get(reqwest::header::RANGE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.rsplit('-').next())
+        .and_then(|last| last.parse::<u64>().ok())
+        .map_or(0, |last| last + 1)
+}
+
+/// Bytes already in a part file, 0 when there is none
+async fn part_len(part: &Path) -> Result<u64> {
+    match tokio::fs::metadata(part).await {
+        Ok(meta) => Ok(meta.len()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(0),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// `Content-Range: bytes <start>-<end>/<total>` (or `bytes */<total>`) as
+/// the start and, unless it is `*`, the total
+fn content_range(resp: &Response) -> Option<(u64, Option<u64>)> {
+    let value = resp.headers().get(reqwest::header::CONTENT_RANGE)?.to_str().ok()?;
+    let (range, total) = value.strip_prefix("bytes ")?.split_once('/')?;
+    let start = range.split('-').next()?.parse().unwrap_or(0);
+    Some((start, total.parse().ok()))
+}
+
+/// Fill `buf` from `file`, short only at end of file
+async fn read_full(file: &mut tokio::fs::File, buf: &mut [u8]) -> Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match file.read(&mut buf[filled..]).await? {
+            0 => break,
+            n => filled += n,
+        }
+    }
+    Ok(filled)
+}
+
+/// Turn a non-success response into a typed error from Google's error envelope
+async fn api_error(resp: Response) -> VgoogError {
+    let code = resp.status().as_u16();
+    let retry_after = retry::retry_after(resp.headers()).map(|d| d.as_secs());
+    let www_authenticate = resp
+        .headers()
+        .get(reqwest::header::WWW_AUTHENTICATE)
+        .and_then(|v| v.to_str().ok())
+        .map(String::from);
+    let body = resp.text().await.unwrap_or_default();
+    VgoogError::from_api_response(code, retry_after, www_authenticate.as_deref(), &body)
+}
+
+/// The error for scopes that were never granted and could not be asked for
+fn consent_required(account: &str, service: Service, missing: &[&str]) -> VgoogError {
+    let flags: String = missing.iter().map(|s| format!(" --scope {s}")).collect();
+    VgoogError::InsufficientScopes(Box::new(ApiErrorDetail {
+        code: 403,
+        status: Some("PERMISSION_DENIED".into()),
+        message: format!(
+            "account '{account}' has not granted {} access; run `vgoog auth login --account {account}{flags}`",
+            service.name()
+        ),
+        reasons: vec!["consentRequired".into()],
+        ..Default::default()
+    }))
 }
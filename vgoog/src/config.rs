@@ -1,8 +1,15 @@
+use crate::cache::CacheConfig;
+use crate::endpoints::Endpoints;
 use crate::error::{Result, VgoogError};
+use crate::network::NetworkConfig;
+use crate::quota::RateLimit;
+use crate::retry::RetryPolicy;
+use crate::vault::{self, VaultConfig, VaultKey};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use std::collections::BTreeMap;
-use std::path::PathBuf;
+use std::io::Write;
+use std::path::{Path, PathBuf};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Config {
@@ -14,22 +21,109 @@ pub struct Config {
     #[serde(default)]
     pub accounts: BTreeMap<String, Account>,
 
+    /// Service-account keys, referenced by accounts that impersonate users
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub service_accounts: BTreeMap<String, ServiceAccountKey>,
+
+    /// Base URL overrides (`root`, `gmail`, `drive`, `token_uri`, ...), see `Endpoints`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub endpoints: BTreeMap<String, String>,
+
+    /// Proxy, extra CA certificates, timeouts and user agent, see `NetworkConfig`
+    #[serde(default, skip_serializing_if = "NetworkConfig::is_default")]
+    pub network: NetworkConfig,
+
+    /// Per-API budgets replacing the defaults, see `quota::DEFAULT_LIMITS`
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub rate_limits: BTreeMap<String, RateLimit>,
+
+    /// Whether GET responses are cached and for how long, see `cache`
+    #[serde(default, skip_serializing_if = "CacheConfig::is_default")]
+    pub cache: CacheConfig,
+
+    /// Present when secrets are sealed in `credentials.enc`, see `vault`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vault: Option<VaultConfig>,
+
     /// Legacy single-account field — migrated on load
     #[serde(skip_serializing_if = "Option::is_none")]
     pub auth: Option<AuthConfig>,
+
+    /// Key that unlocked the store, reused to re-seal it on save
+    #[serde(skip)]
+    pub vault_key: Option<VaultKey>,
+
+    /// Built from `VGOOG_*` environment variables, see `from_env`; such a
+    /// config is never written to disk
+    #[serde(skip)]
+    pub ephemeral: bool,
 }
 
+/// Name of the account built from environment variables
+pub const ENV_ACCOUNT: &str = "env";
+
 fn default_active() -> String {
     "default".to_string()
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            active_account: default_active(),
+            accounts: BTreeMap::new(),
+            service_accounts: BTreeMap::new(),
+            endpoints: BTreeMap::new(),
+            network: NetworkConfig::default(),
+            rate_limits: BTreeMap::new(),
+            cache: CacheConfig::default(),
+            vault: None,
+            auth: None,
+            vault_key: None,
+            ephemeral: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct Account {
     pub label: String,
+    /// OAuth scopes the account was granted, kept up to date by every
+    /// consent and refresh. Empty for accounts authorized before they were
+    /// recorded, which are assumed to have everything.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scopes: Vec<String>,
+    /// OAuth credentials; for service-account accounts only the cached
+    /// access token and expiry are used
+    #[serde(default)]
     pub auth: AuthConfig,
+    /// Authenticate with a service-account key instead of a refresh token
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub service_account: Option<ServiceAccountRef>,
+    /// Retry/backoff overrides for transient API failures
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub retry: Option<RetryPolicy>,
+}
+
+/// A service-account JSON key and the scopes it is granted via
+/// domain-wide delegation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceAccountKey {
+    pub key_file: PathBuf,
+    /// Defaults to the full vgoog scope set when empty
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub scopes: Vec<String>,
 }
 
+/// Links an account to a `[service_accounts.<key>]` entry
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServiceAccountRef {
+    pub key: String,
+    /// User to impersonate (JWT `sub` claim); omit to act as the service account itself
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subject: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct AuthConfig {
     pub client_id: String,
     pub client_secret: String,
@@ -43,6 +137,17 @@ fn default_expiry() -> DateTime<Utc> {
     Utc::now()
 }
 
+impl AuthConfig {
+    /// The fields kept in the credential store when it is locked
+    fn secrets_mut(&mut self) -> [(&'static str, &mut String); 3] {
+        [
+            ("client_secret", &mut self.client_secret),
+            ("access_token", &mut self.access_token),
+            ("refresh_token", &mut self.refresh_token),
+        ]
+    }
+}
+
 impl Config {
     pub fn config_dir() -> Result<PathBuf> {
         if let Ok(dir) = std::env::var("VGOOG_CONFIG_DIR") {
@@ -58,18 +163,90 @@ impl Config {
         Ok(Self::config_dir()?.join("config.toml"))
     }
 
+    pub fn vault_path() -> Result<PathBuf> {
+        Ok(Self::config_dir()?.join(vault::FILE_NAME))
+    }
+
+    /// Load the config, unlocking the credential store if there is one
     pub fn load() -> Result<Self> {
+        let mut config = Self::load_locked()?;
+        config.unseal(None)?;
+        Ok(config)
+    }
+
+    /// Load the config without unlocking it: sealed secrets stay as
+    /// `vault:` references. Enough for anything but talking to Google.
+    pub fn load_locked() -> Result<Self> {
+        if let Some(config) = Self::from_env()? {
+            return Ok(config);
+        }
         let path = Self::config_path()?;
         if !path.exists() {
             return Err(VgoogError::Config(
                 "Config not found. Run `vgoog` to set up.".into(),
             ));
         }
-        let content = std::fs::read_to_string(&path)?;
+        let (config, migrated) = Self::read(&path)?;
+        if migrated {
+            // Save migrated config
+            let _ = config.save();
+        }
+        Ok(config)
+    }
+
+    /// A single-account config from the environment, for CI: either
+    /// `VGOOG_REFRESH_TOKEN` with `VGOOG_CLIENT_ID` and `VGOOG_CLIENT_SECRET`,
+    /// or a bare `VGOOG_ACCESS_TOKEN`, which is used until Google rejects
+    /// it. When set, these win over config.toml, and nothing is saved.
+    pub fn from_env() -> Result<Option<Self>> {
+        let var = |name: &str| std::env::var(name).ok().filter(|v| !v.is_empty());
+        let auth = match (var("VGOOG_REFRESH_TOKEN"), var("VGOOG_ACCESS_TOKEN")) {
+            (Some(refresh_token), _) => {
+                let (Some(client_id), Some(client_secret)) =
+                    (var("VGOOG_CLIENT_ID"), var("VGOOG_CLIENT_SECRET"))
+                else {
+                    return Err(VgoogError::Config(
+                        "VGOOG_REFRESH_TOKEN needs VGOOG_CLIENT_ID and VGOOG_CLIENT_SECRET".into(),
+                    ));
+                };
+                AuthConfig {
+                    client_id,
+                    client_secret,
+                    access_token: String::new(),
+                    refresh_token,
+                    token_expiry: Utc::now(),
+                }
+            }
+            (None, Some(access_token)) => AuthConfig {
+                client_id: var("VGOOG_CLIENT_ID").unwrap_or_default(),
+                client_secret: var("VGOOG_CLIENT_SECRET").unwrap_or_default(),
+                access_token,
+                refresh_token: String::new(),
+                // Nothing to refresh with, so never try
+                token_expiry: DateTime::<Utc>::MAX_UTC,
+            },
+            (None, None) => return Ok(None),
+        };
+
+        let mut config = Config {
+            active_account: ENV_ACCOUNT.to_string(),
+            ephemeral: true,
+            ..Default::default()
+        };
+        config.add_account(
+            ENV_ACCOUNT.to_string(),
+            Account { label: "Environment".to_string(), auth, ..Default::default() },
+        );
+        Ok(Some(config))
+    }
+
+    /// Parse config.toml, migrating a legacy single-account config in memory
+    fn read(path: &Path) -> Result<(Self, bool)> {
+        let content = std::fs::read_to_string(path)?;
         let mut config: Config =
             toml::from_str(&content).map_err(|e| VgoogError::Config(e.to_string()))?;
 
-        // Migrate legacy single-account config
+        let mut migrated = false;
         if let Some(legacy_auth) = config.auth.take() {
             if !config.accounts.contains_key("default") {
                 config.accounts.insert(
@@ -77,24 +254,178 @@ impl Config {
                     Account {
                         label: "Default Account".to_string(),
                         auth: legacy_auth,
+                        ..Default::default()
                     },
                 );
                 config.active_account = "default".to_string();
-                // Save migrated config
-                let _ = config.save();
+                migrated = true;
             }
         }
+        Ok((config, migrated))
+    }
 
-        Ok(config)
+    /// Resolve `vault:` references, with `key` if the store is already
+    /// unlocked or else by asking for the passphrase
+    fn unseal(&mut self, key: Option<&VaultKey>) -> Result<()> {
+        let Some(vault) = &self.vault else {
+            return Ok(());
+        };
+        let path = Self::vault_path()?;
+        let key = match key {
+            Some(key) => key.clone(),
+            None => VaultKey::unlock(vault, &path)?,
+        };
+        let secrets = key.open(&path)?;
+        self.resolve_secrets(&secrets)?;
+        self.vault_key = Some(key);
+        Ok(())
     }
 
+    /// Replace the whole file with this config, readable by the owner only.
+    /// With a credential store, secrets are sealed into it and replaced by
+    /// references. Prefer `update` to change part of the config.
     pub fn save(&self) -> Result<()> {
-        let dir = Self::config_dir()?;
-        std::fs::create_dir_all(&dir)?;
+        self.check_writable()?;
+        let lock = ConfigLock::acquire()?;
+        self.write(&lock)
+    }
+
+    /// Apply `change` to the latest config on disk and save it, so changes
+    /// made by other processes since this one was loaded are kept. `self`
+    /// becomes the merged result.
+    pub fn update<T>(&mut self, change: impl FnOnce(&mut Config) -> T) -> Result<T> {
+        self.check_writable()?;
+        let lock = ConfigLock::acquire()?;
+        self.update_with_lock(&lock, change)
+    }
+
+    /// `update` for async code: waits for the lock without blocking the
+    /// runtime's thread
+    pub async fn update_async<T>(&mut self, change: impl FnOnce(&mut Config) -> T) -> Result<T> {
+        self.check_writable()?;
+        let lock = ConfigLock::acquire_async().await?;
+        self.update_with_lock(&lock, change)
+    }
+
+    /// `save` for async code, like `update_async`
+    pub async fn save_async(&self) -> Result<()> {
+        self.check_writable()?;
+        let lock = ConfigLock::acquire_async().await?;
+        self.write(&lock)
+    }
+
+    /// `update` for a caller already holding the config lock
+    pub fn update_with_lock<T>(
+        &mut self,
+        lock: &ConfigLock,
+        change: impl FnOnce(&mut Config) -> T,
+    ) -> Result<T> {
+        self.check_writable()?;
+        let mut latest = self.latest(lock)?;
+        let result = change(&mut latest);
+        latest.write(lock)?;
+        *self = latest;
+        Ok(result)
+    }
+
+    /// Refuse to save a config that came from the environment
+    fn check_writable(&self) -> Result<()> {
+        if self.ephemeral {
+            return Err(VgoogError::Config(
+                "Credentials come from VGOOG_* environment variables, so nothing is saved; unset them to change config.toml".into(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// The config as currently saved, unlocked with this config's key; this
+    /// config itself when nothing has been saved yet
+    fn latest(&self, _lock: &ConfigLock) -> Result<Config> {
         let path = Self::config_path()?;
+        if !path.exists() {
+            return Ok(self.clone());
+        }
+        let (mut latest, _) = Self::read(&path)?;
+        latest.unseal(self.vault_key.as_ref())?;
+        Ok(latest)
+    }
+
+    /// Write config.toml (and the credential store) atomically: to a
+    /// temporary file first, then renamed over the old one
+    fn write(&self, _lock: &ConfigLock) -> Result<()> {
+        let mut on_disk = self.clone();
+        if on_disk.vault.is_some() {
+            let key = self.vault_key.as_ref().ok_or_else(|| {
+                VgoogError::Config("Credential store is locked; load the config with Config::load".into())
+            })?;
+            let secrets = on_disk.take_secrets();
+            write_private(&Self::vault_path()?, &key.seal(&secrets)?)?;
+        }
         let content =
-            toml::to_string_pretty(self).map_err(|e| VgoogError::Config(e.to_string()))?;
-        std::fs::write(&path, content)?;
+            toml::to_string_pretty(&on_disk).map_err(|e| VgoogError::Config(e.to_string()))?;
+        write_private(&Self::config_path()?, content.as_bytes())?;
+        Ok(())
+    }
+
+    /// Whether secrets are sealed in the credential store
+    pub fn is_locked(&self) -> bool {
+        self.vault.is_some()
+    }
+
+    /// Seal every secret with `key` from now on, migrating a plaintext
+    /// config (or re-keying a locked one). Saves.
+    pub fn lock(&mut self, key: VaultKey, key_file: Option<PathBuf>) -> Result<()> {
+        self.vault = Some(VaultConfig { key_file });
+        self.vault_key = Some(key);
+        self.save()
+    }
+
+    /// Move secrets back into config.toml and delete the store. Saves.
+    pub fn unlock(&mut self) -> Result<()> {
+        self.check_writable()?;
+        if self.vault.take().is_none() {
+            return Err(VgoogError::Config("Credentials are not locked".into()));
+        }
+        self.vault_key = None;
+        let lock = ConfigLock::acquire()?;
+        self.write(&lock)?;
+        let path = Self::vault_path()?;
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+        Ok(())
+    }
+
+    /// Move secrets out of the accounts, leaving references behind
+    fn take_secrets(&mut self) -> BTreeMap<String, String> {
+        let mut secrets = BTreeMap::new();
+        for (name, account) in &mut self.accounts {
+            for (field, value) in account.auth.secrets_mut() {
+                if value.is_empty() || value.starts_with(vault::REFERENCE_PREFIX) {
+                    continue;
+                }
+                let reference = format!("{name}.{field}");
+                secrets.insert(reference.clone(), std::mem::take(value));
+                *value = format!("{}{reference}", vault::REFERENCE_PREFIX);
+            }
+        }
+        secrets
+    }
+
+    /// Replace references with the secrets they point to
+    fn resolve_secrets(&mut self, secrets: &BTreeMap<String, String>) -> Result<()> {
+        for account in self.accounts.values_mut() {
+            for (_, value) in account.auth.secrets_mut() {
+                if let Some(reference) = value.strip_prefix(vault::REFERENCE_PREFIX) {
+                    *value = secrets.get(reference).cloned().ok_or_else(|| {
+                        VgoogError::Config(format!(
+                            "Credential '{reference}' is missing from {}",
+                            vault::FILE_NAME
+                        ))
+                    })?;
+                }
+            }
+        }
         Ok(())
     }
 
@@ -156,38 +487,190 @@ impl Config {
 
     /// Check if active account has valid credentials
     pub fn active_is_valid(&self) -> bool {
-        self.active().is_ok_and(|a| {
-            !a.auth.client_id.is_empty()
-                && !a.auth.client_secret.is_empty()
-                && !a.auth.access_token.is_empty()
-                && !a.auth.refresh_token.is_empty()
+        // `from_env` already insisted on enough to get a token
+        if self.ephemeral {
+            return true;
+        }
+        self.active().is_ok_and(|a| match &a.service_account {
+            Some(sa) => self.service_accounts.contains_key(&sa.key),
+            None => {
+                !a.auth.client_id.is_empty()
+                    && !a.auth.client_secret.is_empty()
+                    && !a.auth.access_token.is_empty()
+                    && !a.auth.refresh_token.is_empty()
+            }
         })
     }
 
     /// Build a legacy-compatible Config for a single account (used by GoogleClient)
     pub fn for_active_account(&self) -> Result<SingleAccountConfig> {
-        let account = self.active()?;
+        self.for_account(&self.active_account)
+    }
+
+    /// Single-account view of `name`, whichever account is active
+    pub fn for_account(&self, name: &str) -> Result<SingleAccountConfig> {
+        let account = self
+            .accounts
+            .get(name)
+            .ok_or_else(|| VgoogError::Config(format!("Account '{name}' not found")))?;
+        let service_account = match &account.service_account {
+            Some(sa) => {
+                let key = self.service_accounts.get(&sa.key).ok_or_else(|| {
+                    VgoogError::Config(format!("Service account key '{}' not found", sa.key))
+                })?;
+                Some(ServiceAccountAuth {
+                    key_file: key.key_file.clone(),
+                    scopes: key.scopes.clone(),
+                    subject: sa.subject.clone(),
+                })
+            }
+            None => None,
+        };
         Ok(SingleAccountConfig {
+            account: name.to_string(),
             auth: account.auth.clone(),
+            scopes: account.scopes.clone(),
+            service_account,
+            retry: account.retry.clone().unwrap_or_default(),
+            endpoints: Endpoints::resolve(&self.endpoints)?,
             config_ref: self.clone(),
         })
     }
 }
 
+/// Exclusive lock on the config directory (`config.lock`), held across
+/// every read-modify-write so concurrent vgoog processes don't lose each
+/// other's changes. Released on drop.
+#[derive(Debug)]
+pub struct ConfigLock {
+    _file: std::fs::File,
+}
+
+impl ConfigLock {
+    /// Block until the lock is free
+    pub fn acquire() -> Result<Self> {
+        let file = Self::open()?;
+        file.lock()?;
+        Ok(Self { _file: file })
+    }
+
+    /// Wait for the lock without blocking the async runtime
+    pub async fn acquire_async() -> Result<Self> {
+        let file = Self::open()?;
+        loop {
+            match file.try_lock() {
+                Ok(()) => return Ok(Self { _file: file }),
+                Err(std::fs::TryLockError::WouldBlock) => {
+                    tokio::time::sleep(std::time::Duration::from_millis(20)).await
+                }
+                Err(std::fs::TryLockError::Error(e)) => return Err(e.into()),
+            }
+        }
+    }
+
+    fn open() -> Result<std::fs::File> {
+        let dir = Config::config_dir()?;
+        create_private_dir(&dir)?;
+        let mut options = std::fs::OpenOptions::new();
+        options.read(true).write(true).create(true).truncate(false);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        Ok(options.open(dir.join("config.lock"))?)
+    }
+}
+
+/// Create `dir` if needed and keep it private to the owner (0700)
+pub fn create_private_dir(dir: &Path) -> Result<()> {
+    std::fs::create_dir_all(dir)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700))?;
+    }
+    Ok(())
+}
+
+/// Write a file readable by the owner only (0600). The data goes to a
+/// temporary file that is renamed over `path`, so readers see either the
+/// old contents or the new, never a partial write.
+pub fn write_private(path: &Path, contents: &[u8]) -> Result<()> {
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(format!(".tmp-{}", std::process::id()));
+    let tmp = path.with_file_name(tmp_name);
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let written = options.open(&tmp).and_then(|mut file| {
+        file.write_all(contents)?;
+        file.sync_all()
+    });
+    if let Err(e) = written.and_then(|()| std::fs::rename(&tmp, path)) {
+        let _ = std::fs::remove_file(&tmp);
+        return Err(e.into());
+    }
+    Ok(())
+}
+
 /// A view of a single account's config, used by GoogleClient
 #[derive(Debug, Clone)]
 pub struct SingleAccountConfig {
+    /// Name of the account in the multi-account config
+    pub account: String,
     pub auth: AuthConfig,
+    /// Granted scopes; empty when unknown
+    pub scopes: Vec<String>,
+    pub service_account: Option<ServiceAccountAuth>,
+    pub retry: RetryPolicy,
+    pub endpoints: Endpoints,
     pub config_ref: Config,
 }
 
+/// Resolved service-account settings for one impersonated account
+#[derive(Debug, Clone)]
+pub struct ServiceAccountAuth {
+    pub key_file: PathBuf,
+    pub scopes: Vec<String>,
+    pub subject: Option<String>,
+}
+
 impl SingleAccountConfig {
-    /// Save updated tokens back to the multi-account config
-    pub fn save(&self) -> Result<()> {
-        let mut config = self.config_ref.clone();
-        if let Some(account) = config.accounts.get_mut(&config.active_account) {
-            account.auth = self.auth.clone();
+    /// Save updated tokens into the latest config on disk, touching
+    /// nothing but this account's credentials and granted scopes
+    pub fn save(&mut self, lock: &ConfigLock) -> Result<()> {
+        let auth = self.auth.clone();
+        let scopes = self.scopes.clone();
+        self.config_ref.update_with_lock(lock, |config| {
+            // Don't resurrect an account removed in the meantime
+            if let Some(account) = config.accounts.get_mut(&self.account) {
+                account.auth = auth;
+                account.scopes = scopes;
+            }
+        })
+    }
+
+    /// Take this account's tokens from disk if another process refreshed
+    /// them since they were loaded. Returns whether a usable token was found.
+    pub fn reload_token(&mut self, lock: &ConfigLock) -> Result<bool> {
+        let latest = self.config_ref.latest(lock)?;
+        let Some(saved) = latest.accounts.get(&self.account) else {
+            return Ok(false);
+        };
+        if saved.auth.token_expiry <= self.auth.token_expiry
+            || saved.auth.token_expiry - chrono::Duration::minutes(2) <= Utc::now()
+        {
+            return Ok(false);
         }
-        config.save()
+        self.auth = saved.auth.clone();
+        self.scopes = saved.scopes.clone();
+        self.config_ref = latest;
+        Ok(true)
     }
 }
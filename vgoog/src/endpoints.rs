@@ -0,0 +1,187 @@
+use crate::config::Config;
+use crate::error::{Result, VgoogError};
+use std::collections::BTreeMap;
+
+pub const DEFAULT_AUTH_URI: &str = "https://accounts.google.com/o/oauth2/v2/auth";
+pub const DEFAULT_TOKEN_URI: &str = "https://oauth2.googleapis.com/token";
+pub const DEFAULT_DEVICE_URI: &str = "https://oauth2.googleapis.com/device/code";
+pub const DEFAULT_TOKENINFO_URI: &str = "https://oauth2.googleapis.com/tokeninfo";
+pub const DEFAULT_REVOKE_URI: &str = "https://oauth2.googleapis.com/revoke";
+
+/// Base URLs for every Google service vgoog talks to.
+///
+/// Resolved in layers, later ones winning: built-in defaults, the `[endpoints]`
+/// table in config.toml, then `VGOOG_ENDPOINT_*` environment variables. Within
+/// each layer `root` is applied first and replaces the scheme and host of every
+/// endpoint while keeping its path, so one fake server can stand in for all of
+/// Google; the per-service keys then override individual URLs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Endpoints {
+    pub gmail: String,
+    pub gmail_batch: String,
+    pub calendar: String,
+    pub calendar_batch: String,
+    pub drive: String,
+    pub drive_upload: String,
+    pub drive_batch: String,
+    pub sheets: String,
+    pub docs: String,
+    pub slides: String,
+    pub forms: String,
+    pub tasks: String,
+    pub people: String,
+    pub script: String,
+    pub auth_uri: String,
+    pub token_uri: String,
+    pub device_uri: String,
+    pub tokeninfo_uri: String,
+    pub revoke_uri: String,
+}
+
+impl Default for Endpoints {
+    fn default() -> Self {
+        Self {
+            gmail: "https://gmail.googleapis.com/gmail/v1/users/me".into(),
+            gmail_batch: "https://gmail.googleapis.com/batch/gmail/v1".into(),
+            calendar: "https://www.googleapis.com/calendar/v3".into(),
+            calendar_batch: "https://www.googleapis.com/batch/calendar/v3".into(),
+            drive: "https://www.googleapis.com/drive/v3".into(),
+            drive_upload: "https://www.googleapis.com/upload/drive/v3".into(),
+            drive_batch: "https://www.googleapis.com/batch/drive/v3".into(),
+            sheets: "https://sheets.googleapis.com/v4/spreadsheets".into(),
+            docs: "https://docs.googleapis.com/v1/documents".into(),
+            slides: "https://slides.googleapis.com/v1/presentations".into(),
+            forms: "https://forms.googleapis.com/v1/forms".into(),
+            tasks: "https://tasks.googleapis.com/tasks/v1".into(),
+            people: "https://people.googleapis.com/v1".into(),
+            script: "https://script.googleapis.com/v1".into(),
+            auth_uri: DEFAULT_AUTH_URI.into(),
+            token_uri: DEFAULT_TOKEN_URI.into(),
+            device_uri: DEFAULT_DEVICE_URI.into(),
+            tokeninfo_uri: DEFAULT_TOKENINFO_URI.into(),
+            revoke_uri: DEFAULT_REVOKE_URI.into(),
+        }
+    }
+}
+
+/// Keys accepted in `[endpoints]`; the env var is `VGOOG_ENDPOINT_<KEY>`
+pub const ENDPOINT_KEYS: &[&str] = &[
+    "gmail",
+    "gmail_batch",
+    "calendar",
+    "calendar_batch",
+    "drive",
+    "drive_upload",
+    "drive_batch",
+    "sheets",
+    "docs",
+    "slides",
+    "forms",
+    "tasks",
+    "people",
+    "script",
+    "auth_uri",
+    "token_uri",
+    "device_uri",
+    "tokeninfo_uri",
+    "revoke_uri",
+];
+
+/// Variables from before `VGOOG_ENDPOINT_*`, still honoured when the new
+/// variable for their key is unset
+pub const DEPRECATED_ENV: &[(&str, &str)] = &[("VGOOG_AUTH_URI", "auth_uri"), ("VGOOG_TOKEN_URI", "token_uri")];
+
+impl Endpoints {
+    /// Resolve against `[endpoints]` overrides and the environment
+    pub fn resolve(overrides: &BTreeMap<String, String>) -> Result<Self> {
+        let mut endpoints = Self::default();
+        endpoints.apply(overrides)?;
+
+        let mut env: BTreeMap<String, String> = std::iter::once("root")
+            .chain(ENDPOINT_KEYS.iter().copied())
+            .filter_map(|key| {
+                let var = format!("VGOOG_ENDPOINT_{}", key.to_uppercase());
+                std::env::var(var).ok().map(|v| (key.to_string(), v))
+            })
+            .collect();
+        for (var, key) in DEPRECATED_ENV {
+            if let (false, Ok(url)) = (env.contains_key(*key), std::env::var(var)) {
+                static WARNED: std::sync::Once = std::sync::Once::new();
+                WARNED.call_once(|| eprintln!("vgoog: {var} is deprecated, use VGOOG_ENDPOINT_{}", key.to_uppercase()));
+                env.insert(key.to_string(), url);
+            }
+        }
+        endpoints.apply(&env)?;
+
+        Ok(endpoints)
+    }
+
+    /// Resolve from the saved config (if there is one) and the environment,
+    /// for flows that run before a `GoogleClient` exists
+    pub fn current() -> Result<Self> {
+        if Config::exists() {
+            Self::resolve(&Config::load_locked()?.endpoints)
+        } else {
+            Self::resolve(&BTreeMap::new())
+        }
+    }
+
+    /// Whether the token endpoint was overridden; service-account keys carry
+    /// their own `token_uri`, which is only used when it was not
+    pub fn token_uri_overridden(&self) -> bool {
+        self.token_uri != DEFAULT_TOKEN_URI
+    }
+
+    fn apply(&mut self, overrides: &BTreeMap<String, String>) -> Result<()> {
+        if let Some(root) = overrides.get("root") {
+            let root = root.trim_end_matches('/');
+            for key in ENDPOINT_KEYS {
+                let url = self.get_mut(key).expect("known endpoint key");
+                *url = format!("{root}{}", url_path(url));
+            }
+        }
+        for (key, value) in overrides.iter().filter(|(k, _)| k.as_str() != "root") {
+            let url = self.get_mut(key).ok_or_else(|| {
+                VgoogError::Config(format!(
+                    "Unknown endpoint '{key}' (expected root or one of: {})",
+                    ENDPOINT_KEYS.join(", ")
+                ))
+            })?;
+            *url = value.trim_end_matches('/').to_string();
+        }
+        Ok(())
+    }
+
+    fn get_mut(&mut self, key: &str) -> Option<&mut String> {
+        Some(match key {
+            "gmail" => &mut self.gmail,
+            "gmail_batch" => &mut self.gmail_batch,
+            "calendar" => &mut self.calendar,
+            "calendar_batch" => &mut self.calendar_batch,
+            "drive" => &mut self.drive,
+            "drive_upload" => &mut self.drive_upload,
+            "drive_batch" => &mut self.drive_batch,
+            "sheets" => &mut self.sheets,
+            "docs" => &mut self.docs,
+            "slides" => &mut self.slides,
+            "forms" => &mut self.forms,
+            "tasks" => &mut self.tasks,
+            "people" => &mut self.people,
+            "script" => &mut self.script,
+            "auth_uri" => &mut self.auth_uri,
+            "token_uri" => &mut self.token_uri,
+            "device_uri" => &mut self.device_uri,
+            "tokeninfo_uri" => &mut self.tokeninfo_uri,
+            "revoke_uri" => &mut self.revoke_uri,
+            _ => return None,
+        })
+    }
+}
+
+/// The path of an absolute URL (`https://host/a/b` → `/a/b`)
+fn url_path(url: &str) -> &str {
+    let after_scheme = url.find("://").map_or(0, |i| i + 3);
+    url[after_scheme..]
+        .find('/')
+        .map_or("", |i| &url[after_scheme + i..])
+}
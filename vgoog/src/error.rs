@@ -1,3 +1,5 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -26,8 +28,221 @@ pub enum VgoogError {
     #[error("Rate limited, retry after {retry_after_secs}s")]
     RateLimited { retry_after_secs: u64 },
 
+    #[error("User rate limit exceeded: {}", .detail.message)]
+    UserRateLimited {
+        detail: Box<ApiErrorDetail>,
+        retry_after_secs: Option<u64>,
+    },
+
+    #[error("Quota exceeded: {}", .0.message)]
+    QuotaExceeded(Box<ApiErrorDetail>),
+
+    #[error("Insufficient scopes: {}", .0.message)]
+    InsufficientScopes(Box<ApiErrorDetail>),
+
+    #[error("Permission denied: {}", .0.message)]
+    PermissionDenied(Box<ApiErrorDetail>),
+
+    #[error("Precondition failed: {}", .0.message)]
+    PreconditionFailed(Box<ApiErrorDetail>),
+
+    #[error("Invalid argument: {}{}", .0.message, .0.field_summary())]
+    InvalidArgument(Box<ApiErrorDetail>),
+
     #[error("{0}")]
     Other(String),
 }
 
 pub type Result<T> = std::result::Result<T, VgoogError>;
+
+/// Google's standard error envelope: `{"error": {"code", "message", "status", "errors", "details"}}`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ApiErrorDetail {
+    /// HTTP status code
+    pub code: u16,
+    /// Canonical status, e.g. `PERMISSION_DENIED`, `RESOURCE_EXHAUSTED`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+    pub message: String,
+    /// `errors[].reason` and `google.rpc.ErrorInfo` reasons, in order of appearance
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub reasons: Vec<String>,
+    /// Offending fields from `google.rpc.BadRequest` or `errors[].location`
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub field_violations: Vec<FieldViolation>,
+    /// Raw `details` entries for anything not lifted into the fields above
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub details: Vec<Value>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FieldViolation {
+    pub field: String,
+    pub description: String,
+}
+
+impl ApiErrorDetail {
+    /// Parse an error body; non-envelope bodies become the message verbatim
+    pub fn parse(code: u16, body: &str) -> Self {
+        let mut detail = ApiErrorDetail {
+            code,
+            message: body.to_string(),
+            ..Default::default()
+        };
+        let Some(err) = serde_json::from_str::<Value>(body)
+            .ok()
+            .and_then(|v| v.get("error").cloned())
+            .filter(|e| e.is_object())
+        else {
+            return detail;
+        };
+
+        if let Some(m) = err.get("message").and_then(|v| v.as_str()) {
+            detail.message = m.to_string();
+        }
+        detail.status = err.get("status").and_then(|v| v.as_str()).map(String::from);
+
+        for e in err.get("errors").and_then(|v| v.as_array()).into_iter().flatten() {
+            if let Some(r) = e.get("reason").and_then(|v| v.as_str()) {
+                detail.reasons.push(r.to_string());
+            }
+            if e.get("locationType").and_then(|v| v.as_str()) == Some("parameter") {
+                if let Some(loc) = e.get("location").and_then(|v| v.as_str()) {
+                    detail.field_violations.push(FieldViolation {
+                        field: loc.to_string(),
+                        description: e.get("message").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+                    });
+                }
+            }
+        }
+
+        for d in err.get("details").and_then(|v| v.as_array()).into_iter().flatten() {
+            let ty = d.get("@type").and_then(|v| v.as_str()).unwrap_or("");
+            if ty.ends_with("google.rpc.ErrorInfo") {
+                if let Some(r) = d.get("reason").and_then(|v| v.as_str()) {
+                    detail.reasons.push(r.to_string());
+                }
+            } else if ty.ends_with("google.rpc.BadRequest") {
+                for fv in d.get("fieldViolations").and_then(|v| v.as_array()).into_iter().flatten() {
+                    detail.field_violations.push(FieldViolation {
+                        field: fv.get("field").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+                        description: fv.get("description").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+                    });
+                }
+            }
+            detail.details.push(d.clone());
+        }
+
+        detail
+    }
+
+    pub fn has_reason(&self, reasons: &[&str]) -> bool {
+        self.reasons.iter().any(|r| reasons.contains(&r.as_str()))
+    }
+
+    fn field_summary(&self) -> String {
+        if self.field_violations.is_empty() {
+            return String::new();
+        }
+        let fields: Vec<&str> = self.field_violations.iter().map(|f| f.field.as_str()).collect();
+        format!(" (fields: {})", fields.join(", "))
+    }
+}
+
+const USER_RATE_REASONS: &[&str] = &["userRateLimitExceeded", "rateLimitExceeded", "RATE_LIMIT_EXCEEDED"];
+const QUOTA_REASONS: &[&str] = &["quotaExceeded", "dailyLimitExceeded", "storageQuotaExceeded"];
+const SCOPE_REASONS: &[&str] = &["ACCESS_TOKEN_SCOPE_INSUFFICIENT", "insufficientPermissions"];
+const PRECONDITION_REASONS: &[&str] = &["conditionNotMet", "failedPrecondition"];
+
+impl VgoogError {
+    /// Classify a non-success API response into the most specific variant
+    pub fn from_api_response(code: u16, retry_after_secs: Option<u64>, www_authenticate: Option<&str>, body: &str) -> Self {
+        let detail = Box::new(ApiErrorDetail::parse(code, body));
+        let status = detail.status.as_deref().unwrap_or("");
+
+        if detail.has_reason(USER_RATE_REASONS) {
+            return VgoogError::UserRateLimited { detail, retry_after_secs };
+        }
+        if detail.has_reason(QUOTA_REASONS) {
+            return VgoogError::QuotaExceeded(detail);
+        }
+        if code == 429 {
+            return if status == "RESOURCE_EXHAUSTED" && retry_after_secs.is_none() {
+                VgoogError::QuotaExceeded(detail)
+            } else {
+                VgoogError::RateLimited { retry_after_secs: retry_after_secs.unwrap_or(60) }
+            };
+        }
+        if detail.has_reason(SCOPE_REASONS) || www_authenticate.is_some_and(|h| h.contains("insufficient_scope")) {
+            return VgoogError::InsufficientScopes(detail);
+        }
+        if code == 412 || status == "FAILED_PRECONDITION" || detail.has_reason(PRECONDITION_REASONS) {
+            return VgoogError::PreconditionFailed(detail);
+        }
+        match code {
+            400 => VgoogError::InvalidArgument(detail),
+            401 => VgoogError::Auth(detail.message),
+            403 => VgoogError::PermissionDenied(detail),
+            404 => VgoogError::NotFound(detail.message),
+            _ => VgoogError::Api { status: code, message: detail.message },
+        }
+    }
+
+    /// Stable snake_case identifier for scripts
+    pub fn kind(&self) -> &'static str {
+        match self {
+            VgoogError::Http(_) => "http",
+            VgoogError::Json(_) => "json",
+            VgoogError::Io(_) => "io",
+            VgoogError::Config(_) => "config",
+            VgoogError::Auth(_) => "auth",
+            VgoogError::Api { .. } => "api",
+            VgoogError::NotFound(_) => "not_found",
+            VgoogError::RateLimited { .. } => "rate_limited",
+            VgoogError::UserRateLimited { .. } => "user_rate_limited",
+            VgoogError::QuotaExceeded(_) => "quota_exceeded",
+            VgoogError::InsufficientScopes(_) => "insufficient_scopes",
+            VgoogError::PermissionDenied(_) => "permission_denied",
+            VgoogError::PreconditionFailed(_) => "precondition_failed",
+            VgoogError::InvalidArgument(_) => "invalid_argument",
+            VgoogError::Other(_) => "other",
+        }
+    }
+
+    pub fn api_detail(&self) -> Option<&ApiErrorDetail> {
+        match self {
+            VgoogError::UserRateLimited { detail, .. }
+            | VgoogError::QuotaExceeded(detail)
+            | VgoogError::InsufficientScopes(detail)
+            | VgoogError::PermissionDenied(detail)
+            | VgoogError::PreconditionFailed(detail)
+            | VgoogError::InvalidArgument(detail) => Some(detail.as_ref()),
+            _ => None,
+        }
+    }
+
+    /// Rate-limit errors that are worth retrying after a backoff
+    pub fn is_rate_limit(&self) -> bool {
+        matches!(self, VgoogError::RateLimited { .. } | VgoogError::UserRateLimited { .. })
+    }
+
+    /// The `{"ok": false, ...}` envelope printed by the CLI
+    pub fn to_json(&self) -> Value {
+        let mut out = json!({
+            "ok": false,
+            "error": self.to_string(),
+            "error_kind": self.kind(),
+        });
+        match self {
+            VgoogError::Api { status, .. } => out["http_status"] = json!(status),
+            VgoogError::RateLimited { retry_after_secs } => out["retry_after_secs"] = json!(retry_after_secs),
+            VgoogError::UserRateLimited { retry_after_secs: Some(secs), .. } => out["retry_after_secs"] = json!(secs),
+            _ => {}
+        }
+        if let Some(detail) = self.api_detail() {
+            out["http_status"] = json!(detail.code);
+            out["error_detail"] = serde_json::to_value(detail).unwrap_or(Value::Null);
+        }
+        out
+    }
+}
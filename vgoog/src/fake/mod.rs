@@ -0,0 +1,412 @@
+//! In-memory fake of the Google REST APIs vgoog talks to, for tests and
+//! offline development.
+//!
+//! Gmail, Drive, Calendar, Tasks and People keep real state: resources can be
+//! created, listed with page tokens, fetched, updated and deleted. Sheets,
+//! Docs, Slides, Forms and Apps Script are stubs: created documents can be
+//! fetched back and every other call succeeds with an empty body. The OAuth
+//! token and device endpoints hand out tokens the fake then accepts, and every
+//! request is recorded so tests can assert on what was sent.
+//!
+//! All services share one host and are told apart by their upstream paths, so
+//! a config only needs `[endpoints] root = FakeGoogle::url()`.
+
+mod routes;
+
+use crate::config::{Account, AuthConfig, Config};
+use chrono::{Duration, Utc};
+use serde_json::Value;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::task::JoinHandle;
+
+/// Access token accepted without going through the token endpoint
+pub const ACCESS_TOKEN: &str = "fake-access-token";
+pub const REFRESH_TOKEN: &str = "fake-refresh-token";
+pub const CLIENT_ID: &str = "fake-client.apps.googleusercontent.com";
+pub const CLIENT_SECRET: &str = "fake-secret";
+/// The service account whose JWT assertions the token endpoint accepts
+pub const SERVICE_ACCOUNT_EMAIL: &str = "vgoog-test@fake-project.iam.gserviceaccount.com";
+/// Its private key (PKCS#8 PEM); the token endpoint checks signatures
+/// against the public half
+pub const SERVICE_ACCOUNT_KEY: &str = include_str!("service_account.pem");
+/// Address of the signed-in fake user
+pub const USER_EMAIL: &str = "me@example.com";
+
+/// A request as the fake received it
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub method: String,
+    /// Percent-decoded path without the query string
+    pub path: String,
+    pub query: Vec<(String, String)>,
+    /// Header names are lowercase
+    pub headers: Vec<(String, String)>,
+    /// JSON body, the raw text for non-JSON bodies, or `Null` when empty
+    pub body: Value,
+}
+
+impl RecordedRequest {
+    pub fn param(&self, key: &str) -> Option<&str> {
+        self.query.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
+    }
+
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(k, _)| k == name).map(|(_, v)| v.as_str())
+    }
+}
+
+/// A running fake server; stops when dropped
+pub struct FakeGoogle {
+    addr: SocketAddr,
+    state: Arc<Mutex<routes::State>>,
+    task: JoinHandle<()>,
+}
+
+impl FakeGoogle {
+    /// Start on an ephemeral port on 127.0.0.1
+    pub async fn start() -> std::io::Result<Self> {
+        Self::bind("127.0.0.1:0").await
+    }
+
+    pub async fn bind(addr: &str) -> std::io::Result<Self> {
+        let listener = TcpListener::bind(addr).await?;
+        let addr = listener.local_addr()?;
+        let state = Arc::new(Mutex::new(routes::State::new(format!("http://{addr}/token"))));
+        let task = tokio::spawn(serve(listener, state.clone()));
+        Ok(Self { addr, state, task })
+    }
+
+    pub fn url(&self) -> String {
+        format!("http://{}", self.addr)
+    }
+
+    /// A config with a single account, `fake`, pointed at this server
+    pub fn config(&self) -> Config {
+        let mut config = Config {
+            active_account: "fake".to_string(),
+            ..Default::default()
+        };
+        config.endpoints.insert("root".to_string(), self.url());
+        // Tests share the config dir, so only those that ask for it cache
+        config.cache.enabled = false;
+        config.add_account(
+            "fake".to_string(),
+            Account {
+                label: USER_EMAIL.to_string(),
+                auth: AuthConfig {
+                    client_id: CLIENT_ID.to_string(),
+                    client_secret: CLIENT_SECRET.to_string(),
+                    access_token: ACCESS_TOKEN.to_string(),
+                    refresh_token: REFRESH_TOKEN.to_string(),
+                    token_expiry: Utc::now() + Duration::hours(1),
+                },
+                ..Default::default()
+            },
+        );
+        config
+    }
+
+    /// Every request received so far, oldest first
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        self.lock().log.clone()
+    }
+
+    pub fn clear_requests(&self) {
+        self.lock().log.clear();
+    }
+
+    /// Store a resource at `path` (e.g. `/gmail/v1/users/me/messages/m1`),
+    /// adding it to its collection when the parent path is one
+    pub fn seed(&self, path: &str, value: Value) {
+        self.lock().seed(path, value);
+    }
+
+    /// Store file content served by `?alt=media` and export requests
+    pub fn seed_media(&self, file_id: &str, content: Vec<u8>) {
+        self.lock().seed_media(file_id, content);
+    }
+
+    /// Scopes granted to the fake user's tokens (all of vgoog's by default),
+    /// as reported by tokeninfo and the token endpoint
+    pub fn set_granted_scopes(&self, scopes: &[&str]) {
+        self.lock().set_granted_scope(scopes.join(" "));
+    }
+
+    /// Answer the next device-code polls with these OAuth errors
+    /// (`authorization_pending`, `slow_down`, `access_denied`,
+    /// `expired_token`) in order; the poll after them is approved
+    pub fn script_device_polls(&self, errors: &[&str]) {
+        self.lock().device_polls = errors.iter().map(|e| e.to_string()).collect();
+    }
+
+    /// A service-account JSON key for `SERVICE_ACCOUNT_EMAIL`, as Google
+    /// hands them out
+    pub fn service_account_key(&self) -> Value {
+        serde_json::json!({
+            "type": "service_account",
+            "project_id": "fake-project",
+            "private_key_id": "fake-key-1",
+            "private_key": SERVICE_ACCOUNT_KEY,
+            "client_email": SERVICE_ACCOUNT_EMAIL,
+            "token_uri": format!("{}/token", self.url()),
+        })
+    }
+
+    /// Claims of every JWT assertion the token endpoint accepted, oldest first
+    pub fn assertions(&self) -> Vec<Value> {
+        self.lock().assertions.clone()
+    }
+
+    /// `expires_in` of the access tokens handed out from now on
+    pub fn set_token_lifetime(&self, seconds: i64) {
+        self.lock().token_lifetime = seconds;
+    }
+
+    /// The resource currently stored at `path`
+    pub fn get(&self, path: &str) -> Option<Value> {
+        self.lock().get(path)
+    }
+
+    /// Answer the next API request (token requests excluded) with `status`
+    /// and `body` instead of handling it
+    pub fn fail_next(&self, status: u16, body: Value) {
+        self.lock().failures.push_back(Some((status, body)));
+    }
+
+    /// Like `fail_next`, but let `skip` API requests through first
+    pub fn fail_after(&self, skip: usize, status: u16, body: Value) {
+        let mut state = self.lock();
+        while state.failures.len() <= skip {
+            state.failures.push_back(None);
+        }
+        state.failures[skip] = Some((status, body));
+    }
+
+    /// Serve until the task is aborted (for `vgoog fake-server`)
+    pub async fn wait(&mut self) {
+        let _ = (&mut self.task).await;
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, routes::State> {
+        self.state.lock().expect("fake server state poisoned")
+    }
+}
+
+impl Drop for FakeGoogle {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+async fn serve(listener: TcpListener, state: Arc<Mutex<routes::State>>) {
+    loop {
+        let Ok((stream, _)) = listener.accept().await else {
+            continue;
+        };
+        let state = state.clone();
+        tokio::spawn(async move {
+            let _ = handle_connection(stream, state).await;
+        });
+    }
+}
+
+/// One request per connection; responses carry `Connection: close`
+async fn handle_connection(mut stream: TcpStream, state: Arc<Mutex<routes::State>>) -> std::io::Result<()> {
+    let Some(request) = read_request(&mut stream).await? else {
+        return Ok(());
+    };
+    let response = state
+        .lock()
+        .expect("fake server state poisoned")
+        .handle(&request);
+
+    let mut head = format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n",
+        response.status,
+        reason_phrase(response.status),
+        response.content_type,
+        response.body.len()
+    );
+    for (name, value) in &response.headers {
+        head.push_str(&format!("{name}: {value}\r\n"));
+    }
+    head.push_str("\r\n");
+    stream.write_all(head.as_bytes()).await?;
+    stream.write_all(&response.body).await?;
+    stream.shutdown().await
+}
+
+/// A parsed HTTP request
+pub(crate) struct Request {
+    pub method: String,
+    pub path: String,
+    pub query: Vec<(String, String)>,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    pub fn new(method: String, target: &str, headers: Vec<(String, String)>, body: Vec<u8>) -> Self {
+        let (path, query) = target.split_once('?').unwrap_or((target, ""));
+        let path = urlencoding::decode(path).map(|p| p.into_owned()).unwrap_or_else(|_| path.to_string());
+        Self {
+            method,
+            path,
+            query: parse_query(query),
+            headers,
+            body,
+        }
+    }
+
+    pub fn param(&self, key: &str) -> Option<&str> {
+        self.query.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
+    }
+
+    pub fn params(&self, key: &str) -> Vec<&str> {
+        self.query
+            .iter()
+            .filter(|(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+            .collect()
+    }
+
+    /// Header lookup by lowercase name
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.iter().find(|(k, _)| k == name).map(|(_, v)| v.as_str())
+    }
+
+    /// The body as JSON, `Null` if empty or not JSON
+    pub fn json(&self) -> Value {
+        serde_json::from_slice(&self.body).unwrap_or(Value::Null)
+    }
+}
+
+async fn read_request(stream: &mut TcpStream) -> std::io::Result<Option<Request>> {
+    let mut buf = Vec::new();
+    let head_end = loop {
+        if let Some(i) = find(&buf, b"\r\n\r\n") {
+            break i;
+        }
+        if !read_more(stream, &mut buf).await? {
+            return Ok(None);
+        }
+    };
+
+    let head = String::from_utf8_lossy(&buf[..head_end]).into_owned();
+    let (method, target, headers) = parse_head(&head);
+    let header = |name: &str| headers.iter().find(|(k, _)| k == name).map(|(_, v)| v.as_str());
+
+    let mut body = buf.split_off(head_end + 4);
+    if header("transfer-encoding").is_some_and(|v| v.eq_ignore_ascii_case("chunked")) {
+        body = read_chunked(stream, body).await?;
+    } else {
+        let len: usize = header("content-length").and_then(|v| v.parse().ok()).unwrap_or(0);
+        while body.len() < len {
+            if !read_more(stream, &mut body).await? {
+                return Err(std::io::ErrorKind::UnexpectedEof.into());
+            }
+        }
+        body.truncate(len);
+    }
+
+    Ok(Some(Request::new(method, &target, headers, body)))
+}
+
+/// Request line and headers (names lowercased) of an HTTP/1.1 message head
+pub(crate) fn parse_head(head: &str) -> (String, String, Vec<(String, String)>) {
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next().unwrap_or("").split_whitespace();
+    let method = request_line.next().unwrap_or("GET").to_string();
+    let mut target = request_line.next().unwrap_or("/").to_string();
+    // Absolute-form, as sent to a proxy: serve it like any other request
+    if let Some(rest) = target.strip_prefix("http://").or_else(|| target.strip_prefix("https://")) {
+        target = rest.find('/').map_or_else(|| "/".to_string(), |i| rest[i..].to_string());
+    }
+    let headers = lines
+        .filter_map(|line| line.split_once(':'))
+        .map(|(k, v)| (k.trim().to_ascii_lowercase(), v.trim().to_string()))
+        .collect();
+    (method, target, headers)
+}
+
+async fn read_chunked(stream: &mut TcpStream, mut raw: Vec<u8>) -> std::io::Result<Vec<u8>> {
+    let mut body = Vec::new();
+    let mut pos = 0;
+    loop {
+        let line_end = loop {
+            if let Some(i) = find(&raw[pos..], b"\r\n") {
+                break pos + i;
+            }
+            if !read_more(stream, &mut raw).await? {
+                return Err(std::io::ErrorKind::UnexpectedEof.into());
+            }
+        };
+        let size_line = String::from_utf8_lossy(&raw[pos..line_end]);
+        let size = usize::from_str_radix(size_line.split(';').next().unwrap_or("").trim(), 16)
+            .map_err(|_| std::io::Error::new(std::io::ErrorKind::InvalidData, "bad chunk size"))?;
+        let data_start = line_end + 2;
+        while raw.len() < data_start + size + 2 {
+            if !read_more(stream, &mut raw).await? {
+                return Err(std::io::ErrorKind::UnexpectedEof.into());
+            }
+        }
+        if size == 0 {
+            return Ok(body);
+        }
+        body.extend_from_slice(&raw[data_start..data_start + size]);
+        pos = data_start + size + 2;
+    }
+}
+
+/// Append whatever is available; `false` on EOF
+async fn read_more(stream: &mut TcpStream, buf: &mut Vec<u8>) -> std::io::Result<bool> {
+    let mut chunk = [0u8; 16 * 1024];
+    let n = stream.read(&mut chunk).await?;
+    buf.extend_from_slice(&chunk[..n]);
+    Ok(n > 0)
+}
+
+pub(crate) fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack.windows(needle.len()).position(|w| w == needle)
+}
+
+/// Decode `a=1&b=2`, keeping repeated keys
+pub(crate) fn parse_query(query: &str) -> Vec<(String, String)> {
+    query
+        .split('&')
+        .filter(|pair| !pair.is_empty())
+        .map(|pair| pair.split_once('=').unwrap_or((pair, "")))
+        .map(|(k, v)| {
+            let decode = |s: &str| {
+                let s = s.replace('+', " ");
+                urlencoding::decode(&s).map(|c| c.into_owned()).unwrap_or(s)
+            };
+            (decode(k), decode(v))
+        })
+        .collect()
+}
+
+fn reason_phrase(status: u16) -> &'static str {
+    match status {
+        200 => "OK",
+        204 => "No Content",
+        206 => "Partial Content",
+        302 => "Found",
+        304 => "Not Modified",
+        308 => "Resume Incomplete",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        409 => "Conflict",
+        412 => "Precondition Failed",
+        416 => "Range Not Satisfiable",
+        429 => "Too Many Requests",
+        500 => "Internal Server Error",
+        503 => "Service Unavailable",
+        _ => "Status",
+    }
+}
@@ -0,0 +1,1571 @@
+use super::{
+    find, parse_head, parse_query, reason_phrase, RecordedRequest, Request, ACCESS_TOKEN, CLIENT_ID,
+    SERVICE_ACCOUNT_EMAIL, SERVICE_ACCOUNT_KEY, USER_EMAIL,
+};
+use crate::auth::DEFAULT_SCOPES;
+use chrono::Utc;
+use serde_json::{json, Value};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+const GMAIL: &str = "/gmail/v1/users/me";
+const DRIVE: &str = "/drive/v3";
+const UPLOAD: &str = "/upload/drive/v3";
+const CALENDAR: &str = "/calendar/v3";
+const TASKS: &str = "/tasks/v1";
+const PEOPLE: &str = "/v1";
+const CONTACTS: &str = "/v1/people/me/connections";
+const BATCH: &str = "/batch/";
+const BATCH_RESPONSE_TYPE: &str = "multipart/mixed; boundary=batch_fake_response";
+
+/// Path prefixes with real state behind them; anything unknown under these
+/// is a 404, while the stubbed services answer `{}`
+const MODELED: &[&str] = &[
+    "/gmail/",
+    "/drive/",
+    "/upload/",
+    "/calendar/",
+    "/tasks/",
+    "/v1/people",
+    "/v1/contactGroups",
+    "/v1/otherContacts",
+];
+
+/// A listable collection and how its members are keyed
+struct Collection {
+    /// Collection path; `*` segments match any id
+    path: &'static str,
+    /// Parent path of the members when they don't live under `path`
+    members_under: Option<&'static str>,
+    list_key: &'static str,
+    id_field: &'static str,
+    id_prefix: &'static str,
+}
+
+const fn collection(
+    path: &'static str,
+    list_key: &'static str,
+    id_field: &'static str,
+    id_prefix: &'static str,
+) -> Collection {
+    Collection {
+        path,
+        members_under: None,
+        list_key,
+        id_field,
+        id_prefix,
+    }
+}
+
+const COLLECTIONS: &[Collection] = &[
+    collection("/gmail/v1/users/me/messages", "messages", "id", "msg"),
+    collection("/gmail/v1/users/me/threads", "threads", "id", "thread"),
+    collection("/gmail/v1/users/me/labels", "labels", "id", "Label_"),
+    collection("/gmail/v1/users/me/drafts", "drafts", "id", "r"),
+    collection("/gmail/v1/users/me/settings/filters", "filter", "id", "filter"),
+    collection("/gmail/v1/users/me/settings/forwardingAddresses", "forwardingAddresses", "forwardingEmail", "fwd"),
+    collection("/gmail/v1/users/me/settings/sendAs", "sendAs", "sendAsEmail", "sendas"),
+    collection("/gmail/v1/users/me/settings/delegates", "delegates", "delegateEmail", "delegate"),
+    collection("/drive/v3/files", "files", "id", "file"),
+    collection("/drive/v3/files/*/permissions", "permissions", "id", "perm"),
+    collection("/drive/v3/files/*/comments", "comments", "id", "comment"),
+    collection("/drive/v3/files/*/comments/*/replies", "replies", "id", "reply"),
+    collection("/drive/v3/files/*/revisions", "revisions", "id", "rev"),
+    collection("/drive/v3/drives", "drives", "id", "drive"),
+    collection("/drive/v3/changes", "changes", "fileId", "change"),
+    collection("/calendar/v3/users/me/calendarList", "items", "id", "cal"),
+    collection("/calendar/v3/users/me/settings", "items", "id", "setting"),
+    collection("/calendar/v3/calendars/*/events", "items", "id", "event"),
+    collection("/calendar/v3/calendars/*/acl", "items", "id", "rule"),
+    collection("/tasks/v1/users/@me/lists", "items", "id", "list"),
+    collection("/tasks/v1/lists/*/tasks", "items", "id", "task"),
+    Collection {
+        path: CONTACTS,
+        members_under: Some("/v1/people"),
+        list_key: "connections",
+        id_field: "resourceName",
+        id_prefix: "c",
+    },
+    collection("/v1/contactGroups", "contactGroups", "resourceName", "g"),
+    collection("/v1/otherContacts", "otherContacts", "resourceName", "o"),
+    // Stubbed services: documents can be created and fetched back
+    collection("/v4/spreadsheets", "spreadsheets", "spreadsheetId", "sheet"),
+    collection("/v1/documents", "documents", "documentId", "doc"),
+    collection("/v1/presentations", "presentations", "presentationId", "pres"),
+    collection("/v1/forms", "forms", "formId", "form"),
+    collection("/v1/projects", "projects", "scriptId", "script"),
+];
+
+fn collection_at(path: &str) -> Option<&'static Collection> {
+    COLLECTIONS.iter().find(|c| capture(c.path, path).is_some())
+}
+
+/// Match `path` against a pattern whose `*` segments (optionally followed by
+/// a literal suffix, as in `*:modify`) match one non-empty segment
+fn capture<'p>(pattern: &str, path: &'p str) -> Option<Vec<&'p str>> {
+    let pattern: Vec<&str> = pattern.split('/').collect();
+    let segments: Vec<&str> = path.split('/').collect();
+    if pattern.len() != segments.len() {
+        return None;
+    }
+    let mut captures = Vec::new();
+    for (p, s) in pattern.iter().zip(segments) {
+        if let Some(suffix) = p.strip_prefix('*') {
+            let cap = s.strip_suffix(suffix).filter(|c| !c.is_empty())?;
+            captures.push(cap);
+        } else if *p != s {
+            return None;
+        }
+    }
+    Some(captures)
+}
+
+pub(super) struct Response {
+    pub status: u16,
+    pub content_type: &'static str,
+    pub headers: Vec<(String, String)>,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    fn json(status: u16, value: Value) -> Self {
+        Self {
+            status,
+            content_type: "application/json; charset=UTF-8",
+            headers: Vec::new(),
+            body: serde_json::to_vec(&value).unwrap_or_default(),
+        }
+    }
+
+    fn ok(value: Value) -> Self {
+        Self::json(200, value)
+    }
+
+    fn empty() -> Self {
+        Self {
+            status: 204,
+            content_type: "text/plain",
+            headers: Vec::new(),
+            body: Vec::new(),
+        }
+    }
+
+    fn bytes(body: Vec<u8>) -> Self {
+        Self {
+            status: 200,
+            content_type: "application/octet-stream",
+            headers: Vec::new(),
+            body,
+        }
+    }
+
+    /// `Range: bytes=<start>-`: the tail of `body` as a 206, or a 416 past the end
+    fn byte_range(body: Vec<u8>, start: usize) -> Self {
+        let len = body.len();
+        if start >= len {
+            let mut response = Self::error(416, "OUT_OF_RANGE", "requestedRangeNotSatisfiable", "Range not satisfiable");
+            response.headers.push(("Content-Range".into(), format!("bytes */{len}")));
+            return response;
+        }
+        let mut response = Self::bytes(body[start..].to_vec());
+        response.status = 206;
+        response.headers.push(("Content-Range".into(), format!("bytes {start}-{}/{len}", len - 1)));
+        response
+    }
+
+    /// Google's error envelope
+    fn error(code: u16, status: &str, reason: &str, message: &str) -> Self {
+        Self::json(
+            code,
+            json!({
+                "error": {
+                    "code": code,
+                    "message": message,
+                    "status": status,
+                    "errors": [{ "message": message, "domain": "global", "reason": reason }],
+                }
+            }),
+        )
+    }
+
+    fn with_header(mut self, name: &str, value: String) -> Self {
+        self.headers.push((name.to_string(), value));
+        self
+    }
+
+    fn not_found() -> Self {
+        Self::error(404, "NOT_FOUND", "notFound", "Requested entity was not found.")
+    }
+}
+
+/// Give a JSON response an `ETag` derived from its body, or answer 304
+/// when the request's `If-None-Match` already names it
+fn with_etag(req: &Request, response: Response) -> Response {
+    if response.status != 200 || !response.content_type.starts_with("application/json") {
+        return response;
+    }
+    let etag = format!("\"{}\"", crate::transfer::md5_hex(&response.body));
+    if req.header("if-none-match") == Some(etag.as_str()) {
+        let mut not_modified = Response::empty();
+        not_modified.status = 304;
+        return not_modified.with_header("ETag", etag);
+    }
+    response.with_header("ETag", etag)
+}
+
+/// A Drive resumable upload in progress
+struct UploadSession {
+    /// File being replaced; `None` creates a new one
+    file_id: Option<String>,
+    metadata: Value,
+    total: usize,
+    received: Vec<u8>,
+    /// The final response, once every byte has arrived
+    done: Option<Value>,
+}
+
+/// What an authorization code was issued for, checked when it is exchanged
+struct Consent {
+    scope: String,
+    redirect_uri: String,
+    /// PKCE `code_challenge`, if the consent request carried one
+    challenge: Option<String>,
+}
+
+pub(super) struct State {
+    items: HashMap<String, Value>,
+    /// Collection path → member paths in insertion order
+    collections: HashMap<String, Vec<String>>,
+    /// Drive file id → content
+    media: HashMap<String, Vec<u8>>,
+    /// Resumable upload id → session
+    uploads: HashMap<String, UploadSession>,
+    /// Access token → the scopes it was granted
+    tokens: HashMap<String, String>,
+    /// Scopes granted to tokens issued from now on
+    pub(super) granted_scope: String,
+    /// Authorization code → the consent it was issued for
+    codes: HashMap<String, Consent>,
+    /// Refresh tokens revoked through the revoke endpoint
+    revoked: HashSet<String>,
+    /// Device code → the scopes it was requested for, until it is used up
+    device_codes: HashMap<String, String>,
+    /// OAuth errors the next device-code polls get before the user approves
+    pub(super) device_polls: VecDeque<String>,
+    /// The token endpoint's own URL, the `aud` assertions must carry
+    token_uri: String,
+    /// Claims of the JWT assertions accepted so far
+    pub(super) assertions: Vec<Value>,
+    /// `expires_in` of issued access tokens
+    pub(super) token_lifetime: i64,
+    next_id: u64,
+    /// Canned answers for the next API requests; `None` lets one through
+    pub(super) failures: VecDeque<Option<(u16, Value)>>,
+    pub(super) log: Vec<RecordedRequest>,
+}
+
+impl State {
+    pub(super) fn new(token_uri: String) -> Self {
+        let mut state = Self {
+            items: HashMap::new(),
+            collections: HashMap::new(),
+            media: HashMap::new(),
+            uploads: HashMap::new(),
+            tokens: HashMap::from([(ACCESS_TOKEN.to_string(), DEFAULT_SCOPES.join(" "))]),
+            granted_scope: DEFAULT_SCOPES.join(" "),
+            codes: HashMap::new(),
+            revoked: HashSet::new(),
+            device_codes: HashMap::new(),
+            device_polls: VecDeque::new(),
+            token_uri,
+            assertions: Vec::new(),
+            token_lifetime: 3599,
+            next_id: 0,
+            failures: VecDeque::new(),
+            log: Vec::new(),
+        };
+        for label in ["INBOX", "SENT", "DRAFT", "TRASH", "UNREAD", "STARRED"] {
+            state.seed(
+                &format!("{GMAIL}/labels/{label}"),
+                json!({ "id": label, "name": label, "type": "system" }),
+            );
+        }
+        state.seed(
+            &format!("{CALENDAR}/users/me/calendarList/primary"),
+            json!({ "id": "primary", "summary": USER_EMAIL, "primary": true, "accessRole": "owner" }),
+        );
+        state.seed(
+            &format!("{CALENDAR}/calendars/primary"),
+            json!({ "id": "primary", "summary": USER_EMAIL, "timeZone": "UTC" }),
+        );
+        for (id, value) in [("timezone", "UTC"), ("locale", "en")] {
+            state.seed(
+                &format!("{CALENDAR}/users/me/settings/{id}"),
+                json!({ "kind": "calendar#setting", "id": id, "value": value }),
+            );
+        }
+        state.seed(
+            &format!("{TASKS}/users/@me/lists/default"),
+            json!({ "kind": "tasks#taskList", "id": "default", "title": "My Tasks" }),
+        );
+        state
+    }
+
+    pub(super) fn seed(&mut self, path: &str, value: Value) {
+        let parent = path.rsplit_once('/').map_or("", |(p, _)| p);
+        let owner = COLLECTIONS.iter().find_map(|c| match c.members_under {
+            Some(under) => (under == parent).then(|| c.path.to_string()),
+            None => capture(c.path, parent).map(|_| parent.to_string()),
+        });
+        match owner {
+            Some(collection) => self.insert(&collection, path.to_string(), value),
+            None => {
+                self.items.insert(path.to_string(), value);
+            }
+        }
+    }
+
+    pub(super) fn seed_media(&mut self, file_id: &str, content: Vec<u8>) {
+        if let Some(file) = self.items.get_mut(&format!("{DRIVE}/files/{file_id}")) {
+            file["size"] = json!(content.len().to_string());
+            file["md5Checksum"] = json!(crate::transfer::md5_hex(&content));
+        }
+        self.media.insert(file_id.to_string(), content);
+    }
+
+    pub(super) fn get(&self, path: &str) -> Option<Value> {
+        self.items.get(path).cloned()
+    }
+
+    pub(super) fn handle(&mut self, req: &Request) -> Response {
+        self.log.push(RecordedRequest {
+            method: req.method.clone(),
+            path: req.path.clone(),
+            query: req.query.clone(),
+            headers: req.headers.clone(),
+            body: if req.body.is_empty() {
+                Value::Null
+            } else {
+                serde_json::from_slice(&req.body)
+                    .unwrap_or_else(|_| Value::String(String::from_utf8_lossy(&req.body).into_owned()))
+            },
+        });
+
+        match (req.method.as_str(), req.path.as_str()) {
+            ("GET", "/o/oauth2/v2/auth") => return self.authorize(req),
+            ("POST", "/token") => return self.token(req),
+            ("GET", "/tokeninfo") => return self.tokeninfo(req),
+            ("POST", "/revoke") => return self.revoke(req),
+            ("POST", "/device/code") => return self.device_code(req),
+            _ => {}
+        }
+
+        let token = req.header("authorization").and_then(|h| h.strip_prefix("Bearer "));
+        if !token.is_some_and(|t| self.tokens.contains_key(t)) {
+            return Response::error(
+                401,
+                "UNAUTHENTICATED",
+                "authError",
+                "Request had invalid authentication credentials.",
+            );
+        }
+        if let Some(Some((status, body))) = self.failures.pop_front() {
+            return Response::json(status, body);
+        }
+
+        let path = req.path.as_str();
+        if req.method == "POST" && path.starts_with(BATCH) {
+            return self.batch(req);
+        }
+        let handled = if path.starts_with(GMAIL) {
+            self.gmail(req)
+        } else if path.starts_with(UPLOAD) {
+            self.upload(req)
+        } else if path.starts_with(DRIVE) {
+            self.drive(req)
+        } else if path.starts_with(CALENDAR) {
+            self.calendar(req)
+        } else if path.starts_with(TASKS) {
+            self.tasks(req)
+        } else if MODELED.iter().any(|p| p.starts_with(PEOPLE) && path.starts_with(p)) {
+            self.people(req)
+        } else {
+            None
+        };
+        let response = handled.unwrap_or_else(|| self.generic(req));
+        if req.method == "GET" {
+            return with_etag(req, response);
+        }
+        response
+    }
+
+    // ── Batch ──
+
+    /// Run each `application/http` part as its own request, with the outer
+    /// request's credentials, and answer with one part per request
+    fn batch(&mut self, req: &Request) -> Response {
+        let Some(parts) = multipart_parts(req) else {
+            return Response::error(400, "INVALID_ARGUMENT", "badRequest", "Malformed batch request.");
+        };
+        let authorization = req.header("authorization").unwrap_or_default().to_string();
+
+        let mut body = Vec::new();
+        for (i, part) in parts.iter().enumerate() {
+            let Some(head_end) = find(part, b"\r\n\r\n") else { continue };
+            let part_headers = String::from_utf8_lossy(&part[..head_end]).into_owned();
+            let content_id = part_headers
+                .split("\r\n")
+                .filter_map(|line| line.split_once(':'))
+                .find(|(k, _)| k.trim().eq_ignore_ascii_case("content-id"))
+                .map(|(_, v)| v.trim().trim_matches(['<', '>']).to_string())
+                .unwrap_or_else(|| format!("item-{}", i + 1));
+
+            let message = &part[head_end + 4..];
+            let inner_end = find(message, b"\r\n\r\n").unwrap_or(message.len());
+            let (method, target, mut headers) =
+                parse_head(&String::from_utf8_lossy(&message[..inner_end]));
+            headers.push(("authorization".into(), authorization.clone()));
+            let inner_body = message.get(inner_end + 4..).unwrap_or_default().to_vec();
+            let response = self.handle(&Request::new(method, &target, headers, inner_body));
+
+            body.extend_from_slice(
+                format!(
+                    "--batch_fake_response\r\nContent-Type: application/http\r\nContent-ID: <response-{content_id}>\r\n\r\n\
+                     HTTP/1.1 {} {}\r\nContent-Type: {}\r\n\r\n",
+                    response.status,
+                    reason_phrase(response.status),
+                    response.content_type,
+                )
+                .as_bytes(),
+            );
+            body.extend_from_slice(&response.body);
+            body.extend_from_slice(b"\r\n");
+        }
+        body.extend_from_slice(b"--batch_fake_response--\r\n");
+
+        Response {
+            status: 200,
+            content_type: BATCH_RESPONSE_TYPE,
+            headers: Vec::new(),
+            body,
+        }
+    }
+
+    // ── OAuth ──
+
+    /// The consent screen, approving at once: redirects back with a code
+    /// for the requested scopes, plus those granted before when
+    /// `include_granted_scopes` is set
+    fn authorize(&mut self, req: &Request) -> Response {
+        let Some(redirect_uri) = req.param("redirect_uri") else {
+            return Response::json(400, json!({ "error": "invalid_request", "error_description": "Missing redirect_uri" }));
+        };
+        let mut scopes: Vec<&str> = req.param("scope").unwrap_or_default().split_whitespace().collect();
+        if req.param("include_granted_scopes") == Some("true") {
+            for granted in self.granted_scope.split_whitespace() {
+                if !scopes.contains(&granted) {
+                    scopes.push(granted);
+                }
+            }
+        }
+        let scope = scopes.join(" ");
+        let code = self.new_id("fake-code-");
+        let location = format!(
+            "{redirect_uri}?code={code}&state={}",
+            urlencoding::encode(req.param("state").unwrap_or_default())
+        );
+        self.codes.insert(
+            code,
+            Consent {
+                scope,
+                redirect_uri: redirect_uri.to_string(),
+                challenge: req.param("code_challenge").map(str::to_string),
+            },
+        );
+
+        let mut response = Response::empty();
+        response.status = 302;
+        response.headers.push(("Location".into(), location));
+        response
+    }
+
+    fn token(&mut self, req: &Request) -> Response {
+        let form = parse_query(&String::from_utf8_lossy(&req.body));
+        let field = |key: &str| {
+            form.iter()
+                .find(|(k, _)| k == key)
+                .map_or("", |(_, v)| v.as_str())
+        };
+        let oauth_error = |error: &str, description: &str| {
+            Response::json(400, json!({ "error": error, "error_description": description }))
+        };
+
+        // Whether a refresh token comes along, and the scopes granted
+        let (issues_refresh_token, scope) = match field("grant_type") {
+            "refresh_token" if field("refresh_token").is_empty() => {
+                return oauth_error("invalid_request", "Missing required parameter: refresh_token")
+            }
+            "refresh_token" if self.revoked.contains(field("refresh_token")) => {
+                return oauth_error("invalid_grant", "Token has been expired or revoked.")
+            }
+            "refresh_token" => (false, self.granted_scope.clone()),
+            "urn:ietf:params:oauth:grant-type:jwt-bearer" => {
+                let claims = match self.check_assertion(field("assertion")) {
+                    Ok(claims) => claims,
+                    Err(description) => return oauth_error("invalid_grant", description),
+                };
+                let scope = claims["scope"].as_str().unwrap_or_default().to_string();
+                if scope.is_empty() {
+                    return oauth_error("invalid_scope", "Empty or missing scope not allowed.");
+                }
+                self.assertions.push(claims);
+                (false, scope)
+            }
+            "authorization_code" => {
+                let Some(consent) = self.codes.remove(field("code")) else {
+                    return oauth_error("invalid_grant", "Malformed auth code.");
+                };
+                if consent.redirect_uri != field("redirect_uri") {
+                    return oauth_error("redirect_uri_mismatch", "Bad Request");
+                }
+                if consent.challenge.is_some_and(|challenge| challenge != s256(field("code_verifier"))) {
+                    return oauth_error("invalid_grant", "Invalid code verifier.");
+                }
+                self.granted_scope = consent.scope;
+                (true, self.granted_scope.clone())
+            }
+            "urn:ietf:params:oauth:grant-type:device_code" => {
+                if !self.device_codes.contains_key(field("device_code")) {
+                    return oauth_error("invalid_grant", "Malformed device code.");
+                }
+                if let Some(error) = self.device_polls.pop_front() {
+                    let (status, description) = match error.as_str() {
+                        "authorization_pending" => (428, "Precondition Required"),
+                        "slow_down" | "access_denied" => (403, "Forbidden"),
+                        _ => (400, "Bad Request"),
+                    };
+                    // A denied or expired code can't be polled again
+                    if error == "access_denied" || error == "expired_token" {
+                        self.device_codes.remove(field("device_code"));
+                    }
+                    return Response::json(status, json!({ "error": error, "error_description": description }));
+                }
+                self.granted_scope = self.device_codes.remove(field("device_code")).unwrap_or_default();
+                (true, self.granted_scope.clone())
+            }
+            other => return oauth_error("unsupported_grant_type", &format!("Invalid grant_type: {other}")),
+        };
+
+        let access_token = self.new_id("fake-access-");
+        self.tokens.insert(access_token.clone(), scope.clone());
+        let mut body = json!({
+            "access_token": access_token,
+            "expires_in": self.token_lifetime,
+            "token_type": "Bearer",
+            "scope": scope,
+        });
+        if issues_refresh_token {
+            body["refresh_token"] = json!(self.new_id("fake-refresh-"));
+        }
+        Response::ok(body)
+    }
+
+    fn device_code(&mut self, req: &Request) -> Response {
+        let form = parse_query(&String::from_utf8_lossy(&req.body));
+        let scope = form.iter().find(|(k, _)| k == "scope").map_or("", |(_, v)| v.as_str()).to_string();
+        let device_code = self.new_id("fake-device-");
+        self.device_codes.insert(device_code.clone(), scope);
+        Response::ok(json!({
+            "device_code": device_code,
+            "user_code": "FAKE-CODE",
+            "verification_url": "https://www.google.com/device",
+            "expires_in": 1800,
+            "interval": 1,
+        }))
+    }
+
+    /// The claims of a service-account assertion, once its RS256 signature
+    /// checks out against `SERVICE_ACCOUNT_KEY` and it is addressed to this
+    /// endpoint for at most an hour; Google's error description otherwise
+    fn check_assertion(&self, assertion: &str) -> Result<Value, &'static str> {
+        use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+        use base64::Engine;
+
+        let parts: Vec<&str> = assertion.split('.').collect();
+        let [header, claims, signature] = parts.as_slice() else {
+            return Err("Invalid JWT.");
+        };
+        let decode = |part: &str| -> Option<Value> { serde_json::from_slice(&URL_SAFE_NO_PAD.decode(part).ok()?).ok() };
+        let (Some(header), Some(claims)) = (decode(header), decode(claims)) else {
+            return Err("Invalid JWT.");
+        };
+        if header["alg"] != "RS256" {
+            return Err("Invalid JWT: unsupported algorithm.");
+        }
+
+        let der = crate::auth::service_account::pem_to_der(SERVICE_ACCOUNT_KEY).expect("fake service account key");
+        let key = ring::signature::RsaKeyPair::from_pkcs8(&der).expect("fake service account key");
+        let public = ring::signature::UnparsedPublicKey::new(&ring::signature::RSA_PKCS1_2048_8192_SHA256, key.public().as_ref());
+        let signed = &assertion[..assertion.rfind('.').unwrap_or_default()];
+        let signature = URL_SAFE_NO_PAD.decode(signature).unwrap_or_default();
+        if public.verify(signed.as_bytes(), &signature).is_err() {
+            return Err("Invalid JWT Signature.");
+        }
+
+        if claims["iss"] != SERVICE_ACCOUNT_EMAIL {
+            return Err("Invalid issuer: the service account is unknown.");
+        }
+        if claims["aud"] != self.token_uri.as_str() {
+            return Err("Invalid JWT: audience does not match the token endpoint.");
+        }
+        let now = Utc::now().timestamp();
+        let (Some(iat), Some(exp)) = (claims["iat"].as_i64(), claims["exp"].as_i64()) else {
+            return Err("Invalid JWT: missing iat or exp.");
+        };
+        if iat > now + 60 || exp <= now || exp - iat > 3600 {
+            return Err("Invalid JWT: Token must be a short-lived token (60 minutes) and in a reasonable timeframe.");
+        }
+        Ok(claims)
+    }
+
+    fn tokeninfo(&self, req: &Request) -> Response {
+        let Some(scope) = req.param("access_token").and_then(|t| self.tokens.get(t)) else {
+            return Response::json(400, json!({ "error": "invalid_token", "error_description": "Invalid Value" }));
+        };
+        Response::ok(json!({
+            "azp": CLIENT_ID,
+            "aud": CLIENT_ID,
+            "sub": "100000000000000000000",
+            "scope": scope,
+            "exp": (Utc::now().timestamp() + 3599).to_string(),
+            "expires_in": "3599",
+            "email": USER_EMAIL,
+            "email_verified": "true",
+            "access_type": "offline",
+        }))
+    }
+
+    /// Access tokens stop working at once; revoked refresh tokens can no
+    /// longer be exchanged
+    fn revoke(&mut self, req: &Request) -> Response {
+        let form = parse_query(&String::from_utf8_lossy(&req.body));
+        let token = form.iter().find(|(k, _)| k == "token").map_or("", |(_, v)| v.as_str());
+        if token.is_empty() || self.revoked.contains(token) {
+            return Response::json(400, json!({ "error": "invalid_token", "error_description": "Token expired or revoked" }));
+        }
+        if self.tokens.remove(token).is_none() {
+            self.revoked.insert(token.to_string());
+        }
+        Response::ok(json!({}))
+    }
+
+    /// Grant `scope` to every token, issued or yet to be
+    pub(super) fn set_granted_scope(&mut self, scope: String) {
+        for granted in self.tokens.values_mut() {
+            granted.clone_from(&scope);
+        }
+        self.granted_scope = scope;
+    }
+
+    // ── Storage ──
+
+    fn new_id(&mut self, prefix: &str) -> String {
+        self.next_id += 1;
+        format!("{prefix}{}", self.next_id)
+    }
+
+    fn insert(&mut self, collection: &str, path: String, value: Value) {
+        let members = self.collections.entry(collection.to_string()).or_default();
+        if !members.contains(&path) {
+            members.push(path.clone());
+        }
+        self.items.insert(path, value);
+    }
+
+    fn remove(&mut self, path: &str) -> Option<Value> {
+        let removed = self.items.remove(path)?;
+        for members in self.collections.values_mut() {
+            members.retain(|p| p != path);
+        }
+        Some(removed)
+    }
+
+    fn list(&self, collection: &str) -> Vec<Value> {
+        self.collections
+            .get(collection)
+            .into_iter()
+            .flatten()
+            .filter_map(|p| self.items.get(p).cloned())
+            .collect()
+    }
+
+    fn member_paths(&self, collection: &str) -> Vec<String> {
+        self.collections.get(collection).cloned().unwrap_or_default()
+    }
+
+    /// Create a member of the collection at `path`, keeping a caller-chosen id
+    fn create(&mut self, path: &str, mut body: Value) -> Value {
+        let Some(spec) = collection_at(path) else {
+            return body;
+        };
+        if !body.is_object() {
+            body = json!({});
+        }
+        let id = match body.get(spec.id_field).and_then(Value::as_str) {
+            Some(id) => id.to_string(),
+            None => self.new_id(spec.id_prefix),
+        };
+        body[spec.id_field] = json!(id);
+        let parent = spec.members_under.unwrap_or(path);
+        self.insert(path, format!("{parent}/{id}"), body.clone());
+        body
+    }
+
+    // ── Generic REST semantics ──
+
+    fn generic(&mut self, req: &Request) -> Response {
+        let path = req.path.as_str();
+        match req.method.as_str() {
+            "GET" => {
+                if let Some(spec) = collection_at(path) {
+                    return page(req, spec.list_key, self.list(path));
+                }
+                if let Some(item) = self.items.get(path) {
+                    return Response::ok(item.clone());
+                }
+            }
+            "POST" => {
+                if collection_at(path).is_some() {
+                    return Response::ok(self.create(path, req.json()));
+                }
+                if path.ends_with(":batchUpdate") {
+                    let count = req.json()["requests"].as_array().map_or(0, Vec::len);
+                    return Response::ok(json!({ "replies": vec![json!({}); count] }));
+                }
+            }
+            "PATCH" => {
+                if let Some(item) = self.items.get_mut(path) {
+                    merge(item, &req.json());
+                    return Response::ok(item.clone());
+                }
+            }
+            "PUT" => {
+                let id_field = path
+                    .rsplit_once('/')
+                    .and_then(|(parent, _)| collection_at(parent))
+                    .map_or("id", |spec| spec.id_field);
+                if let Some(item) = self.items.get_mut(path) {
+                    let mut replacement = req.json();
+                    if let (Some(new), Some(old)) = (replacement.as_object_mut(), item.as_object()) {
+                        for key in ["id", "resourceName", "etag", id_field] {
+                            if let (false, Some(v)) = (new.contains_key(key), old.get(key)) {
+                                new.insert(key.to_string(), v.clone());
+                            }
+                        }
+                    }
+                    *item = replacement;
+                    return Response::ok(item.clone());
+                }
+            }
+            "DELETE" if self.remove(path).is_some() => return Response::empty(),
+            _ => {}
+        }
+        if MODELED.iter().any(|p| path.starts_with(p)) {
+            Response::not_found()
+        } else {
+            Response::ok(json!({}))
+        }
+    }
+
+    // ── Gmail ──
+
+    fn gmail(&mut self, req: &Request) -> Option<Response> {
+        let rest = req.path.strip_prefix(GMAIL)?;
+        let segments: Vec<&str> = rest.trim_start_matches('/').split('/').collect();
+        let body = req.json();
+        let messages = format!("{GMAIL}/messages");
+
+        Some(match (req.method.as_str(), segments.as_slice()) {
+            ("GET", ["messages"]) => {
+                let wanted = req.params("labelIds");
+                let found: Vec<Value> = self
+                    .list(&messages)
+                    .into_iter()
+                    .filter(|m| wanted.iter().all(|l| has_label(m, l)))
+                    .collect();
+                let estimate = found.len();
+                let mut resp = page(req, "messages", found);
+                if let Ok(mut v) = serde_json::from_slice::<Value>(&resp.body) {
+                    v["resultSizeEstimate"] = json!(estimate);
+                    resp = Response::ok(v);
+                }
+                resp
+            }
+            ("POST", ["messages", "send"]) => {
+                Response::ok(self.store_message(body["raw"].as_str().unwrap_or(""), "SENT"))
+            }
+            ("POST", ["messages", "batchModify"]) => {
+                for id in str_values(&body["ids"]) {
+                    if let Some(m) = self.items.get_mut(&format!("{messages}/{id}")) {
+                        relabel(m, &body["addLabelIds"], &body["removeLabelIds"]);
+                    }
+                }
+                Response::empty()
+            }
+            ("POST", ["messages", "batchDelete"]) => {
+                for id in str_values(&body["ids"]) {
+                    self.remove(&format!("{messages}/{id}"));
+                }
+                Response::empty()
+            }
+            ("POST", [kind @ ("messages" | "threads"), id, verb @ ("trash" | "untrash" | "modify")]) => {
+                let (add, remove) = match *verb {
+                    "trash" => (json!(["TRASH"]), json!([])),
+                    "untrash" => (json!([]), json!(["TRASH"])),
+                    _ => (body["addLabelIds"].clone(), body["removeLabelIds"].clone()),
+                };
+                let path = format!("{GMAIL}/{kind}/{id}");
+                let Some(item) = self.items.get_mut(&path) else {
+                    return Some(Response::not_found());
+                };
+                relabel(item, &add, &remove);
+                if *kind == "threads" {
+                    for p in self.member_paths(&messages) {
+                        if let Some(m) = self.items.get_mut(&p).filter(|m| m["threadId"] == json!(id)) {
+                            relabel(m, &add, &remove);
+                        }
+                    }
+                    return Some(Response::ok(self.thread(id)?));
+                }
+                Response::ok(self.items[&path].clone())
+            }
+            ("GET", ["threads", id]) => self.thread(id).map_or_else(Response::not_found, Response::ok),
+            ("DELETE", ["threads", id]) => {
+                if self.remove(&format!("{GMAIL}/threads/{id}")).is_none() {
+                    return Some(Response::not_found());
+                }
+                for p in self.member_paths(&messages) {
+                    if self.items.get(&p).is_some_and(|m| m["threadId"] == json!(id)) {
+                        self.remove(&p);
+                    }
+                }
+                Response::empty()
+            }
+            ("POST", ["drafts", "send"]) => {
+                let id = body["id"].as_str().unwrap_or("");
+                match self.remove(&format!("{GMAIL}/drafts/{id}")) {
+                    Some(draft) => {
+                        Response::ok(self.store_message(draft["message"]["raw"].as_str().unwrap_or(""), "SENT"))
+                    }
+                    None => Response::not_found(),
+                }
+            }
+            ("POST", ["drafts"]) => {
+                let mut draft = body;
+                draft["message"]["id"] = json!(self.new_id("msg"));
+                draft["message"]["labelIds"] = json!(["DRAFT"]);
+                Response::ok(self.create(&format!("{GMAIL}/drafts"), draft))
+            }
+            ("GET", ["settings", name @ ("vacation" | "autoForwarding" | "imap" | "pop" | "language")]) => {
+                let path = format!("{GMAIL}/settings/{name}");
+                Response::ok(self.items.get(&path).cloned().unwrap_or_else(|| default_setting(name)))
+            }
+            ("PUT" | "PATCH", ["settings", name @ ("vacation" | "autoForwarding" | "imap" | "pop" | "language")]) => {
+                let path = format!("{GMAIL}/settings/{name}");
+                let mut setting = self.items.get(&path).cloned().unwrap_or_else(|| default_setting(name));
+                merge(&mut setting, &body);
+                self.items.insert(path, setting.clone());
+                Response::ok(setting)
+            }
+            ("POST", ["settings", "forwardingAddresses"]) => {
+                let mut address = body;
+                address["verificationStatus"] = json!("accepted");
+                Response::ok(self.create(&format!("{GMAIL}/settings/forwardingAddresses"), address))
+            }
+            ("POST", ["settings", "delegates"]) => {
+                let mut delegate = body;
+                delegate["verificationStatus"] = json!("accepted");
+                Response::ok(self.create(&format!("{GMAIL}/settings/delegates"), delegate))
+            }
+            ("POST", ["settings", "sendAs", email, "verify"]) => {
+                if self.items.contains_key(&format!("{GMAIL}/settings/sendAs/{email}")) {
+                    Response::empty()
+                } else {
+                    Response::not_found()
+                }
+            }
+            ("GET", ["profile"]) => Response::ok(json!({
+                "emailAddress": USER_EMAIL,
+                "messagesTotal": self.list(&messages).len(),
+                "threadsTotal": self.list(&format!("{GMAIL}/threads")).len(),
+                "historyId": self.next_id.to_string(),
+            })),
+            ("GET", ["history"]) => Response::ok(json!({ "history": [], "historyId": self.next_id.to_string() })),
+            _ => return None,
+        })
+    }
+
+    /// Store a message in its own new thread
+    fn store_message(&mut self, raw: &str, label: &str) -> Value {
+        let id = self.new_id("msg");
+        let message = json!({
+            "id": id,
+            "threadId": id,
+            "labelIds": [label],
+            "snippet": "",
+            "raw": raw,
+            "historyId": self.next_id.to_string(),
+            "internalDate": Utc::now().timestamp_millis().to_string(),
+        });
+        self.insert(&format!("{GMAIL}/messages"), format!("{GMAIL}/messages/{id}"), message.clone());
+        self.insert(
+            &format!("{GMAIL}/threads"),
+            format!("{GMAIL}/threads/{id}"),
+            json!({ "id": id, "snippet": "", "historyId": self.next_id.to_string() }),
+        );
+        message
+    }
+
+    /// A thread with its current messages
+    fn thread(&self, id: &str) -> Option<Value> {
+        let mut thread = self.items.get(&format!("{GMAIL}/threads/{id}"))?.clone();
+        let messages: Vec<Value> = self
+            .list(&format!("{GMAIL}/messages"))
+            .into_iter()
+            .filter(|m| m["threadId"] == json!(id))
+            .collect();
+        thread["messages"] = json!(messages);
+        Some(thread)
+    }
+
+    // ── Drive ──
+
+    fn drive(&mut self, req: &Request) -> Option<Response> {
+        let rest = req.path.strip_prefix(DRIVE)?;
+        let segments: Vec<&str> = rest.trim_start_matches('/').split('/').collect();
+        let body = req.json();
+        let files = format!("{DRIVE}/files");
+
+        Some(match (req.method.as_str(), segments.as_slice()) {
+            ("POST", ["files"]) => Response::ok(self.create_file(body, None)),
+            ("DELETE", ["files", "trash"]) => {
+                for p in self.member_paths(&files) {
+                    if self.items.get(&p).is_some_and(|f| f["trashed"] == json!(true)) {
+                        self.remove(&p);
+                    }
+                }
+                Response::empty()
+            }
+            ("GET", ["files", "generateIds"]) => {
+                let count: usize = req.param("count").and_then(|c| c.parse().ok()).unwrap_or(10);
+                let ids: Vec<String> = (0..count).map(|_| self.new_id("file")).collect();
+                Response::ok(json!({ "kind": "drive#generatedIds", "space": "drive", "ids": ids }))
+            }
+            ("GET", ["files", id]) if req.param("alt") == Some("media") => {
+                if !self.items.contains_key(&format!("{files}/{id}")) {
+                    return Some(Response::not_found());
+                }
+                let content = self.media.get(*id).cloned().unwrap_or_default();
+                match req.header("range").and_then(|r| r.strip_prefix("bytes=")?.strip_suffix('-')?.parse().ok()) {
+                    Some(start) => Response::byte_range(content, start),
+                    None => Response::bytes(content),
+                }
+            }
+            ("GET", ["files", id, "export"]) => {
+                if !self.items.contains_key(&format!("{files}/{id}")) {
+                    return Some(Response::not_found());
+                }
+                Response::bytes(self.media.get(*id).cloned().unwrap_or_default())
+            }
+            ("PATCH", ["files", id]) => {
+                let Some(file) = self.items.get_mut(&format!("{files}/{id}")) else {
+                    return Some(Response::not_found());
+                };
+                let mut parents = str_values(&file["parents"]);
+                let removed: Vec<&str> = req.param("removeParents").unwrap_or("").split(',').collect();
+                parents.retain(|p| !removed.contains(&p.as_str()));
+                for p in req.param("addParents").unwrap_or("").split(',').filter(|p| !p.is_empty()) {
+                    if !parents.iter().any(|q| q == p) {
+                        parents.push(p.to_string());
+                    }
+                }
+                file["parents"] = json!(parents);
+                merge(file, &body);
+                file["modifiedTime"] = json!(Utc::now().to_rfc3339());
+                Response::ok(file.clone())
+            }
+            ("POST", ["files", id, "copy"]) => {
+                let Some(original) = self.items.get(&format!("{files}/{id}")).cloned() else {
+                    return Some(Response::not_found());
+                };
+                let mut copy = original.clone();
+                copy["name"] = json!(format!("Copy of {}", original["name"].as_str().unwrap_or("")));
+                if let Some(obj) = copy.as_object_mut() {
+                    obj.remove("id");
+                }
+                merge(&mut copy, &body);
+                let content = self.media.get(*id).cloned();
+                Response::ok(self.create_file(copy, content))
+            }
+            ("POST", ["files", id, "comments"]) => {
+                let mut comment = body;
+                comment["author"] = json!({ "displayName": "Fake User", "me": true });
+                comment["createdTime"] = json!(Utc::now().to_rfc3339());
+                comment["resolved"] = json!(false);
+                Response::ok(self.create(&format!("{files}/{id}/comments"), comment))
+            }
+            ("POST", ["files", id, "comments", comment_id, "replies"]) => {
+                let mut reply = body;
+                reply["author"] = json!({ "displayName": "Fake User", "me": true });
+                reply["createdTime"] = json!(Utc::now().to_rfc3339());
+                Response::ok(self.create(&format!("{files}/{id}/comments/{comment_id}/replies"), reply))
+            }
+            ("POST", ["drives"]) => {
+                let request_id = req.param("requestId").unwrap_or("").to_string();
+                let existing = self
+                    .list(&format!("{DRIVE}/drives"))
+                    .into_iter()
+                    .find(|d| !request_id.is_empty() && d["requestId"] == json!(request_id));
+                match existing {
+                    Some(drive) => Response::ok(drive),
+                    None => {
+                        let mut drive = body;
+                        drive["requestId"] = json!(request_id);
+                        drive["kind"] = json!("drive#drive");
+                        Response::ok(self.create(&format!("{DRIVE}/drives"), drive))
+                    }
+                }
+            }
+            ("POST", ["drives", id, action @ ("hide" | "unhide")]) => {
+                let Some(drive) = self.items.get_mut(&format!("{DRIVE}/drives/{id}")) else {
+                    return Some(Response::not_found());
+                };
+                drive["hidden"] = json!(*action == "hide");
+                Response::ok(drive.clone())
+            }
+            ("GET", ["files", id, "listLabels"]) => {
+                if !self.items.contains_key(&format!("{files}/{id}")) {
+                    return Some(Response::not_found());
+                }
+                Response::ok(json!({ "kind": "drive#labelList", "labels": [] }))
+            }
+            ("GET", ["about"]) => {
+                let usage: u64 = self.media.values().map(|m| m.len() as u64).sum();
+                Response::ok(json!({
+                    "user": { "displayName": "Fake User", "emailAddress": USER_EMAIL, "me": true },
+                    "storageQuota": { "limit": "16106127360", "usage": usage.to_string() },
+                }))
+            }
+            ("GET", ["changes", "startPageToken"]) => {
+                Response::ok(json!({ "kind": "drive#startPageToken", "startPageToken": self.next_id.to_string() }))
+            }
+            ("GET", ["changes"]) => Response::ok(json!({
+                "kind": "drive#changeList",
+                "changes": [],
+                "newStartPageToken": self.next_id.to_string(),
+            })),
+            _ => return None,
+        })
+    }
+
+    fn create_file(&mut self, metadata: Value, content: Option<Vec<u8>>) -> Value {
+        let now = Utc::now().to_rfc3339();
+        let mut file = json!({
+            "kind": "drive#file",
+            "mimeType": "application/octet-stream",
+            "parents": ["root"],
+            "trashed": false,
+            "starred": false,
+            "createdTime": now,
+            "modifiedTime": now,
+        });
+        merge(&mut file, &metadata);
+        let file = self.create(&format!("{DRIVE}/files"), file);
+        let id = file["id"].as_str().unwrap_or_default().to_string();
+        if let Some(content) = content {
+            self.seed_media(&id, content);
+        }
+        self.seed(
+            &format!("{DRIVE}/files/{id}/revisions/1"),
+            json!({ "id": "1", "modifiedTime": now, "keepForever": false }),
+        );
+        let path = format!("{DRIVE}/files/{id}");
+        let file = self.items.get_mut(&path).expect("file was just created");
+        file["webViewLink"] = json!(format!("https://drive.google.com/file/d/{id}/view"));
+        file.clone()
+    }
+
+    fn upload(&mut self, req: &Request) -> Option<Response> {
+        let rest = req.path.strip_prefix(UPLOAD)?;
+        let segments: Vec<&str> = rest.trim_start_matches('/').split('/').collect();
+        if let Some(upload_id) = req.param("upload_id") {
+            return Some(self.upload_chunk(req, upload_id));
+        }
+        let file_id = match (req.method.as_str(), segments.as_slice()) {
+            ("POST", ["files"]) => None,
+            ("PATCH", ["files", id]) if self.items.contains_key(&format!("{DRIVE}/files/{id}")) => {
+                Some(id.to_string())
+            }
+            ("PATCH", ["files", _]) => return Some(Response::not_found()),
+            _ => return None,
+        };
+
+        if req.param("uploadType") == Some("resumable") {
+            let id = self.new_id("upload");
+            let total = req.header("x-upload-content-length").and_then(|v| v.parse().ok()).unwrap_or(0);
+            let location = format!(
+                "http://{}{}?uploadType=resumable&upload_id={id}",
+                req.header("host").unwrap_or("localhost"),
+                req.path
+            );
+            self.uploads.insert(
+                id,
+                UploadSession { file_id, metadata: req.json(), total, received: Vec::new(), done: None },
+            );
+            let mut response = Response::ok(json!({}));
+            response.headers.push(("Location".into(), location));
+            return Some(response);
+        }
+
+        let Some((metadata, content)) = parse_multipart(req) else {
+            return Some(Response::error(400, "INVALID_ARGUMENT", "badContent", "Expected a multipart body"));
+        };
+        Some(Response::ok(self.store_upload(file_id.as_deref(), metadata, content)))
+    }
+
+    /// One `PUT` to a resumable session: `Content-Range: bytes a-b/total`
+    /// appends a chunk at `a`, `bytes */total` only asks for the status
+    fn upload_chunk(&mut self, req: &Request, upload_id: &str) -> Response {
+        let range = req.header("content-range").unwrap_or_default();
+        let Some(session) = self.uploads.get_mut(upload_id) else {
+            return Response::not_found();
+        };
+        if let Some(done) = &session.done {
+            return Response::ok(done.clone());
+        }
+        let start = range
+            .strip_prefix("bytes ")
+            .and_then(|r| r.split_once('-'))
+            .and_then(|(start, _)| start.parse::<usize>().ok());
+        if let Some(start) = start {
+            if start > session.received.len() {
+                return Response::error(400, "INVALID_ARGUMENT", "badContent", "Chunk starts past the data received");
+            }
+            session.received.truncate(start);
+            session.received.extend_from_slice(&req.body);
+        }
+
+        if session.received.len() < session.total {
+            let mut response = Response::empty();
+            response.status = 308;
+            if !session.received.is_empty() {
+                response.headers.push(("Range".into(), format!("bytes=0-{}", session.received.len() - 1)));
+            }
+            return response;
+        }
+        let session = self.uploads.remove(upload_id).expect("session was just found");
+        let file = self.store_upload(session.file_id.as_deref(), session.metadata.clone(), session.received);
+        self.uploads.insert(upload_id.to_string(), UploadSession { done: Some(file.clone()), received: Vec::new(), ..session });
+        Response::ok(file)
+    }
+
+    /// Create a file with `content`, or replace an existing one's
+    fn store_upload(&mut self, file_id: Option<&str>, metadata: Value, content: Vec<u8>) -> Value {
+        let Some(id) = file_id else {
+            return self.create_file(metadata, Some(content));
+        };
+        let path = format!("{DRIVE}/files/{id}");
+        if let Some(file) = self.items.get_mut(&path) {
+            merge(file, &metadata);
+            file["modifiedTime"] = json!(Utc::now().to_rfc3339());
+        }
+        self.seed_media(id, content);
+        self.items.get(&path).cloned().unwrap_or(Value::Null)
+    }
+
+    // ── Calendar ──
+
+    fn calendar(&mut self, req: &Request) -> Option<Response> {
+        let rest = req.path.strip_prefix(CALENDAR)?;
+        let segments: Vec<&str> = rest.trim_start_matches('/').split('/').collect();
+        let body = req.json();
+
+        Some(match (req.method.as_str(), segments.as_slice()) {
+            ("POST", ["calendars"]) => {
+                let id = self.new_id("cal");
+                let mut calendar = json!({ "kind": "calendar#calendar", "id": id, "timeZone": "UTC" });
+                merge(&mut calendar, &body);
+                self.seed(&format!("{CALENDAR}/calendars/{id}"), calendar.clone());
+                self.seed(
+                    &format!("{CALENDAR}/users/me/calendarList/{id}"),
+                    json!({ "id": id, "summary": calendar["summary"], "accessRole": "owner" }),
+                );
+                Response::ok(calendar)
+            }
+            ("DELETE", ["calendars", id]) => {
+                if self.remove(&format!("{CALENDAR}/calendars/{id}")).is_none() {
+                    return Some(Response::not_found());
+                }
+                self.remove(&format!("{CALENDAR}/users/me/calendarList/{id}"));
+                Response::empty()
+            }
+            ("POST", ["calendars", id, "clear"]) => {
+                for p in self.member_paths(&format!("{CALENDAR}/calendars/{id}/events")) {
+                    self.remove(&p);
+                }
+                Response::empty()
+            }
+            ("POST", ["calendars", cal, "events", "quickAdd"]) => {
+                let text = req.param("text").unwrap_or("");
+                let event = json!({ "kind": "calendar#event", "status": "confirmed", "summary": text });
+                Response::ok(self.create(&format!("{CALENDAR}/calendars/{cal}/events"), event))
+            }
+            ("POST", ["calendars", cal, "events"]) => {
+                let mut event = json!({ "kind": "calendar#event", "status": "confirmed" });
+                merge(&mut event, &body);
+                Response::ok(self.create(&format!("{CALENDAR}/calendars/{cal}/events"), event))
+            }
+            ("POST", ["calendars", cal, "events", "import"]) => {
+                let mut event = json!({ "kind": "calendar#event", "status": "confirmed" });
+                merge(&mut event, &body);
+                Response::ok(self.create(&format!("{CALENDAR}/calendars/{cal}/events"), event))
+            }
+            ("POST", ["calendars", cal, "events", id, "move"]) => {
+                let destination = req.param("destination").unwrap_or("primary");
+                let Some(event) = self.remove(&format!("{CALENDAR}/calendars/{cal}/events/{id}")) else {
+                    return Some(Response::not_found());
+                };
+                self.seed(&format!("{CALENDAR}/calendars/{destination}/events/{id}"), event.clone());
+                Response::ok(event)
+            }
+            ("GET", ["calendars", cal, "events", id, "instances"]) => {
+                match self.items.get(&format!("{CALENDAR}/calendars/{cal}/events/{id}")) {
+                    Some(event) => Response::ok(json!({ "kind": "calendar#events", "items": [event] })),
+                    None => Response::not_found(),
+                }
+            }
+            ("GET", ["colors"]) => Response::ok(json!({
+                "kind": "calendar#colors",
+                "calendar": { "1": { "background": "#ac725e", "foreground": "#1d1d1d" } },
+                "event": { "1": { "background": "#a4bdfc", "foreground": "#1d1d1d" } },
+            })),
+            ("POST", ["freeBusy"]) => {
+                let calendars: serde_json::Map<String, Value> = body["items"]
+                    .as_array()
+                    .into_iter()
+                    .flatten()
+                    .filter_map(|i| i["id"].as_str())
+                    .map(|id| (id.to_string(), json!({ "busy": [] })))
+                    .collect();
+                Response::ok(json!({
+                    "kind": "calendar#freeBusy",
+                    "timeMin": body["timeMin"],
+                    "timeMax": body["timeMax"],
+                    "calendars": calendars,
+                }))
+            }
+            _ => return None,
+        })
+    }
+
+    // ── Tasks ──
+
+    fn tasks(&mut self, req: &Request) -> Option<Response> {
+        let rest = req.path.strip_prefix(TASKS)?;
+        let segments: Vec<&str> = rest.trim_start_matches('/').split('/').collect();
+
+        Some(match (req.method.as_str(), segments.as_slice()) {
+            ("GET", ["lists", list, "tasks"]) => {
+                let show_completed = req.param("showCompleted") != Some("false");
+                let tasks: Vec<Value> = self
+                    .list(&format!("{TASKS}/lists/{list}/tasks"))
+                    .into_iter()
+                    .filter(|t| show_completed || t["status"] != json!("completed"))
+                    .collect();
+                page(req, "items", tasks)
+            }
+            ("POST", ["lists", list, "tasks"]) => {
+                let mut task = json!({ "kind": "tasks#task", "status": "needsAction" });
+                merge(&mut task, &req.json());
+                if let Some(parent) = req.param("parent") {
+                    task["parent"] = json!(parent);
+                }
+                Response::ok(self.create(&format!("{TASKS}/lists/{list}/tasks"), task))
+            }
+            ("POST", ["lists", list, "tasks", id, "move"]) => {
+                let Some(task) = self.items.get_mut(&format!("{TASKS}/lists/{list}/tasks/{id}")) else {
+                    return Some(Response::not_found());
+                };
+                match req.param("parent") {
+                    Some(parent) => task["parent"] = json!(parent),
+                    None => {
+                        if let Some(obj) = task.as_object_mut() {
+                            obj.remove("parent");
+                        }
+                    }
+                }
+                Response::ok(task.clone())
+            }
+            ("POST", ["lists", list, "clear"]) => {
+                for p in self.member_paths(&format!("{TASKS}/lists/{list}/tasks")) {
+                    if self.items.get(&p).is_some_and(|t| t["status"] == json!("completed")) {
+                        self.remove(&p);
+                    }
+                }
+                Response::empty()
+            }
+            _ => return None,
+        })
+    }
+
+    // ── People ──
+
+    fn people(&mut self, req: &Request) -> Option<Response> {
+        let rest = req.path.strip_prefix(PEOPLE)?;
+        let segments: Vec<&str> = rest.trim_start_matches('/').split('/').collect();
+        let body = req.json();
+
+        Some(match (req.method.as_str(), segments.as_slice()) {
+            ("GET", ["people", "me"]) => Response::ok(json!({
+                "resourceName": "people/me",
+                "names": [{ "displayName": "Fake User" }],
+                "emailAddresses": [{ "value": USER_EMAIL }],
+            })),
+            ("POST", ["people:createContact"]) => Response::ok(self.create_contact(body)),
+            ("GET", ["people:batchGet"]) => {
+                let responses: Vec<Value> = req
+                    .params("resourceNames")
+                    .into_iter()
+                    .map(|rn| match self.items.get(&format!("{PEOPLE}/{rn}")) {
+                        Some(person) => json!({ "requestedResourceName": rn, "httpStatusCode": 200, "person": person }),
+                        None => json!({ "requestedResourceName": rn, "httpStatusCode": 404 }),
+                    })
+                    .collect();
+                Response::ok(json!({ "responses": responses }))
+            }
+            ("GET", ["people:searchContacts"]) => {
+                let query = req.param("query").unwrap_or("").to_lowercase();
+                let results: Vec<Value> = self
+                    .list(CONTACTS)
+                    .into_iter()
+                    .filter(|p| p.to_string().to_lowercase().contains(&query))
+                    .map(|p| json!({ "person": p }))
+                    .collect();
+                Response::ok(json!({ "results": results }))
+            }
+            ("GET", ["people:searchDirectoryPeople"]) => Response::ok(json!({ "people": [], "totalSize": 0 })),
+            ("POST", ["people:batchCreateContacts"]) => {
+                let created: Vec<Value> = body["contacts"]
+                    .as_array()
+                    .cloned()
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|c| json!({ "person": self.create_contact(c["contactPerson"].clone()) }))
+                    .collect();
+                Response::ok(json!({ "createdPeople": created }))
+            }
+            ("POST", ["people:batchDeleteContacts"]) => {
+                for rn in str_values(&body["resourceNames"]) {
+                    self.remove(&format!("{PEOPLE}/{rn}"));
+                }
+                Response::ok(json!({}))
+            }
+            ("POST", ["people:batchUpdateContacts"]) => {
+                let mut results = serde_json::Map::new();
+                for (rn, person) in body["contacts"].as_object().cloned().unwrap_or_default() {
+                    if let Some(existing) = self.items.get_mut(&format!("{PEOPLE}/{rn}")) {
+                        merge(existing, &person);
+                        results.insert(rn, json!({ "person": existing, "httpStatusCode": 200 }));
+                    }
+                }
+                Response::ok(json!({ "updateResult": results }))
+            }
+            ("PATCH", ["people", target]) if target.ends_with(":updateContact") => {
+                let id = target.trim_end_matches(":updateContact");
+                let Some(person) = self.items.get_mut(&format!("{PEOPLE}/people/{id}")) else {
+                    return Some(Response::not_found());
+                };
+                merge(person, &body);
+                Response::ok(person.clone())
+            }
+            ("DELETE", ["people", target]) if target.ends_with(":deleteContact") => {
+                let id = target.trim_end_matches(":deleteContact");
+                match self.remove(&format!("{PEOPLE}/people/{id}")) {
+                    Some(_) => Response::ok(json!({})),
+                    None => Response::not_found(),
+                }
+            }
+            ("POST", ["contactGroups"]) => {
+                let id = self.new_id("g");
+                let mut group = json!({
+                    "resourceName": format!("contactGroups/{id}"),
+                    "groupType": "USER_CONTACT_GROUP",
+                    "memberCount": 0,
+                });
+                merge(&mut group, &body["contactGroup"]);
+                self.seed(&format!("{PEOPLE}/contactGroups/{id}"), group.clone());
+                Response::ok(group)
+            }
+            ("PUT", ["contactGroups", id]) => {
+                let Some(group) = self.items.get_mut(&format!("{PEOPLE}/contactGroups/{id}")) else {
+                    return Some(Response::not_found());
+                };
+                merge(group, &body["contactGroup"]);
+                Response::ok(group.clone())
+            }
+            ("POST", ["contactGroups", id, "members:modify"]) => {
+                let Some(group) = self.items.get_mut(&format!("{PEOPLE}/contactGroups/{id}")) else {
+                    return Some(Response::not_found());
+                };
+                let remove = str_values(&body["resourceNamesToRemove"]);
+                let mut members = str_values(&group["memberResourceNames"]);
+                members.retain(|m| !remove.contains(m));
+                for m in str_values(&body["resourceNamesToAdd"]) {
+                    if !members.contains(&m) {
+                        members.push(m);
+                    }
+                }
+                group["memberCount"] = json!(members.len());
+                group["memberResourceNames"] = json!(members);
+                Response::ok(json!({}))
+            }
+            ("POST", ["otherContacts", target]) if target.ends_with(":copyOtherContactToMyContactsGroup") => {
+                let id = target.trim_end_matches(":copyOtherContactToMyContactsGroup");
+                let Some(mut person) = self.items.get(&format!("{PEOPLE}/otherContacts/{id}")).cloned() else {
+                    return Some(Response::not_found());
+                };
+                if let Some(obj) = person.as_object_mut() {
+                    obj.remove("resourceName");
+                }
+                Response::ok(self.create_contact(person))
+            }
+            _ => return None,
+        })
+    }
+
+    fn create_contact(&mut self, person: Value) -> Value {
+        let id = self.new_id("c");
+        let mut person = if person.is_object() { person } else { json!({}) };
+        person["resourceName"] = json!(format!("people/{id}"));
+        person["etag"] = json!(format!("%E{id}"));
+        self.insert(CONTACTS, format!("{PEOPLE}/people/{id}"), person.clone());
+        person
+    }
+}
+
+/// One page of `items`; page tokens are offsets
+fn page(req: &Request, list_key: &str, items: Vec<Value>) -> Response {
+    let size = req
+        .param("maxResults")
+        .or_else(|| req.param("pageSize"))
+        .and_then(|v| v.parse::<usize>().ok())
+        .filter(|n| *n > 0)
+        .unwrap_or(100);
+    let start = req
+        .param("pageToken")
+        .and_then(|t| t.parse::<usize>().ok())
+        .unwrap_or(0)
+        .min(items.len());
+    let end = (start + size).min(items.len());
+    let mut body = json!({ list_key: items[start..end] });
+    if end < items.len() {
+        body["nextPageToken"] = json!(end.to_string());
+    }
+    Response::ok(body)
+}
+
+/// The S256 PKCE challenge of `verifier`
+fn s256(verifier: &str) -> String {
+    use base64::Engine;
+    let digest = ring::digest::digest(&ring::digest::SHA256, verifier.as_bytes());
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(digest.as_ref())
+}
+
+/// JSON merge patch (RFC 7386)
+fn merge(target: &mut Value, patch: &Value) {
+    let Some(patch) = patch.as_object() else {
+        return;
+    };
+    if !target.is_object() {
+        *target = json!({});
+    }
+    if let Some(target) = target.as_object_mut() {
+        for (key, value) in patch {
+            if value.is_null() {
+                target.remove(key);
+            } else if value.is_object() {
+                merge(target.entry(key.clone()).or_insert_with(|| json!({})), value);
+            } else {
+                target.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+fn str_values(value: &Value) -> Vec<String> {
+    value
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|v| v.as_str().map(String::from))
+        .collect()
+}
+
+fn has_label(message: &Value, label: &str) -> bool {
+    message["labelIds"]
+        .as_array()
+        .is_some_and(|ls| ls.iter().any(|l| l == label))
+}
+
+fn relabel(item: &mut Value, add: &Value, remove: &Value) {
+    let remove = str_values(remove);
+    let mut labels = str_values(&item["labelIds"]);
+    labels.retain(|l| !remove.contains(l));
+    for label in str_values(add) {
+        if !labels.contains(&label) {
+            labels.push(label);
+        }
+    }
+    item["labelIds"] = json!(labels);
+}
+
+fn default_setting(name: &str) -> Value {
+    match name {
+        "vacation" => json!({ "enableAutoReply": false }),
+        "autoForwarding" => json!({ "enabled": false }),
+        "imap" => json!({ "enabled": true, "autoExpunge": true, "expungeBehavior": "archive" }),
+        "pop" => json!({ "accessWindow": "disabled", "disposition": "leaveInInbox" }),
+        "language" => json!({ "displayLanguage": "en" }),
+        _ => json!({}),
+    }
+}
+
+/// Split a two-part multipart body into its JSON metadata and content
+fn parse_multipart(req: &Request) -> Option<(Value, Vec<u8>)> {
+    let mut parts = multipart_parts(req)?.into_iter().map(|part| {
+        let head_end = find(&part, b"\r\n\r\n").map_or(part.len(), |i| i + 4);
+        part[head_end..].to_vec()
+    });
+    let metadata = serde_json::from_slice(&parts.next()?).ok()?;
+    Some((metadata, parts.next().unwrap_or_default()))
+}
+
+/// The parts of a multipart body, each still with its headers
+fn multipart_parts(req: &Request) -> Option<Vec<Vec<u8>>> {
+    let content_type = req.header("content-type")?;
+    let boundary = content_type
+        .split(';')
+        .find_map(|p| p.trim().strip_prefix("boundary="))?
+        .trim_matches('"');
+    let delimiter = format!("--{boundary}");
+    let next_delimiter = format!("\r\n{delimiter}");
+
+    let mut rest = &req.body[find(&req.body, delimiter.as_bytes())? + delimiter.len()..];
+    let mut parts = Vec::new();
+    while !rest.starts_with(b"--") {
+        let part_start = rest.strip_prefix(b"\r\n")?;
+        let end = find(part_start, next_delimiter.as_bytes())?;
+        parts.push(part_start[..end].to_vec());
+        rest = &part_start[end + next_delimiter.len()..];
+    }
+    Some(parts)
+}
@@ -0,0 +1,20 @@
+pub mod actions;
+pub mod api;
+pub mod auth;
+pub mod batch;
+pub mod cache;
+pub mod cassette;
+pub mod cli;
+pub mod client;
+pub mod config;
+pub mod endpoints;
+pub mod error;
+#[cfg(feature = "test-support")]
+pub mod fake;
+pub mod network;
+pub mod pager;
+pub mod quota;
+pub mod retry;
+pub mod transfer;
+pub mod ui;
+pub mod vault;
@@ -1,18 +1,16 @@
 #![allow(dead_code)]
 
-mod api;
-mod auth;
-mod cli;
-mod client;
-mod config;
-mod error;
-mod ui;
-
-use crate::client::GoogleClient;
-use crate::config::{Account, AuthConfig, Config};
-use crate::ui::app::{App, Screen};
-use crate::ui::views::handlers;
-use crate::ui::views::render;
+use vgoog::auth::Grant;
+use vgoog::cassette::CassetteMode;
+use vgoog::client::GoogleClient;
+use vgoog::config::{Account, AuthConfig, Config};
+use vgoog::endpoints::Endpoints;
+use vgoog::network::NetworkConfig;
+use vgoog::transfer::TransferStatus;
+use vgoog::ui::app::{App, Screen};
+use vgoog::ui::views::handlers;
+use vgoog::ui::views::render;
+use vgoog::{api, auth, cli, error};
 
 use clap::Parser;
 
@@ -22,24 +20,26 @@ use crossterm::{
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::prelude::*;
-use std::io;
+use std::io::{self, IsTerminal};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     let cli_args = cli::Cli::parse();
+    let cassette = cli_args.cassette()?;
 
     if let Some(command) = cli_args.command {
-        return run_cli(command).await;
+        return run_cli(command, cassette, cli_args.no_cache).await;
     }
 
-    let config = if Config::exists() {
+    let config = if Config::exists() || Config::from_env()?.is_some() {
         let mut cfg = Config::load()?;
         if cfg.accounts.is_empty() {
             println!("\n  No accounts configured. Let's add one.\n");
             let (name, account) = add_account_flow().await?;
-            cfg.add_account(name.clone(), account);
-            cfg.active_account = name;
-            cfg.save()?;
+            cfg.update_async(|c| {
+                c.add_account(name.clone(), account);
+                c.active_account = name;
+            }).await?;
         }
         cfg
     } else {
@@ -51,12 +51,13 @@ async fn main() -> anyhow::Result<()> {
         eprintln!("  Run vgoog and add a new account.\n");
         let mut cfg = config;
         let (name, account) = add_account_flow().await?;
-        cfg.add_account(name.clone(), account);
-        cfg.active_account = name;
-        cfg.save()?;
-        run_tui(cfg).await?;
+        cfg.update_async(|c| {
+            c.add_account(name.clone(), account);
+            c.active_account = name;
+        }).await?;
+        run_tui(cfg, cassette, cli_args.no_cache).await?;
     } else {
-        run_tui(config).await?;
+        run_tui(config, cassette, cli_args.no_cache).await?;
     }
 
     Ok(())
@@ -71,11 +72,10 @@ async fn first_time_setup() -> anyhow::Result<Config> {
 
     let mut config = Config {
         active_account: name.clone(),
-        accounts: Default::default(),
-        auth: None,
+        ..Default::default()
     };
     config.add_account(name, account);
-    config.save()?;
+    config.save_async().await?;
 
     println!("\n  Config saved! Starting vgoog...\n");
     Ok(config)
@@ -87,7 +87,19 @@ async fn add_account_flow() -> anyhow::Result<(String, Account)> {
     let name = prompt("  Account name (e.g. work, personal): ")?;
     let label = prompt("  Display label (e.g. Work Gmail): ")?;
 
-    let auth = manual_token_flow()?;
+    println!("\n  How would you like to authorize this account?\n");
+    println!("  [1] Sign in with browser (recommended)");
+    println!("  [2] Paste tokens manually");
+    println!("  [3] Device code (no browser on this machine, e.g. SSH)");
+    println!("  [4] Import a client_secret.json or gcloud credentials file\n");
+
+    // Pasted tokens come without a record of their scopes
+    let grant = match prompt("  Choice [1]: ")?.as_str() {
+        "2" => Grant { auth: manual_token_flow()?, scopes: Vec::new() },
+        "3" => device_flow().await?,
+        "4" => import_flow().await?,
+        _ => browser_flow().await?,
+    };
 
     let account_name = if name.is_empty() {
         "default".to_string()
@@ -105,11 +117,87 @@ async fn add_account_flow() -> anyhow::Result<(String, Account)> {
         account_name,
         Account {
             label: account_label,
-            auth,
+            scopes: grant.scopes,
+            auth: grant.auth,
+            ..Default::default()
         },
     ))
 }
 
+// ── Browser (loopback + PKCE) Flow ──
+
+async fn browser_flow() -> anyhow::Result<Grant> {
+    println!("\n  ── Browser Sign-in ──\n");
+    println!("  Create a \"Desktop app\" OAuth client at:");
+    println!("  https://console.cloud.google.com/apis/credentials\n");
+
+    let client_id = prompt("  GOOGLE_OAUTH_CLIENT_ID: ")?;
+    let client_secret = prompt("  GOOGLE_OAUTH_CLIENT_SECRET: ")?;
+
+    let endpoints = Endpoints::current()?;
+    let http = NetworkConfig::current()?.client()?;
+    let grant = auth::loopback::run(&http, &endpoints, &client_id, &client_secret, auth::DEFAULT_SCOPES, false).await?;
+    println!("  Authorization successful!");
+    Ok(grant)
+}
+
+// ── Device Code Flow ──
+
+async fn device_flow() -> anyhow::Result<Grant> {
+    println!("\n  ── Device Sign-in ──\n");
+    println!("  Requires a \"TVs and Limited Input devices\" OAuth client.\n");
+
+    let client_id = prompt("  GOOGLE_OAUTH_CLIENT_ID: ")?;
+    let client_secret = prompt("  GOOGLE_OAUTH_CLIENT_SECRET: ")?;
+
+    let endpoints = Endpoints::current()?;
+    let http = NetworkConfig::current()?.client()?;
+    let grant = run_device_flow(&http, &endpoints, &client_id, &client_secret, auth::DEFAULT_SCOPES).await?;
+    println!("  Authorization successful!");
+    Ok(grant)
+}
+
+async fn run_device_flow(
+    http: &reqwest::Client,
+    endpoints: &Endpoints,
+    client_id: &str,
+    client_secret: &str,
+    scopes: &[&str],
+) -> anyhow::Result<Grant> {
+    let code = auth::device::request_code(http, endpoints, client_id, scopes).await?;
+    eprintln!("\n  On any device, open: {}", code.verification_url);
+    eprintln!("  and enter the code:  {}\n", code.user_code);
+    eprintln!("  Waiting for approval (expires in {}s) ...\n", code.expires_in);
+    Ok(auth::device::poll_for_token(http, endpoints, client_id, client_secret, &code).await?)
+}
+
+// ── Import Flow ──
+
+async fn import_flow() -> anyhow::Result<Grant> {
+    use auth::import::CredentialsFile;
+
+    println!("\n  ── Import Credentials ──\n");
+    println!("  A client_secret_*.json downloaded from the Cloud console, or the");
+    println!("  file written by `gcloud auth application-default login`.\n");
+
+    let default = auth::import::default_adc_path()?;
+    let path = prompt(&format!("  Path [{}]: ", default.display()))?;
+    let path = if path.is_empty() { default } else { std::path::PathBuf::from(path) };
+
+    let endpoints = Endpoints::current()?;
+    let http = NetworkConfig::current()?.client()?;
+    let grant = match CredentialsFile::load(&path)? {
+        CredentialsFile::Client { client_id, client_secret } => {
+            auth::loopback::run(&http, &endpoints, &client_id, &client_secret, auth::DEFAULT_SCOPES, false).await?
+        }
+        CredentialsFile::AuthorizedUser { client_id, client_secret, refresh_token } => {
+            auth::import::redeem(&http, &endpoints, &client_id, &client_secret, &refresh_token).await?
+        }
+    };
+    println!("  Credentials verified!");
+    Ok(grant)
+}
+
 // ── Manual Token Flow ──
 
 fn manual_token_flow() -> anyhow::Result<AuthConfig> {
@@ -149,9 +237,10 @@ async fn manage_accounts_menu(config: &mut Config) -> anyhow::Result<bool> {
     match choice.trim() {
         "a" => {
             let (name, account) = add_account_flow().await?;
-            config.add_account(name.clone(), account);
-            config.active_account = name;
-            config.save()?;
+            config.update_async(|c| {
+                c.add_account(name.clone(), account);
+                c.active_account = name;
+            }).await?;
             println!("  Account added and activated!");
             Ok(true)
         }
@@ -161,8 +250,7 @@ async fn manage_accounts_menu(config: &mut Config) -> anyhow::Result<bool> {
                 return Ok(true);
             }
             let name = prompt("  Account name to remove: ")?;
-            if config.remove_account(&name) {
-                config.save()?;
+            if config.update_async(|c| c.remove_account(&name)).await? {
                 println!("  Account '{}' removed.", name);
             } else {
                 println!("  Account '{}' not found.", name);
@@ -171,8 +259,7 @@ async fn manage_accounts_menu(config: &mut Config) -> anyhow::Result<bool> {
         }
         "s" => {
             let name = prompt("  Account name to switch to: ")?;
-            if config.switch_account(&name) {
-                config.save()?;
+            if config.update_async(|c| c.switch_account(&name)).await? {
                 println!("  Switched to '{}'.", name);
             } else {
                 println!("  Account '{}' not found.", name);
@@ -185,8 +272,11 @@ async fn manage_accounts_menu(config: &mut Config) -> anyhow::Result<bool> {
 
 // ── TUI ──
 
-async fn run_tui(config: Config) -> anyhow::Result<()> {
-    let client = GoogleClient::new(config)?;
+async fn run_tui(config: Config, cassette: Option<CassetteMode>, no_cache: bool) -> anyhow::Result<()> {
+    let mut client = GoogleClient::with_cassette(config, cassette)?;
+    if no_cache {
+        client.disable_cache();
+    }
 
     enable_raw_mode()?;
     let mut stdout = io::stdout();
@@ -228,15 +318,13 @@ async fn run_tui(config: Config) -> anyhow::Result<()> {
                 // Handle account switcher overlay
                 if app.show_account_switcher {
                     match key.code {
-                        KeyCode::Up | KeyCode::Char('k') => {
-                            if app.account_cursor > 0 {
-                                app.account_cursor -= 1;
-                            }
+                        KeyCode::Up | KeyCode::Char('k') if app.account_cursor > 0 => {
+                            app.account_cursor -= 1;
                         }
-                        KeyCode::Down | KeyCode::Char('j') => {
-                            if app.account_cursor < app.account_list.len().saturating_sub(1) {
-                                app.account_cursor += 1;
-                            }
+                        KeyCode::Down | KeyCode::Char('j')
+                            if app.account_cursor < app.account_list.len().saturating_sub(1) =>
+                        {
+                            app.account_cursor += 1;
                         }
                         KeyCode::Enter => {
                             if let Some(name) = app.account_list.get(app.account_cursor).cloned() {
@@ -253,7 +341,7 @@ async fn run_tui(config: Config) -> anyhow::Result<()> {
                                         app.service = None;
                                     }
                                     Err(e) => {
-                                        app.set_status(format!("Error: {e}"));
+                                        app.set_error(&e);
                                     }
                                 }
                             }
@@ -281,7 +369,15 @@ async fn run_tui(config: Config) -> anyhow::Result<()> {
                         KeyCode::Up | KeyCode::Char('k') => app.move_up(),
                         KeyCode::Down | KeyCode::Char('j') => app.move_down(),
                         KeyCode::Enter => {
-                            app.service = Some(app.current_service());
+                            let service = app.current_service();
+                            let missing = app.client.missing_scopes(service, None).await;
+                            if !missing.is_empty() {
+                                if let Err(e) = grant_outside_tui(&mut terminal, &app.client, &missing).await? {
+                                    app.set_error(&e);
+                                    continue;
+                                }
+                            }
+                            app.service = Some(service);
                             app.selected_action = 0;
                             app.screen = Screen::ActionSelect;
                             app.set_status(format!(
@@ -296,6 +392,17 @@ async fn run_tui(config: Config) -> anyhow::Result<()> {
                         KeyCode::Up | KeyCode::Char('k') => app.move_up(),
                         KeyCode::Down | KeyCode::Char('j') => app.move_down(),
                         KeyCode::Enter => {
+                            let service = app.current_service();
+                            let action = app.current_actions()[app.selected_action];
+                            if let Some(exec_action) = service.scoped_action(action) {
+                                let missing = app.client.missing_scopes(service, Some(exec_action)).await;
+                                if !missing.is_empty() {
+                                    if let Err(e) = grant_outside_tui(&mut terminal, &app.client, &missing).await? {
+                                        app.set_error(&e);
+                                        continue;
+                                    }
+                                }
+                            }
                             app.set_status("Loading...");
                             handlers::execute_action(&mut app).await;
                         }
@@ -317,17 +424,13 @@ async fn run_tui(config: Config) -> anyhow::Result<()> {
                                 app.move_down();
                             }
                         }
-                        KeyCode::Enter => {
-                            if app.detail.is_none() {
-                                handlers::execute_detail(&mut app).await;
-                            }
+                        KeyCode::Enter if app.detail.is_none() => {
+                            handlers::execute_detail(&mut app).await;
                         }
-                        KeyCode::Char('d') => {
-                            if app.detail.is_none() && !app.items.is_empty() {
-                                if let Some(item) = app.current_item() {
-                                    app.confirm_message = format!("Delete '{}'?", item.title);
-                                    app.screen = Screen::Confirm;
-                                }
+                        KeyCode::Char('d') if app.detail.is_none() && !app.items.is_empty() => {
+                            if let Some(item) = app.current_item() {
+                                app.confirm_message = format!("Delete '{}'?", item.title);
+                                app.screen = Screen::Confirm;
                             }
                         }
                         KeyCode::Char('n') => {
@@ -361,7 +464,10 @@ async fn run_tui(config: Config) -> anyhow::Result<()> {
                                 .iter()
                                 .all(|f| !f.required || !f.value.is_empty());
                             if all_valid {
-                                handlers::submit_input(&mut app).await;
+                                let last_frame = terminal.draw(|f| render::render(f, &app))?.buffer.clone();
+                                let transfer = app.transfer.clone();
+                                with_progress(&mut terminal, &last_frame, &transfer, handlers::submit_input(&mut app))
+                                    .await?;
                             } else {
                                 app.set_status("Please fill in all required fields (*)");
                             }
@@ -399,15 +505,58 @@ async fn run_tui(config: Config) -> anyhow::Result<()> {
     execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
     terminal.show_cursor()?;
 
+    let _ = app.client.record_usage("tui").await;
     println!("Thanks for using vgoog!");
     Ok(())
 }
 
+/// Await a TUI action, redrawing the progress gauge over `last_frame` while
+/// it reports an upload
+async fn with_progress<B: Backend, T>(
+    terminal: &mut Terminal<B>,
+    last_frame: &Buffer,
+    transfer: &TransferStatus,
+    action: impl std::future::Future<Output = T>,
+) -> anyhow::Result<T> {
+    tokio::pin!(action);
+    let mut tick = tokio::time::interval(std::time::Duration::from_millis(100));
+    loop {
+        tokio::select! {
+            out = &mut action => return Ok(out),
+            _ = tick.tick() => {
+                if let Some((label, progress)) = transfer.get() {
+                    terminal.draw(|f| {
+                        f.buffer_mut().merge(last_frame);
+                        render::render_transfer(f, &label, progress);
+                    })?;
+                }
+            }
+        }
+    }
+}
+
+/// Leave the TUI for an incremental consent, then come back to it. The
+/// outer error is the terminal's, the inner one the consent's.
+async fn grant_outside_tui(
+    terminal: &mut Terminal<CrosstermBackend<io::Stdout>>,
+    client: &GoogleClient,
+    scopes: &[&str],
+) -> anyhow::Result<error::Result<()>> {
+    disable_raw_mode()?;
+    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
+    eprintln!("\n  Asking Google for: {}", scopes.join(", "));
+    let granted = client.request_scopes(scopes).await;
+    execute!(terminal.backend_mut(), EnterAlternateScreen)?;
+    enable_raw_mode()?;
+    terminal.clear()?;
+    Ok(granted)
+}
+
 // ── CLI mode ──
 
-async fn run_cli(command: cli::CliCommand) -> anyhow::Result<()> {
+async fn run_cli(command: cli::CliCommand, cassette: Option<CassetteMode>, no_cache: bool) -> anyhow::Result<()> {
     match command {
-        cli::CliCommand::Exec { service, action, args, account } => {
+        cli::CliCommand::Exec { service, action, args, account, all, limit } => {
             let mut config = Config::load()?;
 
             if let Some(ref acct_name) = account {
@@ -420,14 +569,24 @@ async fn run_cli(command: cli::CliCommand) -> anyhow::Result<()> {
                 }
             }
 
-            let client = GoogleClient::new(config)?;
+            let mut client = GoogleClient::with_cassette(config, cassette)?;
+            client.set_prompt_consent(io::stdin().is_terminal());
+            if no_cache {
+                client.disable_cache();
+            }
 
             let parsed_args: serde_json::Value = args
                 .map(|s| serde_json::from_str(&s))
                 .transpose()?
                 .unwrap_or(serde_json::json!({}));
 
-            match cli::exec::execute(&client, &service, &action, parsed_args).await {
+            let result = if all || limit.is_some() {
+                cli::exec::execute_paged(&client, &service, &action, parsed_args, limit).await
+            } else {
+                cli::exec::execute(&client, &service, &action, parsed_args).await
+            };
+            let _ = client.record_usage(&format!("exec {service} {action}")).await;
+            match result {
                 Ok(val) => {
                     println!("{}", serde_json::to_string(&serde_json::json!({
                         "ok": true,
@@ -435,23 +594,83 @@ async fn run_cli(command: cli::CliCommand) -> anyhow::Result<()> {
                     }))?);
                 }
                 Err(e) => {
+                    eprintln!("{}", serde_json::to_string(&e.to_json())?);
+                    std::process::exit(1);
+                }
+            }
+        }
+        cli::CliCommand::Batch { service, requests, account } => {
+            let mut config = Config::load()?;
+
+            if let Some(ref acct_name) = account {
+                if !config.switch_account(acct_name) {
                     eprintln!("{}", serde_json::to_string(&serde_json::json!({
                         "ok": false,
-                        "error": e.to_string()
+                        "error": format!("Account '{}' not found", acct_name)
                     }))?);
                     std::process::exit(1);
                 }
             }
+
+            let mut client = GoogleClient::with_cassette(config, cassette)?;
+            client.set_prompt_consent(io::stdin().is_terminal());
+            let requests: serde_json::Value = serde_json::from_str(&requests)?;
+
+            let result = cli::batch::execute(&client, &service, requests).await;
+            let _ = client.record_usage(&format!("batch {service}")).await;
+            match result {
+                Ok(val) => {
+                    println!("{}", serde_json::to_string(&serde_json::json!({
+                        "ok": true,
+                        "data": val
+                    }))?);
+                }
+                Err(e) => {
+                    eprintln!("{}", serde_json::to_string(&e.to_json())?);
+                    std::process::exit(1);
+                }
+            }
         }
         cli::CliCommand::List => {
             println!("{}", serde_json::to_string_pretty(&cli::exec::list_all())?);
         }
+        cli::CliCommand::Describe { service, action } => {
+            let described = match (service.as_deref(), action.as_deref()) {
+                (Some(service), Some(action)) => cli::exec::describe(service, action),
+                (Some(service), None) => cli::exec::describe_service(service),
+                (None, _) => cli::exec::describe_all(),
+            };
+            print_json_result(described.map_err(Into::into))?;
+        }
+        cli::CliCommand::Quota { last } => {
+            let config = if Config::exists() { Config::load_locked()? } else { Config::default() };
+            print_json_result(cli::quota::report(&config, last).map_err(Into::into))?;
+        }
+        cli::CliCommand::Cache { command: cli::CacheCommand::Clear { account } } => {
+            let removed = vgoog::cache::clear(account.as_deref());
+            print_json_result(removed.map(|removed| serde_json::json!({ "removed": removed })).map_err(Into::into))?;
+        }
+        cli::CliCommand::Auth { command } => run_auth_cli(command, cassette).await?,
+        #[cfg(feature = "test-support")]
+        cli::CliCommand::FakeServer { port } => {
+            let mut server = vgoog::fake::FakeGoogle::bind(&format!("127.0.0.1:{port}")).await?;
+            println!("{}", serde_json::to_string(&serde_json::json!({
+                "ok": true,
+                "data": {
+                    "url": server.url(),
+                    "access_token": vgoog::fake::ACCESS_TOKEN,
+                }
+            }))?);
+            server.wait().await;
+        }
         cli::CliCommand::Status => {
             let config = Config::load()?;
-            let client = GoogleClient::new(config)?;
+            let client = GoogleClient::with_cassette(config, cassette)?;
             let api = api::gmail::GmailApi::new(&client);
 
-            match api.get_profile().await {
+            let result = api.get_profile().await;
+            let _ = client.record_usage("status").await;
+            match result {
                 Ok(val) => {
                     println!("{}", serde_json::to_string(&serde_json::json!({
                         "ok": true,
@@ -459,10 +678,7 @@ async fn run_cli(command: cli::CliCommand) -> anyhow::Result<()> {
                     }))?);
                 }
                 Err(e) => {
-                    eprintln!("{}", serde_json::to_string(&serde_json::json!({
-                        "ok": false,
-                        "error": e.to_string()
-                    }))?);
+                    eprintln!("{}", serde_json::to_string(&e.to_json())?);
                     std::process::exit(1);
                 }
             }
@@ -471,6 +687,332 @@ async fn run_cli(command: cli::CliCommand) -> anyhow::Result<()> {
     Ok(())
 }
 
+async fn run_auth_cli(command: cli::AuthCommand, cassette: Option<CassetteMode>) -> anyhow::Result<()> {
+    match command {
+        cli::AuthCommand::Login { account, label, device, client_id, client_secret, scopes } => {
+            let scopes = scopes.resolve()?;
+            print_json_result(auth_login(account, label, device, client_id, client_secret, scopes).await)?;
+        }
+        cli::AuthCommand::Import { path, account, label, device, scopes } => {
+            let scopes = scopes.resolve()?;
+            print_json_result(auth_import(path, account, label, device, scopes).await)?;
+        }
+        cli::AuthCommand::ServiceAccount { account, subject, key_file, key_name, scopes, replace, label } => {
+            print_json_result(
+                add_service_account(account, subject, key_file, key_name, scopes, replace, label).await,
+            )?;
+        }
+        cli::AuthCommand::Info { account } => {
+            let client = GoogleClient::with_cassette(Config::load()?, cassette)?;
+            print_json_result(cli::auth::info(&client, account.as_deref()).await.map_err(Into::into))?;
+        }
+        cli::AuthCommand::Revoke { account, remove } => {
+            let client = GoogleClient::with_cassette(Config::load()?, cassette)?;
+            let account = match account {
+                Some(account) => account,
+                None => client.active_account_name().await,
+            };
+            print_json_result(cli::auth::revoke(&client, &account, remove).await.map_err(Into::into))?;
+        }
+        // Passphrase prompts and the config lock block, so off the runtime
+        cli::AuthCommand::Lock { key_file } => {
+            print_json_result(tokio::task::spawn_blocking(move || auth_lock(key_file)).await?)?
+        }
+        cli::AuthCommand::Unlock => print_json_result(tokio::task::spawn_blocking(auth_unlock).await?)?,
+    }
+    Ok(())
+}
+
+fn print_json_result(result: anyhow::Result<serde_json::Value>) -> anyhow::Result<()> {
+    match result {
+        Ok(val) => {
+            println!("{}", serde_json::to_string(&serde_json::json!({
+                "ok": true,
+                "data": val
+            }))?);
+        }
+        Err(e) => {
+            let out = match e.downcast_ref::<error::VgoogError>() {
+                Some(ve) => ve.to_json(),
+                None => serde_json::json!({ "ok": false, "error": e.to_string() }),
+            };
+            eprintln!("{}", serde_json::to_string(&out)?);
+            std::process::exit(1);
+        }
+    }
+    Ok(())
+}
+
+/// Non-interactive login: creates the account or replaces its tokens
+async fn auth_login(
+    account: Option<String>,
+    label: Option<String>,
+    device: bool,
+    client_id: Option<String>,
+    client_secret: Option<String>,
+    scopes: Vec<String>,
+) -> anyhow::Result<serde_json::Value> {
+    let mut config = if Config::exists() {
+        Config::load()?
+    } else {
+        Config::default()
+    };
+
+    let name = account.unwrap_or_else(|| config.active_account.clone());
+    let existing = config.accounts.get(&name).cloned();
+
+    // An empty flag or variable falls through to the next source
+    let given = |v: &String| !v.is_empty();
+    let client_id = client_id
+        .filter(given)
+        .or_else(|| std::env::var("VGOOG_CLIENT_ID").ok().filter(given))
+        .or_else(|| existing.as_ref().map(|a| a.auth.client_id.clone()).filter(given))
+        .ok_or_else(|| anyhow::anyhow!("No OAuth client ID: pass --client-id or set VGOOG_CLIENT_ID"))?;
+    let client_secret = client_secret
+        .filter(given)
+        .or_else(|| std::env::var("VGOOG_CLIENT_SECRET").ok().filter(given))
+        .or_else(|| existing.as_ref().map(|a| a.auth.client_secret.clone()).filter(given))
+        .ok_or_else(|| anyhow::anyhow!("No OAuth client secret: pass --client-secret or set VGOOG_CLIENT_SECRET"))?;
+
+    let endpoints = Endpoints::resolve(&config.endpoints)?;
+    let http = config.network.client()?;
+    let scopes: Vec<&str> = scopes.iter().map(String::as_str).collect();
+    // Re-authorizing keeps what the account was granted before
+    let incremental = existing.as_ref().is_some_and(|a| !a.auth.refresh_token.is_empty());
+    let grant = if device {
+        run_device_flow(&http, &endpoints, &client_id, &client_secret, &scopes).await?
+    } else {
+        auth::loopback::run(&http, &endpoints, &client_id, &client_secret, &scopes, incremental).await?
+    };
+
+    save_grant(&mut config, name, label, grant).await
+}
+
+/// Import a client secret (then sign in with it) or an authorized-user ADC
+/// file (refreshed once to prove it works) into an account
+async fn auth_import(
+    path: Option<std::path::PathBuf>,
+    account: Option<String>,
+    label: Option<String>,
+    device: bool,
+    scopes: Vec<String>,
+) -> anyhow::Result<serde_json::Value> {
+    use auth::import::CredentialsFile;
+
+    let path = match path {
+        Some(path) => path,
+        None => auth::import::default_adc_path()?,
+    };
+    match CredentialsFile::load(&path)? {
+        CredentialsFile::Client { client_id, client_secret } => {
+            auth_login(account, label, device, Some(client_id), Some(client_secret), scopes).await
+        }
+        CredentialsFile::AuthorizedUser { client_id, client_secret, refresh_token } => {
+            let mut config = if Config::exists() {
+                Config::load()?
+            } else {
+                Config::default()
+            };
+            let endpoints = Endpoints::resolve(&config.endpoints)?;
+            let http = config.network.client()?;
+            let grant = auth::import::redeem(&http, &endpoints, &client_id, &client_secret, &refresh_token).await?;
+            let name = account.unwrap_or_else(|| config.active_account.clone());
+            save_grant(&mut config, name, label, grant).await
+        }
+    }
+}
+
+/// Store a fresh grant as account `name`, activating it when no valid
+/// account is active
+async fn save_grant(
+    config: &mut Config,
+    name: String,
+    label: Option<String>,
+    grant: Grant,
+) -> anyhow::Result<serde_json::Value> {
+    let label = label
+        .or_else(|| config.accounts.get(&name).map(|a| a.label.clone()))
+        .unwrap_or_else(|| name.clone());
+    let token_expiry = grant.auth.token_expiry;
+    let granted = grant.scopes.clone();
+    config.update_async(|c| {
+        c.add_account(
+            name.clone(),
+            Account {
+                label: label.clone(),
+                scopes: grant.scopes,
+                auth: grant.auth,
+                ..Default::default()
+            },
+        );
+        if c.active().is_err() {
+            c.active_account = name.clone();
+        }
+    }).await?;
+
+    Ok(serde_json::json!({
+        "account": name,
+        "label": label,
+        "active": config.active_account == name,
+        "token_expiry": token_expiry,
+        "scopes": granted,
+    }))
+}
+
+/// Register a service-account key (if given) and an account impersonating
+/// `subject`, fetching a token first to validate the delegation
+async fn add_service_account(
+    account: String,
+    subject: Option<String>,
+    key_file: Option<std::path::PathBuf>,
+    key_name: Option<String>,
+    scopes: Vec<String>,
+    replace: bool,
+    label: Option<String>,
+) -> anyhow::Result<serde_json::Value> {
+    use vgoog::config::{ServiceAccountAuth, ServiceAccountKey, ServiceAccountRef};
+
+    let mut config = if Config::exists() {
+        Config::load()?
+    } else {
+        Config::default()
+    };
+
+    let key_name = match (key_file, key_name) {
+        (Some(path), name) => {
+            let key = auth::service_account::ServiceAccountKeyFile::load(&path)?;
+            let name = name.unwrap_or_else(|| {
+                key.client_email.split('@').next().unwrap_or("default").to_string()
+            });
+            // The key's scopes are shared by every account using it
+            if config.service_accounts.contains_key(&name) && !replace {
+                let users: Vec<&str> = config
+                    .accounts
+                    .iter()
+                    .filter(|(_, a)| a.service_account.as_ref().is_some_and(|sa| sa.key == name))
+                    .map(|(n, _)| n.as_str())
+                    .collect();
+                let used_by = if users.is_empty() { String::new() } else { format!(" and used by {}", users.join(", ")) };
+                anyhow::bail!(
+                    "Service account key '{name}' is already registered{used_by}; \
+                     pass --replace to overwrite it, or --key-name alone to reuse it"
+                );
+            }
+            let path = std::fs::canonicalize(&path)?;
+            config
+                .service_accounts
+                .insert(name.clone(), ServiceAccountKey { key_file: path, scopes });
+            name
+        }
+        (None, Some(name)) => {
+            if !config.service_accounts.contains_key(&name) {
+                anyhow::bail!("Service account key '{name}' is not registered; pass --key-file");
+            }
+            name
+        }
+        (None, None) => anyhow::bail!("Pass --key-file, or --key-name for an already registered key"),
+    };
+
+    // Validate the delegation before persisting anything
+    let key = &config.service_accounts[&key_name];
+    let sa = ServiceAccountAuth {
+        key_file: key.key_file.clone(),
+        scopes: key.scopes.clone(),
+        subject: subject.clone(),
+    };
+    let endpoints = Endpoints::resolve(&config.endpoints)?;
+    let http = config.network.client()?;
+    let (access_token, expiry) = auth::service_account::fetch_token(&http, &sa, &endpoints).await?;
+
+    let key = key.clone();
+    let label = label.unwrap_or_else(|| subject.clone().unwrap_or_else(|| account.clone()));
+    config.update_async(|c| {
+        c.service_accounts.insert(key_name.clone(), key);
+        c.add_account(
+            account.clone(),
+            Account {
+                label,
+                auth: AuthConfig {
+                    access_token,
+                    token_expiry: expiry,
+                    ..Default::default()
+                },
+                service_account: Some(ServiceAccountRef {
+                    key: key_name.clone(),
+                    subject: subject.clone(),
+                }),
+                ..Default::default()
+            },
+        );
+        if c.active().is_err() {
+            c.active_account = account.clone();
+        }
+    }).await?;
+
+    Ok(serde_json::json!({
+        "account": account,
+        "key": key_name,
+        "subject": subject,
+        "token_expiry": expiry,
+    }))
+}
+
+/// Seal the config's secrets, creating the key file if asked for one that
+/// does not exist yet
+fn auth_lock(key_file: Option<std::path::PathBuf>) -> anyhow::Result<serde_json::Value> {
+    use vgoog::vault::{self, VaultKey};
+
+    let mut config = Config::load()?;
+    let rekey = config.is_locked();
+    let (key, key_file) = match key_file {
+        Some(path) => {
+            let created = !path.exists();
+            if created {
+                vgoog::config::write_private(&path, &vault::generate_key()?)?;
+            }
+            let path = std::fs::canonicalize(&path)?;
+            (VaultKey::from_key_file(&path)?, Some((path, created)))
+        }
+        None => {
+            let passphrase = new_passphrase()?;
+            (VaultKey::from_passphrase(&passphrase)?, None)
+        }
+    };
+    config.lock(key, key_file.as_ref().map(|(path, _)| path.clone()))?;
+
+    Ok(serde_json::json!({
+        "locked": true,
+        "rekeyed": rekey,
+        "store": Config::vault_path()?,
+        "key_file": key_file.as_ref().map(|(path, _)| path),
+        "key_file_created": key_file.is_some_and(|(_, created)| created),
+        "accounts": config.accounts.len(),
+    }))
+}
+
+/// A passphrase to seal with: VGOOG_PASSPHRASE, or typed twice
+fn new_passphrase() -> anyhow::Result<String> {
+    use vgoog::vault;
+
+    if let Ok(passphrase) = std::env::var("VGOOG_PASSPHRASE") {
+        return Ok(passphrase);
+    }
+    let passphrase = vault::passphrase("New passphrase: ")?;
+    if vault::prompt_hidden("Repeat passphrase: ")? != passphrase {
+        anyhow::bail!("Passphrases do not match");
+    }
+    Ok(passphrase)
+}
+
+fn auth_unlock() -> anyhow::Result<serde_json::Value> {
+    let mut config = Config::load()?;
+    config.unlock()?;
+    Ok(serde_json::json!({
+        "locked": false,
+        "config": Config::config_path()?,
+    }))
+}
+
 // ── Utilities ──
 
 fn print_banner(subtitle: &str) {
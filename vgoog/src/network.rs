@@ -0,0 +1,102 @@
+use crate::config::Config;
+use crate::error::{Result, VgoogError};
+use reqwest::{Certificate, Client, Proxy};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Sent with every request, followed by `user_agent_suffix` when set
+pub const USER_AGENT: &str = concat!("vgoog/", env!("CARGO_PKG_VERSION"));
+
+/// Connection settings (`[network]` in config.toml). One HTTP client is
+/// built from them and used for everything: API calls, uploads,
+/// downloads and the OAuth endpoints.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkConfig {
+    /// Proxy for every request, e.g. `http://proxy.corp:3128`. Without it
+    /// `HTTPS_PROXY`, `ALL_PROXY` and `NO_PROXY` are honoured.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    /// PEM files of extra root certificates to trust, e.g. the CA of a
+    /// TLS-inspecting proxy; the built-in roots stay trusted
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub ca_certificates: Vec<PathBuf>,
+    /// Time allowed to establish a connection, TLS included
+    pub connect_timeout_secs: u64,
+    /// Longest wait for the server between reads, so slow transfers still
+    /// finish but a stalled one fails
+    pub read_timeout_secs: u64,
+    /// Appended to the `vgoog/<version>` user agent
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_agent_suffix: Option<String>,
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        Self {
+            proxy: None,
+            ca_certificates: Vec::new(),
+            connect_timeout_secs: 30,
+            read_timeout_secs: 300,
+            user_agent_suffix: None,
+        }
+    }
+}
+
+impl NetworkConfig {
+    /// The saved config's settings, if there is one, for flows that run
+    /// before a `GoogleClient` exists
+    pub fn current() -> Result<Self> {
+        if Config::exists() {
+            Ok(Config::load_locked()?.network)
+        } else {
+            Ok(Self::default())
+        }
+    }
+
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn user_agent(&self) -> String {
+        match &self.user_agent_suffix {
+            Some(suffix) => format!("{USER_AGENT} {suffix}"),
+            None => USER_AGENT.to_string(),
+        }
+    }
+
+    /// Build the HTTP client these settings describe
+    pub fn client(&self) -> Result<Client> {
+        let mut builder = Client::builder()
+            .user_agent(self.user_agent())
+            .connect_timeout(Duration::from_secs(self.connect_timeout_secs))
+            .read_timeout(Duration::from_secs(self.read_timeout_secs));
+
+        if let Some(proxy) = &self.proxy {
+            let proxy = Proxy::all(proxy)
+                .map_err(|e| VgoogError::Config(format!("Invalid proxy '{proxy}': {e}")))?;
+            builder = builder.proxy(proxy);
+        }
+        for path in &self.ca_certificates {
+            for cert in load_certificates(path)? {
+                builder = builder.add_root_certificate(cert);
+            }
+        }
+
+        builder
+            .build()
+            .map_err(|e| VgoogError::Config(format!("Cannot create HTTP client: {e}")))
+    }
+}
+
+/// Every certificate in a PEM file, refusing files that hold none
+fn load_certificates(path: &Path) -> Result<Vec<Certificate>> {
+    let invalid = |reason: String| VgoogError::Config(format!("CA certificates {}: {reason}", path.display()));
+    let pem = std::fs::read(path).map_err(|e| invalid(e.to_string()))?;
+    let certs = Certificate::from_pem_bundle(&pem).map_err(|e| invalid(e.to_string()))?;
+    if certs.is_empty() {
+        return Err(invalid("no PEM certificates found".into()));
+    }
+    Ok(certs)
+}
@@ -0,0 +1,117 @@
+//! Following `nextPageToken` through any list call. Google's list methods
+//! return one page and a token for the next; the streams here keep asking
+//! until the token runs out, so callers never thread it by hand.
+
+use crate::error::Result;
+use futures_util::stream::{self, Stream, StreamExt};
+use serde_json::Value;
+use std::future::Future;
+
+/// The token a list response hands out for its next page, if any
+pub fn next_page_token(page: &Value) -> Option<&str> {
+    page.get("nextPageToken").and_then(|v| v.as_str()).filter(|t| !t.is_empty())
+}
+
+/// The field holding a list response's items and the items themselves.
+/// `field` is where the list call puts them (`messages`, `files`,
+/// `connections`, ...); a response without it has no items. Only when the
+/// field is unknown is it guessed: `items` where present, otherwise the
+/// response's first array.
+pub fn page_items<'a>(page: &'a Value, field: Option<&'a str>) -> Option<(&'a str, &'a Vec<Value>)> {
+    let fields = page.as_object()?;
+    if let Some(field) = field {
+        return Some((field, fields.get(field)?.as_array()?));
+    }
+    if let Some(items) = fields.get("items").and_then(|v| v.as_array()) {
+        return Some(("items", items));
+    }
+    fields.iter().find_map(|(key, value)| Some((key.as_str(), value.as_array()?)))
+}
+
+/// Every page of a list call, starting from the first. `fetch` gets the
+/// page token to send, `None` for the first page.
+pub fn pages<F, Fut>(fetch: F) -> impl Stream<Item = Result<Value>>
+where
+    F: FnMut(Option<String>) -> Fut,
+    Fut: Future<Output = Result<Value>>,
+{
+    pages_from(fetch, None)
+}
+
+/// Every page from `start` on. The stream ends after an error, and when a
+/// page hands back the token it was fetched with, so a misbehaving server
+/// can't loop it forever.
+pub fn pages_from<F, Fut>(fetch: F, start: Option<String>) -> impl Stream<Item = Result<Value>>
+where
+    F: FnMut(Option<String>) -> Fut,
+    Fut: Future<Output = Result<Value>>,
+{
+    // `None` once there is nothing left to fetch
+    stream::unfold((fetch, Some(start)), |(mut fetch, token)| async move {
+        let token = token?;
+        match fetch(token.clone()).await {
+            Ok(page) => {
+                let next = next_page_token(&page)
+                    .filter(|next| Some(*next) != token.as_deref())
+                    .map(|next| Some(next.to_string()));
+                Some((Ok(page), (fetch, next)))
+            }
+            Err(e) => Some((Err(e), (fetch, None))),
+        }
+    })
+}
+
+/// The items of every page, one at a time, stopping after `limit` of them
+/// without fetching pages it doesn't need. `field` is as for `page_items`.
+pub fn items<'a, F, Fut>(fetch: F, field: Option<&'a str>, limit: Option<usize>) -> impl Stream<Item = Result<Value>> + 'a
+where
+    F: FnMut(Option<String>) -> Fut + 'a,
+    Fut: Future<Output = Result<Value>> + 'a,
+{
+    pages(fetch)
+        .flat_map(move |page| {
+            let items: Vec<Result<Value>> = match page {
+                Ok(page) => page_items(&page, field).map(|(_, items)| items.clone()).unwrap_or_default().into_iter().map(Ok).collect(),
+                Err(e) => vec![Err(e)],
+            };
+            stream::iter(items)
+        })
+        .take(limit.unwrap_or(usize::MAX))
+}
+
+/// Everything a list call returns as one response: the first page with
+/// its items replaced by those of every page (up to `limit`) and no
+/// `nextPageToken`. With `field` known, a list with no items at all gets
+/// it as an empty array.
+pub async fn collect<F, Fut>(fetch: F, field: Option<&str>, limit: Option<usize>) -> Result<Value>
+where
+    F: FnMut(Option<String>) -> Fut,
+    Fut: Future<Output = Result<Value>>,
+{
+    let mut pages = std::pin::pin!(pages(fetch));
+    let mut first: Option<Value> = None;
+    let mut found = field.map(String::from);
+    let mut items = Vec::new();
+
+    while let Some(page) = pages.next().await {
+        let page = page?;
+        if let Some((key, page_items)) = page_items(&page, field) {
+            found.get_or_insert_with(|| key.to_string());
+            items.extend(page_items.iter().cloned());
+        }
+        first.get_or_insert(page);
+        if limit.is_some_and(|limit| items.len() >= limit) {
+            break;
+        }
+    }
+
+    let mut response = first.unwrap_or(Value::Null);
+    if let Some(fields) = response.as_object_mut() {
+        fields.remove("nextPageToken");
+        if let Some(field) = found {
+            items.truncate(limit.unwrap_or(usize::MAX));
+            fields.insert(field, Value::Array(items));
+        }
+    }
+    Ok(response)
+}
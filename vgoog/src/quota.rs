@@ -0,0 +1,340 @@
+//! Client-side pacing against Google's per-user quotas: one token bucket
+//! per API, filled at the API's budget rate, plus a log of what each
+//! command spent for `vgoog quota`. Bucket levels are saved when a command
+//! ends and picked up by the next, so a script running `vgoog exec` in a
+//! loop is paced like one long command.
+
+use crate::config::{self, Config, ConfigLock};
+use crate::endpoints::Endpoints;
+use crate::error::Result;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+pub const GMAIL: &str = "gmail.googleapis.com";
+pub const CALENDAR: &str = "calendar-json.googleapis.com";
+pub const DRIVE: &str = "drive.googleapis.com";
+pub const SHEETS: &str = "sheets.googleapis.com";
+pub const DOCS: &str = "docs.googleapis.com";
+pub const SLIDES: &str = "slides.googleapis.com";
+pub const FORMS: &str = "forms.googleapis.com";
+pub const TASKS: &str = "tasks.googleapis.com";
+pub const PEOPLE: &str = "people.googleapis.com";
+pub const SCRIPT: &str = "script.googleapis.com";
+
+/// Usage log in the config dir, one JSON entry per command
+pub const USAGE_LOG: &str = "usage.jsonl";
+
+/// Bucket levels left by the last commands, next to the usage log
+pub const BUCKETS_FILE: &str = "quota-buckets.json";
+
+/// The log is cut back to its newer half once it grows past this
+const MAX_LOG_BYTES: u64 = 256 * 1024;
+
+/// A budget of `units` per `window_secs` (`[rate_limits."<api>"]` in
+/// config.toml). Zero units turns pacing off for the API.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RateLimit {
+    pub units: u32,
+    pub window_secs: u64,
+}
+
+impl RateLimit {
+    pub const fn new(units: u32, window_secs: u64) -> Self {
+        Self { units, window_secs }
+    }
+
+    /// Units regained per second
+    fn rate(&self) -> f64 {
+        self.units as f64 / self.window_secs.max(1) as f64
+    }
+}
+
+/// Google's default per-user quotas, rounded down a little
+pub const DEFAULT_LIMITS: &[(&str, RateLimit)] = &[
+    (GMAIL, RateLimit::new(250, 1)),
+    (CALENDAR, RateLimit::new(600, 60)),
+    (DRIVE, RateLimit::new(20_000, 100)),
+    (SHEETS, RateLimit::new(60, 60)),
+    (DOCS, RateLimit::new(300, 60)),
+    (SLIDES, RateLimit::new(600, 60)),
+    (FORMS, RateLimit::new(300, 60)),
+    (TASKS, RateLimit::new(500, 100)),
+    (PEOPLE, RateLimit::new(90, 60)),
+    (SCRIPT, RateLimit::new(100, 100)),
+];
+
+/// The defaults with the config's `[rate_limits]` applied on top
+pub fn limits(overrides: &BTreeMap<String, RateLimit>) -> BTreeMap<String, RateLimit> {
+    let mut limits: BTreeMap<String, RateLimit> =
+        DEFAULT_LIMITS.iter().map(|(api, limit)| (api.to_string(), *limit)).collect();
+    limits.extend(overrides.iter().map(|(api, limit)| (api.clone(), *limit)));
+    limits
+}
+
+/// What one request costs against an API's budget
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Charge {
+    pub api: &'static str,
+    pub units: u32,
+}
+
+/// The API a request URL belongs to and its cost: Gmail's per-method quota
+/// units, one unit elsewhere. `None` for OAuth and batch endpoints; a batch
+/// is charged for the calls inside it.
+pub fn charge(endpoints: &Endpoints, method: &str, url: &str) -> Option<Charge> {
+    let bases = [
+        (&endpoints.gmail, GMAIL),
+        (&endpoints.calendar, CALENDAR),
+        (&endpoints.drive, DRIVE),
+        (&endpoints.drive_upload, DRIVE),
+        (&endpoints.sheets, SHEETS),
+        (&endpoints.docs, DOCS),
+        (&endpoints.slides, SLIDES),
+        (&endpoints.forms, FORMS),
+        (&endpoints.tasks, TASKS),
+        (&endpoints.people, PEOPLE),
+        (&endpoints.script, SCRIPT),
+    ];
+    let (rest, api) = bases
+        .into_iter()
+        .filter_map(|(base, api)| {
+            let rest = url.strip_prefix(base.as_str())?;
+            (rest.is_empty() || rest.starts_with(['/', '?'])).then_some((base.len(), rest, api))
+        })
+        .max_by_key(|(len, _, _)| *len)
+        .map(|(_, rest, api)| (rest, api))?;
+
+    let path = rest.split('?').next().unwrap_or_default();
+    let units = match api {
+        GMAIL => gmail_units(method, path),
+        _ => 1,
+    };
+    Some(Charge { api, units })
+}
+
+/// Gmail's documented cost of a call, by path under `users/me`
+fn gmail_units(method: &str, path: &str) -> u32 {
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+    match (method, segments.as_slice()) {
+        (_, ["messages" | "drafts", "send"]) | (_, ["watch"]) => 100,
+        (_, ["stop"]) | (_, ["messages", "batchModify" | "batchDelete"]) => 50,
+        ("POST", ["messages"]) | (_, ["messages", "import"]) => 25,
+        ("DELETE", ["threads", _]) => 20,
+        ("PUT", ["drafts", _]) => 15,
+        (_, ["threads", ..]) | ("POST", ["drafts"]) | ("DELETE", ["drafts" | "messages", _]) => 10,
+        (_, ["history"]) => 2,
+        (_, ["profile"]) | (_, ["labels", ..]) | (_, ["settings", ..]) => 1,
+        _ => 5,
+    }
+}
+
+/// What one API was sent, for a command or the life of a client
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ApiUsage {
+    pub requests: u64,
+    pub units: u64,
+    /// Time spent waiting for budget
+    pub waited_ms: u64,
+}
+
+struct Bucket {
+    units: f64,
+    refilled: Instant,
+}
+
+/// An API's bucket as a command left it, in `quota-buckets.json`
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SavedBucket {
+    pub units: f64,
+    pub at: DateTime<Utc>,
+}
+
+impl SavedBucket {
+    /// The level after refilling from `at` until `now`
+    fn level(&self, limit: &RateLimit, now: DateTime<Utc>) -> f64 {
+        let elapsed = (now - self.at).num_milliseconds().max(0) as f64 / 1000.0;
+        (self.units + elapsed * limit.rate()).min(limit.units as f64)
+    }
+}
+
+/// Token buckets for every API, shared by all clients of one process
+pub struct RateLimiter {
+    limits: BTreeMap<String, RateLimit>,
+    buckets: Mutex<HashMap<&'static str, Bucket>>,
+    usage: Mutex<BTreeMap<String, ApiUsage>>,
+}
+
+impl RateLimiter {
+    pub fn new(limits: BTreeMap<String, RateLimit>) -> Self {
+        Self {
+            limits,
+            buckets: Mutex::new(HashMap::new()),
+            usage: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Start from the levels saved by earlier commands, refilled for the
+    /// time since. APIs with nothing saved start full.
+    pub fn resume(&self, saved: &BTreeMap<String, SavedBucket>) {
+        let now = Utc::now();
+        let mut buckets = self.buckets.lock().unwrap();
+        for (api, bucket) in saved {
+            let known = DEFAULT_LIMITS.iter().map(|(known, _)| *known).find(|known| known == api);
+            if let (Some(api), Some(limit)) = (known, self.limits.get(api.as_str()).filter(|l| l.units > 0)) {
+                let units = bucket.level(limit, now);
+                buckets.insert(api, Bucket { units, refilled: Instant::now() });
+            }
+        }
+    }
+
+    /// Take what this limiter spent out of the saved levels. Each API's
+    /// level is re-read under the lock and refilled to now before the
+    /// units are taken, so commands running side by side all count.
+    pub fn save(&self, _lock: &ConfigLock) -> Result<()> {
+        let path = buckets_path()?;
+        let mut saved = load_buckets(&path);
+        let now = Utc::now();
+        for (api, usage) in self.usage() {
+            let Some(limit) = self.limits.get(&api).filter(|l| l.units > 0) else {
+                continue;
+            };
+            let level = saved.get(&api).map_or(limit.units as f64, |bucket| bucket.level(limit, now));
+            let units = (level - usage.units as f64).max(0.0);
+            saved.insert(api, SavedBucket { units, at: now });
+        }
+        // A bucket that has refilled tells the next command nothing
+        saved.retain(|api, bucket| {
+            self.limits.get(api).is_some_and(|limit| bucket.level(limit, now) < limit.units as f64)
+        });
+        config::write_private(&path, serde_json::to_string_pretty(&saved)?.as_bytes())
+    }
+
+    /// Wait until the API's bucket holds `charge.units`, then take them.
+    /// Buckets start full unless resumed, so short commands never wait; a
+    /// call costing more than the whole budget waits for a full bucket.
+    pub async fn acquire(&self, charge: Charge) {
+        let started = Instant::now();
+        if let Some(limit) = self.limits.get(charge.api).filter(|l| l.units > 0) {
+            let cost = charge.units.min(limit.units) as f64;
+            loop {
+                let shortfall = {
+                    let mut buckets = self.buckets.lock().unwrap();
+                    let bucket = buckets
+                        .entry(charge.api)
+                        .or_insert_with(|| Bucket { units: limit.units as f64, refilled: started });
+                    let now = Instant::now();
+                    let regained = now.duration_since(bucket.refilled).as_secs_f64() * limit.rate();
+                    bucket.units = (bucket.units + regained).min(limit.units as f64);
+                    bucket.refilled = now;
+                    if bucket.units >= cost {
+                        bucket.units -= cost;
+                        None
+                    } else {
+                        Some(cost - bucket.units)
+                    }
+                };
+                match shortfall {
+                    Some(units) => tokio::time::sleep(Duration::from_secs_f64(units / limit.rate())).await,
+                    None => break,
+                }
+            }
+        }
+
+        let mut usage = self.usage.lock().unwrap();
+        let entry = usage.entry(charge.api.to_string()).or_default();
+        entry.requests += 1;
+        entry.units += charge.units as u64;
+        entry.waited_ms += started.elapsed().as_millis() as u64;
+    }
+
+    pub fn limits(&self) -> &BTreeMap<String, RateLimit> {
+        &self.limits
+    }
+
+    /// Everything acquired so far, by API
+    pub fn usage(&self) -> BTreeMap<String, ApiUsage> {
+        self.usage.lock().unwrap().clone()
+    }
+}
+
+/// One command's line in the usage log
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UsageEntry {
+    pub at: DateTime<Utc>,
+    pub command: String,
+    pub account: String,
+    pub elapsed_ms: u64,
+    pub apis: BTreeMap<String, ApiUsage>,
+}
+
+pub fn usage_log_path() -> Result<PathBuf> {
+    Ok(Config::config_dir()?.join(USAGE_LOG))
+}
+
+pub fn buckets_path() -> Result<PathBuf> {
+    Ok(Config::config_dir()?.join(BUCKETS_FILE))
+}
+
+/// The saved bucket levels; a missing or unreadable file means all full
+pub fn load_buckets(path: &Path) -> BTreeMap<String, SavedBucket> {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|content| serde_json::from_str(&content).ok())
+        .unwrap_or_default()
+}
+
+/// Append an entry to the usage log (0600). The lock keeps the trim and
+/// the append of concurrent commands from interleaving.
+pub fn record(entry: &UsageEntry, _lock: &ConfigLock) -> Result<()> {
+    let path = usage_log_path()?;
+    if let Some(dir) = path.parent() {
+        config::create_private_dir(dir)?;
+    }
+    if std::fs::metadata(&path).is_ok_and(|m| m.len() > MAX_LOG_BYTES) {
+        let content = std::fs::read_to_string(&path)?;
+        let lines: Vec<&str> = content.lines().collect();
+        let kept = lines[lines.len() / 2..].join("\n");
+        config::write_private(&path, (kept + "\n").as_bytes())?;
+    }
+    let mut options = std::fs::OpenOptions::new();
+    options.create(true).append(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    // One write per line, so even a reader without the lock never sees half an entry
+    let line = serde_json::to_string(entry)? + "\n";
+    options.open(&path)?.write_all(line.as_bytes())?;
+    Ok(())
+}
+
+/// The newest `count` log entries, newest first; unreadable lines are skipped
+pub fn recent(count: usize) -> Result<Vec<UsageEntry>> {
+    let content = match std::fs::read_to_string(usage_log_path()?) {
+        Ok(content) => content,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+    Ok(content
+        .lines()
+        .rev()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .take(count)
+        .collect())
+}
+
+/// Share of the budget a command used: its units against what the bucket
+/// could have given over the command's run (a full bucket plus the refill)
+pub fn budget_percent(usage: &ApiUsage, limit: &RateLimit, elapsed_ms: u64) -> Option<f64> {
+    if limit.units == 0 {
+        return None;
+    }
+    let available = limit.units as f64 + limit.rate() * elapsed_ms as f64 / 1000.0;
+    Some((usage.units as f64 * 100.0 / available * 10.0).round() / 10.0)
+}
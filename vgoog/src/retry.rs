@@ -0,0 +1,88 @@
+use reqwest::header::HeaderMap;
+use reqwest::{Method, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+
+/// Per-account retry settings (`[accounts.<name>.retry]` in config.toml)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetryPolicy {
+    /// Total attempts including the first one; 1 disables retries
+    pub max_attempts: u32,
+    /// Backoff ceiling for the first retry, doubled on each further attempt
+    pub base_delay_ms: u64,
+    /// Upper bound for any single wait, including server-sent `Retry-After`
+    pub max_delay_ms: u64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 4,
+            base_delay_ms: 500,
+            max_delay_ms: 32_000,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Full-jitter exponential backoff for the given (1-based) failed attempt
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let exp = self
+            .base_delay_ms
+            .saturating_mul(1u64 << attempt.saturating_sub(1).min(20));
+        let ceiling = exp.min(self.max_delay_ms);
+        Duration::from_millis(rand::random_range(0..=ceiling))
+    }
+
+    /// Delay before the next attempt, or `None` once attempts are exhausted or
+    /// the server asks us to wait longer than `max_delay_ms`
+    pub fn next_delay(&self, attempt: u32, retry_after: Option<Duration>) -> Option<Duration> {
+        if attempt >= self.max_attempts {
+            return None;
+        }
+        match retry_after {
+            Some(d) if d > Duration::from_millis(self.max_delay_ms) => None,
+            Some(d) => Some(d),
+            None => Some(self.backoff(attempt)),
+        }
+    }
+}
+
+/// Methods that can be replayed without changing the outcome (RFC 9110 §9.2.2)
+pub fn is_idempotent(method: &Method) -> bool {
+    matches!(
+        *method,
+        Method::GET | Method::HEAD | Method::PUT | Method::DELETE | Method::OPTIONS
+    )
+}
+
+/// 429 means the request was rejected unprocessed, so it is always safe to
+/// replay; server errors only when the request itself is replay-safe
+pub fn is_retryable_status(status: StatusCode, replay_safe: bool) -> bool {
+    match status {
+        StatusCode::TOO_MANY_REQUESTS => true,
+        StatusCode::INTERNAL_SERVER_ERROR
+        | StatusCode::BAD_GATEWAY
+        | StatusCode::SERVICE_UNAVAILABLE
+        | StatusCode::GATEWAY_TIMEOUT => replay_safe,
+        _ => false,
+    }
+}
+
+/// Connection failures happen before the server sees the request; timeouts
+/// and dropped responses may not
+pub fn is_retryable_error(err: &reqwest::Error, replay_safe: bool) -> bool {
+    err.is_connect() || (replay_safe && (err.is_timeout() || err.is_request() || err.is_body()))
+}
+
+/// Parse `Retry-After` as delta-seconds or an HTTP date
+pub fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    let value = headers.get(reqwest::header::RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(secs) = value.parse::<u64>() {
+        return Some(Duration::from_secs(secs));
+    }
+    let at = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let delta = at.with_timezone(&chrono::Utc) - chrono::Utc::now();
+    Some(delta.to_std().unwrap_or(Duration::ZERO))
+}
@@ -0,0 +1,117 @@
+//! Progress reporting for long uploads and downloads, shared by the CLI and
+//! the TUI.
+
+use crate::error::Result;
+use md5::{Digest, Md5};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use tokio::io::AsyncReadExt;
+
+/// Default resumable upload chunk; Drive requires multiples of 256 KiB
+pub const CHUNK_SIZE: usize = 8 * 1024 * 1024;
+
+/// Granularity Drive accepts for every chunk but the last
+pub const CHUNK_ALIGN: usize = 256 * 1024;
+
+/// Bytes moved so far out of `total`, when the size is known up front
+/// (exports and chunked responses don't say)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    pub done: u64,
+    pub total: Option<u64>,
+}
+
+impl Progress {
+    pub fn percent(&self) -> Option<u16> {
+        let total = self.total?;
+        Some((self.done.min(total) * 100).checked_div(total).map_or(100, |p| p as u16))
+    }
+}
+
+impl std::fmt::Display for Progress {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (self.total, self.percent()) {
+            (Some(total), Some(percent)) => write!(
+                f,
+                "{} / {} ({percent}%)",
+                human_bytes(self.done),
+                human_bytes(total)
+            ),
+            _ => write!(f, "{}", human_bytes(self.done)),
+        }
+    }
+}
+
+/// Called after every chunk sent or received
+pub type ProgressFn<'a> = dyn Fn(Progress) + Send + Sync + 'a;
+
+/// The transfer in flight, if any, for a UI to poll while it waits
+#[derive(Debug, Clone, Default)]
+pub struct TransferStatus {
+    inner: Arc<Mutex<Option<(String, Progress)>>>,
+}
+
+impl TransferStatus {
+    pub fn start(&self, label: impl Into<String>, total: Option<u64>) {
+        *self.lock() = Some((label.into(), Progress { done: 0, total }));
+    }
+
+    pub fn update(&self, progress: Progress) {
+        if let Some((_, current)) = self.lock().as_mut() {
+            *current = progress;
+        }
+    }
+
+    pub fn finish(&self) {
+        *self.lock() = None;
+    }
+
+    pub fn get(&self) -> Option<(String, Progress)> {
+        self.lock().clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Option<(String, Progress)>> {
+        self.inner.lock().expect("transfer status poisoned")
+    }
+}
+
+/// Where a download collects data until it completes: `<path>.part`
+pub fn part_path(path: &Path) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".part");
+    path.with_file_name(name)
+}
+
+/// MD5 of a file on disk, read in chunks
+pub async fn file_md5(path: &Path) -> Result<String> {
+    let mut file = tokio::fs::File::open(path).await?;
+    let mut md5 = Md5::new();
+    let mut buf = vec![0u8; 1024 * 1024];
+    loop {
+        match file.read(&mut buf).await? {
+            0 => return Ok(format!("{:x}", md5.finalize())),
+            n => md5.update(&buf[..n]),
+        }
+    }
+}
+
+/// MD5 of a buffer in hex, as in Drive's `md5Checksum`
+pub fn md5_hex(data: &[u8]) -> String {
+    format!("{:x}", Md5::digest(data))
+}
+
+/// `1536` → `1.5 KiB`
+pub fn human_bytes(bytes: u64) -> String {
+    const UNITS: &[&str] = &["B", "KiB", "MiB", "GiB", "TiB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{value:.1} {}", UNITS[unit])
+    }
+}
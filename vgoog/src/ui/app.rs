@@ -1,4 +1,6 @@
 use crate::client::GoogleClient;
+use crate::error::VgoogError;
+use crate::transfer::TransferStatus;
 use serde_json::Value;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -44,6 +46,86 @@ impl Service {
         }
     }
 
+    /// Service name used by `vgoog exec`
+    pub fn key(&self) -> &'static str {
+        match self {
+            Service::Gmail => "gmail",
+            Service::Calendar => "calendar",
+            Service::Drive => "drive",
+            Service::Sheets => "sheets",
+            Service::Docs => "docs",
+            Service::Slides => "slides",
+            Service::Forms => "forms",
+            Service::Tasks => "tasks",
+            Service::People => "contacts",
+            Service::AppsScript => "apps_script",
+        }
+    }
+
+    /// OAuth scopes every action of the service needs
+    pub fn scopes(&self) -> &'static [&'static str] {
+        match self {
+            Service::Gmail => &[
+                "https://www.googleapis.com/auth/gmail.modify",
+                "https://www.googleapis.com/auth/gmail.compose",
+                "https://www.googleapis.com/auth/gmail.settings.basic",
+                "https://www.googleapis.com/auth/gmail.settings.sharing",
+            ],
+            Service::Calendar => &["https://www.googleapis.com/auth/calendar"],
+            Service::Drive => &["https://www.googleapis.com/auth/drive"],
+            Service::Sheets => &["https://www.googleapis.com/auth/spreadsheets"],
+            Service::Docs => &["https://www.googleapis.com/auth/documents"],
+            Service::Slides => &["https://www.googleapis.com/auth/presentations"],
+            Service::Forms => &[
+                "https://www.googleapis.com/auth/forms.body",
+                "https://www.googleapis.com/auth/forms.responses.readonly",
+            ],
+            Service::Tasks => &["https://www.googleapis.com/auth/tasks"],
+            Service::People => &["https://www.googleapis.com/auth/contacts"],
+            Service::AppsScript => &[
+                "https://www.googleapis.com/auth/script.projects",
+                "https://www.googleapis.com/auth/script.processes",
+            ],
+        }
+    }
+
+    /// Narrower scopes enough for the service's read-only actions
+    pub fn read_scopes(&self) -> &'static [&'static str] {
+        match self {
+            Service::Gmail => &["https://www.googleapis.com/auth/gmail.readonly"],
+            Service::Calendar => &["https://www.googleapis.com/auth/calendar.readonly"],
+            Service::Drive => &["https://www.googleapis.com/auth/drive.readonly"],
+            Service::Sheets => &["https://www.googleapis.com/auth/spreadsheets.readonly"],
+            Service::Docs => &["https://www.googleapis.com/auth/documents.readonly"],
+            Service::Slides => &["https://www.googleapis.com/auth/presentations.readonly"],
+            Service::Forms => &[
+                "https://www.googleapis.com/auth/forms.body.readonly",
+                "https://www.googleapis.com/auth/forms.responses.readonly",
+            ],
+            Service::Tasks => &["https://www.googleapis.com/auth/tasks.readonly"],
+            Service::People => &["https://www.googleapis.com/auth/contacts.readonly"],
+            Service::AppsScript => &[
+                "https://www.googleapis.com/auth/script.projects.readonly",
+                "https://www.googleapis.com/auth/script.processes",
+            ],
+        }
+    }
+
+    /// The exec action behind a TUI action that needs scopes beyond the
+    /// service's own
+    pub fn scoped_action(&self, action: &str) -> Option<&'static str> {
+        match (self, action) {
+            (Service::People, "Directory") => Some("search_directory"),
+            (Service::People, "Other Contacts") => Some("list_other_contacts"),
+            _ => None,
+        }
+    }
+
+    /// The service `vgoog exec` knows as `key`
+    pub fn from_key(key: &str) -> Option<Service> {
+        Service::ALL.into_iter().find(|s| s.key() == key)
+    }
+
     pub fn icon(&self) -> &'static str {
         match self {
             Service::Gmail => "📧",
@@ -72,7 +154,7 @@ impl Service {
             ],
             Service::Drive => &[
                 "My Files", "Search", "Upload", "Create Folder", "Shared",
-                "Recent", "Starred", "Trash", "Storage Info", "Shared Drives",
+                "Recent", "Starred", "Trash", "Storage Info", "Shared Drives", "Download",
             ],
             Service::Sheets => &[
                 "Open Sheet", "Create Sheet", "Read Range", "Write Range",
@@ -135,6 +217,8 @@ pub struct App {
     pub detail: Option<Value>,
     pub status_message: String,
     pub loading: bool,
+    /// Upload in flight, drawn in the status bar while it runs
+    pub transfer: TransferStatus,
 
     // Input
     pub input_buffer: String,
@@ -145,6 +229,8 @@ pub struct App {
     // Pagination
     pub next_page_token: Option<String>,
     pub page_info: String,
+    /// The list call behind `items`, for loading its next page
+    pub list_call: Option<ListCall>,
 
     // Confirm dialog
     pub confirm_message: String,
@@ -169,6 +255,17 @@ pub struct ListItem {
     pub metadata: Value,
 }
 
+/// A paged list on screen: the `vgoog exec` call that fetched it, so "load
+/// more" can ask for the next page with the same arguments
+#[derive(Debug, Clone)]
+pub struct ListCall {
+    pub service: &'static str,
+    pub action: &'static str,
+    pub args: Value,
+    /// Builds a row from one item of a page and the call's args
+    pub to_item: fn(&Value, &Value) -> ListItem,
+}
+
 #[derive(Debug, Clone)]
 pub struct InputField {
     pub label: String,
@@ -208,12 +305,14 @@ impl App {
             detail: None,
             status_message: "Welcome to vgoog! Select a service.".to_string(),
             loading: false,
+            transfer: TransferStatus::default(),
             input_buffer: String::new(),
             input_target: InputTarget::Search,
             input_fields: Vec::new(),
             input_field_cursor: 0,
             next_page_token: None,
             page_info: String::new(),
+            list_call: None,
             should_quit: false,
             scroll_offset: 0,
             confirm_message: String::new(),
@@ -317,10 +416,32 @@ impl App {
         self.status_message = msg.into();
     }
 
+    /// Show an error in the status bar, with a hint for the cases a user can act on
+    pub fn set_error(&mut self, err: &VgoogError) {
+        let grant;
+        let hint = match err {
+            VgoogError::InsufficientScopes(_) => match self.service {
+                Some(service) => {
+                    grant = format!(" — grant it with `vgoog auth login --service {}`", service.key());
+                    grant.as_str()
+                }
+                None => " — re-authorize with `vgoog auth login`",
+            },
+            VgoogError::QuotaExceeded(_) => " — quota resets later, try again then",
+            VgoogError::UserRateLimited { .. } | VgoogError::RateLimited { .. } => " — slow down and retry",
+            _ => "",
+        };
+        self.set_status(format!("Error: {err}{hint}"));
+    }
+
+    /// Replace the list on screen; a paged list sets its pagination after
     pub fn set_items(&mut self, items: Vec<ListItem>) {
         self.items = items;
         self.item_cursor = 0;
         self.scroll_offset = 0;
+        self.next_page_token = None;
+        self.page_info.clear();
+        self.list_call = None;
     }
 
     pub fn scroll_detail_up(&mut self) {
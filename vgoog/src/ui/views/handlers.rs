@@ -1,5 +1,6 @@
 use crate::api::gmail::{self, GmailApi};
 use crate::api::calendar::CalendarApi;
+use crate::api::models::{Event, File, Message, Person, Task};
 use crate::api::drive::DriveApi;
 use crate::api::sheets::SheetsApi;
 use crate::api::docs::DocsApi;
@@ -8,7 +9,11 @@ use crate::api::forms::FormsApi;
 use crate::api::tasks::TasksApi;
 use crate::api::people::PeopleApi;
 use crate::api::apps_script::AppsScriptApi;
-use crate::ui::app::{App, InputField, ListItem, Screen, Service};
+use crate::client::GoogleClient;
+use crate::pager;
+use crate::ui::app::{App, InputField, ListCall, ListItem, Screen, Service};
+use futures_util::StreamExt;
+use serde::Deserialize;
 use serde_json::Value;
 
 pub async fn execute_action(app: &mut App) {
@@ -31,7 +36,7 @@ pub async fn execute_action(app: &mut App) {
 
     app.loading = false;
     if let Err(e) = result {
-        app.set_status(format!("Error: {e}"));
+        app.set_error(&e);
     }
 }
 
@@ -41,10 +46,13 @@ pub async fn execute_detail(app: &mut App) {
         app.loading = true;
 
         let result = match service {
-            Service::Gmail => {
-                let api = GmailApi::new(&app.client);
-                api.get_message(&item.id, "full").await
-            }
+            Service::Gmail => match item_client(app, &item).await {
+                Ok(bound) => {
+                    let api = GmailApi::new(bound.as_ref().unwrap_or(&app.client));
+                    api.get_message(&item.id, "full").await
+                }
+                Err(e) => Err(e),
+            },
             Service::Calendar => {
                 let cal_id = item.metadata.get("calendarId")
                     .and_then(|v| v.as_str())
@@ -96,21 +104,33 @@ pub async fn execute_detail(app: &mut App) {
                 app.scroll_offset = 0;
                 app.set_status("Detail loaded. ↑↓ to scroll, Esc to go back.");
             }
-            Err(e) => app.set_status(format!("Error: {e}")),
+            Err(e) => app.set_error(&e),
         }
     }
 }
 
+/// A client for the account an item was found in, when that isn't
+/// necessarily the active one (Unified Search results)
+async fn item_client(app: &App, item: &ListItem) -> crate::error::Result<Option<GoogleClient>> {
+    match item.metadata.get("_account").and_then(|v| v.as_str()) {
+        Some(name) => app.client.for_account(name).await.map(Some),
+        None => Ok(None),
+    }
+}
+
 pub async fn execute_delete(app: &mut App) {
     if let Some(item) = app.current_item().cloned() {
         let service = app.service.unwrap_or(app.current_service());
         app.loading = true;
 
         let result = match service {
-            Service::Gmail => {
-                let api = GmailApi::new(&app.client);
-                api.trash_message(&item.id).await
-            }
+            Service::Gmail => match item_client(app, &item).await {
+                Ok(bound) => {
+                    let api = GmailApi::new(bound.as_ref().unwrap_or(&app.client));
+                    api.trash_message(&item.id).await
+                }
+                Err(e) => Err(e),
+            },
             Service::Calendar => {
                 let cal_id = item.metadata.get("calendarId")
                     .and_then(|v| v.as_str())
@@ -149,7 +169,7 @@ pub async fn execute_delete(app: &mut App) {
                 }
                 app.set_status("Deleted successfully");
             }
-            Err(e) => app.set_status(format!("Error: {e}")),
+            Err(e) => app.set_error(&e),
         }
     }
 }
@@ -179,41 +199,105 @@ pub async fn submit_input(app: &mut App) {
             app.screen = Screen::ActionSelect;
             app.input_fields.clear();
         }
-        Err(e) => app.set_status(format!("Error: {e}")),
+        Err(e) => app.set_error(&e),
     }
 }
 
+/// Append the next page of the list on screen, fetched with the same
+/// call (query, ordering and all) that produced the first
 pub async fn load_next_page(app: &mut App) {
-    if let Some(token) = app.next_page_token.clone() {
-        let service = app.service.unwrap_or(app.current_service());
-        app.loading = true;
-        app.set_status("Loading next page...");
-
-        // Re-execute the same action with the page token
-        let _ = match service {
-            Service::Gmail => {
-                let api = GmailApi::new(&app.client);
-                match api.list_messages(None, None, 20, Some(&token)).await {
-                    Ok(val) => {
-                        parse_gmail_messages(app, &val);
-                        Ok(())
-                    }
-                    Err(e) => Err(e),
-                }
-            }
-            Service::Drive => {
-                let api = DriveApi::new(&app.client);
-                match api.list_files(None, 20, Some(&token), None, None, None).await {
-                    Ok(val) => {
-                        parse_drive_files(app, &val);
-                        Ok(())
-                    }
-                    Err(e) => Err(e),
-                }
-            }
-            _ => Ok(()),
-        };
-        app.loading = false;
+    let (Some(token), Some(call)) = (app.next_page_token.clone(), app.list_call.clone()) else {
+        app.set_status("No more items");
+        return;
+    };
+    app.loading = true;
+    app.set_status("Loading next page...");
+
+    let page = {
+        let fetch = |token| fetch_page(&app.client, &call, token);
+        let mut pages = std::pin::pin!(pager::pages_from(fetch, Some(token)));
+        pages.next().await
+    };
+
+    app.loading = false;
+    match page {
+        Some(Ok(page)) => {
+            show_page(app, call, &page, true);
+            app.set_status(format!("{} items loaded", app.items.len()));
+        }
+        Some(Err(e)) => app.set_error(&e),
+        None => {}
+    }
+}
+
+// ── Paged lists ──
+
+async fn fetch_page(client: &GoogleClient, call: &ListCall, token: Option<String>) -> crate::error::Result<Value> {
+    let mut args = call.args.clone();
+    if let Some(token) = token {
+        args["page_token"] = Value::String(token);
+    }
+    crate::cli::exec::execute(client, call.service, call.action, args).await
+}
+
+/// Show the first page of a list call, remembering the call so `n` can
+/// load the pages after it
+async fn open_list(app: &mut App, call: ListCall) -> crate::error::Result<()> {
+    let page = {
+        let fetch = |token| fetch_page(&app.client, &call, token);
+        let mut pages = std::pin::pin!(pager::pages(fetch));
+        pages.next().await.transpose()?.unwrap_or_default()
+    };
+    show_page(app, call, &page, false);
+    Ok(())
+}
+
+fn show_page(app: &mut App, call: ListCall, page: &Value, append: bool) {
+    let field = crate::cli::exec::find(call.service, call.action).ok().and_then(|action| action.items);
+    let items: Vec<ListItem> = pager::page_items(page, field)
+        .map(|(_, items)| items.iter().map(|item| (call.to_item)(item, &call.args)).collect())
+        .unwrap_or_default();
+    let sent = if append { app.next_page_token.take() } else { None };
+    if append {
+        app.items.extend(items);
+    } else {
+        app.set_items(items);
+    }
+    // A page handing back the token it was fetched with has nothing after it
+    app.next_page_token = pager::next_page_token(page)
+        .filter(|next| Some(*next) != sent.as_deref())
+        .map(|next| next.to_string());
+    app.page_info = if app.next_page_token.is_some() { "more available".to_string() } else { String::new() };
+    app.list_call = Some(call);
+}
+
+fn gmail_messages(query: Option<&str>) -> ListCall {
+    ListCall {
+        service: "gmail",
+        action: "list_messages",
+        args: serde_json::json!({ "query": query, "max_results": 20 }),
+        to_item: gmail_message_item,
+    }
+}
+
+fn drive_files(query: &str, order_by: Option<&str>) -> ListCall {
+    ListCall {
+        service: "drive",
+        action: "list_files",
+        args: serde_json::json!({ "query": query, "page_size": 20, "order_by": order_by }),
+        to_item: drive_file_item,
+    }
+}
+
+fn calendar_events(time_min: Option<&str>, time_max: Option<&str>) -> ListCall {
+    ListCall {
+        service: "calendar",
+        action: "list_events",
+        args: serde_json::json!({
+            "calendar_id": "primary", "time_min": time_min, "time_max": time_max,
+            "max_results": 50, "single_events": true, "order_by": "startTime",
+        }),
+        to_item: calendar_event_item,
     }
 }
 
@@ -224,8 +308,7 @@ async fn handle_gmail(app: &mut App, action: usize) -> crate::error::Result<()>
     match action {
         0 => {
             // Inbox
-            let val = api.list_messages(Some("in:inbox"), None, 20, None).await?;
-            parse_gmail_messages(app, &val);
+            open_list(app, gmail_messages(Some("in:inbox"))).await?;
             app.service = Some(Service::Gmail);
             app.screen = Screen::ActionView;
             app.set_status(format!("{} messages loaded", app.items.len()));
@@ -252,16 +335,15 @@ async fn handle_gmail(app: &mut App, action: usize) -> crate::error::Result<()>
         }
         3 => {
             // Labels
-            let val = api.list_labels().await?;
-            let labels = val.get("labels").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+            let labels = api.labels().await?;
             app.set_items(
                 labels
-                    .iter()
+                    .into_iter()
                     .map(|l| ListItem {
-                        id: l.get("id").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-                        title: l.get("name").and_then(|v| v.as_str()).unwrap_or("Unnamed").to_string(),
-                        subtitle: l.get("type").and_then(|v| v.as_str()).unwrap_or("user").to_string(),
-                        metadata: l.clone(),
+                        metadata: serde_json::to_value(&l).unwrap_or_default(),
+                        subtitle: l.kind.unwrap_or_else(|| "user".to_string()),
+                        title: l.name,
+                        id: l.id,
                     })
                     .collect(),
             );
@@ -271,38 +353,34 @@ async fn handle_gmail(app: &mut App, action: usize) -> crate::error::Result<()>
         }
         4 => {
             // Drafts
-            let val = api.list_drafts(20, None).await?;
-            let drafts = val.get("drafts").and_then(|v| v.as_array()).cloned().unwrap_or_default();
-            app.set_items(
-                drafts
-                    .iter()
-                    .map(|d| ListItem {
-                        id: d.get("id").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-                        title: format!("Draft {}", d.get("id").and_then(|v| v.as_str()).unwrap_or("")),
-                        subtitle: "Draft message".to_string(),
-                        metadata: d.clone(),
-                    })
-                    .collect(),
-            );
+            open_list(app, ListCall {
+                service: "gmail",
+                action: "list_drafts",
+                args: serde_json::json!({ "max_results": 20 }),
+                to_item: |d, _| ListItem {
+                    id: d.get("id").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+                    title: format!("Draft {}", d.get("id").and_then(|v| v.as_str()).unwrap_or("")),
+                    subtitle: "Draft message".to_string(),
+                    metadata: d.clone(),
+                },
+            }).await?;
             app.service = Some(Service::Gmail);
             app.screen = Screen::ActionView;
             app.set_status(format!("{} drafts", app.items.len()));
         }
         5 => {
             // Threads
-            let val = api.list_threads(None, 20, None).await?;
-            let threads = val.get("threads").and_then(|v| v.as_array()).cloned().unwrap_or_default();
-            app.set_items(
-                threads
-                    .iter()
-                    .map(|t| ListItem {
-                        id: t.get("id").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-                        title: format!("Thread {}", t.get("id").and_then(|v| v.as_str()).unwrap_or("")),
-                        subtitle: t.get("snippet").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-                        metadata: t.clone(),
-                    })
-                    .collect(),
-            );
+            open_list(app, ListCall {
+                service: "gmail",
+                action: "list_threads",
+                args: serde_json::json!({ "max_results": 20 }),
+                to_item: |t, _| ListItem {
+                    id: t.get("id").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+                    title: format!("Thread {}", t.get("id").and_then(|v| v.as_str()).unwrap_or("")),
+                    subtitle: t.get("snippet").and_then(|v| v.as_str()).unwrap_or("").to_string(),
+                    metadata: t.clone(),
+                },
+            }).await?;
             app.service = Some(Service::Gmail);
             app.screen = Screen::ActionView;
             app.set_status(format!("{} threads", app.items.len()));
@@ -400,38 +478,19 @@ async fn handle_gmail(app: &mut App, action: usize) -> crate::error::Result<()>
     Ok(())
 }
 
-fn parse_gmail_messages(app: &mut App, val: &Value) {
-    app.next_page_token = val
-        .get("nextPageToken")
-        .and_then(|v| v.as_str())
-        .map(|s| s.to_string());
-
-    let messages = val.get("messages").and_then(|v| v.as_array()).cloned().unwrap_or_default();
-    app.set_items(
-        messages
-            .iter()
-            .map(|m| {
-                let id = m.get("id").and_then(|v| v.as_str()).unwrap_or("").to_string();
-                let snippet = m.get("snippet").and_then(|v| v.as_str()).unwrap_or("").to_string();
-                let _thread_id = m.get("threadId").and_then(|v| v.as_str()).unwrap_or("").to_string();
-                ListItem {
-                    id: id.clone(),
-                    title: format!("Message {}", &id[..id.len().min(12)]),
-                    subtitle: if snippet.len() > 80 {
-                        format!("{}...", &snippet[..77])
-                    } else {
-                        snippet
-                    },
-                    metadata: m.clone(),
-                }
-            })
-            .collect(),
-    );
-    app.page_info = if app.next_page_token.is_some() {
-        "more available".to_string()
-    } else {
-        String::new()
-    };
+fn gmail_message_item(m: &Value, _args: &Value) -> ListItem {
+    let message = Message::deserialize(m).unwrap_or_default();
+    let snippet = message.snippet.unwrap_or_default();
+    ListItem {
+        title: format!("Message {}", &message.id[..message.id.len().min(12)]),
+        id: message.id,
+        subtitle: if snippet.chars().count() > 80 {
+            format!("{}...", snippet.chars().take(77).collect::<String>())
+        } else {
+            snippet
+        },
+        metadata: m.clone(),
+    }
 }
 
 async fn submit_gmail(app: &mut App, fields: &[InputField]) -> crate::error::Result<String> {
@@ -442,8 +501,7 @@ async fn submit_gmail(app: &mut App, fields: &[InputField]) -> crate::error::Res
         1 => {
             // Search
             let query = &fields[0].value;
-            let val = api.list_messages(Some(query), None, 20, None).await?;
-            parse_gmail_messages(app, &val);
+            open_list(app, gmail_messages(Some(query))).await?;
             app.screen = Screen::ActionView;
             Ok(format!("{} messages found", app.items.len()))
         }
@@ -459,65 +517,64 @@ async fn submit_gmail(app: &mut App, fields: &[InputField]) -> crate::error::Res
             Ok("Email sent successfully!".to_string())
         }
         11 => {
-            // Unified Search — search across all accounts
+            // Unified Search — search across all accounts in parallel,
+            // each with its own client so the active account never changes
             let query = fields[0].value.clone();
-            let original_account = app.client.active_account_name().await;
             let all_accounts = app.client.account_names().await;
-            let mut all_items: Vec<ListItem> = Vec::new();
-            let mut errors: Vec<String> = Vec::new();
+            let mut searches = tokio::task::JoinSet::new();
+            for (index, account_name) in all_accounts.iter().enumerate() {
+                let client = app.client.for_account(account_name).await;
+                let query = query.clone();
+                searches.spawn(async move {
+                    let client = client?;
+                    let label = client.account_label().await;
+                    let val = GmailApi::new(&client)
+                        .list_messages(Some(&query), None, 10, None)
+                        .await
+                        .map_err(|e| crate::error::VgoogError::Other(format!("{label}: {e}")))?;
+                    Ok::<_, crate::error::VgoogError>((index, label, val))
+                });
+            }
 
-            for account_name in &all_accounts {
-                let label = if *account_name == original_account {
-                    // Already on this account, no need to switch
-                    app.client.active_account_label().await
-                } else {
-                    match app.client.switch_account(account_name).await {
-                        Ok(()) => app.client.active_account_label().await,
-                        Err(e) => {
-                            errors.push(format!("{account_name}: {e}"));
-                            continue;
-                        }
-                    }
-                };
-
-                let acct_api = GmailApi::new(&app.client);
-                match acct_api.list_messages(Some(&query), None, 10, None).await {
-                    Ok(val) => {
-                        let messages = val.get("messages").and_then(|v| v.as_array()).cloned().unwrap_or_default();
-                        for m in &messages {
-                            let id = m.get("id").and_then(|v| v.as_str()).unwrap_or("").to_string();
-                            let snippet = m.get("snippet").and_then(|v| v.as_str()).unwrap_or("").to_string();
-                            let subtitle = if snippet.len() > 60 {
-                                format!("{}...", &snippet[..57])
-                            } else {
-                                snippet
-                            };
-                            all_items.push(ListItem {
-                                id: id.clone(),
-                                title: format!("[{}] Message {}", label, &id[..id.len().min(10)]),
-                                subtitle,
-                                metadata: {
-                                    let mut meta = m.clone();
-                                    meta["_account"] = serde_json::json!(account_name);
-                                    meta["_label"] = serde_json::json!(&label);
-                                    meta
-                                },
-                            });
-                        }
-                    }
-                    Err(e) => {
-                        errors.push(format!("{label}: {e}"));
-                    }
+            let mut results = Vec::new();
+            let mut errors: Vec<String> = Vec::new();
+            while let Some(joined) = searches.join_next().await {
+                match joined {
+                    Ok(Ok(result)) => results.push(result),
+                    Ok(Err(e)) => errors.push(e.to_string()),
+                    Err(e) => errors.push(e.to_string()),
                 }
             }
+            // Keep the account order stable however the searches finished
+            results.sort_by_key(|(index, _, _)| *index);
 
-            // Switch back to original account
-            if app.client.active_account_name().await != original_account {
-                let _ = app.client.switch_account(&original_account).await;
+            let mut all_items: Vec<ListItem> = Vec::new();
+            for (index, label, val) in results {
+                let account_name = &all_accounts[index];
+                let messages = val.get("messages").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+                for m in &messages {
+                    let id = m.get("id").and_then(|v| v.as_str()).unwrap_or("").to_string();
+                    let snippet = m.get("snippet").and_then(|v| v.as_str()).unwrap_or("").to_string();
+                    let subtitle = if snippet.chars().count() > 60 {
+                        format!("{}...", snippet.chars().take(57).collect::<String>())
+                    } else {
+                        snippet
+                    };
+                    all_items.push(ListItem {
+                        id: id.clone(),
+                        title: format!("[{}] Message {}", label, &id[..id.len().min(10)]),
+                        subtitle,
+                        metadata: {
+                            let mut meta = m.clone();
+                            meta["_account"] = serde_json::json!(account_name);
+                            meta["_label"] = serde_json::json!(&label);
+                            meta
+                        },
+                    });
+                }
             }
 
             app.set_items(all_items);
-            app.next_page_token = None;
             app.screen = Screen::ActionView;
 
             let msg = format!("{} results across {} accounts", app.items.len(), all_accounts.len());
@@ -539,18 +596,13 @@ async fn handle_calendar(app: &mut App, action: usize) -> crate::error::Result<(
         0 | 1 => {
             // Today / Week View
             let now = chrono::Utc::now();
-            let time_min = if action == 0 {
-                now.format("%Y-%m-%dT00:00:00Z").to_string()
-            } else {
-                now.format("%Y-%m-%dT00:00:00Z").to_string()
-            };
+            let time_min = now.format("%Y-%m-%dT00:00:00Z").to_string();
             let time_max = if action == 0 {
                 now.format("%Y-%m-%dT23:59:59Z").to_string()
             } else {
                 (now + chrono::Duration::days(7)).format("%Y-%m-%dT23:59:59Z").to_string()
             };
-            let val = api.list_events("primary", Some(&time_min), Some(&time_max), None, 50, None, true, Some("startTime")).await?;
-            parse_calendar_events(app, &val);
+            open_list(app, calendar_events(Some(&time_min), Some(&time_max))).await?;
             app.service = Some(Service::Calendar);
             app.screen = Screen::ActionView;
             let label = if action == 0 { "today" } else { "this week" };
@@ -558,8 +610,7 @@ async fn handle_calendar(app: &mut App, action: usize) -> crate::error::Result<(
         }
         2 => {
             // All events
-            let val = api.list_events("primary", None, None, None, 50, None, true, Some("startTime")).await?;
-            parse_calendar_events(app, &val);
+            open_list(app, calendar_events(None, None)).await?;
             app.service = Some(Service::Calendar);
             app.screen = Screen::ActionView;
         }
@@ -571,14 +622,13 @@ async fn handle_calendar(app: &mut App, action: usize) -> crate::error::Result<(
         }
         4 => {
             // Calendars
-            let val = api.list_calendars(None).await?;
-            let items = val.get("items").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+            let calendars = api.calendars(None).await?;
             app.set_items(
-                items.iter().map(|c| ListItem {
-                    id: c.get("id").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-                    title: c.get("summary").and_then(|v| v.as_str()).unwrap_or("Unnamed").to_string(),
-                    subtitle: c.get("accessRole").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-                    metadata: c.clone(),
+                calendars.items.into_iter().map(|c| ListItem {
+                    metadata: serde_json::to_value(&c).unwrap_or_default(),
+                    title: c.summary.unwrap_or_else(|| "Unnamed".to_string()),
+                    subtitle: c.access_role.unwrap_or_default(),
+                    id: c.id,
                 }).collect(),
             );
             app.service = Some(Service::Calendar);
@@ -599,7 +649,7 @@ async fn handle_calendar(app: &mut App, action: usize) -> crate::error::Result<(
         }
         6 => {
             // ACL
-            let val = api.list_acl("primary").await?;
+            let val = api.list_acl("primary", None).await?;
             let items = val.get("items").and_then(|v| v.as_array()).cloned().unwrap_or_default();
             app.set_items(
                 items.iter().map(|a| ListItem {
@@ -614,7 +664,7 @@ async fn handle_calendar(app: &mut App, action: usize) -> crate::error::Result<(
         }
         7 => {
             // Settings
-            let val = api.list_settings().await?;
+            let val = api.list_settings(None).await?;
             app.detail = Some(val);
             app.service = Some(Service::Calendar);
             app.screen = Screen::ActionView;
@@ -634,22 +684,15 @@ async fn handle_calendar(app: &mut App, action: usize) -> crate::error::Result<(
     Ok(())
 }
 
-fn parse_calendar_events(app: &mut App, val: &Value) {
-    let events = val.get("items").and_then(|v| v.as_array()).cloned().unwrap_or_default();
-    app.set_items(
-        events.iter().map(|e| {
-            let start = e.get("start")
-                .and_then(|s| s.get("dateTime").or(s.get("date")))
-                .and_then(|v| v.as_str())
-                .unwrap_or("No date");
-            ListItem {
-                id: e.get("id").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-                title: e.get("summary").and_then(|v| v.as_str()).unwrap_or("(No title)").to_string(),
-                subtitle: start.to_string(),
-                metadata: e.clone(),
-            }
-        }).collect(),
-    );
+fn calendar_event_item(e: &Value, _args: &Value) -> ListItem {
+    let event = Event::deserialize(e).unwrap_or_default();
+    let start = event.start.as_ref().and_then(|s| s.as_str()).unwrap_or("No date");
+    ListItem {
+        subtitle: start.to_string(),
+        id: event.id,
+        title: event.summary.unwrap_or_else(|| "(No title)".to_string()),
+        metadata: e.clone(),
+    }
 }
 
 async fn submit_calendar(app: &mut App, fields: &[InputField]) -> crate::error::Result<String> {
@@ -707,8 +750,7 @@ async fn handle_drive(app: &mut App, action: usize) -> crate::error::Result<()>
     match action {
         0 => {
             // My Files
-            let val = api.list_files(Some("'root' in parents and trashed=false"), 20, None, Some("modifiedTime desc"), None, None).await?;
-            parse_drive_files(app, &val);
+            open_list(app, drive_files("'root' in parents and trashed=false", Some("modifiedTime desc"))).await?;
             app.service = Some(Service::Drive);
             app.screen = Screen::ActionView;
         }
@@ -738,29 +780,25 @@ async fn handle_drive(app: &mut App, action: usize) -> crate::error::Result<()>
         }
         4 => {
             // Shared with me
-            let val = api.list_files(Some("sharedWithMe=true"), 20, None, Some("modifiedTime desc"), None, None).await?;
-            parse_drive_files(app, &val);
+            open_list(app, drive_files("sharedWithMe=true", Some("modifiedTime desc"))).await?;
             app.service = Some(Service::Drive);
             app.screen = Screen::ActionView;
         }
         5 => {
             // Recent
-            let val = api.list_files(Some("trashed=false"), 20, None, Some("viewedByMeTime desc"), None, None).await?;
-            parse_drive_files(app, &val);
+            open_list(app, drive_files("trashed=false", Some("viewedByMeTime desc"))).await?;
             app.service = Some(Service::Drive);
             app.screen = Screen::ActionView;
         }
         6 => {
             // Starred
-            let val = api.list_files(Some("starred=true and trashed=false"), 20, None, None, None, None).await?;
-            parse_drive_files(app, &val);
+            open_list(app, drive_files("starred=true and trashed=false", None)).await?;
             app.service = Some(Service::Drive);
             app.screen = Screen::ActionView;
         }
         7 => {
             // Trash
-            let val = api.list_files(Some("trashed=true"), 20, None, None, None, None).await?;
-            parse_drive_files(app, &val);
+            open_list(app, drive_files("trashed=true", None)).await?;
             app.service = Some(Service::Drive);
             app.screen = Screen::ActionView;
         }
@@ -787,34 +825,38 @@ async fn handle_drive(app: &mut App, action: usize) -> crate::error::Result<()>
             app.service = Some(Service::Drive);
             app.screen = Screen::ActionView;
         }
+        10 => {
+            // Download
+            app.input_fields = vec![
+                InputField::new("File ID", "1AbC...", true),
+                InputField::new("Save to (optional)", "./report.pdf", false),
+            ];
+            app.service = Some(Service::Drive);
+            app.screen = Screen::Input;
+        }
         _ => {}
     }
     Ok(())
 }
 
-fn parse_drive_files(app: &mut App, val: &Value) {
-    app.next_page_token = val.get("nextPageToken").and_then(|v| v.as_str()).map(|s| s.to_string());
-    let files = val.get("files").and_then(|v| v.as_array()).cloned().unwrap_or_default();
-    app.set_items(
-        files.iter().map(|f| {
-            let mime = f.get("mimeType").and_then(|v| v.as_str()).unwrap_or("");
-            let icon = if mime.contains("folder") { "📁" }
-                else if mime.contains("spreadsheet") { "📊" }
-                else if mime.contains("document") { "📄" }
-                else if mime.contains("presentation") { "📽" }
-                else if mime.contains("form") { "📝" }
-                else if mime.contains("image") { "🖼" }
-                else if mime.contains("pdf") { "📕" }
-                else { "📎" };
-            ListItem {
-                id: f.get("id").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-                title: format!("{} {}", icon, f.get("name").and_then(|v| v.as_str()).unwrap_or("Unnamed")),
-                subtitle: f.get("modifiedTime").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-                metadata: f.clone(),
-            }
-        }).collect(),
-    );
-    app.page_info = if app.next_page_token.is_some() { "more available".to_string() } else { String::new() };
+fn drive_file_item(f: &Value, _args: &Value) -> ListItem {
+    let file = File::deserialize(f).unwrap_or_default();
+    let mime = file.mime_type.as_str();
+    let icon = if file.is_folder() { "📁" }
+        else if mime.contains("spreadsheet") { "📊" }
+        else if mime.contains("document") { "📄" }
+        else if mime.contains("presentation") { "📽" }
+        else if mime.contains("form") { "📝" }
+        else if mime.contains("image") { "🖼" }
+        else if mime.contains("pdf") { "📕" }
+        else { "📎" };
+    let name = if file.name.is_empty() { "Unnamed" } else { &file.name };
+    ListItem {
+        title: format!("{icon} {name}"),
+        id: file.id,
+        subtitle: file.modified_time.unwrap_or_default(),
+        metadata: f.clone(),
+    }
 }
 
 async fn submit_drive(app: &mut App, fields: &[InputField]) -> crate::error::Result<String> {
@@ -824,23 +866,28 @@ async fn submit_drive(app: &mut App, fields: &[InputField]) -> crate::error::Res
     match action {
         1 => {
             // Search
-            let val = api.list_files(Some(&fields[0].value), 20, None, None, None, None).await?;
-            parse_drive_files(app, &val);
+            open_list(app, drive_files(&fields[0].value, None)).await?;
             app.screen = Screen::ActionView;
             Ok(format!("{} files found", app.items.len()))
         }
         2 => {
             // Upload
             let path = std::path::Path::new(&fields[0].value);
-            let content = std::fs::read(path)?;
+            let size = std::fs::metadata(path)?.len();
             let mime = mime_guess::from_path(path).first_or_octet_stream().to_string();
             let name = path.file_name().and_then(|n| n.to_str()).unwrap_or("upload");
             let mut meta = serde_json::json!({"name": name});
             if !fields[1].value.is_empty() && fields[1].value != "root" {
                 meta["parents"] = serde_json::json!([&fields[1].value]);
             }
-            api.upload_file(&meta, content, &mime).await?;
-            Ok(format!("Uploaded {name}!"))
+            let transfer = app.transfer.clone();
+            transfer.start(format!("Uploading {name}"), Some(size));
+            let result = api
+                .upload_file(&meta, path, &mime, Some(&|p| transfer.update(p)))
+                .await;
+            transfer.finish();
+            result?;
+            Ok(format!("Uploaded {name} ({})", crate::transfer::human_bytes(size)))
         }
         3 => {
             // Create Folder
@@ -848,6 +895,26 @@ async fn submit_drive(app: &mut App, fields: &[InputField]) -> crate::error::Res
             api.create_folder(&fields[0].value, parent).await?;
             Ok(format!("Folder '{}' created!", fields[0].value))
         }
+        10 => {
+            // Download
+            let meta = api.get_file(&fields[0].value, Some("name,size")).await?;
+            let name = meta.get("name").and_then(|v| v.as_str()).unwrap_or(&fields[0].value).to_string();
+            let size = meta.get("size").and_then(|v| v.as_str()).and_then(|s| s.parse().ok());
+            let path = if fields[1].value.is_empty() {
+                std::path::PathBuf::from(&name)
+            } else {
+                std::path::PathBuf::from(&fields[1].value)
+            };
+            let transfer = app.transfer.clone();
+            transfer.start(format!("Downloading {name}"), size);
+            let result = api
+                .download_file_to(&fields[0].value, &path, Some(&|p| transfer.update(p)))
+                .await;
+            transfer.finish();
+            let val = result?;
+            let bytes = val.get("size").and_then(|v| v.as_u64()).unwrap_or(0);
+            Ok(format!("Saved {} ({})", path.display(), crate::transfer::human_bytes(bytes)))
+        }
         _ => Ok("Action completed".to_string()),
     }
 }
@@ -855,15 +922,13 @@ async fn submit_drive(app: &mut App, fields: &[InputField]) -> crate::error::Res
 // ── Sheets handlers ──
 
 async fn handle_sheets(app: &mut App, action: usize) -> crate::error::Result<()> {
-    let api_drive = DriveApi::new(&app.client);
     match action {
         0 => {
             // Open Sheet (list sheets from drive)
-            let val = api_drive.list_files(
-                Some("mimeType='application/vnd.google-apps.spreadsheet' and trashed=false"),
-                20, None, Some("modifiedTime desc"), None, None,
-            ).await?;
-            parse_drive_files(app, &val);
+            open_list(app, drive_files(
+                "mimeType='application/vnd.google-apps.spreadsheet' and trashed=false",
+                Some("modifiedTime desc"),
+            )).await?;
             app.service = Some(Service::Sheets);
             app.screen = Screen::ActionView;
         }
@@ -996,14 +1061,12 @@ async fn submit_sheets(app: &mut App, fields: &[InputField]) -> crate::error::Re
 // ── Docs handlers ──
 
 async fn handle_docs(app: &mut App, action: usize) -> crate::error::Result<()> {
-    let api_drive = DriveApi::new(&app.client);
     match action {
         0 => {
-            let val = api_drive.list_files(
-                Some("mimeType='application/vnd.google-apps.document' and trashed=false"),
-                20, None, Some("modifiedTime desc"), None, None,
-            ).await?;
-            parse_drive_files(app, &val);
+            open_list(app, drive_files(
+                "mimeType='application/vnd.google-apps.document' and trashed=false",
+                Some("modifiedTime desc"),
+            )).await?;
             app.service = Some(Service::Docs);
             app.screen = Screen::ActionView;
         }
@@ -1119,14 +1182,12 @@ async fn submit_docs(app: &mut App, fields: &[InputField]) -> crate::error::Resu
 // ── Slides handlers ──
 
 async fn handle_slides(app: &mut App, action: usize) -> crate::error::Result<()> {
-    let api_drive = DriveApi::new(&app.client);
     match action {
         0 => {
-            let val = api_drive.list_files(
-                Some("mimeType='application/vnd.google-apps.presentation' and trashed=false"),
-                20, None, Some("modifiedTime desc"), None, None,
-            ).await?;
-            parse_drive_files(app, &val);
+            open_list(app, drive_files(
+                "mimeType='application/vnd.google-apps.presentation' and trashed=false",
+                Some("modifiedTime desc"),
+            )).await?;
             app.service = Some(Service::Slides);
             app.screen = Screen::ActionView;
         }
@@ -1255,14 +1316,12 @@ async fn submit_slides(app: &mut App, fields: &[InputField]) -> crate::error::Re
 // ── Forms handlers ──
 
 async fn handle_forms(app: &mut App, action: usize) -> crate::error::Result<()> {
-    let api_drive = DriveApi::new(&app.client);
     match action {
         0 => {
-            let val = api_drive.list_files(
-                Some("mimeType='application/vnd.google-apps.form' and trashed=false"),
-                20, None, Some("modifiedTime desc"), None, None,
-            ).await?;
-            parse_drive_files(app, &val);
+            open_list(app, drive_files(
+                "mimeType='application/vnd.google-apps.form' and trashed=false",
+                Some("modifiedTime desc"),
+            )).await?;
             app.service = Some(Service::Forms);
             app.screen = Screen::ActionView;
         }
@@ -1395,14 +1454,13 @@ async fn handle_tasks(app: &mut App, action: usize) -> crate::error::Result<()>
     match action {
         0 => {
             // Task Lists
-            let val = api.list_task_lists(20, None).await?;
-            let items = val.get("items").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+            let lists = api.task_lists(20, None).await?;
             app.set_items(
-                items.iter().map(|t| ListItem {
-                    id: t.get("id").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-                    title: t.get("title").and_then(|v| v.as_str()).unwrap_or("Untitled").to_string(),
-                    subtitle: t.get("updated").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-                    metadata: t.clone(),
+                lists.items.into_iter().map(|t| ListItem {
+                    metadata: serde_json::to_value(&t).unwrap_or_default(),
+                    subtitle: t.updated.unwrap_or_default(),
+                    title: t.title,
+                    id: t.id,
                 }).collect(),
             );
             app.service = Some(Service::Tasks);
@@ -1463,24 +1521,26 @@ async fn submit_tasks(app: &mut App, fields: &[InputField]) -> crate::error::Res
 
     match action {
         1 => {
-            let val = api.list_tasks(&fields[0].value, 50, None, true, false, false, None, None).await?;
-            let items = val.get("items").and_then(|v| v.as_array()).cloned().unwrap_or_default();
-            app.set_items(
-                items.iter().map(|t| {
-                    let status = t.get("status").and_then(|v| v.as_str()).unwrap_or("");
-                    let icon = if status == "completed" { "✓" } else { "○" };
+            open_list(app, ListCall {
+                service: "tasks",
+                action: "list_tasks",
+                args: serde_json::json!({ "task_list_id": &fields[0].value, "max_results": 50 }),
+                to_item: |t, args| {
+                    let task = Task::deserialize(t).unwrap_or_default();
+                    let icon = if task.is_completed() { "✓" } else { "○" };
+                    let title = if task.title.is_empty() { "Untitled" } else { &task.title };
                     ListItem {
-                        id: t.get("id").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-                        title: format!("{} {}", icon, t.get("title").and_then(|v| v.as_str()).unwrap_or("Untitled")),
-                        subtitle: t.get("due").and_then(|v| v.as_str()).unwrap_or("No due date").to_string(),
+                        title: format!("{icon} {title}"),
+                        subtitle: task.due.unwrap_or_else(|| "No due date".to_string()),
+                        id: task.id,
                         metadata: {
                             let mut m = t.clone();
-                            m["taskListId"] = serde_json::json!(&fields[0].value);
+                            m["taskListId"] = args["task_list_id"].clone();
                             m
                         },
                     }
-                }).collect(),
-            );
+                },
+            }).await?;
             app.screen = Screen::ActionView;
             Ok(format!("{} tasks loaded", app.items.len()))
         }
@@ -1519,30 +1579,24 @@ async fn handle_people(app: &mut App, action: usize) -> crate::error::Result<()>
     let api = PeopleApi::new(&app.client);
     match action {
         0 => {
-            let val = api.list_contacts(20, None, "names,emailAddresses,phoneNumbers,organizations", Some("LAST_NAME_ASCENDING")).await?;
-            let connections = val.get("connections").and_then(|v| v.as_array()).cloned().unwrap_or_default();
-            app.set_items(
-                connections.iter().map(|c| {
-                    let name = c.get("names")
-                        .and_then(|n| n.as_array())
-                        .and_then(|a| a.first())
-                        .and_then(|n| n.get("displayName"))
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("Unnamed");
-                    let email = c.get("emailAddresses")
-                        .and_then(|e| e.as_array())
-                        .and_then(|a| a.first())
-                        .and_then(|e| e.get("value"))
-                        .and_then(|v| v.as_str())
-                        .unwrap_or("");
+            open_list(app, ListCall {
+                service: "contacts",
+                action: "list_contacts",
+                args: serde_json::json!({
+                    "page_size": 20,
+                    "person_fields": "names,emailAddresses,phoneNumbers,organizations",
+                    "sort_order": "LAST_NAME_ASCENDING",
+                }),
+                to_item: |c, _| {
+                    let person = Person::deserialize(c).unwrap_or_default();
                     ListItem {
-                        id: c.get("resourceName").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-                        title: name.to_string(),
-                        subtitle: email.to_string(),
+                        title: person.display_name().unwrap_or("Unnamed").to_string(),
+                        subtitle: person.email().unwrap_or_default().to_string(),
+                        id: person.resource_name,
                         metadata: c.clone(),
                     }
-                }).collect(),
-            );
+                },
+            }).await?;
             app.service = Some(Service::People);
             app.screen = Screen::ActionView;
             app.set_status(format!("{} contacts loaded", app.items.len()));
@@ -1564,14 +1618,13 @@ async fn handle_people(app: &mut App, action: usize) -> crate::error::Result<()>
             app.screen = Screen::Input;
         }
         3 => {
-            let val = api.list_contact_groups(20, None).await?;
-            let groups = val.get("contactGroups").and_then(|v| v.as_array()).cloned().unwrap_or_default();
+            let groups = api.contact_groups(20, None).await?;
             app.set_items(
-                groups.iter().map(|g| ListItem {
-                    id: g.get("resourceName").and_then(|v| v.as_str()).unwrap_or("").to_string(),
-                    title: g.get("name").and_then(|v| v.as_str()).unwrap_or("Unnamed").to_string(),
-                    subtitle: format!("{} members", g.get("memberCount").and_then(|v| v.as_i64()).unwrap_or(0)),
-                    metadata: g.clone(),
+                groups.items.into_iter().map(|g| ListItem {
+                    metadata: serde_json::to_value(&g).unwrap_or_default(),
+                    subtitle: format!("{} members", g.member_count.unwrap_or(0)),
+                    title: g.name,
+                    id: g.resource_name,
                 }).collect(),
             );
             app.service = Some(Service::People);
@@ -1670,12 +1723,10 @@ async fn handle_apps_script(app: &mut App, action: usize) -> crate::error::Resul
     match action {
         0 => {
             // List projects via Drive
-            let api_drive = DriveApi::new(&app.client);
-            let val = api_drive.list_files(
-                Some("mimeType='application/vnd.google-apps.script' and trashed=false"),
-                20, None, Some("modifiedTime desc"), None, None,
-            ).await?;
-            parse_drive_files(app, &val);
+            open_list(app, drive_files(
+                "mimeType='application/vnd.google-apps.script' and trashed=false",
+                Some("modifiedTime desc"),
+            )).await?;
             app.service = Some(Service::AppsScript);
             app.screen = Screen::ActionView;
         }
@@ -1,9 +1,10 @@
+use crate::transfer::Progress;
 use crate::ui::app::{App, Screen, Service};
 use ratatui::{
     layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span, Text},
-    widgets::{Block, Borders, Clear, List, ListItem, Paragraph, Wrap},
+    widgets::{Block, Borders, Clear, Gauge, List, ListItem, Paragraph, Wrap},
     Frame,
 };
 
@@ -43,6 +44,28 @@ pub fn render(f: &mut Frame, app: &App) {
     }
 }
 
+/// Progress gauge over the status bar, drawn on top of the last full frame
+/// while an upload runs
+pub fn render_transfer(f: &mut Frame, label: &str, progress: Progress) {
+    let area = f.area();
+    let area = Rect {
+        y: area.bottom().saturating_sub(3),
+        height: area.height.min(3),
+        ..area
+    };
+    let gauge = Gauge::default()
+        .block(
+            Block::default()
+                .borders(Borders::TOP)
+                .border_style(Style::default().fg(DIM_COLOR)),
+        )
+        .gauge_style(Style::default().fg(WARN_COLOR))
+        .percent(progress.percent().unwrap_or(0))
+        .label(format!("{label} — {progress}"));
+    f.render_widget(Clear, area);
+    f.render_widget(gauge, area);
+}
+
 fn render_header(f: &mut Frame, app: &App, area: Rect) {
     let title = match &app.service {
         Some(svc) => format!("  vgoog  >  {} {}", svc.icon(), svc.name()),
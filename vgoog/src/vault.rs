@@ -0,0 +1,251 @@
+//! Encrypted-at-rest credential store. Once locked, client secrets and
+//! tokens live sealed in `credentials.enc` next to config.toml, and the
+//! TOML only holds `vault:<account>.<field>` references to them.
+//!
+//! The key is either derived from a passphrase (PBKDF2-HMAC-SHA256, taken
+//! from `VGOOG_PASSPHRASE` or prompted for) or read from a local key file.
+
+use crate::error::{Result, VgoogError};
+use base64::engine::general_purpose::STANDARD as B64;
+use base64::Engine;
+use ring::aead::{Aad, LessSafeKey, Nonce, UnboundKey, CHACHA20_POLY1305, NONCE_LEN};
+use ring::rand::{SecureRandom, SystemRandom};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::io::{IsTerminal, Write};
+use std::num::NonZeroU32;
+use std::path::{Path, PathBuf};
+
+/// Prefix marking a config value as a reference into the store
+pub const REFERENCE_PREFIX: &str = "vault:";
+
+/// Sealed store, in the config directory
+pub const FILE_NAME: &str = "credentials.enc";
+
+/// OWASP's 2023 recommendation for PBKDF2-HMAC-SHA256
+pub const PBKDF2_ITERATIONS: u32 = 600_000;
+
+const FORMAT_VERSION: u32 = 1;
+const KEY_LEN: usize = 32;
+const SALT_LEN: usize = 16;
+const AAD: &[u8] = b"vgoog credentials v1";
+
+/// `[vault]` in config.toml; its presence means the store is locked
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VaultConfig {
+    /// Key file to unlock with; without one a passphrase is asked for
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_file: Option<PathBuf>,
+}
+
+/// How the key was obtained, recorded in the clear alongside the ciphertext
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "algorithm", rename_all = "kebab-case")]
+enum Kdf {
+    Pbkdf2Sha256 { salt: String, iterations: u32 },
+    KeyFile,
+}
+
+/// On-disk layout of `credentials.enc`
+#[derive(Serialize, Deserialize)]
+struct SealedFile {
+    version: u32,
+    kdf: Kdf,
+    nonce: String,
+    ciphertext: String,
+}
+
+/// An unlocked key, kept in memory so later saves can re-seal the store
+/// without asking again
+#[derive(Clone)]
+pub struct VaultKey {
+    key: [u8; KEY_LEN],
+    kdf: Kdf,
+}
+
+impl std::fmt::Debug for VaultKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VaultKey").field("kdf", &self.kdf).finish_non_exhaustive()
+    }
+}
+
+impl VaultKey {
+    /// A new key from a passphrase, with a fresh random salt
+    pub fn from_passphrase(passphrase: &str) -> Result<Self> {
+        Self::from_passphrase_with(passphrase, PBKDF2_ITERATIONS)
+    }
+
+    /// As `from_passphrase`, with a custom work factor
+    pub fn from_passphrase_with(passphrase: &str, iterations: u32) -> Result<Self> {
+        if passphrase.is_empty() {
+            return Err(VgoogError::Config("Passphrase must not be empty".into()));
+        }
+        let mut salt = [0u8; SALT_LEN];
+        SystemRandom::new()
+            .fill(&mut salt)
+            .map_err(|_| VgoogError::Other("System random number generator failed".into()))?;
+        Ok(Self::derive(passphrase, &salt, iterations))
+    }
+
+    /// The key stored in `path`; any file of at least 32 bytes will do
+    pub fn from_key_file(path: &Path) -> Result<Self> {
+        let contents = std::fs::read(path).map_err(|e| {
+            VgoogError::Config(format!("Cannot read key file {}: {e}", path.display()))
+        })?;
+        if contents.len() < KEY_LEN {
+            return Err(VgoogError::Config(format!(
+                "Key file {} is too short; it needs at least {KEY_LEN} bytes",
+                path.display()
+            )));
+        }
+        let digest = ring::digest::digest(&ring::digest::SHA256, &contents);
+        let mut key = [0u8; KEY_LEN];
+        key.copy_from_slice(digest.as_ref());
+        Ok(Self { key, kdf: Kdf::KeyFile })
+    }
+
+    /// Unlock the store sealed at `path`, asking for the passphrase unless
+    /// `config` names a key file
+    pub fn unlock(config: &VaultConfig, path: &Path) -> Result<Self> {
+        let sealed = read_sealed(path)?;
+        match (&config.key_file, &sealed.kdf) {
+            (Some(key_file), Kdf::KeyFile) => Self::from_key_file(key_file),
+            (None, Kdf::Pbkdf2Sha256 { salt, iterations }) => {
+                let salt = B64.decode(salt).map_err(corrupt)?;
+                Ok(Self::derive(&passphrase("Passphrase for vgoog credentials: ")?, &salt, *iterations))
+            }
+            (Some(_), _) => Err(VgoogError::Config(
+                "Credential store was sealed with a passphrase, but [vault] names a key file".into(),
+            )),
+            (None, _) => Err(VgoogError::Config(
+                "Credential store was sealed with a key file; set key_file under [vault]".into(),
+            )),
+        }
+    }
+
+    fn derive(passphrase: &str, salt: &[u8], iterations: u32) -> Self {
+        let mut key = [0u8; KEY_LEN];
+        ring::pbkdf2::derive(
+            ring::pbkdf2::PBKDF2_HMAC_SHA256,
+            NonZeroU32::new(iterations).unwrap_or(NonZeroU32::MIN),
+            salt,
+            passphrase.as_bytes(),
+            &mut key,
+        );
+        let kdf = Kdf::Pbkdf2Sha256 { salt: B64.encode(salt), iterations };
+        Self { key, kdf }
+    }
+
+    /// Encrypt `secrets` into the `credentials.enc` format, under a new nonce
+    pub fn seal(&self, secrets: &BTreeMap<String, String>) -> Result<Vec<u8>> {
+        let mut nonce = [0u8; NONCE_LEN];
+        SystemRandom::new()
+            .fill(&mut nonce)
+            .map_err(|_| VgoogError::Other("System random number generator failed".into()))?;
+        let mut data = serde_json::to_vec(secrets)?;
+        self.aead()
+            .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::from(AAD), &mut data)
+            .map_err(|_| VgoogError::Other("Encrypting credentials failed".into()))?;
+        let sealed = SealedFile {
+            version: FORMAT_VERSION,
+            kdf: self.kdf.clone(),
+            nonce: B64.encode(nonce),
+            ciphertext: B64.encode(&data),
+        };
+        Ok(serde_json::to_vec_pretty(&sealed)?)
+    }
+
+    /// Decrypt the store at `path`
+    pub fn open(&self, path: &Path) -> Result<BTreeMap<String, String>> {
+        let sealed = read_sealed(path)?;
+        let nonce: [u8; NONCE_LEN] = B64
+            .decode(&sealed.nonce)
+            .map_err(corrupt)?
+            .try_into()
+            .map_err(|_| corrupt("bad nonce length"))?;
+        let mut data = B64.decode(&sealed.ciphertext).map_err(corrupt)?;
+        let plain = self
+            .aead()
+            .open_in_place(Nonce::assume_unique_for_key(nonce), Aad::from(AAD), &mut data)
+            .map_err(|_| {
+                VgoogError::Auth("Cannot unlock credentials: wrong passphrase or key file".into())
+            })?;
+        Ok(serde_json::from_slice(plain)?)
+    }
+
+    fn aead(&self) -> LessSafeKey {
+        LessSafeKey::new(UnboundKey::new(&CHACHA20_POLY1305, &self.key).expect("32-byte key"))
+    }
+}
+
+fn read_sealed(path: &Path) -> Result<SealedFile> {
+    let bytes = std::fs::read(path).map_err(|e| {
+        VgoogError::Config(format!("Cannot read credential store {}: {e}", path.display()))
+    })?;
+    let sealed: SealedFile = serde_json::from_slice(&bytes).map_err(corrupt)?;
+    if sealed.version != FORMAT_VERSION {
+        return Err(VgoogError::Config(format!(
+            "Unsupported credential store version {}",
+            sealed.version
+        )));
+    }
+    Ok(sealed)
+}
+
+fn corrupt(e: impl std::fmt::Display) -> VgoogError {
+    VgoogError::Config(format!("Credential store is corrupted: {e}"))
+}
+
+/// 32 random bytes, for `vgoog auth lock --key-file` when the file is new
+pub fn generate_key() -> Result<Vec<u8>> {
+    let mut key = vec![0u8; KEY_LEN];
+    SystemRandom::new()
+        .fill(&mut key)
+        .map_err(|_| VgoogError::Other("System random number generator failed".into()))?;
+    Ok(key)
+}
+
+/// `VGOOG_PASSPHRASE`, or a prompt when stdin is a terminal
+pub fn passphrase(prompt: &str) -> Result<String> {
+    if let Ok(passphrase) = std::env::var("VGOOG_PASSPHRASE") {
+        return Ok(passphrase);
+    }
+    if !std::io::stdin().is_terminal() {
+        return Err(VgoogError::Auth(
+            "Credentials are locked: set VGOOG_PASSPHRASE or run from a terminal".into(),
+        ));
+    }
+    prompt_hidden(prompt)
+}
+
+/// Read a line from the terminal without echoing it
+pub fn prompt_hidden(prompt: &str) -> Result<String> {
+    use crossterm::event::{read, Event, KeyCode, KeyEventKind, KeyModifiers};
+
+    eprint!("{prompt}");
+    std::io::stderr().flush()?;
+    crossterm::terminal::enable_raw_mode()?;
+    let mut input = String::new();
+    let result = loop {
+        let key = match read() {
+            Ok(Event::Key(key)) if key.kind == KeyEventKind::Press => key,
+            Ok(_) => continue,
+            Err(e) => break Err(e.into()),
+        };
+        match key.code {
+            KeyCode::Enter => break Ok(()),
+            KeyCode::Esc => break Err(VgoogError::Other("Cancelled".into())),
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                break Err(VgoogError::Other("Cancelled".into()))
+            }
+            KeyCode::Char(c) => input.push(c),
+            KeyCode::Backspace => {
+                input.pop();
+            }
+            _ => {}
+        }
+    };
+    crossterm::terminal::disable_raw_mode()?;
+    eprintln!();
+    result.map(|()| input)
+}
@@ -0,0 +1,180 @@
+//! Account-bound clients, token introspection and revocation.
+
+mod common;
+
+use chrono::{Duration, Utc};
+use common::{exec, fresh_config_dir};
+use serde_json::json;
+use vgoog::client::GoogleClient;
+use vgoog::config::Config;
+use vgoog::fake::{FakeGoogle, ACCESS_TOKEN};
+use vgoog::ui::app::Service;
+
+/// The fake's config plus a `work` account whose token needs a refresh,
+/// so its requests carry a different bearer token
+fn two_accounts(fake: &FakeGoogle) -> Config {
+    let mut config = fake.config();
+    let mut work = config.accounts["fake"].clone();
+    work.label = "Work".to_string();
+    work.auth.access_token = "stale".to_string();
+    work.auth.token_expiry = Utc::now() - Duration::minutes(5);
+    config.add_account("work".to_string(), work);
+    config.save().unwrap();
+    config
+}
+
+#[tokio::test]
+async fn bound_clients_leave_the_active_account_alone() {
+    let _guard = fresh_config_dir().await;
+    let fake = FakeGoogle::start().await.unwrap();
+    let client = GoogleClient::new(two_accounts(&fake)).unwrap();
+
+    let work = client.for_account("work").await.unwrap();
+    exec(&work, "gmail", "get_profile", json!({})).await;
+
+    assert_eq!(work.account_name().await, "work");
+    assert_eq!(work.account_label().await, "Work");
+    assert_eq!(client.account_name().await, "fake");
+    assert_eq!(client.active_account_name().await, "fake");
+    assert_eq!(Config::load().unwrap().active_account, "fake");
+}
+
+#[tokio::test]
+async fn bound_clients_send_their_own_tokens() {
+    let _guard = fresh_config_dir().await;
+    let fake = FakeGoogle::start().await.unwrap();
+    let client = GoogleClient::new(two_accounts(&fake)).unwrap();
+    let work = client.for_account("work").await.unwrap();
+
+    let (a, b) = tokio::join!(
+        exec(&client, "gmail", "get_profile", json!({})),
+        exec(&work, "gmail", "get_profile", json!({})),
+    );
+    assert_eq!(a["emailAddress"], b["emailAddress"]);
+
+    let bearers: Vec<String> = fake
+        .requests()
+        .iter()
+        .filter(|r| r.path.ends_with("/profile"))
+        .filter_map(|r| r.header("authorization").map(String::from))
+        .collect();
+    assert_eq!(bearers.len(), 2);
+    assert!(bearers.contains(&format!("Bearer {ACCESS_TOKEN}")), "{bearers:?}");
+    assert!(bearers.iter().any(|b| b != &format!("Bearer {ACCESS_TOKEN}")), "{bearers:?}");
+    // Only the work account needed a refresh
+    assert_eq!(fake.requests().iter().filter(|r| r.path == "/token").count(), 1);
+}
+
+#[tokio::test]
+async fn unknown_accounts_are_rejected() {
+    let fake = FakeGoogle::start().await.unwrap();
+    let client = GoogleClient::new(fake.config()).unwrap();
+
+    let err = client.for_account("nope").await.err().unwrap();
+    assert_eq!(err.kind(), "config");
+}
+
+#[tokio::test]
+async fn auth_info_reports_scopes_and_the_services_missing_them() {
+    let _guard = fresh_config_dir().await;
+    let fake = FakeGoogle::start().await.unwrap();
+    fake.set_granted_scopes(&["https://mail.google.com/", "https://www.googleapis.com/auth/drive"]);
+    let client = GoogleClient::new(fake.config()).unwrap();
+
+    let info = vgoog::cli::auth::info(&client, Some("fake")).await.unwrap();
+
+    let fake_info = &info[0];
+    assert_eq!(fake_info["account"], "fake");
+    assert_eq!(fake_info["active"], true);
+    assert_eq!(fake_info["email"], vgoog::fake::USER_EMAIL);
+    assert_eq!(fake_info["expires_in"], 3599);
+    assert_eq!(fake_info["scopes"].as_array().unwrap().len(), 2);
+    let missing = fake_info["missing_scopes"].as_object().unwrap();
+    assert!(!missing.contains_key("gmail"));
+    assert!(!missing.contains_key("drive"));
+    assert_eq!(missing["calendar"], json!(["https://www.googleapis.com/auth/calendar"]));
+    assert_eq!(missing.len(), Service::ALL.len() - 2);
+    assert_eq!(fake_info["warnings"].as_array().unwrap().len(), missing.len());
+}
+
+#[tokio::test]
+async fn auth_info_reports_broken_accounts_without_failing_the_rest() {
+    let _guard = fresh_config_dir().await;
+    let fake = FakeGoogle::start().await.unwrap();
+    let mut config = two_accounts(&fake);
+    config.accounts.get_mut("work").unwrap().auth.refresh_token.clear();
+    let client = GoogleClient::new(config).unwrap();
+
+    let info = vgoog::cli::auth::info(&client, None).await.unwrap();
+
+    assert_eq!(info[0]["account"], "fake");
+    assert!(info[0]["missing_scopes"].as_object().unwrap().is_empty());
+    assert_eq!(info[1]["account"], "work");
+    assert_eq!(info[1]["error_kind"], "auth");
+}
+
+#[tokio::test]
+async fn revoking_clears_the_account_and_google_forgets_the_token() {
+    let _guard = fresh_config_dir().await;
+    let fake = FakeGoogle::start().await.unwrap();
+    let client = GoogleClient::new(two_accounts(&fake)).unwrap();
+
+    let result = vgoog::cli::auth::revoke(&client, "work", false).await.unwrap();
+    assert_eq!(result["revoked"], true);
+
+    let revoke = fake.requests().into_iter().find(|r| r.path == "/revoke").unwrap();
+    assert_eq!(revoke.body, json!(format!("token={}", vgoog::fake::REFRESH_TOKEN)));
+    let saved = Config::load().unwrap();
+    assert!(saved.accounts["work"].auth.refresh_token.is_empty());
+    assert!(saved.accounts["work"].auth.access_token.is_empty());
+    // The other account is untouched, and Google refuses the old token
+    assert_eq!(saved.accounts["fake"].auth.refresh_token, vgoog::fake::REFRESH_TOKEN);
+    let mut stale = two_accounts(&fake);
+    stale.active_account = "work".to_string();
+    let err = GoogleClient::new(stale).unwrap().access_token().await.unwrap_err();
+    assert!(err.to_string().contains("invalid_grant"), "{err}");
+}
+
+#[tokio::test]
+async fn revoking_with_remove_drops_the_account() {
+    let _guard = fresh_config_dir().await;
+    let fake = FakeGoogle::start().await.unwrap();
+    let client = GoogleClient::new(two_accounts(&fake)).unwrap();
+
+    vgoog::cli::auth::revoke(&client, "work", true).await.unwrap();
+
+    assert!(!Config::load().unwrap().accounts.contains_key("work"));
+    assert_eq!(client.account_names().await, ["fake"]);
+}
+
+#[test]
+fn broader_scopes_cover_narrower_ones() {
+    use vgoog::auth::scopes::covers;
+
+    let granted = vec![
+        "https://mail.google.com/".to_string(),
+        "https://www.googleapis.com/auth/drive".to_string(),
+        "https://www.googleapis.com/auth/calendar.readonly".to_string(),
+    ];
+    assert!(covers(&granted, "https://www.googleapis.com/auth/gmail.settings.sharing"));
+    assert!(covers(&granted, "https://www.googleapis.com/auth/drive.readonly"));
+    assert!(!covers(&granted, "https://www.googleapis.com/auth/calendar"));
+    assert!(!covers(&granted, "https://www.googleapis.com/auth/drivelabels"));
+
+    // Only the `.readonly` form is implied, not every scope sharing the prefix
+    let granted = vec!["https://www.googleapis.com/auth/contacts".to_string()];
+    assert!(covers(&granted, "https://www.googleapis.com/auth/contacts.readonly"));
+    assert!(!covers(&granted, "https://www.googleapis.com/auth/contacts.other.readonly"));
+    let granted = vec!["https://www.googleapis.com/auth/forms.body".to_string()];
+    assert!(covers(&granted, "https://www.googleapis.com/auth/forms.body.readonly"));
+    assert!(!covers(&granted, "https://www.googleapis.com/auth/forms.responses.readonly"));
+}
+
+#[test]
+fn every_service_scope_is_requested_at_login() {
+    let mut needed: Vec<&str> = Service::ALL.iter().flat_map(|s| s.scopes()).copied().collect();
+    let mut requested = vgoog::auth::DEFAULT_SCOPES.to_vec();
+    needed.sort();
+    requested.sort();
+    assert_eq!(needed, requested);
+}
@@ -0,0 +1,100 @@
+mod common;
+
+use chrono::{Duration, Utc};
+use common::exec;
+use serde_json::json;
+use std::sync::Once;
+use vgoog::client::GoogleClient;
+use vgoog::fake::FakeGoogle;
+use vgoog::retry::RetryPolicy;
+
+/// Token refreshes save the config; keep that out of the real config dir
+fn isolate_config_dir() {
+    static ONCE: Once = Once::new();
+    ONCE.call_once(|| {
+        let dir = std::env::temp_dir().join(format!("vgoog-tests-{}", std::process::id()));
+        std::env::set_var("VGOOG_CONFIG_DIR", dir);
+    });
+}
+
+#[tokio::test]
+async fn expired_token_is_refreshed_before_the_call() {
+    isolate_config_dir();
+    let fake = FakeGoogle::start().await.unwrap();
+    let mut config = fake.config();
+    let account = config.accounts.get_mut("fake").unwrap();
+    account.auth.access_token = "stale".to_string();
+    account.auth.token_expiry = Utc::now() - Duration::minutes(5);
+    let client = GoogleClient::new(config).unwrap();
+
+    let profile = exec(&client, "gmail", "get_profile", json!({})).await;
+    assert_eq!(profile["emailAddress"], vgoog::fake::USER_EMAIL);
+
+    let requests = fake.requests();
+    assert_eq!(requests[0].path, "/token");
+    assert_eq!(
+        requests[0].body,
+        json!(format!(
+            "client_id={}&client_secret={}&refresh_token={}&grant_type=refresh_token",
+            urlencoding::encode(vgoog::fake::CLIENT_ID),
+            vgoog::fake::CLIENT_SECRET,
+            vgoog::fake::REFRESH_TOKEN
+        ))
+    );
+    assert_eq!(requests[1].path, "/gmail/v1/users/me/profile");
+}
+
+#[tokio::test]
+async fn unknown_tokens_are_rejected() {
+    isolate_config_dir();
+    let fake = FakeGoogle::start().await.unwrap();
+    let mut config = fake.config();
+    config.accounts.get_mut("fake").unwrap().auth.access_token = "forged".to_string();
+    let client = GoogleClient::new(config).unwrap();
+
+    let err = vgoog::cli::exec::execute(&client, "drive", "get_about", json!({})).await.unwrap_err();
+    assert_eq!(err.kind(), "auth");
+}
+
+#[tokio::test]
+async fn google_error_envelopes_are_typed() {
+    isolate_config_dir();
+    let (fake, client) = common::setup().await;
+    fake.fail_next(
+        403,
+        json!({
+            "error": {
+                "code": 403,
+                "message": "Request had insufficient authentication scopes.",
+                "status": "PERMISSION_DENIED",
+                "details": [{
+                    "@type": "type.googleapis.com/google.rpc.ErrorInfo",
+                    "reason": "ACCESS_TOKEN_SCOPE_INSUFFICIENT",
+                }],
+            }
+        }),
+    );
+
+    let err = vgoog::cli::exec::execute(&client, "tasks", "list_task_lists", json!({})).await.unwrap_err();
+    assert_eq!(err.kind(), "insufficient_scopes");
+}
+
+#[tokio::test]
+async fn transient_failures_are_retried() {
+    isolate_config_dir();
+    let fake = FakeGoogle::start().await.unwrap();
+    let mut config = fake.config();
+    config.accounts.get_mut("fake").unwrap().retry = Some(RetryPolicy {
+        max_attempts: 3,
+        base_delay_ms: 1,
+        max_delay_ms: 5,
+    });
+    let client = GoogleClient::new(config).unwrap();
+    let unavailable = json!({ "error": { "code": 503, "message": "Backend Error", "status": "UNAVAILABLE" } });
+    fake.fail_next(503, unavailable.clone());
+    fake.fail_next(503, unavailable);
+
+    let labels = exec(&client, "gmail", "list_labels", json!({})).await;
+    assert!(labels["labels"].as_array().unwrap().len() >= 4);
+    assert_eq!(fake.requests().len(), 3);
+}
@@ -0,0 +1,115 @@
+mod common;
+
+use common::{exec, setup};
+use serde_json::json;
+
+#[tokio::test]
+async fn batch_get_returns_one_result_per_message() {
+    let (fake, client) = setup().await;
+    for id in ["m1", "m2"] {
+        fake.seed(
+            &format!("/gmail/v1/users/me/messages/{id}"),
+            json!({ "id": id, "threadId": id }),
+        );
+    }
+
+    let results = exec(
+        &client,
+        "gmail",
+        "batch_get_messages",
+        json!({ "ids": ["m1", "missing", "m2"], "format": "minimal" }),
+    )
+    .await;
+    assert_eq!(results[0], json!({ "ok": true, "data": { "id": "m1", "threadId": "m1" } }));
+    assert_eq!(results[1]["ok"], json!(false));
+    assert_eq!(results[1]["error_kind"], json!("not_found"));
+    assert_eq!(results[2]["data"]["id"], json!("m2"));
+
+    let requests = fake.requests();
+    assert_eq!(requests[0].method, "POST");
+    assert_eq!(requests[0].path, "/batch/gmail/v1");
+    assert_eq!(requests[1].path, "/gmail/v1/users/me/messages/m1");
+    assert_eq!(requests[1].param("format"), Some("minimal"));
+    assert_eq!(requests.len(), 4);
+}
+
+#[tokio::test]
+async fn large_batches_are_split_into_calls_of_one_hundred() {
+    let (fake, client) = setup().await;
+    let event_ids: Vec<String> = (0..150).map(|i| format!("e{i}")).collect();
+    for id in &event_ids {
+        fake.seed(&format!("/calendar/v3/calendars/primary/events/{id}"), json!({ "id": id }));
+    }
+
+    let results = exec(
+        &client,
+        "calendar",
+        "batch_delete_events",
+        json!({ "calendar_id": "primary", "event_ids": event_ids }),
+    )
+    .await;
+    assert_eq!(results.as_array().unwrap().len(), 150);
+    assert!(results.as_array().unwrap().iter().all(|r| r == &json!({ "ok": true, "data": null })));
+    assert!(fake.get("/calendar/v3/calendars/primary/events/e149").is_none());
+
+    let outer: Vec<_> = fake.requests().into_iter().filter(|r| r.path.starts_with("/batch/")).collect();
+    assert_eq!(outer.len(), 2);
+}
+
+#[tokio::test]
+async fn batch_sends_request_bodies() {
+    let (fake, client) = setup().await;
+    for id in ["p1", "p2"] {
+        fake.seed(
+            &format!("/drive/v3/files/f1/permissions/{id}"),
+            json!({ "id": id, "role": "reader" }),
+        );
+    }
+
+    let results = exec(
+        &client,
+        "drive",
+        "batch_update_permissions",
+        json!({ "file_id": "f1", "permission_ids": ["p1", "p2"], "role": "writer" }),
+    )
+    .await;
+    assert_eq!(results[1]["data"], json!({ "id": "p2", "role": "writer" }));
+    assert_eq!(fake.get("/drive/v3/files/f1/permissions/p1").unwrap()["role"], json!("writer"));
+}
+
+#[tokio::test]
+async fn raw_batch_resolves_paths_against_the_service() {
+    let (fake, client) = setup().await;
+    fake.seed("/drive/v3/files/f1", json!({ "id": "f1", "name": "a.txt" }));
+
+    let results = vgoog::cli::batch::execute(
+        &client,
+        "drive",
+        json!([
+            { "path": "/files/f1" },
+            { "method": "patch", "path": "files/f1", "body": { "name": "b.txt" } },
+        ]),
+    )
+    .await
+    .unwrap();
+    assert_eq!(results[0]["data"]["name"], json!("a.txt"));
+    assert_eq!(results[1]["data"]["name"], json!("b.txt"));
+
+    let err = vgoog::cli::batch::execute(&client, "sheets", json!([])).await.unwrap_err();
+    assert!(err.to_string().contains("not supported"));
+}
+
+#[tokio::test]
+async fn failed_batch_call_is_an_error() {
+    let (fake, client) = setup().await;
+    fake.fail_next(
+        400,
+        json!({ "error": { "code": 400, "message": "Bad batch", "status": "INVALID_ARGUMENT" } }),
+    );
+
+    let err = vgoog::api::gmail::GmailApi::new(&client)
+        .batch_get_messages(&["m1"], "full")
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), "invalid_argument");
+}
@@ -0,0 +1,195 @@
+//! The on-disk response cache: TTLs, ETag revalidation, invalidation on
+//! writes, `--no-cache` and `vgoog cache clear`.
+
+mod common;
+
+use common::{exec, fresh_config_dir};
+use serde_json::json;
+use vgoog::cache::{self, CacheConfig};
+use vgoog::client::GoogleClient;
+use vgoog::config::Config;
+use vgoog::endpoints::Endpoints;
+use vgoog::fake::FakeGoogle;
+
+const LABELS: &str = "/gmail/v1/users/me/labels";
+
+/// A client with the cache on, and `ttls` on top of the defaults
+async fn cached_client(fake: &FakeGoogle, ttls: &[(&str, u64)]) -> GoogleClient {
+    let mut config = fake.config();
+    config.cache.enabled = true;
+    config.cache.ttl_secs.extend(ttls.iter().map(|(resource, ttl)| (resource.to_string(), *ttl)));
+    GoogleClient::new(config).unwrap()
+}
+
+fn gets(fake: &FakeGoogle, path: &str) -> usize {
+    fake.requests().iter().filter(|r| r.method == "GET" && r.path == path).count()
+}
+
+#[test]
+fn urls_map_to_their_resource() {
+    let endpoints = Endpoints::default();
+    let resource = |url: String| cache::resource(&endpoints, &url);
+
+    assert_eq!(resource(format!("{}/labels/Label_1", endpoints.gmail)).as_deref(), Some("gmail.labels"));
+    assert_eq!(
+        resource(format!("{}/users/me/calendarList?maxResults=10", endpoints.calendar)).as_deref(),
+        Some("calendar.calendarList")
+    );
+    assert_eq!(resource(format!("{}/users/@me/lists", endpoints.tasks)).as_deref(), Some("tasks.lists"));
+    assert_eq!(
+        resource(format!("{}/files/abc/permissions/p1", endpoints.drive)).as_deref(),
+        Some("drive.permissions")
+    );
+    assert_eq!(
+        resource(format!("{}/files?q=%27root%27%20in%20parents%20and%20trashed%3Dfalse&pageSize=20", endpoints.drive))
+            .as_deref(),
+        Some("drive.folders")
+    );
+    assert_eq!(
+        resource(format!("{}/files?q=name%20contains%20%27report%27", endpoints.drive)).as_deref(),
+        Some("drive.files")
+    );
+    assert_eq!(resource(format!("{}/files/abc?fields=md5Checksum", endpoints.drive)).as_deref(), Some("drive.files"));
+    assert_eq!(resource(endpoints.token_uri.clone()), None);
+
+    let config: CacheConfig = toml::from_str("[ttl_secs]\n\"gmail.labels\" = 0\n\"drive.permissions\" = 30\n").unwrap();
+    assert!(config.enabled);
+    let ttls = config.ttls();
+    assert_eq!(ttls["gmail.labels"], 0);
+    assert_eq!(ttls["drive.permissions"], 30);
+    assert_eq!(ttls["tasks.lists"], 300);
+    assert!(!ttls.contains_key("drive.files"));
+}
+
+#[tokio::test]
+async fn downloads_verify_against_current_metadata() {
+    let _guard = fresh_config_dir().await;
+    let fake = FakeGoogle::start().await.unwrap();
+    fake.seed("/drive/v3/files/f1", json!({ "id": "f1", "name": "notes.txt" }));
+    fake.seed_media("f1", b"first".to_vec());
+    // Even with every Drive file cached, the checksum comes from Google
+    let client = cached_client(&fake, &[("drive.files", 600)]).await;
+    let path = std::env::temp_dir().join(format!("vgoog-cache-{}-notes.txt", std::process::id()));
+    let download = json!({ "file_id": "f1", "path": path });
+
+    exec(&client, "drive", "download_file", download.clone()).await;
+    fake.seed_media("f1", b"second".to_vec());
+    let result = exec(&client, "drive", "download_file", download).await;
+    assert_eq!(result["verified"], true);
+    assert_eq!(std::fs::read(&path).unwrap(), b"second");
+    let _ = std::fs::remove_file(&path);
+}
+
+#[tokio::test]
+async fn fresh_responses_are_served_from_disk() {
+    let _guard = fresh_config_dir().await;
+    let fake = FakeGoogle::start().await.unwrap();
+    fake.seed(&format!("{LABELS}/Label_1"), json!({ "id": "Label_1", "name": "Work" }));
+
+    let client = cached_client(&fake, &[]).await;
+    let first = exec(&client, "gmail", "list_labels", json!({})).await;
+    let again = exec(&client, "gmail", "list_labels", json!({})).await;
+    assert_eq!(first, again);
+    assert_eq!(gets(&fake, LABELS), 1);
+
+    // Another client for the account, as the next command would be, reads the same entry
+    let next = cached_client(&fake, &[]).await;
+    assert_eq!(exec(&next, "gmail", "list_labels", json!({})).await, first);
+    assert_eq!(gets(&fake, LABELS), 1);
+
+    // Resources without a TTL always go to Google
+    exec(&client, "gmail", "get_profile", json!({})).await;
+    exec(&client, "gmail", "get_profile", json!({})).await;
+    assert_eq!(gets(&fake, "/gmail/v1/users/me/profile"), 2);
+}
+
+#[tokio::test]
+async fn stale_responses_are_revalidated_with_their_etag() {
+    let _guard = fresh_config_dir().await;
+    let fake = FakeGoogle::start().await.unwrap();
+    fake.seed("/tasks/v1/users/@me/lists/L1", json!({ "id": "L1", "title": "Errands" }));
+    let client = cached_client(&fake, &[("tasks.lists", 0)]).await;
+
+    let first = exec(&client, "tasks", "list_task_lists", json!({})).await;
+    let sent = fake.requests();
+    assert_eq!(sent.last().unwrap().header("if-none-match"), None);
+
+    let again = exec(&client, "tasks", "list_task_lists", json!({})).await;
+    assert_eq!(again, first);
+    let revalidation = fake.requests().pop().unwrap();
+    assert!(revalidation.header("if-none-match").is_some_and(|etag| etag.starts_with('"')));
+
+    // A changed resource comes back in full
+    fake.seed("/tasks/v1/users/@me/lists/L2", json!({ "id": "L2", "title": "Work" }));
+    let changed = exec(&client, "tasks", "list_task_lists", json!({})).await;
+    assert!(common::ids(&changed, "items", "id").contains(&"L2".to_string()));
+}
+
+#[tokio::test]
+async fn writes_drop_the_cached_resource() {
+    let _guard = fresh_config_dir().await;
+    let fake = FakeGoogle::start().await.unwrap();
+    let client = cached_client(&fake, &[]).await;
+
+    exec(&client, "gmail", "list_labels", json!({})).await;
+    exec(&client, "gmail", "create_label", json!({ "name": "Receipts" })).await;
+    let labels = exec(&client, "gmail", "list_labels", json!({})).await;
+    assert!(labels["labels"].as_array().unwrap().iter().any(|l| l["name"] == "Receipts"));
+    assert_eq!(gets(&fake, LABELS), 2);
+
+    // Creating a calendar also adds it to the calendar list
+    exec(&client, "calendar", "list_calendars", json!({})).await;
+    exec(&client, "calendar", "create_calendar", json!({ "summary": "Trips" })).await;
+    let calendars = exec(&client, "calendar", "list_calendars", json!({})).await;
+    assert!(calendars["items"].as_array().unwrap().iter().any(|c| c["summary"] == "Trips"));
+}
+
+#[tokio::test]
+async fn no_cache_and_clear_fetch_again() {
+    let _guard = fresh_config_dir().await;
+    let fake = FakeGoogle::start().await.unwrap();
+
+    let mut uncached = cached_client(&fake, &[]).await;
+    uncached.disable_cache();
+    exec(&uncached, "gmail", "list_labels", json!({})).await;
+    exec(&uncached, "gmail", "list_labels", json!({})).await;
+    assert_eq!(gets(&fake, LABELS), 2);
+    assert!(!Config::config_dir().unwrap().join(cache::DIR).exists());
+
+    let client = cached_client(&fake, &[]).await;
+    exec(&client, "gmail", "list_labels", json!({})).await;
+    exec(&client, "tasks", "list_task_lists", json!({})).await;
+    assert_eq!(cache::clear(Some("someone-else")).unwrap(), 0);
+    assert_eq!(cache::clear(Some("fake")).unwrap(), 2);
+    assert_eq!(cache::clear(None).unwrap(), 0);
+
+    exec(&client, "gmail", "list_labels", json!({})).await;
+    assert_eq!(gets(&fake, LABELS), 4);
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn entries_are_private() {
+    use std::os::unix::fs::PermissionsExt;
+    let mode = |path: &std::path::Path| std::fs::metadata(path).unwrap().permissions().mode() & 0o777;
+
+    let _guard = fresh_config_dir().await;
+    let fake = FakeGoogle::start().await.unwrap();
+    let client = cached_client(&fake, &[]).await;
+    exec(&client, "gmail", "list_labels", json!({})).await;
+
+    let root = Config::config_dir().unwrap().join(cache::DIR);
+    let mut dirs = vec![root];
+    while let Some(dir) = dirs.pop() {
+        assert_eq!(mode(&dir), 0o700, "{}", dir.display());
+        for entry in std::fs::read_dir(&dir).unwrap().flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                dirs.push(path);
+            } else {
+                assert_eq!(mode(&path), 0o600, "{}", path.display());
+                assert_eq!(path.extension().unwrap(), "json", "{}", path.display());
+            }
+        }
+    }
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use common::{exec, ids, setup};
+use serde_json::json;
+
+#[tokio::test]
+async fn events_are_created_updated_and_moved() {
+    let (fake, client) = setup().await;
+
+    let calendar = exec(&client, "calendar", "create_calendar", json!({ "summary": "Trips" })).await;
+    let listed = exec(&client, "calendar", "list_calendars", json!({})).await;
+    assert_eq!(ids(&listed, "items", "id"), ["primary", calendar["id"].as_str().unwrap()]);
+
+    let event = exec(
+        &client,
+        "calendar",
+        "create_event",
+        json!({ "event": { "summary": "Flight", "start": { "dateTime": "2026-11-02T09:00:00Z" } } }),
+    )
+    .await;
+    assert_eq!(event["status"], "confirmed");
+
+    exec(
+        &client,
+        "calendar",
+        "events_patch",
+        json!({ "calendar_id": "primary", "event_id": event["id"], "body": { "summary": "Flight BA123" } }),
+    )
+    .await;
+    exec(
+        &client,
+        "calendar",
+        "move_event",
+        json!({ "calendar_id": "primary", "event_id": event["id"], "destination": calendar["id"] }),
+    )
+    .await;
+
+    let primary = exec(&client, "calendar", "list_events", json!({})).await;
+    assert!(ids(&primary, "items", "id").is_empty());
+    let trips = exec(&client, "calendar", "list_events", json!({ "calendar_id": calendar["id"] })).await;
+    assert_eq!(trips["items"][0]["summary"], "Flight BA123");
+
+    let moved = fake.requests().into_iter().find(|r| r.path.ends_with("/move")).unwrap();
+    assert_eq!(moved.param("destination"), calendar["id"].as_str());
+}
+
+#[tokio::test]
+async fn deleting_a_calendar_drops_it_from_the_list() {
+    let (_fake, client) = setup().await;
+
+    let calendar = exec(&client, "calendar", "create_calendar", json!({ "summary": "Temp" })).await;
+    exec(&client, "calendar", "quick_add_event", json!({ "calendar_id": calendar["id"], "text": "Standup 9am" })).await;
+    exec(&client, "calendar", "clear_calendar", json!({ "id": calendar["id"] })).await;
+    let events = exec(&client, "calendar", "list_events", json!({ "calendar_id": calendar["id"] })).await;
+    assert!(ids(&events, "items", "id").is_empty());
+
+    exec(&client, "calendar", "delete_calendar", json!({ "id": calendar["id"] })).await;
+    let listed = exec(&client, "calendar", "list_calendars", json!({})).await;
+    assert_eq!(ids(&listed, "items", "id"), ["primary"]);
+}
+
+#[tokio::test]
+async fn event_list_pages() {
+    let (fake, client) = setup().await;
+    for i in 0..3 {
+        fake.seed(
+            &format!("/calendar/v3/calendars/primary/events/e{i}"),
+            json!({ "id": format!("e{i}"), "summary": format!("Event {i}") }),
+        );
+    }
+
+    let first = exec(&client, "calendar", "list_events", json!({ "max_results": 2 })).await;
+    assert_eq!(ids(&first, "items", "id"), ["e0", "e1"]);
+    let second = exec(
+        &client,
+        "calendar",
+        "list_events",
+        json!({ "max_results": 2, "page_token": first["nextPageToken"] }),
+    )
+    .await;
+    assert_eq!(ids(&second, "items", "id"), ["e2"]);
+}
@@ -0,0 +1,85 @@
+mod common;
+
+use common::exec;
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use vgoog::api::drive::DriveApi;
+use vgoog::cassette::CassetteMode;
+use vgoog::client::GoogleClient;
+use vgoog::fake::{FakeGoogle, ACCESS_TOKEN};
+
+fn cassette_path(name: &str) -> PathBuf {
+    let path = std::env::temp_dir()
+        .join(format!("vgoog-cassettes-{}", std::process::id()))
+        .join(format!("{name}.json"));
+    let _ = std::fs::remove_file(&path);
+    path
+}
+
+#[tokio::test]
+async fn recorded_session_replays_without_network() {
+    let path = cassette_path("replay");
+    let fake = FakeGoogle::start().await.unwrap();
+    let config = fake.config();
+
+    let recorder = GoogleClient::with_cassette(config.clone(), Some(CassetteMode::Record(path.clone()))).unwrap();
+    let created = exec(&recorder, "tasks", "create_task_list", json!({ "title": "Errands" })).await;
+    let listed = exec(&recorder, "tasks", "list_task_lists", json!({})).await;
+    let missing = vgoog::cli::exec::execute(&recorder, "gmail", "get_message", json!({ "id": "nope" })).await;
+    assert!(missing.is_err());
+    drop(fake);
+
+    let player = GoogleClient::with_cassette(config, Some(CassetteMode::Replay(path))).unwrap();
+    assert_eq!(exec(&player, "tasks", "create_task_list", json!({ "title": "Errands" })).await, created);
+    assert_eq!(exec(&player, "tasks", "list_task_lists", json!({})).await, listed);
+    let err = vgoog::cli::exec::execute(&player, "gmail", "get_message", json!({ "id": "nope" }))
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), "not_found");
+}
+
+#[tokio::test]
+async fn bearer_tokens_are_redacted() {
+    let path = cassette_path("redacted");
+    let fake = FakeGoogle::start().await.unwrap();
+    let client = GoogleClient::with_cassette(fake.config(), Some(CassetteMode::Record(path.clone()))).unwrap();
+
+    exec(&client, "drive", "get_about", json!({})).await;
+
+    let content = std::fs::read_to_string(&path).unwrap();
+    assert!(!content.contains(ACCESS_TOKEN));
+    let cassette: Value = serde_json::from_str(&content).unwrap();
+    let request = &cassette["interactions"][0]["request"];
+    assert_eq!(request["headers"]["authorization"], "Bearer [REDACTED]");
+    assert!(request["url"].as_str().unwrap().contains("/drive/v3/about?"));
+}
+
+#[tokio::test]
+async fn binary_bodies_round_trip() {
+    let path = cassette_path("binary");
+    let fake = FakeGoogle::start().await.unwrap();
+    fake.seed("/drive/v3/files/f1", json!({ "id": "f1", "name": "blob" }));
+    fake.seed_media("f1", vec![0, 159, 146, 150, 255]);
+
+    let target = path.with_extension("bin");
+    let recorder = GoogleClient::with_cassette(fake.config(), Some(CassetteMode::Record(path.clone()))).unwrap();
+    DriveApi::new(&recorder).download_file_to("f1", &target, None).await.unwrap();
+    std::fs::remove_file(&target).unwrap();
+
+    let player = GoogleClient::with_cassette(fake.config(), Some(CassetteMode::Replay(path))).unwrap();
+    DriveApi::new(&player).download_file_to("f1", &target, None).await.unwrap();
+    assert_eq!(std::fs::read(&target).unwrap(), vec![0, 159, 146, 150, 255]);
+}
+
+#[tokio::test]
+async fn replay_rejects_unrecorded_requests() {
+    let path = cassette_path("empty");
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    std::fs::write(&path, r#"{ "interactions": [] }"#).unwrap();
+    let fake = FakeGoogle::start().await.unwrap();
+    let player = GoogleClient::with_cassette(fake.config(), Some(CassetteMode::Replay(path))).unwrap();
+
+    let err = vgoog::cli::exec::execute(&player, "gmail", "get_profile", json!({})).await.unwrap_err();
+    assert!(err.to_string().contains("No recorded response for GET /gmail/v1/users/me/profile"), "{err}");
+    assert!(fake.requests().is_empty());
+}
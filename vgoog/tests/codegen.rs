@@ -0,0 +1,157 @@
+//! Actions generated from the Discovery documents in `discovery/`.
+
+mod common;
+
+use common::{exec, setup};
+use serde_json::json;
+use vgoog::api::generated::{self, calendar, drive, tasks};
+
+#[tokio::test]
+async fn typed_methods_build_paths_and_query_strings() {
+    let (fake, client) = setup().await;
+    fake.seed("/drive/v3/drives/sd 1", json!({ "id": "sd 1", "name": "Team" }));
+
+    let params = drive::DrivesGetParams {
+        drive_id: "sd 1".to_string(),
+        use_domain_admin_access: Some(true),
+        fields: Some("id,name".to_string()),
+    };
+    let drive = drive::Api::new(&client).drives_get(&params).await.unwrap();
+    assert_eq!(drive["name"], "Team");
+
+    let request = fake.requests().pop().unwrap();
+    assert_eq!(request.method, "GET");
+    assert_eq!(request.path, "/drive/v3/drives/sd 1");
+    assert_eq!(request.param("useDomainAdminAccess"), Some("true"));
+    assert_eq!(request.param("fields"), Some("id,name"));
+}
+
+#[tokio::test]
+async fn exec_arguments_fill_the_params_and_body() {
+    let (fake, client) = setup().await;
+    fake.seed("/calendar/v3/calendars/primary/events/e1", json!({ "id": "e1", "summary": "Standup" }));
+
+    let event = exec(
+        &client,
+        "calendar",
+        "events_patch",
+        json!({ "calendar_id": "primary", "event_id": "e1", "send_updates": "all", "body": { "location": "Room 2" } }),
+    )
+    .await;
+    assert_eq!(event["summary"], "Standup");
+    assert_eq!(event["location"], "Room 2");
+
+    let request = fake.requests().pop().unwrap();
+    assert_eq!(request.method, "PATCH");
+    assert_eq!(request.param("sendUpdates"), Some("all"));
+    assert_eq!(request.body, json!({ "location": "Room 2" }));
+
+    // Leaving the body out sends an empty one
+    fake.seed("/tasks/v1/users/@me/lists/L1", json!({ "id": "L1", "title": "Errands" }));
+    exec(&client, "tasks", "tasklists_patch", json!({ "tasklist": "L1" })).await;
+    let request = fake.requests().pop().unwrap();
+    assert_eq!(request.path, "/tasks/v1/users/@me/lists/L1");
+    assert_eq!(request.body, json!({}));
+}
+
+#[tokio::test]
+async fn bad_arguments_are_rejected_before_any_request() {
+    let (fake, client) = setup().await;
+
+    let err = vgoog::cli::exec::execute(&client, "drive", "drives_hide", json!({ "drive": "sd1" }))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("Invalid arguments for drives_hide"), "{err}");
+    assert!(err.to_string().contains("unknown field `drive`"), "{err}");
+
+    let err = vgoog::cli::exec::execute(&client, "calendar", "acl_get", json!({ "calendar_id": "primary" }))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("missing field `rule_id`"), "{err}");
+
+    let err = vgoog::cli::exec::execute(&client, "tasks", "tasks_patch", json!({ "tasklist": 1, "task": "t1" }))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("invalid type"), "{err}");
+    assert!(fake.requests().is_empty());
+}
+
+#[test]
+fn generated_actions_are_listed_with_their_service() {
+    let listed = vgoog::cli::exec::list_all();
+    for (service, actions) in generated::SERVICES {
+        for action in *actions {
+            assert!(
+                listed[*service].as_array().unwrap().iter().any(|a| a == action.name),
+                "{service}.{} not listed",
+                action.name
+            );
+        }
+    }
+    assert!(generated::has_action("drive", "files_list_labels"));
+    assert!(!generated::has_action("tasks", "files_list_labels"));
+    assert!(generated::READS.contains(&("calendar", "acl_get")));
+    assert!(!generated::READS.contains(&("calendar", "acl_patch")));
+    assert!(!generated::READS.contains(&("tasks", "acl_get")));
+
+    // The structs are plain data for callers that skip `exec`
+    let params = tasks::TasksPatchParams { tasklist: "L1".to_string(), task: "t1".to_string(), ..Default::default() };
+    assert!(params.body.is_null());
+    let _ = calendar::EventsImportParams::default();
+}
+
+#[tokio::test]
+async fn media_methods_stream_to_and_from_files() {
+    let (fake, client) = setup().await;
+    let dir = std::env::temp_dir().join(format!("vgoog-codegen-media-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    fake.seed("/drive/v3/files/f1", json!({ "id": "f1", "name": "notes.txt" }));
+    fake.seed_media("f1", b"file body".to_vec());
+
+    // Metadata without `download_to`, content with it
+    let meta = exec(&client, "drive", "files_get", json!({ "file_id": "f1" })).await;
+    assert_eq!(meta["name"], "notes.txt");
+    let target = dir.join("notes.txt");
+    let saved = exec(&client, "drive", "files_get", json!({ "file_id": "f1", "download_to": target })).await;
+    assert_eq!(saved["size"], 9);
+    assert_eq!(std::fs::read(&target).unwrap(), b"file body");
+    assert_eq!(fake.requests().pop().unwrap().param("alt"), Some("media"));
+
+    // Export has no JSON response, so it needs a destination
+    let err = vgoog::cli::exec::execute(&client, "drive", "files_export", json!({ "file_id": "f1", "mime_type": "text/plain" }))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("missing field `download_to`"), "{err}");
+    let target = dir.join("notes.pdf");
+    exec(&client, "drive", "files_export", json!({ "file_id": "f1", "mime_type": "application/pdf", "download_to": target })).await;
+    let request = fake.requests().pop().unwrap();
+    assert_eq!(request.path, "/drive/v3/files/f1/export");
+    assert_eq!(request.param("mimeType"), Some("application/pdf"));
+    assert_eq!(request.param("alt"), None);
+    assert_eq!(std::fs::read(&target).unwrap(), b"file body");
+
+    // Uploads go to the upload endpoint with the body as metadata
+    let source = dir.join("upload.txt");
+    std::fs::write(&source, b"uploaded").unwrap();
+    let created = exec(
+        &client,
+        "drive",
+        "files_create",
+        json!({ "upload_from": source, "upload_mime_type": "text/plain", "body": { "name": "up.txt" } }),
+    )
+    .await;
+    assert_eq!(created["name"], "up.txt");
+    let start = fake.requests().into_iter().find(|r| r.param("uploadType") == Some("resumable")).unwrap();
+    assert_eq!(start.path, "/upload/drive/v3/files");
+    assert_eq!(start.body, json!({ "name": "up.txt" }));
+    let _ = std::fs::remove_dir_all(&dir);
+}
+
+#[test]
+fn media_actions_declare_their_file_arguments() {
+    let find = |name: &str| drive::ACTIONS.iter().find(|a| a.name == name).unwrap();
+    assert!(find("files_export").arg("download_to").unwrap().required);
+    assert!(!find("files_get").arg("download_to").unwrap().required);
+    assert!(find("files_update").arg("upload_from").is_some());
+    assert!(find("files_list_labels").arg("download_to").is_none());
+}
@@ -0,0 +1,43 @@
+#![allow(dead_code)]
+
+use serde_json::Value;
+use tokio::sync::{Mutex, MutexGuard};
+use vgoog::client::GoogleClient;
+use vgoog::fake::FakeGoogle;
+
+/// A fresh fake server and a client signed in to it
+pub async fn setup() -> (FakeGoogle, GoogleClient) {
+    let fake = FakeGoogle::start().await.expect("start fake server");
+    let client = GoogleClient::new(fake.config()).expect("client for fake server");
+    (fake, client)
+}
+
+/// Run an exec action, panicking with the error JSON on failure
+pub async fn exec(client: &GoogleClient, service: &str, action: &str, args: Value) -> Value {
+    match vgoog::cli::exec::execute(client, service, action, args).await {
+        Ok(value) => value,
+        Err(e) => panic!("{service}.{action} failed: {}", e.to_json()),
+    }
+}
+
+/// Ids of the objects in `value[key]`, read from `field`
+pub fn ids(value: &Value, key: &str, field: &str) -> Vec<String> {
+    value[key]
+        .as_array()
+        .into_iter()
+        .flatten()
+        .filter_map(|v| v[field].as_str().map(String::from))
+        .collect()
+}
+
+/// The config dir and VGOOG_PASSPHRASE are process-wide, so tests that
+/// save config take turns, each starting from an empty directory
+pub async fn fresh_config_dir() -> MutexGuard<'static, ()> {
+    static LOCK: Mutex<()> = Mutex::const_new(());
+    let guard = LOCK.lock().await;
+    let dir = std::env::temp_dir().join(format!("vgoog-config-{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&dir);
+    std::env::set_var("VGOOG_CONFIG_DIR", &dir);
+    std::env::remove_var("VGOOG_PASSPHRASE");
+    guard
+}
@@ -0,0 +1,137 @@
+//! Config persistence when several clients or processes share the file.
+
+mod common;
+
+use chrono::{Duration, Utc};
+use common::{exec, fresh_config_dir};
+use serde_json::json;
+use vgoog::client::GoogleClient;
+use vgoog::config::{Account, Config, ConfigLock};
+use vgoog::fake::FakeGoogle;
+
+/// A saved config for the fake server whose access token has expired
+fn save_expired(fake: &FakeGoogle) -> Config {
+    let mut config = fake.config();
+    let account = config.accounts.get_mut("fake").unwrap();
+    account.auth.access_token = "stale".to_string();
+    account.auth.token_expiry = Utc::now() - Duration::minutes(5);
+    config.save().unwrap();
+    config
+}
+
+fn token_requests(fake: &FakeGoogle) -> usize {
+    fake.requests().iter().filter(|r| r.path == "/token").count()
+}
+
+#[tokio::test]
+async fn token_refresh_keeps_changes_made_elsewhere() {
+    let _guard = fresh_config_dir().await;
+    let fake = FakeGoogle::start().await.unwrap();
+    let client = GoogleClient::new(save_expired(&fake)).unwrap();
+
+    // Another process adds an account and switches to it
+    let mut other = Config::load().unwrap();
+    other
+        .update(|c| {
+            c.add_account("work".into(), Account { label: "Work".into(), ..Default::default() });
+            c.active_account = "work".into();
+        })
+        .unwrap();
+
+    exec(&client, "gmail", "get_profile", json!({})).await;
+
+    let saved = Config::load().unwrap();
+    assert_eq!(saved.active_account, "work");
+    assert_eq!(saved.accounts["work"].label, "Work");
+    assert_ne!(saved.accounts["fake"].auth.access_token, "stale");
+}
+
+#[tokio::test]
+async fn refresh_does_not_resurrect_a_removed_account() {
+    let _guard = fresh_config_dir().await;
+    let fake = FakeGoogle::start().await.unwrap();
+    let client = GoogleClient::new(save_expired(&fake)).unwrap();
+
+    let mut other = Config::load().unwrap();
+    other.update(|c| c.remove_account("fake")).unwrap();
+
+    exec(&client, "gmail", "get_profile", json!({})).await;
+
+    assert!(!Config::load().unwrap().accounts.contains_key("fake"));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn parallel_refreshes_of_one_account_hit_the_token_endpoint_once() {
+    let _guard = fresh_config_dir().await;
+    let fake = FakeGoogle::start().await.unwrap();
+    let config = save_expired(&fake);
+    // Separate clients stand in for separate processes: no shared token state
+    let mut calls = tokio::task::JoinSet::new();
+    for _ in 0..4 {
+        let client = GoogleClient::new(config.clone()).unwrap();
+        calls.spawn(async move { exec(&client, "gmail", "get_profile", json!({})).await });
+    }
+    while let Some(call) = calls.join_next().await {
+        call.unwrap();
+    }
+
+    assert_eq!(token_requests(&fake), 1);
+}
+
+#[tokio::test]
+async fn concurrent_updates_are_all_kept() {
+    let _guard = fresh_config_dir().await;
+    let fake = FakeGoogle::start().await.unwrap();
+    fake.config().save().unwrap();
+
+    let writers: Vec<_> = (0..8)
+        .map(|i| {
+            std::thread::spawn(move || {
+                let mut config = Config::load().unwrap();
+                config
+                    .update(|c| {
+                        c.add_account(format!("acct-{i}"), Account { label: format!("#{i}"), ..Default::default() })
+                    })
+                    .unwrap();
+            })
+        })
+        .collect();
+    for writer in writers {
+        writer.join().unwrap();
+    }
+
+    let saved = Config::load().unwrap();
+    assert_eq!(saved.accounts.len(), 9);
+    let leftovers: Vec<_> = std::fs::read_dir(Config::config_dir().unwrap())
+        .unwrap()
+        .filter_map(|e| e.ok()?.file_name().into_string().ok())
+        .filter(|name| name.contains(".tmp-"))
+        .collect();
+    assert!(leftovers.is_empty(), "{leftovers:?}");
+}
+
+/// On a single-threaded runtime a blocking wait for the lock would stall
+/// every other task until the lock is released
+#[tokio::test]
+async fn waiting_for_the_lock_leaves_the_runtime_free() {
+    let _guard = fresh_config_dir().await;
+    let fake = FakeGoogle::start().await.unwrap();
+    fake.config().save().unwrap();
+    let client = GoogleClient::new(fake.config()).unwrap();
+
+    let held = ConfigLock::acquire().unwrap();
+    let release = std::thread::spawn(move || {
+        std::thread::sleep(std::time::Duration::from_millis(300));
+        drop(held);
+    });
+    let ticker = tokio::spawn(async {
+        for _ in 0..5 {
+            tokio::time::sleep(std::time::Duration::from_millis(20)).await;
+        }
+    });
+
+    client.update_config(|c| c.accounts.get_mut("fake").unwrap().label = "Renamed".to_string()).await.unwrap();
+    assert!(ticker.is_finished(), "other tasks stalled while waiting for the lock");
+    release.join().unwrap();
+    assert_eq!(Config::load().unwrap().accounts["fake"].label, "Renamed");
+}
@@ -0,0 +1,132 @@
+//! Recorded scopes and incremental consent for services first used
+//! without them.
+
+mod common;
+
+use chrono::{Duration, Utc};
+use common::{exec, fresh_config_dir};
+use serde_json::json;
+use vgoog::auth::loopback::PendingConsent;
+use vgoog::auth::scopes::{for_login, lacking};
+use vgoog::client::GoogleClient;
+use vgoog::config::Config;
+use vgoog::endpoints::Endpoints;
+use vgoog::fake::{FakeGoogle, CLIENT_ID, CLIENT_SECRET, REFRESH_TOKEN};
+use vgoog::ui::app::Service;
+
+const GMAIL_READONLY: &str = "https://www.googleapis.com/auth/gmail.readonly";
+
+/// The fake's config for an account that only consented to read Gmail
+fn read_only_gmail(fake: &FakeGoogle) -> Config {
+    fake.set_granted_scopes(&[GMAIL_READONLY]);
+    let mut config = fake.config();
+    config.accounts.get_mut("fake").unwrap().scopes = vec![GMAIL_READONLY.to_string()];
+    config.save().unwrap();
+    config
+}
+
+#[tokio::test]
+async fn read_only_accounts_read_but_are_refused_writes_before_any_request() {
+    let _guard = fresh_config_dir().await;
+    let fake = FakeGoogle::start().await.unwrap();
+    let client = GoogleClient::new(read_only_gmail(&fake)).unwrap();
+
+    exec(&client, "gmail", "list_messages", json!({ "max_results": 5 })).await;
+    fake.clear_requests();
+
+    let err = vgoog::cli::exec::execute(&client, "gmail", "send_message", json!({ "raw": "cmF3" }))
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), "insufficient_scopes");
+    let message = err.to_string();
+    assert!(message.contains("vgoog auth login --account fake --scope https://www.googleapis.com/auth/gmail.modify"), "{message}");
+    assert!(fake.requests().is_empty());
+
+    // A first read of another service asks for its read-only scope alone
+    let err = vgoog::cli::exec::execute(&client, "calendar", "list_events", json!({})).await.unwrap_err();
+    assert!(err.to_string().ends_with("--scope https://www.googleapis.com/auth/calendar.readonly`"), "{err}");
+}
+
+#[tokio::test]
+async fn incremental_consent_adds_just_the_missing_scopes() {
+    let _guard = fresh_config_dir().await;
+    let fake = FakeGoogle::start().await.unwrap();
+    let config = read_only_gmail(&fake);
+    let endpoints = Endpoints::resolve(&config.endpoints).unwrap();
+    let client = GoogleClient::new(config).unwrap();
+
+    let missing = client.missing_scopes(Service::AppsScript, Some("list_processes")).await;
+    assert_eq!(
+        missing,
+        [
+            "https://www.googleapis.com/auth/script.projects.readonly",
+            "https://www.googleapis.com/auth/script.processes",
+        ]
+    );
+
+    // Stand in for the browser: follow the consent screen's redirect
+    let consent = PendingConsent::start(&endpoints, CLIENT_ID, &missing, true).await.unwrap();
+    assert!(consent.url().contains("include_granted_scopes=true"));
+    let browser = reqwest::get(consent.url().to_string());
+    let (page, grant) = tokio::join!(browser, consent.finish(client.http(), &endpoints, CLIENT_ID, CLIENT_SECRET));
+    assert!(page.unwrap().status().is_success());
+    let grant = grant.unwrap();
+
+    let asked = fake.requests().into_iter().find(|r| r.path == "/o/oauth2/v2/auth").unwrap();
+    assert_eq!(asked.param("scope"), Some(missing.join(" ").as_str()));
+    assert_eq!(grant.scopes.len(), 3);
+    assert!(grant.scopes.contains(&GMAIL_READONLY.to_string()));
+
+    client.save_grant(grant).await.unwrap();
+    assert!(client.missing_scopes(Service::AppsScript, Some("list_processes")).await.is_empty());
+    exec(&client, "apps_script", "list_processes", json!({})).await;
+    exec(&client, "gmail", "list_messages", json!({})).await;
+
+    let saved = &Config::load().unwrap().accounts["fake"];
+    assert_eq!(saved.scopes.len(), 3);
+    assert_ne!(saved.auth.refresh_token, REFRESH_TOKEN);
+}
+
+#[tokio::test]
+async fn refreshes_record_the_granted_scopes() {
+    let _guard = fresh_config_dir().await;
+    let fake = FakeGoogle::start().await.unwrap();
+    let mut config = fake.config();
+    let account = config.accounts.get_mut("fake").unwrap();
+    account.auth.access_token = "stale".to_string();
+    account.auth.token_expiry = Utc::now() - Duration::minutes(5);
+    config.save().unwrap();
+    assert!(config.accounts["fake"].scopes.is_empty());
+
+    let client = GoogleClient::new(config).unwrap();
+    exec(&client, "gmail", "get_profile", json!({})).await;
+
+    let saved = &Config::load().unwrap().accounts["fake"];
+    assert_eq!(saved.scopes, vgoog::auth::DEFAULT_SCOPES);
+}
+
+#[test]
+fn login_scopes_follow_the_chosen_services() {
+    assert_eq!(for_login(&[], false, &[]).unwrap(), vgoog::auth::DEFAULT_SCOPES);
+    assert_eq!(for_login(&["gmail".into()], true, &[]).unwrap(), [GMAIL_READONLY]);
+    assert_eq!(
+        for_login(&["tasks".into()], false, &["directory.readonly".into()]).unwrap(),
+        ["https://www.googleapis.com/auth/tasks", "https://www.googleapis.com/auth/directory.readonly"]
+    );
+    assert_eq!(for_login(&["nope".into()], false, &[]).unwrap_err().kind(), "config");
+}
+
+#[test]
+fn the_directory_needs_its_own_scope() {
+    let granted: Vec<String> = vgoog::auth::DEFAULT_SCOPES.iter().map(|s| s.to_string()).collect();
+
+    assert!(lacking(&granted, Service::People, Some("list_contacts")).is_empty());
+    assert_eq!(
+        lacking(&granted, Service::People, Some("search_directory")),
+        ["https://www.googleapis.com/auth/directory.readonly"]
+    );
+    // Full scopes cover reads, and read-only ones don't cover writes
+    let read_only = vec!["https://www.googleapis.com/auth/drive.readonly".to_string()];
+    assert!(lacking(&read_only, Service::Drive, Some("list_files")).is_empty());
+    assert_eq!(lacking(&read_only, Service::Drive, Some("delete_file")), ["https://www.googleapis.com/auth/drive"]);
+}
@@ -0,0 +1,85 @@
+mod common;
+
+use common::{exec, ids, setup};
+use serde_json::json;
+
+#[tokio::test]
+async fn contacts_are_created_searched_and_deleted() {
+    let (_fake, client) = setup().await;
+
+    let ada = exec(
+        &client,
+        "contacts",
+        "create_contact",
+        json!({ "person": { "names": [{ "givenName": "Ada", "familyName": "Lovelace" }] } }),
+    )
+    .await;
+    exec(
+        &client,
+        "contacts",
+        "create_contact",
+        json!({ "person": { "names": [{ "givenName": "Grace" }] } }),
+    )
+    .await;
+
+    let found = exec(&client, "contacts", "search_contacts", json!({ "query": "lovelace" })).await;
+    assert_eq!(found["results"][0]["person"]["resourceName"], ada["resourceName"]);
+
+    exec(
+        &client,
+        "contacts",
+        "update_contact",
+        json!({
+            "resource_name": ada["resourceName"],
+            "person": { "emailAddresses": [{ "value": "ada@example.com" }] },
+            "update_mask": "emailAddresses",
+        }),
+    )
+    .await;
+    let fetched = exec(&client, "contacts", "get_person", json!({ "resource_name": ada["resourceName"] })).await;
+    assert_eq!(fetched["emailAddresses"][0]["value"], "ada@example.com");
+
+    exec(&client, "contacts", "delete_contact", json!({ "resource_name": ada["resourceName"] })).await;
+    let listed = exec(&client, "contacts", "list_contacts", json!({})).await;
+    assert_eq!(ids(&listed, "connections", "resourceName").len(), 1);
+}
+
+#[tokio::test]
+async fn contact_list_pages() {
+    let (fake, client) = setup().await;
+    for i in 0..3 {
+        exec(&client, "contacts", "create_contact", json!({ "person": { "names": [{ "givenName": format!("P{i}") }] } })).await;
+    }
+
+    let first = exec(&client, "contacts", "list_contacts", json!({ "page_size": 2 })).await;
+    assert_eq!(first["connections"].as_array().unwrap().len(), 2);
+    let second = exec(
+        &client,
+        "contacts",
+        "list_contacts",
+        json!({ "page_size": 2, "page_token": first["nextPageToken"] }),
+    )
+    .await;
+    assert_eq!(second["connections"][0]["names"][0]["givenName"], "P2");
+    assert!(fake.requests().last().unwrap().param("personFields").is_some());
+}
+
+#[tokio::test]
+async fn groups_track_members() {
+    let (_fake, client) = setup().await;
+
+    let person = exec(&client, "contacts", "create_contact", json!({ "person": {} })).await;
+    let group = exec(&client, "contacts", "create_contact_group", json!({ "name": "Family" })).await;
+    exec(
+        &client,
+        "contacts",
+        "modify_contact_group_members",
+        json!({ "resource_name": group["resourceName"], "add": [person["resourceName"]] }),
+    )
+    .await;
+    exec(&client, "contacts", "update_contact_group", json!({ "resource_name": group["resourceName"], "name": "Kin" })).await;
+
+    let fetched = exec(&client, "contacts", "get_contact_group", json!({ "resource_name": group["resourceName"] })).await;
+    assert_eq!(fetched["name"], "Kin");
+    assert_eq!(fetched["memberResourceNames"], json!([person["resourceName"]]));
+}
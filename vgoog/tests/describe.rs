@@ -0,0 +1,130 @@
+//! Action descriptions: argument checking and defaults in `exec`, and the
+//! JSON Schema `vgoog describe` prints.
+
+mod common;
+
+use common::{exec, setup};
+use serde_json::json;
+use vgoog::actions::{ArgType, SCHEMA_DIALECT};
+use vgoog::auth::scopes;
+use vgoog::cli::exec::{describe, describe_all, describe_service, list_all, SERVICES};
+
+#[test]
+fn every_listed_action_has_a_schema() {
+    let all = describe_all().unwrap();
+    for (service, actions) in list_all().as_object().unwrap() {
+        let described = all[service].as_array().unwrap();
+        assert_eq!(described.len(), actions.as_array().unwrap().len(), "{service}");
+        for entry in described {
+            let schema = &entry["schema"];
+            assert_eq!(schema["$schema"], SCHEMA_DIALECT);
+            assert_eq!(schema["type"], "object");
+            assert_eq!(schema["additionalProperties"], false);
+            assert!(!entry["description"].as_str().unwrap().is_empty(), "{service}.{}", entry["action"]);
+            for required in schema["required"].as_array().unwrap() {
+                let name = required.as_str().unwrap();
+                assert!(schema["properties"].get(name).is_some(), "{service}.{}: {name}", entry["action"]);
+            }
+        }
+    }
+}
+
+/// Defaults pass the checks they are put through: `prepare` would reject
+/// a default of the wrong type or outside the allowed values
+#[test]
+fn defaults_match_their_declared_type() {
+    for (service, actions) in SERVICES {
+        for action in *actions {
+            let mut args = json!({});
+            for arg in action.args {
+                if let Some(default) = arg.default {
+                    args[arg.name] = default.to_value();
+                } else if arg.required {
+                    args[arg.name] = match arg.kind {
+                        ArgType::String => json!(arg.values.first().copied().unwrap_or("x")),
+                        ArgType::Integer | ArgType::Number => json!(1),
+                        ArgType::Boolean => json!(true),
+                        ArgType::Strings | ArgType::Array => json!([]),
+                        ArgType::Object => json!({}),
+                    };
+                }
+            }
+            action.prepare(args).unwrap_or_else(|e| panic!("{service}.{}: {e}", action.name));
+        }
+    }
+}
+
+#[test]
+fn describe_covers_hand_written_and_generated_actions() {
+    let described = describe("tasks", "list_tasks").unwrap();
+    assert_eq!(described["read_only"], true);
+    let schema = &described["schema"];
+    assert_eq!(schema["title"], "tasks.list_tasks");
+    assert_eq!(schema["required"], json!(["task_list_id"]));
+    assert_eq!(schema["properties"]["max_results"], json!({ "type": "integer", "description": "Results per page", "default": 20 }));
+
+    let described = describe("gmail", "batch_get_messages").unwrap();
+    let properties = &described["schema"]["properties"];
+    assert_eq!(properties["ids"]["items"], json!({ "type": "string" }));
+    assert_eq!(properties["format"]["enum"], json!(["full", "metadata", "minimal", "raw"]));
+
+    let described = describe("calendar", "events_patch").unwrap();
+    assert_eq!(described["read_only"], false);
+    let schema = &described["schema"];
+    assert_eq!(schema["required"], json!(["calendar_id", "event_id"]));
+    assert_eq!(schema["properties"]["body"]["type"], "object");
+    assert!(schema["properties"]["send_updates"]["enum"].as_array().unwrap().contains(&json!("all")));
+
+    // Generated reads are told apart by service, not by name alone
+    assert!(scopes::is_read("calendar", "acl_get"));
+    assert!(!scopes::is_read("tasks", "acl_get"));
+    assert_eq!(describe("calendar", "acl_get").unwrap()["read_only"], true);
+
+    let err = describe("tasks", "list_task").unwrap_err();
+    assert_eq!(err.to_string(), "Unknown tasks action: list_task");
+    assert!(describe_service("mail").is_err());
+}
+
+#[tokio::test]
+async fn defaults_are_filled_in_before_dispatch() {
+    let (fake, client) = setup().await;
+    exec(&client, "gmail", "list_messages", json!({})).await;
+    assert_eq!(fake.requests().pop().unwrap().param("maxResults"), Some("20"));
+
+    // Null is the same as leaving an argument out
+    exec(&client, "gmail", "list_messages", json!({ "max_results": null, "query": "is:unread" })).await;
+    let request = fake.requests().pop().unwrap();
+    assert_eq!(request.param("maxResults"), Some("20"));
+    assert_eq!(request.param("q"), Some("is:unread"));
+}
+
+#[tokio::test]
+async fn bad_arguments_are_rejected_before_any_request() {
+    let (fake, client) = setup().await;
+    let error = |service: &'static str, action: &'static str, args| {
+        let client = &client;
+        async move { vgoog::cli::exec::execute(client, service, action, args).await.unwrap_err().to_string() }
+    };
+
+    let err = error("tasks", "get_task", json!({ "task_list_id": "L1" })).await;
+    assert_eq!(err, "Invalid arguments for get_task: missing field `task_id`");
+
+    let err = error("gmail", "list_messages", json!({ "max_results": "5" })).await;
+    assert!(err.contains("invalid type for `max_results`: a string, expected an integer"), "{err}");
+
+    let err = error("gmail", "list_messages", json!({ "max": 5 })).await;
+    assert!(err.contains("unknown field `max`, expected one of `query`, `max_results`, `page_token`"), "{err}");
+
+    let err = error("gmail", "list_labels", json!({ "id": "x" })).await;
+    assert!(err.contains("it takes no arguments"), "{err}");
+
+    let err = error("gmail", "get_message", json!({ "id": "m1", "format": "html" })).await;
+    assert!(err.contains("invalid value `html` for `format`"), "{err}");
+
+    let err = error("drive", "get_about", json!(["fields"])).await;
+    assert!(err.contains("expected an object, found an array"), "{err}");
+
+    let err = error("drive", "batch_update_permissions", json!({ "file_id": "f", "permission_ids": [1], "role": "reader" })).await;
+    assert!(err.contains("expected an array of strings"), "{err}");
+    assert!(fake.requests().is_empty());
+}
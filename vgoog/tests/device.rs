@@ -0,0 +1,96 @@
+//! The device-code flow: polling at the server's pace until the user
+//! approves, and giving up on denial or expiry. The clock is paused, so
+//! the waits between polls take no real time.
+
+mod common;
+
+use common::{exec, fresh_config_dir};
+use reqwest::Client;
+use serde_json::json;
+use std::time::Duration;
+use tokio::time::Instant;
+use vgoog::auth::device::{poll_for_token, request_code, DeviceCode};
+use vgoog::client::GoogleClient;
+use vgoog::config::Config;
+use vgoog::endpoints::Endpoints;
+use vgoog::fake::{FakeGoogle, CLIENT_ID, CLIENT_SECRET};
+
+const TASKS: &str = "https://www.googleapis.com/auth/tasks";
+
+async fn start() -> (FakeGoogle, Endpoints, DeviceCode) {
+    let fake = FakeGoogle::start().await.unwrap();
+    let endpoints = Endpoints::resolve(&fake.config().endpoints).unwrap();
+    let code = request_code(&Client::new(), &endpoints, CLIENT_ID, &[TASKS]).await.unwrap();
+    (fake, endpoints, code)
+}
+
+fn polls(fake: &FakeGoogle) -> usize {
+    fake.requests().iter().filter(|r| r.path == "/token").count()
+}
+
+#[tokio::test(start_paused = true)]
+async fn pending_keeps_polling_and_slow_down_adds_five_seconds() {
+    let (fake, endpoints, code) = start().await;
+    assert_eq!(code.interval, 1);
+    fake.script_device_polls(&["authorization_pending", "slow_down", "authorization_pending", "authorization_pending"]);
+
+    let started = Instant::now();
+    let grant = poll_for_token(&Client::new(), &endpoints, CLIENT_ID, CLIENT_SECRET, &code).await.unwrap();
+    assert_eq!(grant.scopes, [TASKS]);
+    assert!(grant.auth.refresh_token.starts_with("fake-refresh-"));
+
+    // Two polls a second apart, then three at 1 + 5 seconds after slow_down
+    assert_eq!(polls(&fake), 5);
+    assert_eq!(started.elapsed(), Duration::from_secs(1 + 1 + 6 + 6 + 6));
+}
+
+#[tokio::test(start_paused = true)]
+async fn a_denied_or_expired_code_is_reported() {
+    for (error, expected) in [
+        ("access_denied", "Auth error: access_denied: Forbidden"),
+        ("expired_token", "Auth error: expired_token: Bad Request"),
+    ] {
+        let (fake, endpoints, code) = start().await;
+        fake.script_device_polls(&["authorization_pending", error]);
+        let err = poll_for_token(&Client::new(), &endpoints, CLIENT_ID, CLIENT_SECRET, &code).await.unwrap_err();
+        assert_eq!(err.to_string(), expected);
+        assert_eq!(polls(&fake), 2);
+    }
+}
+
+#[tokio::test(start_paused = true)]
+async fn polling_stops_once_the_code_runs_out() {
+    let (fake, endpoints, mut code) = start().await;
+    code.expires_in = 3;
+    fake.script_device_polls(&["authorization_pending"; 10]);
+
+    let started = Instant::now();
+    let err = poll_for_token(&Client::new(), &endpoints, CLIENT_ID, CLIENT_SECRET, &code).await.unwrap_err();
+    assert_eq!(err.to_string(), "Auth error: Device code expired before approval");
+    assert_eq!(polls(&fake), 2);
+    assert_eq!(started.elapsed(), Duration::from_secs(3));
+}
+
+#[tokio::test(start_paused = true)]
+async fn the_approved_grant_is_saved_to_the_account() {
+    let _guard = fresh_config_dir().await;
+    let (fake, endpoints, code) = start().await;
+    let config = fake.config();
+    config.save().unwrap();
+    let client = GoogleClient::new(config).unwrap();
+
+    fake.script_device_polls(&["authorization_pending"]);
+    let grant = poll_for_token(&Client::new(), &endpoints, CLIENT_ID, CLIENT_SECRET, &code).await.unwrap();
+    let access_token = grant.auth.access_token.clone();
+    client.save_grant(grant).await.unwrap();
+
+    let saved = &Config::load().unwrap().accounts["fake"];
+    assert_eq!(saved.scopes, [TASKS]);
+    assert_eq!(saved.auth.access_token, access_token);
+    assert!(saved.auth.refresh_token.starts_with("fake-refresh-"));
+
+    // API calls go out with the new token
+    exec(&client, "tasks", "list_task_lists", json!({})).await;
+    let call = fake.requests().into_iter().rfind(|r| r.path.starts_with("/tasks/")).unwrap();
+    assert_eq!(call.header("authorization"), Some(format!("Bearer {access_token}").as_str()));
+}
@@ -0,0 +1,88 @@
+//! Sheets, Docs, Slides, Forms and Apps Script are stubs in the fake, so these
+//! tests assert on the requests vgoog sends.
+
+mod common;
+
+use common::{exec, setup};
+use serde_json::json;
+
+#[tokio::test]
+async fn created_documents_can_be_fetched() {
+    let (_fake, client) = setup().await;
+
+    let sheet = exec(&client, "sheets", "create_spreadsheet", json!({ "title": "Budget" })).await;
+    let fetched = exec(&client, "sheets", "get_spreadsheet", json!({ "id": sheet["spreadsheetId"] })).await;
+    assert_eq!(fetched["properties"]["title"], "Budget");
+
+    let doc = exec(&client, "docs", "create_document", json!({ "title": "Spec" })).await;
+    let fetched = exec(&client, "docs", "get_document", json!({ "document_id": doc["documentId"] })).await;
+    assert_eq!(fetched["title"], "Spec");
+}
+
+#[tokio::test]
+async fn sheet_values_requests() {
+    let (fake, client) = setup().await;
+
+    exec(
+        &client,
+        "sheets",
+        "update_values",
+        json!({ "spreadsheet_id": "s1", "range": "Sheet1!A1:B1", "values": [[1, 2]] }),
+    )
+    .await;
+    let update = fake.requests().pop().unwrap();
+    assert_eq!(update.method, "PUT");
+    assert_eq!(update.path, "/v4/spreadsheets/s1/values/Sheet1!A1:B1");
+    assert_eq!(update.param("valueInputOption"), Some("USER_ENTERED"));
+    assert_eq!(update.body["values"], json!([[1, 2]]));
+}
+
+#[tokio::test]
+async fn docs_edits_are_batch_updates() {
+    let (fake, client) = setup().await;
+
+    let reply = exec(&client, "docs", "insert_text", json!({ "document_id": "d1", "text": "Hello", "index": 5 })).await;
+    assert_eq!(reply["replies"], json!([{}]));
+
+    let request = fake.requests().pop().unwrap();
+    assert_eq!(request.path, "/v1/documents/d1:batchUpdate");
+    assert_eq!(
+        request.body["requests"][0]["insertText"],
+        json!({ "text": "Hello", "location": { "index": 5 } })
+    );
+}
+
+#[tokio::test]
+async fn speaker_notes_target_the_notes_shape() {
+    let (fake, client) = setup().await;
+    fake.seed(
+        "/v1/presentations/p1/pages/s1",
+        json!({ "slideProperties": { "notesPage": { "notesProperties": { "speakerNotesObjectId": "n1" } } } }),
+    );
+
+    exec(
+        &client,
+        "slides",
+        "create_speaker_notes",
+        json!({ "presentation_id": "p1", "slide_id": "s1", "notes_text": "Smile" }),
+    )
+    .await;
+    let request = fake.requests().pop().unwrap();
+    assert_eq!(request.path, "/v1/presentations/p1:batchUpdate");
+    assert_eq!(request.body["requests"][0]["insertText"]["objectId"], "n1");
+    assert_eq!(request.body["requests"][0]["insertText"]["text"], "Smile");
+}
+
+#[tokio::test]
+async fn forms_and_scripts_hit_their_endpoints() {
+    let (fake, client) = setup().await;
+
+    exec(&client, "forms", "add_text_question", json!({ "form_id": "f1", "title": "Name?" })).await;
+    exec(&client, "apps_script", "run", json!({ "script_id": "sc1", "function_name": "main" })).await;
+
+    let requests = fake.requests();
+    assert_eq!(requests[0].path, "/v1/forms/f1:batchUpdate");
+    assert_eq!(requests[0].body["requests"][0]["createItem"]["item"]["title"], "Name?");
+    assert_eq!(requests[1].path, "/v1/scripts/sc1:run");
+    assert_eq!(requests[1].body["function"], "main");
+}
@@ -0,0 +1,269 @@
+mod common;
+
+use common::{exec, ids, setup};
+use serde_json::json;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use vgoog::api::drive::DriveApi;
+use vgoog::client::GoogleClient;
+use vgoog::fake::FakeGoogle;
+use vgoog::retry::RetryPolicy;
+use vgoog::transfer::Progress;
+
+#[tokio::test]
+async fn uploaded_content_downloads_back() {
+    let (fake, client) = setup().await;
+    let drive = DriveApi::new(&client);
+    let path = temp_file("hello.txt", b"hello world");
+
+    let file = drive
+        .upload_file(&json!({ "name": "hello.txt" }), &path, "text/plain", None)
+        .await
+        .unwrap();
+    let id = file["id"].as_str().unwrap();
+    assert_eq!(file["name"], "hello.txt");
+    assert_eq!(file["size"], "11");
+
+    assert_eq!(downloaded(&drive, id).await, b"hello world");
+
+    let requests = fake.requests();
+    assert_eq!(requests[0].path, "/upload/drive/v3/files");
+    assert_eq!(requests[0].param("uploadType"), Some("resumable"));
+    assert_eq!(requests[0].body, json!({ "name": "hello.txt" }));
+    assert_eq!(requests[1].method, "PUT");
+    assert!(requests[1].param("upload_id").is_some());
+}
+
+#[tokio::test]
+async fn resumable_uploads_send_chunks_and_report_progress() {
+    let (fake, mut client) = setup().await;
+    client.set_upload_chunk_size(1);
+    let content: Vec<u8> = (0..600 * 1024).map(|i| (i % 251) as u8).collect();
+    let path = temp_file("big.bin", &content);
+
+    let seen = Mutex::new(Vec::new());
+    let report = |p: Progress| seen.lock().unwrap().push(p.done);
+    let file = DriveApi::new(&client)
+        .upload_file(&json!({ "name": "big.bin" }), &path, "application/octet-stream", Some(&report))
+        .await
+        .unwrap();
+
+    assert_eq!(*seen.lock().unwrap(), [256 * 1024, 512 * 1024, 600 * 1024]);
+    let id = file["id"].as_str().unwrap();
+    assert_eq!(downloaded(&DriveApi::new(&client), id).await, content);
+    let chunks = fake.requests().iter().filter(|r| r.method == "PUT").count();
+    assert_eq!(chunks, 3);
+}
+
+#[tokio::test]
+async fn failed_chunks_resume_from_the_server_offset() {
+    let fake = FakeGoogle::start().await.unwrap();
+    let mut config = fake.config();
+    config.accounts.get_mut("fake").unwrap().retry =
+        Some(RetryPolicy { max_attempts: 3, base_delay_ms: 1, max_delay_ms: 10 });
+    let mut client = GoogleClient::new(config).unwrap();
+    client.set_upload_chunk_size(1);
+    let file = exec(&client, "drive", "files_create", json!({ "body": { "name": "resume.bin" } })).await;
+    let id = file["id"].as_str().unwrap();
+    let content: Vec<u8> = (0..300 * 1024).map(|i| (i % 7) as u8).collect();
+    let path = temp_file("resume.bin", &content);
+    fake.clear_requests();
+
+    // Session start and first chunk go through; the second chunk fails
+    fake.fail_after(2, 503, json!({ "error": { "code": 503, "message": "Backend Error" } }));
+    DriveApi::new(&client)
+        .update_file_content(id, &json!({}), &path, "application/octet-stream", None)
+        .await
+        .unwrap();
+
+    let requests = fake.requests();
+    let methods: Vec<&str> = requests.iter().map(|r| r.method.as_str()).collect();
+    assert_eq!(methods, ["PATCH", "PUT", "PUT", "PUT", "PUT"]);
+    // The status query carries no data
+    assert_eq!(requests[3].body, json!(null));
+    assert_eq!(downloaded(&DriveApi::new(&client), id).await, content);
+}
+
+#[tokio::test]
+async fn downloads_stream_to_disk_and_verify_the_checksum() {
+    let (fake, client) = setup().await;
+    let content: Vec<u8> = (0..200 * 1024).map(|i| (i % 13) as u8).collect();
+    fake.seed("/drive/v3/files/f1", json!({ "id": "f1", "name": "data.bin" }));
+    fake.seed_media("f1", content.clone());
+    let path = temp_file("download.bin", b"");
+    std::fs::remove_file(&path).unwrap();
+
+    let seen = Mutex::new(Vec::new());
+    let report = |p: Progress| seen.lock().unwrap().push(p);
+    let result = DriveApi::new(&client).download_file_to("f1", &path, Some(&report)).await.unwrap();
+
+    assert_eq!(result["size"], content.len());
+    assert_eq!(result["verified"], true);
+    assert_eq!(std::fs::read(&path).unwrap(), content);
+    assert!(!vgoog::transfer::part_path(&path).exists());
+    let seen = seen.lock().unwrap();
+    assert_eq!(seen.last().unwrap().done, content.len() as u64);
+    assert_eq!(seen.last().unwrap().total, Some(content.len() as u64));
+}
+
+#[tokio::test]
+async fn interrupted_downloads_resume_with_a_range_request() {
+    let (fake, client) = setup().await;
+    let content: Vec<u8> = (0..64 * 1024).map(|i| (i % 31) as u8).collect();
+    fake.seed("/drive/v3/files/f2", json!({ "id": "f2", "name": "partial.bin" }));
+    fake.seed_media("f2", content.clone());
+    let path = temp_file("partial.bin", b"");
+    std::fs::remove_file(&path).unwrap();
+    std::fs::write(vgoog::transfer::part_path(&path), &content[..10_000]).unwrap();
+    fake.clear_requests();
+
+    exec(&client, "drive", "download_file", json!({ "file_id": "f2", "path": path })).await;
+
+    assert_eq!(std::fs::read(&path).unwrap(), content);
+    let media = fake.requests().into_iter().find(|r| r.param("alt") == Some("media")).unwrap();
+    assert_eq!(media.header("range"), Some("bytes=10000-"));
+}
+
+#[tokio::test]
+async fn refused_downloads_leave_no_part_file() {
+    let (_fake, client) = setup().await;
+    let path = temp_file("missing.pdf", b"");
+    std::fs::remove_file(&path).unwrap();
+
+    let args = json!({ "file_id": "missing", "mime_type": "application/pdf", "download_to": path });
+    let err = vgoog::cli::exec::execute(&client, "drive", "files_export", args).await.unwrap_err();
+
+    assert!(err.to_string().contains("not found"), "{err}");
+    assert!(!vgoog::transfer::part_path(&path).exists());
+    assert!(!path.exists());
+}
+
+#[tokio::test]
+async fn checksum_mismatches_discard_the_download() {
+    let (fake, client) = setup().await;
+    fake.seed("/drive/v3/files/f3", json!({ "id": "f3", "name": "bad.bin" }));
+    fake.seed_media("f3", b"actual content".to_vec());
+    fake.seed(
+        "/drive/v3/files/f3",
+        json!({ "id": "f3", "name": "bad.bin", "size": "14", "md5Checksum": "00000000000000000000000000000000" }),
+    );
+    let path = temp_file("bad.bin", b"");
+    std::fs::remove_file(&path).unwrap();
+
+    let err = DriveApi::new(&client).download_file_to("f3", &path, None).await.unwrap_err();
+
+    assert!(err.to_string().contains("Checksum mismatch"), "{err}");
+    assert!(!path.exists());
+}
+
+#[tokio::test]
+async fn checksums_are_lowercase_hex_like_drive_reports_them() {
+    use vgoog::transfer::{file_md5, md5_hex};
+
+    assert_eq!(md5_hex(b""), "d41d8cd98f00b204e9800998ecf8427e");
+    assert_eq!(md5_hex(b"abc"), "900150983cd24fb0d6963f7d28e17f72");
+    let path = temp_file("digits.txt", b"12345678901234567890123456789012345678901234567890123456789012345678901234567890");
+    assert_eq!(file_md5(&path).await.unwrap(), "57edf4a22be3c955ac49da2e2107b67a");
+}
+
+fn temp_file(name: &str, content: &[u8]) -> std::path::PathBuf {
+    let dir = std::env::temp_dir().join(format!("vgoog-drive-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(name);
+    std::fs::write(&path, content).unwrap();
+    path
+}
+
+/// A file's content, streamed to a fresh local file and read back
+async fn downloaded(drive: &DriveApi<'_>, file_id: &str) -> Vec<u8> {
+    static DOWNLOADS: AtomicUsize = AtomicUsize::new(0);
+    let name = format!("downloaded-{}", DOWNLOADS.fetch_add(1, Ordering::SeqCst));
+    let path = temp_file(&name, b"");
+    std::fs::remove_file(&path).unwrap();
+    drive.download_file_to(file_id, &path, None).await.unwrap();
+    std::fs::read(&path).unwrap()
+}
+
+#[tokio::test]
+async fn folders_moves_and_copies() {
+    let (fake, client) = setup().await;
+
+    let folder = exec(&client, "drive", "create_folder", json!({ "name": "Reports" })).await;
+    assert_eq!(folder["mimeType"], "application/vnd.google-apps.folder");
+    let file = exec(&client, "drive", "files_create", json!({ "body": { "name": "q1.csv" } })).await;
+    fake.seed_media(file["id"].as_str().unwrap(), b"a,b".to_vec());
+
+    let moved = exec(
+        &client,
+        "drive",
+        "move_file",
+        json!({ "file_id": file["id"], "add_parents": folder["id"], "remove_parents": "root" }),
+    )
+    .await;
+    assert_eq!(moved["parents"], json!([folder["id"]]));
+
+    let copy = exec(&client, "drive", "copy_file", json!({ "file_id": file["id"], "metadata": {} })).await;
+    assert_eq!(copy["name"], "Copy of q1.csv");
+    assert_ne!(copy["id"], file["id"]);
+    let drive = DriveApi::new(&client);
+    assert_eq!(downloaded(&drive, copy["id"].as_str().unwrap()).await, b"a,b");
+}
+
+#[tokio::test]
+async fn file_list_pages_and_trash_empties() {
+    let (_fake, client) = setup().await;
+    for name in ["a", "b", "c"] {
+        exec(&client, "drive", "files_create", json!({ "body": { "name": name } })).await;
+    }
+
+    let first = exec(&client, "drive", "list_files", json!({ "page_size": 2 })).await;
+    assert_eq!(first["files"].as_array().unwrap().len(), 2);
+    let second = exec(
+        &client,
+        "drive",
+        "list_files",
+        json!({ "page_size": 2, "page_token": first["nextPageToken"] }),
+    )
+    .await;
+    assert_eq!(second["files"][0]["name"], "c");
+    assert!(second.get("nextPageToken").is_none());
+
+    let trashed = first["files"][0]["id"].clone();
+    exec(&client, "drive", "files_update", json!({ "file_id": trashed, "body": { "trashed": true } })).await;
+    exec(&client, "drive", "empty_trash", json!({})).await;
+    let remaining = exec(&client, "drive", "list_files", json!({})).await;
+    assert_eq!(remaining["files"].as_array().unwrap().len(), 2);
+    assert!(!ids(&remaining, "files", "id").contains(&trashed.as_str().unwrap().to_string()));
+}
+
+#[tokio::test]
+async fn comments_and_replies() {
+    let (_fake, client) = setup().await;
+    let file = exec(&client, "drive", "files_create", json!({ "body": { "name": "doc" } })).await;
+
+    let comment = exec(&client, "drive", "create_comment", json!({ "file_id": file["id"], "content": "typo" })).await;
+    exec(
+        &client,
+        "drive",
+        "create_reply",
+        json!({ "file_id": file["id"], "comment_id": comment["id"], "content": "fixed" }),
+    )
+    .await;
+
+    let replies = exec(&client, "drive", "list_replies", json!({ "file_id": file["id"], "comment_id": comment["id"] })).await;
+    assert_eq!(replies["replies"][0]["content"], "fixed");
+    let revisions = exec(&client, "drive", "list_revisions", json!({ "file_id": file["id"] })).await;
+    assert_eq!(ids(&revisions, "revisions", "id"), ["1"]);
+}
+
+#[tokio::test]
+async fn shared_drives_are_created_with_a_request_id() {
+    let (fake, client) = setup().await;
+
+    let created = exec(&client, "drive", "create_shared_drive", json!({ "name": "Ops" })).await;
+    let request = fake.requests().pop().unwrap();
+    assert!(request.param("requestId").is_some_and(|id| !id.is_empty()));
+
+    let listed = exec(&client, "drive", "list_shared_drives", json!({})).await;
+    assert_eq!(ids(&listed, "drives", "id"), vec![created["id"].as_str().unwrap()]);
+}
@@ -0,0 +1,128 @@
+//! Running from `VGOOG_*` environment variables alone, as in CI, with
+//! nothing read from or written to the config dir.
+
+mod common;
+
+use common::{exec, fresh_config_dir};
+use serde_json::json;
+use tokio::sync::MutexGuard;
+use vgoog::client::GoogleClient;
+use vgoog::config::{Config, ENV_ACCOUNT};
+use vgoog::endpoints::Endpoints;
+use vgoog::fake::{FakeGoogle, ACCESS_TOKEN, CLIENT_ID, CLIENT_SECRET, REFRESH_TOKEN};
+
+const VARS: &[&str] = &[
+    "VGOOG_CLIENT_ID",
+    "VGOOG_CLIENT_SECRET",
+    "VGOOG_REFRESH_TOKEN",
+    "VGOOG_ACCESS_TOKEN",
+    "VGOOG_ENDPOINT_ROOT",
+    "VGOOG_ENDPOINT_TOKEN_URI",
+    "VGOOG_AUTH_URI",
+    "VGOOG_TOKEN_URI",
+];
+
+/// A fresh config dir with the given credentials in the environment,
+/// pointed at the fake
+async fn env_with(fake: &FakeGoogle, vars: &[(&str, &str)]) -> MutexGuard<'static, ()> {
+    let guard = fresh_config_dir().await;
+    for var in VARS {
+        std::env::remove_var(var);
+    }
+    std::env::set_var("VGOOG_ENDPOINT_ROOT", fake.url());
+    for (name, value) in vars {
+        std::env::set_var(name, value);
+    }
+    guard
+}
+
+fn config_dir_is_untouched() -> bool {
+    !Config::config_dir().unwrap().exists()
+}
+
+#[tokio::test]
+async fn a_refresh_token_in_the_environment_needs_no_config_file() {
+    let fake = FakeGoogle::start().await.unwrap();
+    let _guard = env_with(
+        &fake,
+        &[
+            ("VGOOG_CLIENT_ID", CLIENT_ID),
+            ("VGOOG_CLIENT_SECRET", CLIENT_SECRET),
+            ("VGOOG_REFRESH_TOKEN", REFRESH_TOKEN),
+        ],
+    )
+    .await;
+
+    let config = Config::load().unwrap();
+    assert!(config.ephemeral);
+    assert_eq!(config.active_account, ENV_ACCOUNT);
+    assert!(config.active_is_valid());
+
+    let client = GoogleClient::new(config).unwrap();
+    exec(&client, "gmail", "get_profile", json!({})).await;
+    exec(&client, "tasks", "list_task_lists", json!({})).await;
+
+    // One refresh, kept in memory for the second call
+    let refreshes = fake.requests().into_iter().filter(|r| r.path == "/token").count();
+    assert_eq!(refreshes, 1);
+    assert!(config_dir_is_untouched());
+}
+
+#[tokio::test]
+async fn a_bare_access_token_is_used_as_is() {
+    let fake = FakeGoogle::start().await.unwrap();
+    let _guard = env_with(&fake, &[("VGOOG_ACCESS_TOKEN", ACCESS_TOKEN)]).await;
+
+    let client = GoogleClient::new(Config::load().unwrap()).unwrap();
+    exec(&client, "gmail", "get_profile", json!({})).await;
+
+    assert!(fake.requests().iter().all(|r| r.path != "/token"));
+    assert!(config_dir_is_untouched());
+}
+
+#[tokio::test]
+async fn the_environment_wins_over_config_toml_and_is_never_saved() {
+    let fake = FakeGoogle::start().await.unwrap();
+    let _guard = env_with(&fake, &[]).await;
+    fake.config().save().unwrap();
+    let saved = std::fs::read_to_string(Config::config_path().unwrap()).unwrap();
+
+    std::env::set_var("VGOOG_ACCESS_TOKEN", ACCESS_TOKEN);
+    let mut config = Config::load().unwrap();
+    assert_eq!(config.accounts.keys().collect::<Vec<_>>(), [ENV_ACCOUNT]);
+
+    let err = config.update(|c| c.active_account = "fake".to_string()).unwrap_err();
+    assert_eq!(err.kind(), "config");
+    assert!(err.to_string().contains("environment variables"), "{err}");
+    assert!(config.save().is_err());
+    assert_eq!(std::fs::read_to_string(Config::config_path().unwrap()).unwrap(), saved);
+
+    std::env::remove_var("VGOOG_ACCESS_TOKEN");
+    assert_eq!(Config::load().unwrap().active_account, "fake");
+}
+
+#[tokio::test]
+async fn a_refresh_token_without_its_client_is_refused() {
+    let fake = FakeGoogle::start().await.unwrap();
+    let _guard = env_with(&fake, &[("VGOOG_REFRESH_TOKEN", REFRESH_TOKEN)]).await;
+
+    let err = Config::load().unwrap_err();
+    assert_eq!(err.kind(), "config");
+    assert!(err.to_string().contains("VGOOG_CLIENT_ID"), "{err}");
+    std::env::remove_var("VGOOG_REFRESH_TOKEN");
+}
+
+#[tokio::test]
+async fn the_old_oauth_variables_still_work() {
+    let fake = FakeGoogle::start().await.unwrap();
+    let _guard = env_with(&fake, &[("VGOOG_AUTH_URI", "http://127.0.0.1:1/auth"), ("VGOOG_TOKEN_URI", "http://127.0.0.1:1/token")]).await;
+    let endpoints = Endpoints::resolve(&Default::default()).unwrap();
+    assert_eq!(endpoints.auth_uri, "http://127.0.0.1:1/auth");
+    assert_eq!(endpoints.token_uri, "http://127.0.0.1:1/token");
+
+    // The new name wins when both are set
+    std::env::set_var("VGOOG_ENDPOINT_TOKEN_URI", "http://127.0.0.1:2/token");
+    let endpoints = Endpoints::resolve(&Default::default()).unwrap();
+    assert_eq!(endpoints.token_uri, "http://127.0.0.1:2/token");
+    assert_eq!(endpoints.auth_uri, "http://127.0.0.1:1/auth");
+}
@@ -0,0 +1,436 @@
+//! Every action `vgoog list` advertises runs against the fake server.
+
+mod common;
+
+use serde_json::{json, Value};
+use std::collections::BTreeSet;
+use vgoog::fake::FakeGoogle;
+
+const GMAIL: &str = "/gmail/v1/users/me";
+const DRIVE: &str = "/drive/v3";
+const CALENDAR: &str = "/calendar/v3";
+const TASKS: &str = "/tasks/v1";
+
+/// Any small file on disk will do for the upload actions
+const UPLOAD_SOURCE: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml");
+
+/// Somewhere the download actions can write to
+fn download_target(name: &str) -> String {
+    let dir = std::env::temp_dir().join(format!("vgoog-exec-all-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).expect("create download dir");
+    dir.join(name).to_string_lossy().into_owned()
+}
+
+/// Resources the action table below refers to
+fn seed(fake: &FakeGoogle) {
+    for id in ["m1", "m-del"] {
+        fake.seed(
+            &format!("{GMAIL}/messages/{id}"),
+            json!({ "id": id, "threadId": id, "labelIds": ["INBOX", "UNREAD"] }),
+        );
+        fake.seed(&format!("{GMAIL}/threads/{id}"), json!({ "id": id }));
+    }
+    fake.seed(&format!("{GMAIL}/messages/m1/attachments/a1"), json!({ "size": 3, "data": "YWJj" }));
+    fake.seed(&format!("{GMAIL}/labels/Label_1"), json!({ "id": "Label_1", "name": "Work" }));
+    for id in ["d1", "d-send"] {
+        fake.seed(&format!("{GMAIL}/drafts/{id}"), json!({ "id": id, "message": { "raw": "cmF3" } }));
+    }
+    fake.seed(&format!("{GMAIL}/settings/filters/f1"), json!({ "id": "f1", "action": {} }));
+    fake.seed(
+        &format!("{GMAIL}/settings/sendAs/alias@example.com"),
+        json!({ "sendAsEmail": "alias@example.com", "displayName": "Alias" }),
+    );
+
+    fake.seed(&format!("{DRIVE}/files/file-1"), json!({ "id": "file-1", "name": "notes.txt", "parents": ["root"] }));
+    fake.seed(&format!("{DRIVE}/files/file-del"), json!({ "id": "file-del", "name": "old.txt" }));
+    fake.seed(&format!("{DRIVE}/files/file-1/permissions/p1"), json!({ "id": "p1", "role": "reader", "type": "anyone" }));
+    fake.seed(&format!("{DRIVE}/files/file-1/comments/c1"), json!({ "id": "c1", "content": "hi" }));
+    fake.seed(&format!("{DRIVE}/files/file-1/revisions/r1"), json!({ "id": "r1" }));
+    fake.seed(&format!("{DRIVE}/files/file-1/revisions/r2"), json!({ "id": "r2" }));
+    fake.seed(&format!("{DRIVE}/drives/sd1"), json!({ "id": "sd1", "name": "Team" }));
+    fake.seed(&format!("{DRIVE}/drives/sd2"), json!({ "id": "sd2", "name": "Archive" }));
+    fake.seed(&format!("{DRIVE}/files/file-1/comments/c2"), json!({ "id": "c2", "content": "see below" }));
+    for id in ["r1", "r-del"] {
+        fake.seed(&format!("{DRIVE}/files/file-1/comments/c2/replies/{id}"), json!({ "id": id, "content": id }));
+    }
+
+    fake.seed(&format!("{CALENDAR}/calendars/cal-x"), json!({ "id": "cal-x", "summary": "Extra" }));
+    fake.seed(&format!("{CALENDAR}/calendars/cal-del"), json!({ "id": "cal-del", "summary": "Doomed" }));
+    for id in ["e1", "e-del", "e-move"] {
+        fake.seed(
+            &format!("{CALENDAR}/calendars/primary/events/{id}"),
+            json!({ "id": id, "summary": id, "status": "confirmed" }),
+        );
+    }
+    fake.seed(&format!("{CALENDAR}/calendars/primary/acl/rule-1"), json!({ "id": "rule-1", "role": "reader" }));
+    fake.seed(&format!("{CALENDAR}/calendars/primary/acl/rule-2"), json!({ "id": "rule-2", "role": "reader" }));
+
+    fake.seed(&format!("{TASKS}/users/@me/lists/L1"), json!({ "id": "L1", "title": "Errands" }));
+    fake.seed(&format!("{TASKS}/users/@me/lists/L-del"), json!({ "id": "L-del", "title": "Old" }));
+    for id in ["t1", "t-del"] {
+        fake.seed(&format!("{TASKS}/lists/L1/tasks/{id}"), json!({ "id": id, "title": id, "status": "needsAction" }));
+    }
+
+    for id in ["c1", "c-del", "c-batch"] {
+        fake.seed(
+            &format!("/v1/people/{id}"),
+            json!({ "resourceName": format!("people/{id}"), "names": [{ "displayName": id }] }),
+        );
+    }
+    for id in ["g1", "g-del"] {
+        fake.seed(
+            &format!("/v1/contactGroups/{id}"),
+            json!({ "resourceName": format!("contactGroups/{id}"), "name": id }),
+        );
+    }
+    fake.seed(
+        "/v1/otherContacts/o1",
+        json!({ "resourceName": "otherContacts/o1", "emailAddresses": [{ "value": "o@example.com" }] }),
+    );
+
+    fake.seed(
+        "/v1/presentations/pres-1/pages/slide-1",
+        json!({
+            "objectId": "slide-1",
+            "slideProperties": { "notesPage": { "notesProperties": { "speakerNotesObjectId": "notes-1" } } },
+        }),
+    );
+}
+
+/// Arguments for every action, in `list_all()` order
+fn actions() -> Vec<(&'static str, &'static str, Value)> {
+    vec![
+        ("gmail", "list_messages", json!({ "max_results": 5 })),
+        ("gmail", "get_message", json!({ "id": "m1" })),
+        ("gmail", "batch_get_messages", json!({ "ids": ["m1", "missing"] })),
+        ("gmail", "send_message", json!({ "raw": "cmF3" })),
+        ("gmail", "trash_message", json!({ "id": "m1" })),
+        ("gmail", "untrash_message", json!({ "id": "m1" })),
+        ("gmail", "delete_message", json!({ "id": "m-del" })),
+        ("gmail", "modify_message", json!({ "id": "m1", "add_labels": ["STARRED"], "remove_labels": ["UNREAD"] })),
+        ("gmail", "batch_modify_messages", json!({ "ids": ["m1"], "add_labels": ["Label_1"] })),
+        ("gmail", "batch_delete_messages", json!({ "ids": ["missing"] })),
+        ("gmail", "get_attachment", json!({ "message_id": "m1", "attachment_id": "a1" })),
+        ("gmail", "list_threads", json!({})),
+        ("gmail", "get_thread", json!({ "id": "m1" })),
+        ("gmail", "trash_thread", json!({ "id": "m1" })),
+        ("gmail", "untrash_thread", json!({ "id": "m1" })),
+        ("gmail", "delete_thread", json!({ "id": "m-del" })),
+        ("gmail", "modify_thread", json!({ "id": "m1", "add_labels": ["INBOX"] })),
+        ("gmail", "list_labels", json!({})),
+        ("gmail", "get_label", json!({ "id": "Label_1" })),
+        ("gmail", "create_label", json!({ "name": "Receipts" })),
+        ("gmail", "update_label", json!({ "id": "Label_1", "name": "Work stuff" })),
+        ("gmail", "delete_label", json!({ "id": "Label_1" })),
+        ("gmail", "list_drafts", json!({})),
+        ("gmail", "get_draft", json!({ "id": "d1" })),
+        ("gmail", "create_draft", json!({ "raw": "cmF3" })),
+        ("gmail", "update_draft", json!({ "id": "d1", "raw": "bmV3" })),
+        ("gmail", "send_draft", json!({ "id": "d-send" })),
+        ("gmail", "delete_draft", json!({ "id": "d1" })),
+        ("gmail", "get_vacation_settings", json!({})),
+        ("gmail", "update_vacation_settings", json!({ "settings": { "enableAutoReply": true } })),
+        ("gmail", "get_auto_forwarding", json!({})),
+        ("gmail", "update_auto_forwarding", json!({ "settings": { "enabled": false } })),
+        ("gmail", "get_imap_settings", json!({})),
+        ("gmail", "update_imap_settings", json!({ "settings": { "enabled": false } })),
+        ("gmail", "get_pop_settings", json!({})),
+        ("gmail", "update_pop_settings", json!({ "settings": { "accessWindow": "allMail" } })),
+        ("gmail", "get_language_settings", json!({})),
+        ("gmail", "update_language_settings", json!({ "display_language": "de" })),
+        ("gmail", "list_filters", json!({})),
+        ("gmail", "get_filter", json!({ "id": "f1" })),
+        ("gmail", "create_filter", json!({ "filter": { "criteria": { "from": "a@b.c" }, "action": {} } })),
+        ("gmail", "delete_filter", json!({ "id": "f1" })),
+        ("gmail", "list_forwarding_addresses", json!({})),
+        ("gmail", "create_forwarding_address", json!({ "email": "fwd@example.com" })),
+        ("gmail", "delete_forwarding_address", json!({ "email": "fwd@example.com" })),
+        ("gmail", "list_send_as", json!({})),
+        ("gmail", "get_send_as", json!({ "email": "alias@example.com" })),
+        ("gmail", "create_send_as", json!({ "send_as": { "sendAsEmail": "other@example.com" } })),
+        ("gmail", "update_send_as", json!({ "email": "alias@example.com", "send_as": { "displayName": "Renamed" } })),
+        ("gmail", "delete_send_as", json!({ "email": "other@example.com" })),
+        ("gmail", "verify_send_as", json!({ "email": "alias@example.com" })),
+        ("gmail", "list_delegates", json!({})),
+        ("gmail", "add_delegate", json!({ "email": "assistant@example.com" })),
+        ("gmail", "remove_delegate", json!({ "email": "assistant@example.com" })),
+        ("gmail", "get_profile", json!({})),
+        ("gmail", "list_history", json!({ "start_history_id": "1" })),
+        ("calendar", "list_calendars", json!({})),
+        ("calendar", "get_calendar", json!({ "id": "primary" })),
+        ("calendar", "insert_calendar_to_list", json!({ "id": "cal-x" })),
+        ("calendar", "update_calendar_in_list", json!({ "id": "cal-x", "updates": { "colorId": "2" } })),
+        ("calendar", "remove_calendar_from_list", json!({ "id": "cal-x" })),
+        ("calendar", "create_calendar", json!({ "summary": "Trips" })),
+        ("calendar", "get_calendar_metadata", json!({ "id": "cal-x" })),
+        ("calendar", "update_calendar_metadata", json!({ "id": "cal-x", "updates": { "summary": "Extra!" } })),
+        ("calendar", "delete_calendar", json!({ "id": "cal-del" })),
+        ("calendar", "clear_calendar", json!({ "id": "cal-x" })),
+        ("calendar", "list_events", json!({ "max_results": 2 })),
+        ("calendar", "get_event", json!({ "calendar_id": "primary", "event_id": "e1" })),
+        ("calendar", "create_event", json!({ "event": { "summary": "Lunch" } })),
+        ("calendar", "delete_event", json!({ "calendar_id": "primary", "event_id": "e-del" })),
+        ("calendar", "batch_delete_events", json!({ "calendar_id": "primary", "event_ids": ["missing"] })),
+        ("calendar", "move_event", json!({ "calendar_id": "primary", "event_id": "e-move", "destination": "cal-x" })),
+        ("calendar", "quick_add_event", json!({ "text": "Dentist tomorrow 3pm" })),
+        ("calendar", "list_event_instances", json!({ "calendar_id": "primary", "event_id": "e1" })),
+        ("calendar", "list_acl", json!({ "calendar_id": "primary" })),
+        ("calendar", "insert_acl_rule", json!({ "calendar_id": "primary", "rule": { "role": "reader", "scope": { "type": "default" } } })),
+        ("calendar", "update_acl_rule", json!({ "calendar_id": "primary", "rule_id": "rule-1", "rule": { "role": "writer" } })),
+        ("calendar", "delete_acl_rule", json!({ "calendar_id": "primary", "rule_id": "rule-1" })),
+        ("calendar", "list_settings", json!({})),
+        ("calendar", "get_setting", json!({ "setting": "timezone" })),
+        ("calendar", "get_colors", json!({})),
+        ("calendar", "query_free_busy", json!({ "body": { "timeMin": "2026-01-01T00:00:00Z", "timeMax": "2026-01-02T00:00:00Z", "items": [{ "id": "primary" }] } })),
+        ("calendar", "acl_get", json!({ "calendar_id": "primary", "rule_id": "rule-2" })),
+        ("calendar", "acl_patch", json!({ "calendar_id": "primary", "rule_id": "rule-2", "send_notifications": false, "body": { "role": "writer" } })),
+        ("calendar", "events_import", json!({ "calendar_id": "primary", "body": { "iCalUID": "abc@example.com", "summary": "Imported" } })),
+        ("calendar", "events_patch", json!({ "calendar_id": "primary", "event_id": "e1", "send_updates": "none", "body": { "location": "Room 2" } })),
+        ("drive", "list_files", json!({ "page_size": 1 })),
+        ("drive", "upload_file", json!({ "path": UPLOAD_SOURCE, "metadata": { "parents": ["root"] } })),
+        ("drive", "update_file_content", json!({ "file_id": "file-1", "path": UPLOAD_SOURCE, "mime_type": "text/plain" })),
+        ("drive", "download_file", json!({ "file_id": "file-1", "path": download_target("notes.txt") })),
+        ("drive", "delete_file", json!({ "file_id": "file-del" })),
+        ("drive", "copy_file", json!({ "file_id": "file-1", "metadata": {} })),
+        ("drive", "empty_trash", json!({})),
+        ("drive", "generate_file_ids", json!({ "count": 3 })),
+        ("drive", "move_file", json!({ "file_id": "file-1", "add_parents": "folder-9", "remove_parents": "root" })),
+        ("drive", "create_folder", json!({ "name": "Reports" })),
+        ("drive", "list_permissions", json!({ "file_id": "file-1" })),
+        ("drive", "get_permission", json!({ "file_id": "file-1", "permission_id": "p1" })),
+        ("drive", "create_permission", json!({ "file_id": "file-1", "role": "writer", "type": "user", "email": "bob@example.com" })),
+        ("drive", "update_permission", json!({ "file_id": "file-1", "permission_id": "p1", "role": "commenter" })),
+        ("drive", "batch_update_permissions", json!({ "file_id": "file-1", "permission_ids": ["p1"], "role": "reader" })),
+        ("drive", "delete_permission", json!({ "file_id": "file-1", "permission_id": "p1" })),
+        ("drive", "list_comments", json!({ "file_id": "file-1" })),
+        ("drive", "create_comment", json!({ "file_id": "file-1", "content": "Looks good" })),
+        ("drive", "update_comment", json!({ "file_id": "file-1", "comment_id": "c1", "content": "Edited" })),
+        ("drive", "delete_comment", json!({ "file_id": "file-1", "comment_id": "c1" })),
+        ("drive", "list_replies", json!({ "file_id": "file-1", "comment_id": "c1" })),
+        ("drive", "create_reply", json!({ "file_id": "file-1", "comment_id": "c1", "content": "Thanks" })),
+        ("drive", "list_revisions", json!({ "file_id": "file-1" })),
+        ("drive", "get_revision", json!({ "file_id": "file-1", "revision_id": "r1" })),
+        ("drive", "delete_revision", json!({ "file_id": "file-1", "revision_id": "r2" })),
+        ("drive", "get_start_page_token", json!({})),
+        ("drive", "list_changes", json!({ "page_token": "1" })),
+        ("drive", "get_about", json!({})),
+        ("drive", "list_shared_drives", json!({})),
+        ("drive", "create_shared_drive", json!({ "name": "Ops" })),
+        ("drive", "delete_shared_drive", json!({ "drive_id": "sd1" })),
+        ("drive", "comments_get", json!({ "file_id": "file-1", "comment_id": "c2", "fields": "id,content" })),
+        ("drive", "drives_get", json!({ "drive_id": "sd2" })),
+        ("drive", "drives_hide", json!({ "drive_id": "sd2" })),
+        ("drive", "drives_unhide", json!({ "drive_id": "sd2" })),
+        ("drive", "drives_update", json!({ "drive_id": "sd2", "body": { "name": "Old stuff" } })),
+        ("drive", "files_create", json!({ "upload_from": UPLOAD_SOURCE, "body": { "name": "Cargo.toml" } })),
+        ("drive", "files_export", json!({ "file_id": "file-1", "mime_type": "text/plain", "download_to": download_target("files-export.txt") })),
+        ("drive", "files_get", json!({ "file_id": "file-1", "download_to": download_target("files-get.txt") })),
+        ("drive", "files_list_labels", json!({ "file_id": "file-1", "max_results": 10 })),
+        ("drive", "files_update", json!({ "file_id": "file-1", "body": { "description": "notes" } })),
+        ("drive", "replies_delete", json!({ "file_id": "file-1", "comment_id": "c2", "reply_id": "r-del" })),
+        ("drive", "replies_get", json!({ "file_id": "file-1", "comment_id": "c2", "reply_id": "r1" })),
+        ("drive", "replies_update", json!({ "file_id": "file-1", "comment_id": "c2", "reply_id": "r1", "body": { "content": "Edited" } })),
+        ("drive", "revisions_update", json!({ "file_id": "file-1", "revision_id": "r1", "body": { "keepForever": true } })),
+        ("sheets", "create_spreadsheet", json!({ "title": "Budget" })),
+        ("sheets", "get_spreadsheet", json!({ "id": "sheet-1" })),
+        ("sheets", "get_spreadsheet_with_ranges", json!({ "id": "sheet-1", "ranges": ["A1:B2"] })),
+        ("sheets", "get_values", json!({ "spreadsheet_id": "sheet-1", "range": "Sheet1!A1:B2" })),
+        ("sheets", "batch_get_values", json!({ "spreadsheet_id": "sheet-1", "ranges": ["A1", "B1"] })),
+        ("sheets", "update_values", json!({ "spreadsheet_id": "sheet-1", "range": "A1", "values": [[1]] })),
+        ("sheets", "append_values", json!({ "spreadsheet_id": "sheet-1", "range": "A1", "values": [[2]] })),
+        ("sheets", "clear_values", json!({ "spreadsheet_id": "sheet-1", "range": "A1" })),
+        ("sheets", "batch_update_values", json!({ "spreadsheet_id": "sheet-1", "data": [{ "range": "A1", "values": [[3]] }] })),
+        ("sheets", "batch_clear_values", json!({ "spreadsheet_id": "sheet-1", "ranges": ["A1"] })),
+        ("sheets", "batch_update", json!({ "spreadsheet_id": "sheet-1", "requests": [{}] })),
+        ("sheets", "add_sheet", json!({ "spreadsheet_id": "sheet-1", "title": "Q2" })),
+        ("sheets", "delete_sheet", json!({ "spreadsheet_id": "sheet-1", "sheet_id": 1 })),
+        ("sheets", "rename_sheet", json!({ "spreadsheet_id": "sheet-1", "sheet_id": 0, "new_title": "Main" })),
+        ("sheets", "auto_resize_columns", json!({ "spreadsheet_id": "sheet-1" })),
+        ("sheets", "sort_range", json!({ "spreadsheet_id": "sheet-1" })),
+        ("sheets", "create_named_range", json!({ "spreadsheet_id": "sheet-1", "name": "totals" })),
+        ("docs", "create_document", json!({ "title": "Spec" })),
+        ("docs", "get_document", json!({ "document_id": "doc-1" })),
+        ("docs", "batch_update", json!({ "document_id": "doc-1", "requests": [{}] })),
+        ("docs", "insert_text", json!({ "document_id": "doc-1", "text": "Hello" })),
+        ("docs", "delete_content", json!({ "document_id": "doc-1" })),
+        ("docs", "insert_table", json!({ "document_id": "doc-1" })),
+        ("docs", "insert_inline_image", json!({ "document_id": "doc-1", "uri": "https://example.com/a.png" })),
+        ("docs", "update_text_style", json!({ "document_id": "doc-1", "bold": true })),
+        ("docs", "update_paragraph_style", json!({ "document_id": "doc-1" })),
+        ("docs", "replace_all_text", json!({ "document_id": "doc-1", "find": "a", "replace": "b" })),
+        ("docs", "create_named_range", json!({ "document_id": "doc-1", "name": "intro" })),
+        ("docs", "insert_page_break", json!({ "document_id": "doc-1" })),
+        ("docs", "create_header", json!({ "document_id": "doc-1" })),
+        ("docs", "create_footer", json!({ "document_id": "doc-1" })),
+        ("slides", "create_presentation", json!({ "title": "Deck" })),
+        ("slides", "get_presentation", json!({ "id": "pres-1" })),
+        ("slides", "get_page", json!({ "presentation_id": "pres-1", "page_id": "slide-1" })),
+        ("slides", "get_page_thumbnail", json!({ "presentation_id": "pres-1", "page_id": "slide-1" })),
+        ("slides", "batch_update", json!({ "presentation_id": "pres-1", "requests": [{}] })),
+        ("slides", "create_slide", json!({ "presentation_id": "pres-1", "layout": "BLANK" })),
+        ("slides", "delete_slide", json!({ "presentation_id": "pres-1", "slide_id": "slide-2" })),
+        ("slides", "duplicate_slide", json!({ "presentation_id": "pres-1", "slide_id": "slide-1" })),
+        ("slides", "move_slide", json!({ "presentation_id": "pres-1", "slide_id": "slide-1", "insertion_index": 1 })),
+        ("slides", "insert_text", json!({ "presentation_id": "pres-1", "object_id": "box-1", "text": "Hi" })),
+        ("slides", "delete_text", json!({ "presentation_id": "pres-1", "object_id": "box-1" })),
+        ("slides", "replace_all_text", json!({ "presentation_id": "pres-1", "find": "a", "replace": "b" })),
+        ("slides", "create_shape", json!({ "presentation_id": "pres-1", "page_id": "slide-1", "shape_type": "RECTANGLE" })),
+        ("slides", "create_image", json!({ "presentation_id": "pres-1", "page_id": "slide-1", "url": "https://example.com/a.png" })),
+        ("slides", "create_table", json!({ "presentation_id": "pres-1", "page_id": "slide-1" })),
+        ("slides", "update_text_style", json!({ "presentation_id": "pres-1", "object_id": "box-1", "style": { "bold": true } })),
+        ("slides", "update_shape_properties", json!({ "presentation_id": "pres-1", "object_id": "box-1", "properties": {} })),
+        ("slides", "replace_all_shapes_with_image", json!({ "presentation_id": "pres-1", "find_text": "{{logo}}", "image_url": "https://example.com/l.png" })),
+        ("slides", "update_page_properties", json!({ "presentation_id": "pres-1", "page_id": "slide-1", "properties": {} })),
+        ("slides", "create_speaker_notes", json!({ "presentation_id": "pres-1", "slide_id": "slide-1", "notes_text": "Say hi" })),
+        ("forms", "create_form", json!({ "title": "Survey", "document_title": "Survey 2026" })),
+        ("forms", "get_form", json!({ "form_id": "form-1" })),
+        ("forms", "batch_update", json!({ "form_id": "form-1", "requests": [{}] })),
+        ("forms", "list_responses", json!({ "form_id": "form-1" })),
+        ("forms", "get_response", json!({ "form_id": "form-1", "response_id": "resp-1" })),
+        ("forms", "create_watch", json!({ "form_id": "form-1", "event_type": "RESPONSES", "topic_name": "projects/p/topics/t" })),
+        ("forms", "list_watches", json!({ "form_id": "form-1" })),
+        ("forms", "delete_watch", json!({ "form_id": "form-1", "watch_id": "w1" })),
+        ("forms", "renew_watch", json!({ "form_id": "form-1", "watch_id": "w1" })),
+        ("forms", "add_text_question", json!({ "form_id": "form-1", "title": "Name?" })),
+        ("forms", "add_choice_question", json!({ "form_id": "form-1", "title": "Pick", "choice_type": "RADIO", "options": ["a", "b"] })),
+        ("forms", "add_scale_question", json!({ "form_id": "form-1", "title": "Rate" })),
+        ("forms", "add_date_question", json!({ "form_id": "form-1", "title": "When?" })),
+        ("forms", "add_time_question", json!({ "form_id": "form-1", "title": "What time?" })),
+        ("forms", "add_section_header", json!({ "form_id": "form-1", "title": "Part 2" })),
+        ("forms", "delete_item", json!({ "form_id": "form-1" })),
+        ("forms", "move_item", json!({ "form_id": "form-1" })),
+        ("forms", "update_form_info", json!({ "form_id": "form-1", "title": "Survey v2" })),
+        ("forms", "update_settings", json!({ "form_id": "form-1", "settings": {}, "update_mask": "quizSettings" })),
+        ("forms", "add_file_upload_question", json!({ "form_id": "form-1", "title": "Upload" })),
+        ("forms", "add_grid_question", json!({ "form_id": "form-1", "title": "Grid", "rows": ["r"], "columns": ["c"] })),
+        ("tasks", "list_task_lists", json!({})),
+        ("tasks", "get_task_list", json!({ "id": "L1" })),
+        ("tasks", "create_task_list", json!({ "title": "Groceries" })),
+        ("tasks", "update_task_list", json!({ "id": "L1", "title": "Errands!" })),
+        ("tasks", "delete_task_list", json!({ "id": "L-del" })),
+        ("tasks", "list_tasks", json!({ "task_list_id": "L1" })),
+        ("tasks", "get_task", json!({ "task_list_id": "L1", "task_id": "t1" })),
+        ("tasks", "create_task", json!({ "task_list_id": "L1", "title": "Milk" })),
+        ("tasks", "complete_task", json!({ "task_list_id": "L1", "task_id": "t1" })),
+        ("tasks", "uncomplete_task", json!({ "task_list_id": "L1", "task_id": "t1" })),
+        ("tasks", "delete_task", json!({ "task_list_id": "L1", "task_id": "t-del" })),
+        ("tasks", "move_task", json!({ "task_list_id": "L1", "task_id": "t1" })),
+        ("tasks", "clear_completed", json!({ "task_list_id": "L1" })),
+        ("tasks", "tasklists_patch", json!({ "tasklist": "L1", "body": { "title": "Errands (patched)" } })),
+        ("tasks", "tasks_patch", json!({ "tasklist": "L1", "task": "t1", "body": { "notes": "oat" } })),
+        ("contacts", "get_person", json!({ "resource_name": "people/c1" })),
+        ("contacts", "get_me", json!({})),
+        ("contacts", "get_batch_people", json!({ "resource_names": ["people/c1", "people/nope"] })),
+        ("contacts", "list_contacts", json!({})),
+        ("contacts", "search_contacts", json!({ "query": "c1" })),
+        ("contacts", "create_contact", json!({ "person": { "names": [{ "givenName": "Ada" }] } })),
+        ("contacts", "update_contact", json!({ "resource_name": "people/c1", "person": { "names": [{ "givenName": "Bea" }] }, "update_mask": "names" })),
+        ("contacts", "delete_contact", json!({ "resource_name": "people/c-del" })),
+        ("contacts", "batch_create_contacts", json!({ "contacts": [{ "contactPerson": { "names": [{ "givenName": "Cy" }] } }] })),
+        ("contacts", "batch_delete_contacts", json!({ "resource_names": ["people/c-batch"] })),
+        ("contacts", "batch_update_contacts", json!({ "contacts": { "people/c1": { "names": [{ "givenName": "Di" }] } }, "update_mask": "names" })),
+        ("contacts", "list_contact_groups", json!({})),
+        ("contacts", "get_contact_group", json!({ "resource_name": "contactGroups/g1" })),
+        ("contacts", "create_contact_group", json!({ "name": "Family" })),
+        ("contacts", "update_contact_group", json!({ "resource_name": "contactGroups/g1", "name": "Friends" })),
+        ("contacts", "delete_contact_group", json!({ "resource_name": "contactGroups/g-del" })),
+        ("contacts", "modify_contact_group_members", json!({ "resource_name": "contactGroups/g1", "add": ["people/c1"] })),
+        ("contacts", "list_other_contacts", json!({})),
+        ("contacts", "copy_other_contact_to_contacts", json!({ "resource_name": "otherContacts/o1" })),
+        ("contacts", "search_directory", json!({ "query": "ada" })),
+        ("apps_script", "create_project", json!({ "title": "Automation" })),
+        ("apps_script", "get_project", json!({ "script_id": "script-1" })),
+        ("apps_script", "get_content", json!({ "script_id": "script-1" })),
+        ("apps_script", "update_content", json!({ "script_id": "script-1", "files": [] })),
+        ("apps_script", "get_metrics", json!({ "script_id": "script-1" })),
+        ("apps_script", "list_versions", json!({ "script_id": "script-1" })),
+        ("apps_script", "create_version", json!({ "script_id": "script-1", "description": "v1" })),
+        ("apps_script", "get_version", json!({ "script_id": "script-1" })),
+        ("apps_script", "list_deployments", json!({ "script_id": "script-1" })),
+        ("apps_script", "create_deployment", json!({ "script_id": "script-1", "description": "prod" })),
+        ("apps_script", "get_deployment", json!({ "script_id": "script-1", "deployment_id": "dep-1" })),
+        ("apps_script", "update_deployment", json!({ "script_id": "script-1", "deployment_id": "dep-1", "description": "prod 2" })),
+        ("apps_script", "delete_deployment", json!({ "script_id": "script-1", "deployment_id": "dep-1" })),
+        ("apps_script", "run", json!({ "script_id": "script-1", "function_name": "main" })),
+        ("apps_script", "list_processes", json!({})),
+        ("apps_script", "list_script_processes", json!({ "script_id": "script-1" })),
+    ]
+}
+
+#[test]
+fn table_covers_every_listed_action() {
+    let listed: BTreeSet<(String, String)> = vgoog::cli::exec::list_all()
+        .as_object()
+        .expect("list_all is an object")
+        .iter()
+        .flat_map(|(service, actions)| {
+            actions
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|a| a.as_str())
+                .map(move |a| (service.clone(), a.to_string()))
+        })
+        .collect();
+    let tested: BTreeSet<(String, String)> = actions()
+        .into_iter()
+        .map(|(s, a, _)| (s.to_string(), a.to_string()))
+        .collect();
+
+    assert_eq!(listed.difference(&tested).collect::<Vec<_>>(), Vec::<&(String, String)>::new(), "untested actions");
+    assert_eq!(tested.difference(&listed).collect::<Vec<_>>(), Vec::<&(String, String)>::new(), "unlisted actions");
+}
+
+#[tokio::test]
+async fn every_action_succeeds() {
+    let (fake, client) = common::setup().await;
+    seed(&fake);
+
+    let mut failures = Vec::new();
+    for (service, action, args) in actions() {
+        if let Err(e) = vgoog::cli::exec::execute(&client, service, action, args).await {
+            failures.push(format!("{service}.{action}: {}", e.to_json()));
+        }
+    }
+    assert!(failures.is_empty(), "failed actions:\n{}", failures.join("\n"));
+}
+
+#[tokio::test]
+async fn retired_actions_run_as_their_generated_ones() {
+    let (fake, client) = common::setup().await;
+    seed(&fake);
+    let listed = vgoog::cli::exec::list_all();
+    for (service, aliases) in vgoog::cli::exec::ALIASES {
+        let names = listed[*service].as_array().unwrap();
+        for alias in *aliases {
+            assert!(names.contains(&json!(alias.target)), "{service}.{} stands for a missing action", alias.name);
+            assert!(!names.contains(&json!(alias.name)), "{service}.{} is listed twice", alias.name);
+        }
+    }
+    assert_eq!(vgoog::cli::exec::describe("drive", "get_file").unwrap()["action"], "files_get");
+
+    let exec = |service, action, args| vgoog::cli::exec::execute(&client, service, action, args);
+    let created = exec("drive", "create_file", json!({ "metadata": { "name": "empty.txt" } })).await.unwrap();
+    assert_eq!(created["name"], "empty.txt");
+    let target = download_target("alias-export.txt");
+    let saved = exec("drive", "export_file", json!({ "file_id": "file-1", "mime_type": "text/plain", "path": target })).await.unwrap();
+    assert_eq!(saved["path"], target);
+    exec("tasks", "update_task", json!({ "task_list_id": "L1", "task_id": "t1", "updates": { "notes": "2%" } })).await.unwrap();
+
+    let requests = fake.requests();
+    let patch = requests.iter().find(|r| r.method == "PATCH").unwrap();
+    assert_eq!(patch.path, format!("{TASKS}/lists/L1/tasks/t1"));
+    assert_eq!(patch.body, json!({ "notes": "2%" }));
+    assert!(requests.iter().any(|r| r.path == format!("{DRIVE}/files/file-1/export")));
+}
+
+#[tokio::test]
+async fn unknown_actions_are_rejected() {
+    let (fake, client) = common::setup().await;
+
+    let err = vgoog::cli::exec::execute(&client, "gmail", "explode", json!({})).await.unwrap_err();
+    assert!(err.to_string().contains("Unknown gmail action"));
+    let err = vgoog::cli::exec::execute(&client, "fax", "send", json!({})).await.unwrap_err();
+    assert!(err.to_string().contains("Unknown service"));
+    assert!(fake.requests().is_empty());
+}
@@ -0,0 +1,109 @@
+mod common;
+
+use common::{exec, ids, setup};
+use serde_json::json;
+
+#[tokio::test]
+async fn sent_messages_can_be_listed_labelled_and_trashed() {
+    let (fake, client) = setup().await;
+
+    let sent = exec(&client, "gmail", "send_message", json!({ "raw": "SGk=" })).await;
+    let id = sent["id"].as_str().unwrap().to_string();
+    assert_eq!(sent["labelIds"], json!(["SENT"]));
+
+    exec(&client, "gmail", "modify_message", json!({ "id": id, "add_labels": ["STARRED"] })).await;
+    exec(&client, "gmail", "trash_message", json!({ "id": id })).await;
+    let message = exec(&client, "gmail", "get_message", json!({ "id": id })).await;
+    assert_eq!(message["labelIds"], json!(["SENT", "STARRED", "TRASH"]));
+
+    let thread = exec(&client, "gmail", "get_thread", json!({ "id": sent["threadId"] })).await;
+    assert_eq!(ids(&thread, "messages", "id"), vec![id.clone()]);
+
+    exec(&client, "gmail", "delete_message", json!({ "id": id })).await;
+    assert!(fake.get(&format!("/gmail/v1/users/me/messages/{id}")).is_none());
+}
+
+#[tokio::test]
+async fn message_list_pages_through_page_tokens() {
+    let (fake, client) = setup().await;
+    for i in 0..5 {
+        fake.seed(
+            &format!("/gmail/v1/users/me/messages/m{i}"),
+            json!({ "id": format!("m{i}"), "threadId": format!("m{i}") }),
+        );
+    }
+
+    let mut seen = Vec::new();
+    let mut page_token: Option<String> = None;
+    loop {
+        let page = exec(
+            &client,
+            "gmail",
+            "list_messages",
+            json!({ "max_results": 2, "page_token": page_token }),
+        )
+        .await;
+        seen.extend(ids(&page, "messages", "id"));
+        match page["nextPageToken"].as_str() {
+            Some(token) => page_token = Some(token.to_string()),
+            None => break,
+        }
+    }
+    assert_eq!(seen, ["m0", "m1", "m2", "m3", "m4"]);
+
+    let requests = fake.requests();
+    assert_eq!(requests.len(), 3);
+    assert!(requests.iter().all(|r| r.param("maxResults") == Some("2")));
+    assert_eq!(requests[2].param("pageToken"), Some("4"));
+}
+
+#[tokio::test]
+async fn sending_a_draft_removes_it() {
+    let (fake, client) = setup().await;
+
+    let draft = exec(&client, "gmail", "create_draft", json!({ "raw": "ZHJhZnQ=" })).await;
+    let listed = exec(&client, "gmail", "list_drafts", json!({})).await;
+    assert_eq!(ids(&listed, "drafts", "id"), vec![draft["id"].as_str().unwrap()]);
+
+    let sent = exec(&client, "gmail", "send_draft", json!({ "id": draft["id"] })).await;
+    assert_eq!(sent["raw"], "ZHJhZnQ=");
+    let listed = exec(&client, "gmail", "list_drafts", json!({})).await;
+    assert!(ids(&listed, "drafts", "id").is_empty());
+    let send = fake
+        .requests()
+        .into_iter()
+        .find(|r| r.path == "/gmail/v1/users/me/drafts/send")
+        .expect("drafts.send request");
+    assert_eq!(send.body, json!({ "id": draft["id"] }));
+}
+
+#[tokio::test]
+async fn labels_and_settings_round_trip() {
+    let (_fake, client) = setup().await;
+
+    let label = exec(&client, "gmail", "create_label", json!({ "name": "Receipts" })).await;
+    exec(&client, "gmail", "update_label", json!({ "id": label["id"], "name": "Bills" })).await;
+    let fetched = exec(&client, "gmail", "get_label", json!({ "id": label["id"] })).await;
+    assert_eq!(fetched["name"], "Bills");
+
+    exec(
+        &client,
+        "gmail",
+        "update_vacation_settings",
+        json!({ "settings": { "enableAutoReply": true, "responseSubject": "Away" } }),
+    )
+    .await;
+    let vacation = exec(&client, "gmail", "get_vacation_settings", json!({})).await;
+    assert_eq!(vacation["enableAutoReply"], true);
+    assert_eq!(vacation["responseSubject"], "Away");
+}
+
+#[tokio::test]
+async fn missing_message_is_not_found() {
+    let (_fake, client) = setup().await;
+
+    let err = vgoog::cli::exec::execute(&client, "gmail", "get_message", json!({ "id": "nope" }))
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), "not_found");
+}
@@ -0,0 +1,107 @@
+//! Importing OAuth clients and gcloud Application Default Credentials.
+
+use serde_json::json;
+use std::path::PathBuf;
+use vgoog::auth::import::{default_adc_path, redeem, CredentialsFile, ADC_FILE_NAME};
+use vgoog::endpoints::Endpoints;
+use vgoog::fake::{FakeGoogle, CLIENT_ID, CLIENT_SECRET, REFRESH_TOKEN};
+
+fn write_temp(name: &str, value: serde_json::Value) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("vgoog-import-{}-{name}", std::process::id()));
+    std::fs::write(&path, value.to_string()).unwrap();
+    path
+}
+
+#[test]
+fn client_secret_files_of_both_types_yield_the_client() {
+    let client = CredentialsFile::Client { client_id: "id".into(), client_secret: "secret".into() };
+    for kind in ["installed", "web"] {
+        let path = write_temp(
+            &format!("client_secret_{kind}.json"),
+            json!({ kind: {
+                "client_id": "id",
+                "client_secret": "secret",
+                "project_id": "demo",
+                "auth_uri": "https://accounts.google.com/o/oauth2/auth",
+                "token_uri": "https://oauth2.googleapis.com/token",
+                "redirect_uris": ["http://localhost"],
+            }}),
+        );
+        assert_eq!(CredentialsFile::load(&path).unwrap(), client);
+    }
+}
+
+#[test]
+fn authorized_user_files_carry_the_refresh_token() {
+    let path = write_temp(
+        ADC_FILE_NAME,
+        json!({
+            "type": "authorized_user",
+            "client_id": "id",
+            "client_secret": "secret",
+            "refresh_token": "1//refresh",
+            "quota_project_id": "demo",
+        }),
+    );
+    assert_eq!(
+        CredentialsFile::load(&path).unwrap(),
+        CredentialsFile::AuthorizedUser {
+            client_id: "id".into(),
+            client_secret: "secret".into(),
+            refresh_token: "1//refresh".into(),
+        }
+    );
+}
+
+#[test]
+fn other_files_are_refused_with_a_pointer() {
+    let err = CredentialsFile::parse(&json!({ "type": "service_account", "client_email": "x" }).to_string())
+        .unwrap_err();
+    assert_eq!(err.kind(), "config");
+    assert!(err.to_string().contains("vgoog auth service-account"), "{err}");
+
+    let err = CredentialsFile::parse(&json!({ "type": "external_account" }).to_string()).unwrap_err();
+    assert!(err.to_string().contains("external_account"), "{err}");
+    let err = CredentialsFile::load(&write_temp("empty.json", json!({}))).unwrap_err();
+    assert!(err.to_string().contains("empty.json"), "{err}");
+}
+
+#[tokio::test]
+async fn imported_refresh_tokens_are_validated_with_a_refresh() {
+    let fake = FakeGoogle::start().await.unwrap();
+    let endpoints = Endpoints::resolve(&fake.config().endpoints).unwrap();
+    let http = reqwest::Client::new();
+
+    let grant = redeem(&http, &endpoints, CLIENT_ID, CLIENT_SECRET, REFRESH_TOKEN).await.unwrap();
+
+    assert!(grant.auth.access_token.starts_with("fake-access-"));
+    assert_eq!(grant.auth.refresh_token, REFRESH_TOKEN);
+    assert_eq!(grant.auth.client_id, CLIENT_ID);
+    assert_eq!(grant.scopes, vgoog::auth::DEFAULT_SCOPES);
+    let refresh = fake.requests().into_iter().find(|r| r.path == "/token").unwrap();
+    assert!(refresh.body.as_str().unwrap().contains("grant_type=refresh_token"));
+}
+
+#[tokio::test]
+async fn revoked_refresh_tokens_fail_the_import() {
+    let fake = FakeGoogle::start().await.unwrap();
+    let endpoints = Endpoints::resolve(&fake.config().endpoints).unwrap();
+    let http = reqwest::Client::new();
+    vgoog::auth::revoke(&http, &endpoints, REFRESH_TOKEN).await.unwrap();
+
+    let err = redeem(&http, &endpoints, CLIENT_ID, CLIENT_SECRET, REFRESH_TOKEN).await.unwrap_err();
+    assert_eq!(err.kind(), "auth");
+    assert!(err.to_string().contains("invalid_grant"), "{err}");
+}
+
+#[test]
+fn adc_is_looked_for_where_gcloud_puts_it() {
+    std::env::remove_var("GOOGLE_APPLICATION_CREDENTIALS");
+    std::env::set_var("CLOUDSDK_CONFIG", "/tmp/gcloud-test");
+    assert_eq!(default_adc_path().unwrap(), PathBuf::from("/tmp/gcloud-test").join(ADC_FILE_NAME));
+
+    std::env::set_var("GOOGLE_APPLICATION_CREDENTIALS", "/tmp/creds.json");
+    assert_eq!(default_adc_path().unwrap(), PathBuf::from("/tmp/creds.json"));
+    std::env::remove_var("GOOGLE_APPLICATION_CREDENTIALS");
+    std::env::remove_var("CLOUDSDK_CONFIG");
+}
@@ -0,0 +1,108 @@
+//! The loopback consent flow: the redirect listener checks `state` and
+//! the code is exchanged with the PKCE verifier behind the challenge.
+
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use reqwest::{Client, StatusCode, Url};
+use vgoog::auth::loopback::{authorization_url, exchange_code, PendingConsent, Pkce};
+use vgoog::endpoints::Endpoints;
+use vgoog::fake::{FakeGoogle, CLIENT_ID, CLIENT_SECRET};
+
+const SCOPES: &[&str] = &["https://www.googleapis.com/auth/tasks"];
+
+async fn start() -> (FakeGoogle, Endpoints, PendingConsent) {
+    let fake = FakeGoogle::start().await.unwrap();
+    let endpoints = Endpoints::resolve(&fake.config().endpoints).unwrap();
+    let consent = PendingConsent::start(&endpoints, CLIENT_ID, SCOPES, false).await.unwrap();
+    (fake, endpoints, consent)
+}
+
+fn param(url: &str, name: &str) -> String {
+    let url = Url::parse(url).unwrap();
+    url.query_pairs().find(|(k, _)| k == name).unwrap().1.into_owned()
+}
+
+/// Stand in for the browser coming back to the listener
+async fn redirect(redirect_uri: &str, query: &str) -> StatusCode {
+    reqwest::get(format!("{redirect_uri}/?{query}")).await.unwrap().status()
+}
+
+#[tokio::test]
+async fn the_code_is_exchanged_with_the_verifier_behind_the_challenge() {
+    let (fake, endpoints, consent) = start().await;
+    let url = consent.url().to_string();
+    let redirect_uri = consent.redirect_uri().to_string();
+    assert_eq!(param(&url, "code_challenge_method"), "S256");
+    assert_eq!(param(&url, "redirect_uri"), redirect_uri);
+
+    let http = Client::new();
+    let browser = reqwest::get(url.clone());
+    let (page, grant) = tokio::join!(browser, consent.finish(&http, &endpoints, CLIENT_ID, CLIENT_SECRET));
+    assert!(page.unwrap().status().is_success());
+    assert_eq!(grant.unwrap().scopes, SCOPES);
+
+    let token = fake.requests().into_iter().find(|r| r.path == "/token").unwrap();
+    let form = format!("{redirect_uri}/?{}", token.body.as_str().unwrap());
+    let field = |name: &str| param(&form, name);
+    assert_eq!(field("grant_type"), "authorization_code");
+    assert_eq!(field("redirect_uri"), redirect_uri);
+    let verifier = field("code_verifier");
+    let digest = ring::digest::digest(&ring::digest::SHA256, verifier.as_bytes());
+    assert_eq!(URL_SAFE_NO_PAD.encode(digest), param(&url, "code_challenge"));
+}
+
+#[tokio::test]
+async fn stray_requests_are_ignored_and_a_wrong_state_is_refused() {
+    let (fake, endpoints, consent) = start().await;
+    let redirect_uri = consent.redirect_uri().to_string();
+    assert!(!param(consent.url(), "state").is_empty());
+
+    let http = Client::new();
+    let browser = async {
+        let favicon = reqwest::get(format!("{redirect_uri}/favicon.ico")).await.unwrap().status();
+        let forged = redirect(&redirect_uri, "code=forged&state=not-the-state").await;
+        (favicon, forged)
+    };
+    let ((favicon, forged), grant) = tokio::join!(browser, consent.finish(&http, &endpoints, CLIENT_ID, CLIENT_SECRET));
+    assert_eq!(favicon, StatusCode::NOT_FOUND);
+    assert_eq!(forged, StatusCode::BAD_REQUEST);
+    assert_eq!(grant.unwrap_err().to_string(), "Auth error: OAuth state mismatch");
+    assert!(fake.requests().iter().all(|r| r.path != "/token"));
+}
+
+#[tokio::test]
+async fn a_denied_consent_is_reported() {
+    let (fake, endpoints, consent) = start().await;
+    let redirect_uri = consent.redirect_uri().to_string();
+    let query = format!("error=access_denied&state={}", param(consent.url(), "state"));
+    let http = Client::new();
+    let browser = redirect(&redirect_uri, &query);
+    let (status, grant) = tokio::join!(browser, consent.finish(&http, &endpoints, CLIENT_ID, CLIENT_SECRET));
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(grant.unwrap_err().to_string(), "Auth error: Authorization denied: access_denied");
+    assert!(fake.requests().iter().all(|r| r.path != "/token"));
+}
+
+#[tokio::test]
+async fn a_code_is_refused_without_its_verifier() {
+    let fake = FakeGoogle::start().await.unwrap();
+    let endpoints = Endpoints::resolve(&fake.config().endpoints).unwrap();
+    let pkce = Pkce::generate().unwrap();
+    let redirect_uri = "http://127.0.0.1:1/";
+    let url = authorization_url(&endpoints, CLIENT_ID, redirect_uri, SCOPES, &pkce.challenge, "s", false);
+
+    // Take the code from the consent screen's redirect without following it
+    let no_redirects = Client::builder().redirect(reqwest::redirect::Policy::none()).build().unwrap();
+    let response = no_redirects.get(url).send().await.unwrap();
+    let location = response.headers()["location"].to_str().unwrap().to_string();
+    let code = param(&location, "code");
+
+    let http = Client::new();
+    let other = Pkce::generate().unwrap();
+    let err = exchange_code(&http, &endpoints, CLIENT_ID, CLIENT_SECRET, &code, redirect_uri, &other.verifier).await.unwrap_err();
+    assert!(err.to_string().contains("Invalid code verifier"), "{err}");
+
+    // A refused code is used up, like Google's
+    let err = exchange_code(&http, &endpoints, CLIENT_ID, CLIENT_SECRET, &code, redirect_uri, &pkce.verifier).await.unwrap_err();
+    assert!(err.to_string().contains("Malformed auth code"), "{err}");
+}
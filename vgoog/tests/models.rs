@@ -0,0 +1,118 @@
+//! Typed models and the typed API methods built on the `Value` ones.
+
+mod common;
+
+use common::{exec, setup};
+use serde_json::json;
+use vgoog::api::calendar::CalendarApi;
+use vgoog::api::drive::DriveApi;
+use vgoog::api::gmail::GmailApi;
+use vgoog::api::models::{self, Event, EventTime, Message, Name, Page, Person, Task};
+use vgoog::api::people::PeopleApi;
+use vgoog::api::tasks::TasksApi;
+
+#[test]
+fn missing_fields_default_and_unknown_ones_are_ignored() {
+    let message: Message = models::parse(json!({
+        "id": "m1",
+        "labelIds": ["INBOX"],
+        "payload": { "headers": [{ "name": "Subject", "value": "Hi" }] },
+        "someFutureField": true,
+    }))
+    .unwrap();
+    assert!(message.has_label("INBOX"));
+    assert_eq!(message.header("subject"), Some("Hi"));
+    assert_eq!(message.thread_id, "");
+
+    // A wrong type is an error rather than a silent default
+    let err = models::parse::<Message>(json!({ "labelIds": "INBOX" })).unwrap_err();
+    assert_eq!(err.kind(), "json");
+
+    let page: Page<Message> = Page::parse(json!({ "messages": [{ "id": "m1" }], "nextPageToken": "2" }), "messages").unwrap();
+    assert_eq!(page.items[0].id, "m1");
+    assert_eq!(page.next_page_token.as_deref(), Some("2"));
+}
+
+#[test]
+fn request_bodies_leave_out_unset_fields() {
+    let event = Event {
+        summary: Some("Standup".to_string()),
+        start: Some(EventTime { date_time: Some("2026-11-02T09:00:00Z".to_string()), ..Default::default() }),
+        ..Default::default()
+    };
+    let body = models::request_body(&event).unwrap();
+    assert_eq!(
+        body,
+        json!({ "summary": "Standup", "start": { "dateTime": "2026-11-02T09:00:00Z" } })
+    );
+}
+
+#[tokio::test]
+async fn gmail_messages_threads_and_labels_are_typed() {
+    let (_fake, client) = setup().await;
+    let gmail = GmailApi::new(&client);
+    let sent = exec(&client, "gmail", "send_message", json!({ "raw": "SGk=" })).await;
+
+    let page = gmail.messages(None, 10, None).await.unwrap();
+    assert_eq!(page.items.len(), 1);
+    let message = gmail.message(&page.items[0].id, "full").await.unwrap();
+    assert!(message.has_label("SENT"));
+
+    let thread = gmail.thread(sent["threadId"].as_str().unwrap(), "full").await.unwrap();
+    assert_eq!(thread.messages[0].id, message.id);
+
+    let labels = gmail.labels().await.unwrap();
+    let inbox = labels.iter().find(|l| l.id == "INBOX").unwrap();
+    assert_eq!(inbox.kind.as_deref(), Some("system"));
+}
+
+#[tokio::test]
+async fn events_and_tasks_round_trip_through_their_models() {
+    let (_fake, client) = setup().await;
+
+    let calendar = CalendarApi::new(&client);
+    let created = calendar
+        .insert_event(
+            "primary",
+            &Event { summary: Some("Flight".to_string()), ..Default::default() },
+        )
+        .await
+        .unwrap();
+    assert!(!created.id.is_empty());
+    let events = calendar.events("primary", None, None, None, 10, None, false, None).await.unwrap();
+    assert_eq!(events.items, [created]);
+    assert!(calendar.calendars(None).await.unwrap().items.iter().any(|c| c.id == "primary"));
+
+    let tasks = TasksApi::new(&client);
+    let list = &tasks.task_lists(10, None).await.unwrap().items[0];
+    let task = tasks
+        .insert_task(&list.id, &Task { title: "Pack".to_string(), ..Default::default() })
+        .await
+        .unwrap();
+    assert_eq!(tasks.task(&list.id, &task.id).await.unwrap().title, "Pack");
+    assert!(!task.is_completed());
+}
+
+#[tokio::test]
+async fn drive_files_and_contacts_are_typed() {
+    let (_fake, client) = setup().await;
+
+    let drive = DriveApi::new(&client);
+    let folder = drive.create_folder("Reports", None).await.unwrap();
+    let file = drive.file(folder["id"].as_str().unwrap()).await.unwrap();
+    assert!(file.is_folder());
+    let files = drive.files(None, 10, None, None).await.unwrap();
+    assert_eq!(files.items[0].name, "Reports");
+
+    let people = PeopleApi::new(&client);
+    let ada = people
+        .insert_contact(&Person {
+            names: vec![Name { given_name: Some("Ada".to_string()), ..Default::default() }],
+            ..Default::default()
+        })
+        .await
+        .unwrap();
+    let contacts = people.contacts(10, None, None).await.unwrap();
+    assert_eq!(contacts.items[0].resource_name, ada.resource_name);
+    assert_eq!(people.person(&ada.resource_name).await.unwrap().names[0].given_name.as_deref(), Some("Ada"));
+}
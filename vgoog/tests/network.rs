@@ -0,0 +1,102 @@
+//! `[network]` settings reach every request, OAuth included.
+
+mod common;
+
+use chrono::{Duration, Utc};
+use common::{exec, fresh_config_dir};
+use serde_json::json;
+use vgoog::client::GoogleClient;
+use vgoog::config::Config;
+use vgoog::fake::FakeGoogle;
+use vgoog::network::{NetworkConfig, USER_AGENT};
+use vgoog::retry::RetryPolicy;
+
+/// The fake's config with an expired token, so the first call refreshes
+/// it; callers hold `fresh_config_dir` since the refresh is saved
+fn expired(fake: &FakeGoogle) -> Config {
+    let mut config = fake.config();
+    config.accounts.get_mut("fake").unwrap().auth.token_expiry = Utc::now() - Duration::minutes(5);
+    config
+}
+
+#[test]
+fn missing_settings_keep_their_defaults() {
+    let config: Config = toml::from_str("[network]\nread_timeout_secs = 5\n").unwrap();
+    assert_eq!(config.network.read_timeout_secs, 5);
+    assert_eq!(config.network.connect_timeout_secs, NetworkConfig::default().connect_timeout_secs);
+
+    let saved = toml::to_string(&Config::default()).unwrap();
+    assert!(!saved.contains("[network]"), "{saved}");
+}
+
+#[tokio::test]
+async fn the_user_agent_suffix_is_sent_to_apis_and_the_token_endpoint() {
+    let _guard = fresh_config_dir().await;
+    let fake = FakeGoogle::start().await.unwrap();
+    let mut config = expired(&fake);
+    config.network.user_agent_suffix = Some("nightly-sync".to_string());
+    let client = GoogleClient::new(config).unwrap();
+
+    exec(&client, "gmail", "get_profile", json!({})).await;
+
+    let requests = fake.requests();
+    assert_eq!(requests.len(), 2);
+    for request in requests {
+        let agent = request.headers.iter().find(|(k, _)| k == "user-agent").map(|(_, v)| v.as_str());
+        assert_eq!(agent, Some(format!("{USER_AGENT} nightly-sync").as_str()), "{}", request.path);
+    }
+}
+
+#[tokio::test]
+async fn everything_goes_through_the_proxy() {
+    let _guard = fresh_config_dir().await;
+    let fake = FakeGoogle::start().await.unwrap();
+    let mut config = expired(&fake);
+    // Only reachable through the proxy, which is the fake itself
+    config.endpoints.insert("root".to_string(), "http://google.invalid".to_string());
+    config.network.proxy = Some(fake.url());
+    let client = GoogleClient::new(config).unwrap();
+
+    exec(&client, "gmail", "get_profile", json!({})).await;
+
+    let paths: Vec<String> = fake.requests().into_iter().map(|r| r.path).collect();
+    assert_eq!(paths, ["/token", "/gmail/v1/users/me/profile"]);
+}
+
+#[tokio::test]
+async fn a_stalled_server_times_out() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        let mut held = Vec::new();
+        while let Ok((stream, _)) = listener.accept().await {
+            held.push(stream);
+        }
+    });
+
+    let fake = FakeGoogle::start().await.unwrap();
+    let mut config = fake.config();
+    config.endpoints.insert("root".to_string(), url);
+    config.network.read_timeout_secs = 1;
+    config.accounts.get_mut("fake").unwrap().retry = Some(RetryPolicy { max_attempts: 1, ..Default::default() });
+    let client = GoogleClient::new(config).unwrap();
+
+    let started = std::time::Instant::now();
+    let err = vgoog::cli::exec::execute(&client, "gmail", "get_profile", json!({})).await.unwrap_err();
+    assert_eq!(err.kind(), "http");
+    assert!(started.elapsed() < std::time::Duration::from_secs(10));
+}
+
+#[test]
+fn ca_files_without_certificates_are_refused() {
+    let path = std::env::temp_dir().join(format!("vgoog-network-{}-empty.pem", std::process::id()));
+    std::fs::write(&path, "not a certificate\n").unwrap();
+    let network = NetworkConfig { ca_certificates: vec![path.clone()], ..Default::default() };
+
+    let err = network.client().unwrap_err();
+    assert_eq!(err.kind(), "config");
+    assert!(err.to_string().contains(&path.display().to_string()), "{err}");
+
+    let network = NetworkConfig { proxy: Some("not a url".to_string()), ..Default::default() };
+    assert_eq!(network.client().unwrap_err().kind(), "config");
+}
@@ -0,0 +1,174 @@
+//! Following `nextPageToken` for any list call: the pager itself,
+//! `vgoog exec --all/--limit` and "load more" in the TUI.
+
+mod common;
+
+use common::{ids, setup};
+use futures_util::StreamExt;
+use serde_json::{json, Value};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use vgoog::fake::FakeGoogle;
+use vgoog::pager;
+use vgoog::ui::app::{App, InputField, Service};
+use vgoog::ui::views::handlers;
+
+fn seed_messages(fake: &FakeGoogle, count: usize) {
+    for i in 0..count {
+        fake.seed(
+            &format!("/gmail/v1/users/me/messages/m{i:02}"),
+            json!({ "id": format!("m{i:02}"), "threadId": format!("m{i:02}") }),
+        );
+    }
+}
+
+#[tokio::test]
+async fn the_stream_follows_tokens_and_stops_on_a_repeated_one() {
+    // Page "b" hands back its own token, which would loop forever
+    let responses = |token: Option<String>| async move {
+        Ok(match token.as_deref() {
+            None => json!({ "items": [1, 2], "nextPageToken": "a" }),
+            Some("a") => json!({ "items": [3], "nextPageToken": "b" }),
+            _ => json!({ "items": [4], "nextPageToken": "b" }),
+        })
+    };
+
+    let pages: Vec<Value> = pager::pages(responses).map(Result::unwrap).collect().await;
+    assert_eq!(pages.len(), 3);
+
+    let items: Vec<Value> = pager::items(responses, Some("items"), None).map(Result::unwrap).collect().await;
+    assert_eq!(items, [json!(1), json!(2), json!(3), json!(4)]);
+
+    let fetched = AtomicUsize::new(0);
+    let counted = |token: Option<String>| {
+        fetched.fetch_add(1, Ordering::SeqCst);
+        responses(token)
+    };
+    let first: Vec<Value> = pager::items(counted, Some("items"), Some(2)).map(Result::unwrap).collect().await;
+    assert_eq!(first, [json!(1), json!(2)]);
+    assert_eq!(fetched.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn items_are_found_under_their_list_field() {
+    // A last page of events has no `items` at all, only other arrays
+    let events = json!({ "defaultReminders": [{ "method": "popup" }], "items": [{ "id": "e1" }] });
+    assert_eq!(pager::page_items(&events, Some("items")).unwrap().1.len(), 1);
+    let last = json!({ "defaultReminders": [{ "method": "popup" }] });
+    assert!(pager::page_items(&last, Some("items")).is_none());
+
+    // Unknown fields are guessed: `items`, otherwise the first array
+    assert_eq!(pager::page_items(&events, None).unwrap().0, "items");
+    let files = json!({ "files": [{ "id": "f1" }], "nextPageToken": "2" });
+    assert_eq!(pager::page_items(&files, None).unwrap().0, "files");
+    assert_eq!(pager::next_page_token(&files), Some("2"));
+    assert_eq!(pager::next_page_token(&json!({ "nextPageToken": "" })), None);
+}
+
+#[tokio::test]
+async fn collecting_keeps_to_the_list_field() {
+    // `connections` sorts after the other array, which a guess would pick
+    let responses = |token: Option<String>| async move {
+        Ok(match token.as_deref() {
+            None => json!({ "connections": [1], "alternates": ["x"], "nextPageToken": "a" }),
+            _ => json!({ "connections": [2], "alternates": ["y"] }),
+        })
+    };
+    let all = pager::collect(responses, Some("connections"), None).await.unwrap();
+    assert_eq!(all, json!({ "connections": [1, 2], "alternates": ["x"] }));
+
+    // An empty list still has its field
+    let empty = pager::collect(|_| async { Ok(json!({ "totalItems": 0 })) }, Some("connections"), None).await.unwrap();
+    assert_eq!(empty, json!({ "totalItems": 0, "connections": [] }));
+}
+
+#[test]
+fn paged_actions_name_their_list_field() {
+    let field = |service, action| vgoog::cli::exec::find(service, action).unwrap().items;
+    assert_eq!(field("contacts", "list_contacts"), Some("connections"));
+    assert_eq!(field("calendar", "list_events"), Some("items"));
+    assert_eq!(field("drive", "files_list_labels"), Some("labels"));
+    assert_eq!(field("gmail", "get_message"), None);
+
+    // Every action that takes a page token says where its items are
+    for (service, _) in vgoog::cli::exec::SERVICES {
+        for action in vgoog::cli::exec::actions(service) {
+            if action.arg("page_token").is_some() {
+                assert!(action.items.is_some(), "{service}.{}", action.name);
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn exec_all_collects_every_page() {
+    let (fake, client) = setup().await;
+    seed_messages(&fake, 5);
+
+    let all = vgoog::cli::exec::execute_paged(&client, "gmail", "list_messages", json!({ "max_results": 2 }), None)
+        .await
+        .unwrap();
+    assert_eq!(ids(&all, "messages", "id"), ["m00", "m01", "m02", "m03", "m04"]);
+    assert!(all.get("nextPageToken").is_none());
+    assert_eq!(all["resultSizeEstimate"], 5);
+    assert_eq!(fake.requests().len(), 3);
+}
+
+#[tokio::test]
+async fn exec_limit_stops_without_fetching_further_pages() {
+    let (fake, client) = setup().await;
+    seed_messages(&fake, 5);
+
+    let some = vgoog::cli::exec::execute_paged(&client, "gmail", "list_messages", json!({ "max_results": 2 }), Some(3))
+        .await
+        .unwrap();
+    assert_eq!(ids(&some, "messages", "id"), ["m00", "m01", "m02"]);
+    assert_eq!(fake.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn load_more_appends_the_next_page_of_the_same_query() {
+    let (fake, client) = setup().await;
+    seed_messages(&fake, 25);
+
+    let mut app = App::new(client);
+    app.service = Some(Service::Gmail);
+    app.selected_action = 1;
+    let mut query = InputField::new("Search Query", "", true);
+    query.value = "from:boss".to_string();
+    app.input_fields = vec![query];
+    handlers::submit_input(&mut app).await;
+    assert_eq!(app.items.len(), 20);
+    assert_eq!(app.page_info, "more available");
+
+    app.item_cursor = 7;
+    handlers::load_next_page(&mut app).await;
+    assert_eq!(app.items.len(), 25);
+    assert_eq!(app.items[24].id, "m24");
+    assert_eq!(app.item_cursor, 7);
+    assert!(app.next_page_token.is_none());
+    assert!(app.page_info.is_empty());
+
+    let requests = fake.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[1].param("q"), Some("from:boss"));
+    assert_eq!(requests[1].param("pageToken"), Some("20"));
+}
+
+#[tokio::test]
+async fn long_snippets_are_cut_between_characters() {
+    let (fake, client) = setup().await;
+    // Byte 77 falls inside a two-byte character
+    let snippet = "Déjà vu, café crème ".repeat(10);
+    fake.seed("/gmail/v1/users/me/messages/m1", json!({ "id": "m1", "threadId": "m1", "snippet": snippet }));
+
+    let mut app = App::new(client);
+    app.service = Some(Service::Gmail);
+    app.selected_action = 1;
+    app.input_fields = vec![InputField::new("Search Query", "", true)];
+    handlers::submit_input(&mut app).await;
+
+    let subtitle = &app.items[0].subtitle;
+    assert_eq!(subtitle.chars().count(), 80);
+    assert!(subtitle.ends_with("..."));
+    assert!(snippet.starts_with(subtitle.trim_end_matches("...")));
+}
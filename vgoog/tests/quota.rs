@@ -0,0 +1,143 @@
+//! Per-API pacing and the usage log behind `vgoog quota`.
+
+mod common;
+
+use common::{exec, fresh_config_dir, setup};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+use vgoog::client::GoogleClient;
+use vgoog::endpoints::Endpoints;
+use vgoog::fake::FakeGoogle;
+use vgoog::quota::{buckets_path, charge, load_buckets, Charge, RateLimit, RateLimiter, DRIVE, GMAIL};
+
+#[test]
+fn requests_are_charged_to_their_api() {
+    let endpoints = Endpoints::default();
+    let gmail = |method, path: &str| charge(&endpoints, method, &format!("{}{path}", endpoints.gmail));
+
+    assert_eq!(gmail("POST", "/messages/send"), Some(Charge { api: GMAIL, units: 100 }));
+    assert_eq!(gmail("GET", "/messages/abc?format=full").unwrap().units, 5);
+    assert_eq!(gmail("DELETE", "/threads/abc").unwrap().units, 20);
+    assert_eq!(gmail("GET", "/labels").unwrap().units, 1);
+
+    let upload = format!("{}/files?uploadType=resumable", endpoints.drive_upload);
+    assert_eq!(charge(&endpoints, "POST", &upload), Some(Charge { api: DRIVE, units: 1 }));
+    // Batches are charged per call inside them, OAuth not at all
+    assert_eq!(charge(&endpoints, "POST", &endpoints.gmail_batch), None);
+    assert_eq!(charge(&endpoints, "POST", &endpoints.token_uri), None);
+}
+
+#[tokio::test]
+async fn a_full_bucket_is_spent_then_refilled_at_the_budget_rate() {
+    let limits = BTreeMap::from([(GMAIL.to_string(), RateLimit::new(20, 1))]);
+    let limiter = RateLimiter::new(limits);
+
+    let started = Instant::now();
+    limiter.acquire(Charge { api: GMAIL, units: 20 }).await;
+    assert!(started.elapsed() < Duration::from_millis(100));
+
+    limiter.acquire(Charge { api: GMAIL, units: 10 }).await;
+    assert!(started.elapsed() >= Duration::from_millis(400), "{:?}", started.elapsed());
+
+    // APIs without a limit are counted but never wait
+    limiter.acquire(Charge { api: DRIVE, units: 1_000 }).await;
+    let usage = limiter.usage();
+    assert_eq!(usage[GMAIL].requests, 2);
+    assert_eq!(usage[GMAIL].units, 30);
+    assert!(usage[GMAIL].waited_ms >= 400);
+    assert_eq!(usage[DRIVE].waited_ms, 0);
+}
+
+#[tokio::test]
+async fn requests_wait_for_the_configured_budget() {
+    let fake = FakeGoogle::start().await.unwrap();
+    let mut config = fake.config();
+    config.rate_limits.insert(GMAIL.to_string(), RateLimit::new(2, 1));
+    let client = GoogleClient::new(config).unwrap();
+
+    let started = Instant::now();
+    for _ in 0..3 {
+        exec(&client, "gmail", "get_profile", json!({})).await;
+    }
+    assert!(started.elapsed() >= Duration::from_millis(400), "{:?}", started.elapsed());
+    assert_eq!(client.usage()[GMAIL].units, 3);
+}
+
+#[tokio::test]
+async fn batches_are_charged_for_every_call() {
+    let (fake, client) = setup().await;
+    let ids = ["m1", "m2", "m3"];
+    for id in ids {
+        fake.seed(&format!("/gmail/v1/users/me/messages/{id}"), json!({ "id": id }));
+    }
+
+    let requests: Vec<_> = ids.iter().map(|id| json!({ "path": format!("messages/{id}") })).collect();
+    vgoog::cli::batch::execute(&client, "gmail", json!(requests)).await.unwrap();
+
+    let usage = client.usage()[GMAIL];
+    assert_eq!(usage.requests, 3);
+    assert_eq!(usage.units, 15);
+}
+
+#[tokio::test]
+async fn commands_are_logged_for_the_quota_report() {
+    let _guard = fresh_config_dir().await;
+    let (fake, client) = setup().await;
+
+    exec(&client, "gmail", "list_labels", json!({})).await;
+    exec(&client, "tasks", "list_task_lists", json!({})).await;
+    client.record_usage("exec gmail list_labels").await.unwrap();
+    // Nothing sent, nothing logged
+    let (_, idle) = setup().await;
+    idle.record_usage("exec gmail get_profile").await.unwrap();
+
+    let report = vgoog::cli::quota::report(&fake.config(), 10).unwrap();
+    let commands = report["commands"].as_array().unwrap();
+    assert_eq!(commands.len(), 1);
+    assert_eq!(commands[0]["command"], "exec gmail list_labels");
+    assert_eq!(commands[0]["account"], "fake");
+    assert_eq!(commands[0]["apis"][GMAIL]["units"], 1);
+    assert!(commands[0]["apis"]["tasks.googleapis.com"]["budget_percent"].as_f64().unwrap() > 0.0);
+    assert_eq!(report["totals"][GMAIL]["requests"], 1);
+    assert_eq!(report["limits"][GMAIL], json!({ "units": 250, "window_secs": 1 }));
+}
+
+#[tokio::test]
+async fn pacing_carries_over_to_the_next_command() {
+    let _guard = fresh_config_dir().await;
+    let fake = FakeGoogle::start().await.unwrap();
+    let mut config = fake.config();
+    config.rate_limits.insert(GMAIL.to_string(), RateLimit::new(2, 1));
+
+    let first = GoogleClient::new(config.clone()).unwrap();
+    for _ in 0..2 {
+        exec(&first, "gmail", "get_profile", json!({})).await;
+    }
+    first.record_usage("exec gmail get_profile").await.unwrap();
+
+    // A new process starts from the empty bucket, not a full one
+    let second = GoogleClient::new(config).unwrap();
+    exec(&second, "gmail", "get_profile", json!({})).await;
+    assert!(second.usage()[GMAIL].waited_ms >= 300, "{:?}", second.usage()[GMAIL]);
+    second.record_usage("exec gmail get_profile").await.unwrap();
+
+    let saved = load_buckets(&buckets_path().unwrap());
+    assert!(saved[GMAIL].units < 1.0, "{saved:?}");
+    assert!(!saved.contains_key(DRIVE));
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn the_usage_log_is_private() {
+    use std::os::unix::fs::PermissionsExt;
+    let _guard = fresh_config_dir().await;
+    let (_fake, client) = setup().await;
+    exec(&client, "gmail", "list_labels", json!({})).await;
+    client.record_usage("exec gmail list_labels").await.unwrap();
+
+    for path in [vgoog::quota::usage_log_path().unwrap(), buckets_path().unwrap()] {
+        let mode = std::fs::metadata(&path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode, 0o600, "{}", path.display());
+    }
+}
@@ -0,0 +1,135 @@
+//! Service-account accounts: the JWT assertion's signature and claims, as
+//! the fake token endpoint checks them, and tokens fetched again through
+//! `ensure_token` once they run out.
+
+mod common;
+
+use chrono::Utc;
+use common::{exec, fresh_config_dir};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use vgoog::auth::service_account::{fetch_token, sign_assertion, ServiceAccountKeyFile};
+use vgoog::client::GoogleClient;
+use vgoog::config::{AuthConfig, Config, ServiceAccountAuth, ServiceAccountKey, ServiceAccountRef};
+use vgoog::endpoints::Endpoints;
+use vgoog::fake::{FakeGoogle, SERVICE_ACCOUNT_EMAIL};
+
+const TASKS: &str = "https://www.googleapis.com/auth/tasks";
+const SUBJECT: &str = "someone@example.com";
+
+/// Write the fake's service-account key into the config dir
+fn key_file(fake: &FakeGoogle) -> PathBuf {
+    let dir = Config::config_dir().unwrap();
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("fake-sa.json");
+    std::fs::write(&path, fake.service_account_key().to_string()).unwrap();
+    path
+}
+
+fn decode(part: &str) -> Value {
+    use base64::Engine;
+    serde_json::from_slice(&base64::engine::general_purpose::URL_SAFE_NO_PAD.decode(part).unwrap()).unwrap()
+}
+
+#[tokio::test]
+async fn assertions_carry_the_key_and_delegation_claims() {
+    let _guard = fresh_config_dir().await;
+    let fake = FakeGoogle::start().await.unwrap();
+    let endpoints = Endpoints::resolve(&fake.config().endpoints).unwrap();
+    let sa = ServiceAccountAuth { key_file: key_file(&fake), scopes: vec![TASKS.to_string()], subject: Some(SUBJECT.to_string()) };
+
+    let before = Utc::now().timestamp();
+    let (token, expiry) = fetch_token(&reqwest::Client::new(), &sa, &endpoints).await.unwrap();
+    assert!(token.starts_with("fake-access-"));
+    assert!(expiry > Utc::now());
+
+    let claims = &fake.assertions()[0];
+    assert_eq!(claims["iss"], SERVICE_ACCOUNT_EMAIL);
+    assert_eq!(claims["sub"], SUBJECT);
+    assert_eq!(claims["aud"], format!("{}/token", fake.url()));
+    assert_eq!(claims["scope"], TASKS);
+    let iat = claims["iat"].as_i64().unwrap();
+    assert!(iat >= before && iat <= Utc::now().timestamp());
+    assert_eq!(claims["exp"].as_i64().unwrap() - iat, 3600);
+
+    let request = fake.requests().into_iter().find(|r| r.path == "/token").unwrap();
+    let form = request.body.as_str().unwrap();
+    let assertion = form.split('&').find_map(|pair| pair.strip_prefix("assertion=")).unwrap();
+    let header = decode(assertion.split('.').next().unwrap());
+    assert_eq!(header, json!({ "alg": "RS256", "typ": "JWT", "kid": "fake-key-1" }));
+}
+
+#[tokio::test]
+async fn tampered_or_misaddressed_assertions_are_refused() {
+    let _guard = fresh_config_dir().await;
+    let fake = FakeGoogle::start().await.unwrap();
+    let key = ServiceAccountKeyFile::load(&key_file(&fake)).unwrap();
+    let token_uri = format!("{}/token", fake.url());
+    let scopes = [TASKS.to_string()];
+    let exchange = |assertion: String| {
+        let token_uri = token_uri.clone();
+        async move {
+            let form = [("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer"), ("assertion", assertion.as_str())];
+            let response = reqwest::Client::new().post(token_uri).form(&form).send().await.unwrap();
+            assert_eq!(response.status(), 400);
+            response.json::<Value>().await.unwrap()["error_description"].as_str().unwrap().to_string()
+        }
+    };
+
+    // Impersonating someone else under the same signature
+    let signed = sign_assertion(&key, &scopes, Some(SUBJECT), &token_uri, Utc::now()).unwrap();
+    let parts: Vec<&str> = signed.split('.').collect();
+    let mut claims = decode(parts[1]);
+    claims["sub"] = json!("ceo@example.com");
+    use base64::Engine;
+    let forged_claims = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(claims.to_string());
+    let forged = format!("{}.{forged_claims}.{}", parts[0], parts[2]);
+    assert_eq!(exchange(forged).await, "Invalid JWT Signature.");
+
+    let elsewhere = sign_assertion(&key, &scopes, None, "https://oauth2.googleapis.com/token", Utc::now()).unwrap();
+    assert!(exchange(elsewhere).await.contains("audience"));
+
+    let stale = sign_assertion(&key, &scopes, None, &token_uri, Utc::now() - chrono::Duration::hours(2)).unwrap();
+    assert!(exchange(stale).await.contains("short-lived"));
+    assert!(fake.assertions().is_empty());
+}
+
+#[tokio::test]
+async fn api_calls_fetch_and_refresh_the_token_through_ensure_token() {
+    let _guard = fresh_config_dir().await;
+    let fake = FakeGoogle::start().await.unwrap();
+    let mut config = fake.config();
+    config
+        .service_accounts
+        .insert("fake-sa".to_string(), ServiceAccountKey { key_file: key_file(&fake), scopes: vec![TASKS.to_string()] });
+    let account = config.accounts.get_mut("fake").unwrap();
+    account.service_account = Some(ServiceAccountRef { key: "fake-sa".to_string(), subject: Some(SUBJECT.to_string()) });
+    account.auth = AuthConfig::default();
+    config.save().unwrap();
+
+    // Tokens inside the refresh margin are fetched again on every call
+    fake.set_token_lifetime(60);
+    let client = GoogleClient::new(config).unwrap();
+    exec(&client, "tasks", "list_task_lists", json!({})).await;
+    exec(&client, "tasks", "list_task_lists", json!({})).await;
+
+    let assertions = fake.assertions();
+    assert_eq!(assertions.len(), 2);
+    assert!(assertions.iter().all(|claims| claims["sub"] == SUBJECT));
+
+    let requests = fake.requests();
+    assert_eq!(requests.iter().filter(|r| r.path == "/token").count(), 2);
+    let used: Vec<&str> = requests
+        .iter()
+        .filter(|r| r.path.starts_with("/tasks/"))
+        .map(|r| r.header("authorization").unwrap())
+        .collect();
+    assert_eq!(used.len(), 2);
+    assert_ne!(used[0], used[1]);
+    assert!(used.iter().all(|auth| auth.starts_with("Bearer fake-access-")));
+
+    // The latest token is saved to the account, without a refresh token
+    let saved = &Config::load().unwrap().accounts["fake"].auth;
+    assert_eq!(format!("Bearer {}", saved.access_token), used[1]);
+    assert!(saved.refresh_token.is_empty());
+}
@@ -0,0 +1,50 @@
+mod common;
+
+use common::{exec, ids, setup};
+use serde_json::json;
+
+#[tokio::test]
+async fn completed_tasks_are_hidden_and_cleared() {
+    let (_fake, client) = setup().await;
+
+    let list = exec(&client, "tasks", "create_task_list", json!({ "title": "Groceries" })).await;
+    let list_id = list["id"].clone();
+    let milk = exec(&client, "tasks", "create_task", json!({ "task_list_id": list_id, "title": "Milk" })).await;
+    let eggs = exec(&client, "tasks", "create_task", json!({ "task_list_id": list_id, "title": "Eggs" })).await;
+
+    exec(&client, "tasks", "complete_task", json!({ "task_list_id": list_id, "task_id": milk["id"] })).await;
+    let open = exec(&client, "tasks", "list_tasks", json!({ "task_list_id": list_id, "show_completed": false })).await;
+    assert_eq!(ids(&open, "items", "id"), vec![eggs["id"].as_str().unwrap()]);
+
+    exec(&client, "tasks", "clear_completed", json!({ "task_list_id": list_id })).await;
+    let all = exec(&client, "tasks", "list_tasks", json!({ "task_list_id": list_id })).await;
+    assert_eq!(ids(&all, "items", "id"), vec![eggs["id"].as_str().unwrap()]);
+}
+
+#[tokio::test]
+async fn subtasks_and_moves() {
+    let (_fake, client) = setup().await;
+
+    let parent = exec(&client, "tasks", "create_task", json!({ "task_list_id": "default", "title": "Trip" })).await;
+    let child = exec(
+        &client,
+        "tasks",
+        "create_task",
+        json!({ "task_list_id": "default", "title": "Pack", "parent": parent["id"] }),
+    )
+    .await;
+    assert_eq!(child["parent"], parent["id"]);
+
+    let moved = exec(&client, "tasks", "move_task", json!({ "task_list_id": "default", "task_id": child["id"] })).await;
+    assert!(moved.get("parent").is_none());
+}
+
+#[tokio::test]
+async fn task_lists_include_the_default_list() {
+    let (_fake, client) = setup().await;
+
+    exec(&client, "tasks", "create_task_list", json!({ "title": "Work" })).await;
+    let lists = exec(&client, "tasks", "list_task_lists", json!({})).await;
+    assert_eq!(lists["items"][0]["title"], "My Tasks");
+    assert_eq!(lists["items"][1]["title"], "Work");
+}
@@ -0,0 +1,137 @@
+//! Encrypted credential store: locking, unlocking and re-sealing on save.
+
+mod common;
+
+use chrono::{Duration, Utc};
+use common::{exec, fresh_config_dir};
+use serde_json::json;
+use std::path::PathBuf;
+use vgoog::client::GoogleClient;
+use vgoog::config::Config;
+use vgoog::fake::{FakeGoogle, CLIENT_SECRET, REFRESH_TOKEN};
+use vgoog::vault::VaultKey;
+
+/// Keeps the full work factor out of debug-build test runs
+const TEST_ITERATIONS: u32 = 1_000;
+
+fn key_file() -> PathBuf {
+    let dir = Config::config_dir().unwrap();
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("test.key");
+    std::fs::write(&path, vgoog::vault::generate_key().unwrap()).unwrap();
+    path
+}
+
+fn config_toml() -> String {
+    std::fs::read_to_string(Config::config_path().unwrap()).unwrap()
+}
+
+#[tokio::test]
+async fn locking_moves_secrets_out_of_the_toml() {
+    let _guard = fresh_config_dir().await;
+    let fake = FakeGoogle::start().await.unwrap();
+    let mut config = fake.config();
+    config.save().unwrap();
+    assert!(config_toml().contains(REFRESH_TOKEN));
+
+    let key_file = key_file();
+    config.lock(VaultKey::from_key_file(&key_file).unwrap(), Some(key_file)).unwrap();
+
+    let toml = config_toml();
+    assert!(!toml.contains(REFRESH_TOKEN), "{toml}");
+    assert!(!toml.contains(CLIENT_SECRET), "{toml}");
+    assert!(toml.contains("refresh_token = \"vault:fake.refresh_token\""), "{toml}");
+    let sealed = std::fs::read_to_string(Config::vault_path().unwrap()).unwrap();
+    assert!(!sealed.contains(REFRESH_TOKEN));
+
+    let loaded = Config::load().unwrap();
+    assert_eq!(loaded.accounts["fake"].auth.refresh_token, REFRESH_TOKEN);
+    assert_eq!(loaded.accounts["fake"].auth.client_secret, CLIENT_SECRET);
+    let locked = Config::load_locked().unwrap();
+    assert_eq!(locked.accounts["fake"].auth.refresh_token, "vault:fake.refresh_token");
+}
+
+#[tokio::test]
+async fn passphrases_unlock_and_wrong_ones_are_rejected() {
+    let _guard = fresh_config_dir().await;
+    let fake = FakeGoogle::start().await.unwrap();
+    let mut config = fake.config();
+    config.lock(VaultKey::from_passphrase_with("correct horse", TEST_ITERATIONS).unwrap(), None).unwrap();
+
+    std::env::set_var("VGOOG_PASSPHRASE", "battery staple");
+    let err = Config::load().unwrap_err();
+    assert_eq!(err.kind(), "auth");
+
+    std::env::set_var("VGOOG_PASSPHRASE", "correct horse");
+    let loaded = Config::load().unwrap();
+    assert_eq!(loaded.accounts["fake"].auth.refresh_token, REFRESH_TOKEN);
+}
+
+#[tokio::test]
+async fn unlocking_restores_the_plaintext_config() {
+    let _guard = fresh_config_dir().await;
+    let fake = FakeGoogle::start().await.unwrap();
+    let mut config = fake.config();
+    let key_file = key_file();
+    config.lock(VaultKey::from_key_file(&key_file).unwrap(), Some(key_file)).unwrap();
+
+    let mut loaded = Config::load().unwrap();
+    loaded.unlock().unwrap();
+
+    assert!(!Config::vault_path().unwrap().exists());
+    assert!(config_toml().contains(REFRESH_TOKEN));
+    let reloaded = Config::load().unwrap();
+    assert!(!reloaded.is_locked());
+    assert_eq!(reloaded.accounts["fake"].auth.refresh_token, REFRESH_TOKEN);
+}
+
+#[tokio::test]
+async fn refreshed_tokens_are_sealed_on_save() {
+    let _guard = fresh_config_dir().await;
+    let fake = FakeGoogle::start().await.unwrap();
+    let mut config = fake.config();
+    let account = config.accounts.get_mut("fake").unwrap();
+    account.auth.access_token = "stale".to_string();
+    account.auth.token_expiry = Utc::now() - Duration::minutes(5);
+    let key_file = key_file();
+    config.lock(VaultKey::from_key_file(&key_file).unwrap(), Some(key_file)).unwrap();
+
+    let client = GoogleClient::new(Config::load().unwrap()).unwrap();
+    exec(&client, "gmail", "get_profile", json!({})).await;
+
+    let toml = config_toml();
+    assert!(toml.contains("access_token = \"vault:fake.access_token\""), "{toml}");
+    assert_eq!(fake.requests()[0].path, "/token");
+    let reloaded = Config::load().unwrap();
+    assert_ne!(reloaded.accounts["fake"].auth.access_token, "stale");
+    assert_eq!(reloaded.accounts["fake"].auth.refresh_token, REFRESH_TOKEN);
+}
+
+#[cfg(unix)]
+#[tokio::test]
+async fn everything_written_is_private() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let _guard = fresh_config_dir().await;
+    let fake = FakeGoogle::start().await.unwrap();
+    let config_path = Config::config_path().unwrap();
+    std::fs::create_dir_all(config_path.parent().unwrap()).unwrap();
+    // A config written world-readable by an older version
+    std::fs::write(&config_path, "").unwrap();
+    std::fs::set_permissions(&config_path, std::fs::Permissions::from_mode(0o644)).unwrap();
+
+    let mut config = fake.config();
+    config.save().unwrap();
+    assert_eq!(mode(&config_path), 0o600);
+    config.lock(VaultKey::from_passphrase_with("pw", TEST_ITERATIONS).unwrap(), None).unwrap();
+
+    assert_eq!(mode(&Config::vault_path().unwrap()), 0o600);
+    assert_eq!(mode(&config_path), 0o600);
+    assert_eq!(mode(&Config::config_dir().unwrap()), 0o700);
+}
+
+#[cfg(unix)]
+fn mode(path: &std::path::Path) -> u32 {
+    use std::os::unix::fs::PermissionsExt;
+    std::fs::metadata(path).unwrap().permissions().mode() & 0o777
+}